@@ -6,9 +6,11 @@
 //! system fonts are resolved through a lazily-built index so repeated previews
 //! don't re-scan the font database.
 
-use crate::config::{FontData, FontMetadata, FontSource, RenderConfig};
+use crate::config::{
+    FontData, FontMetadata, FontSource, RenderConfig, SampleCanvas, TextDirection,
+};
 use crate::core::google_fonts_downloader::download_google_font_subset_temp;
-use crate::core::{session::load_font_data, AppState};
+use crate::core::{session::load_font_data, AppState, SystemFontResolver, EMBEDDED_FONT_FILE};
 use crate::error::{AppError, Result};
 use crate::rendering::FontRenderer;
 use ritecache::{DiskCacheError, LruDiskCache};
@@ -42,91 +44,18 @@ pub struct RenderFontPreviewPayload {
     font: FontMetadata,
     text: String,
     font_size: f32,
-}
-
-/// One indexed system font face: where to find it plus the fields used to
-/// match it against a [`FontMetadata`].
-struct SystemFontFace {
-    path: PathBuf,
-    font_index: u32,
-    weight: i32,
-    families: Vec<String>,
-}
-
-/// In-memory index of installed system fonts for fast resolution.
-///
-/// `by_postscript_name` gives an exact lookup; `faces` backs the fuzzy
-/// family-and-weight fallback when the PostScript name is unknown.
-struct SystemFontResolver {
-    faces: Vec<SystemFontFace>,
-    by_postscript_name: HashMap<String, (PathBuf, u32)>,
-}
-
-impl SystemFontResolver {
-    /// Builds the index by scanning the system font database once.
-    fn new() -> Self {
-        let mut db = fontdb::Database::new();
-        db.load_system_fonts();
-
-        let mut faces = Vec::new();
-        let mut by_postscript_name = HashMap::new();
-        for face in db.faces() {
-            let path = match &face.source {
-                fontdb::Source::File(path) => path,
-                fontdb::Source::SharedFile(path, _) => path,
-                fontdb::Source::Binary(_) => continue,
-            };
-            let path = path.to_path_buf();
-            by_postscript_name.insert(face.post_script_name.clone(), (path.clone(), face.index));
-            faces.push(SystemFontFace {
-                path,
-                font_index: face.index,
-                weight: face.weight.0 as i32,
-                families: face
-                    .families
-                    .iter()
-                    .map(|(family, _)| family.clone())
-                    .collect(),
-            });
-        }
-
-        Self {
-            faces,
-            by_postscript_name,
-        }
-    }
-
-    /// Resolves a font to a `(path, face index)`, matching on PostScript name
-    /// first and falling back to a family-name + nearby-weight search.
-    fn resolve(&self, font: &FontMetadata) -> Result<(PathBuf, u32)> {
-        if let Some((path, font_index)) = font
-            .postscript_name
-            .as_ref()
-            .and_then(|postscript_name| self.by_postscript_name.get(postscript_name))
-        {
-            return Ok((path.clone(), *font_index));
-        }
-
-        let face = self
-            .faces
-            .iter()
-            .find(|face| {
-                (face.weight - font.weight).abs() <= 50
-                    && face.families.iter().any(|family| {
-                        family == &font.family_name
-                            || font.family_names.values().any(|name| name == family)
-                            || font
-                                .preferred_family_names
-                                .values()
-                                .any(|name| name == family)
-                    })
-            })
-            .ok_or_else(|| {
-                AppError::Processing(format!("Failed to resolve system font {}", font.font_name))
-            })?;
-
-        Ok((face.path.clone(), face.font_index))
-    }
+    #[serde(default)]
+    direction: TextDirection,
+    #[serde(default)]
+    features: Vec<String>,
+    #[serde(default)]
+    language: Option<String>,
+    #[serde(default)]
+    canvas: SampleCanvas,
+    /// Session the font belongs to, used to find a font subset embedded by a
+    /// portable export when the font isn't installed.
+    #[serde(default)]
+    session_id: Option<String>,
 }
 
 /// Resolves a system font, building the [`SystemFontResolver`] index on first
@@ -145,6 +74,29 @@ fn resolve_system_font(
     resolver.as_ref().unwrap().resolve(font)
 }
 
+/// Resolves a system font for previewing: the installed face when there is
+/// one, otherwise the subset embedded in a portable session document (which
+/// only covers the session's sample text).
+fn resolve_preview_system_font(
+    preview_cache_state: &FontPreviewCacheState,
+    font: &FontMetadata,
+    session_id: Option<&str>,
+) -> Result<(PathBuf, u32)> {
+    let installed = resolve_system_font(preview_cache_state, font);
+    let Some(session_id) = session_id.filter(|_| installed.is_err()) else {
+        return installed;
+    };
+    let embedded_font = AppState::resolve_session_dir(session_id)?
+        .join("samples")
+        .join(&font.safe_name)
+        .join(EMBEDDED_FONT_FILE);
+    if embedded_font.is_file() {
+        Ok((embedded_font, 0))
+    } else {
+        installed
+    }
+}
+
 /// Reads every font in a session as a `safe_name -> FontData` map.
 ///
 /// Shared by [`crate::commands::load_session`] rather than exposed as its own
@@ -197,7 +149,8 @@ pub async fn render_font_preview(
 /// Renders a preview, short-circuiting on a cache hit.
 ///
 /// A cache key is hashed from every input that can change the output (font
-/// identity, size, text, and the source file's size/mtime for system fonts).
+/// identity, size, text, and the source file's size/mtime for system fonts,
+/// whether installed or embedded).
 /// On a miss the font is rendered to a temp file and inserted into the LRU
 /// cache; either way the resulting cached file path is returned.
 fn render_font_preview_blocking(
@@ -212,7 +165,11 @@ fn render_font_preview_blocking(
     };
     let font_size = payload.font_size;
     let resolved_system_font = if payload.font.source == FontSource::System {
-        Some(resolve_system_font(preview_cache_state, &payload.font)?)
+        Some(resolve_preview_system_font(
+            preview_cache_state,
+            &payload.font,
+            payload.session_id.as_deref(),
+        )?)
     } else {
         None
     };
@@ -237,6 +194,10 @@ fn render_font_preview_blocking(
     payload.font.weight.hash(&mut hasher);
     payload.font.font_index.hash(&mut hasher);
     font_size.to_bits().hash(&mut hasher);
+    payload.direction.hash(&mut hasher);
+    payload.features.hash(&mut hasher);
+    payload.language.hash(&mut hasher);
+    payload.canvas.hash(&mut hasher);
     font_file_len.hash(&mut hasher);
     font_file_modified.hash(&mut hasher);
     text.hash(&mut hasher);
@@ -271,8 +232,12 @@ fn render_font_preview_blocking(
     let renderer = FontRenderer::new(Arc::new(RenderConfig {
         text: text.clone(),
         font_size,
+        direction: payload.direction,
+        features: payload.features,
+        language: payload.language,
+        canvas: payload.canvas,
         output_dir: cache_root,
-    }));
+    }))?;
     match payload.font.source {
         FontSource::System => {
             let (font_path, font_index) = resolved_system_font.ok_or_else(|| {
@@ -3,34 +3,50 @@
 //! The pipeline runs in a **separate worker process** (the same executable
 //! re-invoked with `WORKER_RUN_JOBS_ARG`) so a crash in native model code
 //! can't take down the UI. This module has two sides:
-//! - the app side ([`run_jobs`]/[`stop_jobs`]) spawns the worker, reads the
-//!   JSON event lines it prints, and forwards them to the webview;
+//! - the app side ([`run_jobs`]/[`resume_session`]/[`stop_jobs`]) queues the
+//!   job (see [`crate::commands::queue`]), spawns the worker once the queue
+//!   starts it, reads the JSON event lines it prints, and forwards them to the
+//!   webview; [`pause_jobs`]/[`resume_jobs`] and [`stop_jobs`] reach a running
+//!   worker through its stdin;
 //! - the worker side ([`run_jobs_worker`]/[`run_jobs_pipeline`]) actually runs
 //!   the discovery → render → analyse → cluster stages.
+//!
+//! Clustering sweeps ([`run_sweep`]/[`run_sweep_worker`]) run in a worker the
+//! same way.
 
 use crate::commands::progress::progress_events;
+use crate::commands::queue::{await_job, emit_job_cancelled, emit_job_queue, submit_job};
 use crate::config::{
     AlgorithmConfig, AnalysisConfig, ClusteringConfig, FontSet, ProcessStatus, ProgressStage,
     RenderingConfig,
 };
+use crate::core::resume::{count_font_markers, discard_partial_outputs, FontStage};
 use crate::core::{
-    clusterer, ensure_model, Analyzer, AppState, Discoverer, EventSink, GoogleFontsDownloader,
-    RunningJob, SampleRenderer, StdoutEventSink,
+    clusterer, ensure_model, read_clustering_sweep, read_resource_profile, run_clustering_sweep,
+    validate_resource_profile, write_resource_profile, Analyzer, AppState, CancellationToken,
+    ClusteringSweep, ClusteringSweepGrid, Discoverer, EventSink, GoogleFontsDownloader, QueuedJob,
+    QueuedJobKind, ResourceProfile, RunLog, RunLogEvent, RunStage, RunningJob, SampleRenderer,
+    StdoutEventSink,
 };
-use crate::error::{AppError, Result};
+use crate::error::{AppError, ErrorCode, Result};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
 use std::process::{Command, Stdio};
-use std::sync::atomic::Ordering;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tauri::{command, AppHandle, Emitter, Manager, State};
-use uuid::Uuid;
 
 /// CLI flag that puts the executable into worker mode.
 const WORKER_RUN_JOBS_ARG: &str = "--fontcluster-worker-run-jobs";
-/// Upper bound on concurrently running job workers.
-const MAX_RUNNING_JOBS: usize = 4;
+/// CLI flag that puts the executable into sweep-worker mode.
+const WORKER_RUN_SWEEP_ARG: &str = "--fontcluster-worker-run-sweep";
+/// How long a cancelled worker may take to stop on its own before
+/// [`stop_jobs`] kills it.
+const WORKER_CANCEL_GRACE: Duration = Duration::from_secs(10);
+/// Result of a worker that exited without sending a `worker_result`.
+const WORKER_FAILED_RESULT: &str = "Failed";
 
 /// Everything needed to start a pipeline run; serialised and passed to the
 /// worker process on its command line.
@@ -49,6 +65,25 @@ pub struct RunJobsRequest {
     /// Whether to copy, resume in place, or start from an empty session.
     #[serde(default)]
     pub run_mode: RunMode,
+    /// Continues an interrupted run: rendering and analysis skip the fonts
+    /// already marked complete instead of starting over.
+    #[serde(default)]
+    pub resume: bool,
+    /// Resource limits for this run; `None` uses the stored profile (see
+    /// [`read_resource_profile`]).
+    #[serde(default)]
+    pub resource_profile: Option<ResourceProfile>,
+}
+
+/// A clustering sweep to run; serialised and passed to the sweep worker on its
+/// command line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunSweepRequest {
+    /// Analysed session whose vectors the sweep clusters.
+    pub session_id: String,
+    /// Parameter values to evaluate.
+    pub grid: ClusteringSweepGrid,
 }
 
 /// How a processing request owns its resulting session.
@@ -77,19 +112,21 @@ pub struct AlgorithmConfigPatch {
     pub clustering: Option<ClusteringConfig>,
 }
 
-/// Spawns a worker to run the pipeline and streams its events to the webview.
+/// Queues a pipeline run and returns the worker's final result string
+/// (`"Success"`/`"Cancelled"`/…) once it has run.
 ///
-/// Rejects the call if too many jobs are already running, or if the target
-/// session already has a job in flight. Returns the worker's final result
-/// string (`"Success"`/`"Cancelled"`/…) once it exits.
+/// The job waits in the [`JobQueue`](crate::core::JobQueue) while the
+/// concurrency limit is reached or the target session already has a job in
+/// flight; among waiting jobs a higher `priority` (default `0`) starts first.
+/// A `resource_profile` overrides the stored one for this run only.
 ///
-/// The child process owns all pipeline and model-download work. This app-side
-/// function owns only child lifecycle and event adaptation: it records the
-/// child under a temporary run ID, replaces that key with the real session ID
-/// after `session_started`, attaches that ID to progress and model-download
-/// events, and removes the child from [`AppState::current_job_children`] on
-/// completion or process exit. Blocking process I/O and `Child::wait` run in a
-/// Tokio blocking task rather than on the command runtime.
+/// The child process owns all pipeline and model-download work. The app side
+/// owns only child lifecycle and event adaptation: it records the child under
+/// the job ID, replaces that key with the real session ID after
+/// `session_started`, attaches that ID to progress and model-download events,
+/// and removes the child from [`AppState::current_job_children`] on
+/// completion or process exit. Blocking process I/O and `Child::wait` run on
+/// the job's own thread rather than on the command runtime.
 #[command]
 pub async fn run_jobs(
     app: AppHandle,
@@ -98,147 +135,290 @@ pub async fn run_jobs(
     source_session_id: Option<String>,
     override_status: Option<ProcessStatus>,
     run_mode: RunMode,
+    priority: Option<i32>,
+    resource_profile: Option<ResourceProfile>,
     state: State<'_, AppState>,
 ) -> Result<String> {
+    if let Some(profile) = &resource_profile {
+        validate_resource_profile(profile)?;
+    }
     let request = RunJobsRequest {
         algorithm,
         session_id,
         source_session_id,
         override_status,
         run_mode,
+        resume: false,
+        resource_profile,
     };
-    let state = state.inner().clone();
-    let run_id = Uuid::now_v7().to_string();
-    {
-        let running_jobs = state.current_job_children.lock().unwrap();
-        if running_jobs.len() >= MAX_RUNNING_JOBS {
-            return Err(AppError::Processing(format!(
-                "The maximum of {MAX_RUNNING_JOBS} processing jobs is already running"
-            )));
-        }
-        let protected_session_id = match request.run_mode {
-            RunMode::DuplicateChanged => request.source_session_id.as_ref(),
-            RunMode::InPlaceChanged => request.session_id.as_ref(),
-            RunMode::Fresh => None,
-        };
-        if let Some(session_id) = protected_session_id {
-            if running_jobs.contains_key(session_id) {
-                return Err(AppError::Processing(
-                    "This session already has a processing job running".into(),
-                ));
-            }
-        }
+    let kind = QueuedJobKind::Pipeline { request };
+    await_job(submit_job(&app, &state, kind, priority.unwrap_or(0))?).await
+}
+
+/// Resumes an interrupted session (see
+/// [`find_interrupted_sessions`](crate::core::find_interrupted_sessions)) in
+/// place from its persisted `process_status`, reusing the fonts its rendering
+/// or analysis already finished. Queued and answered like [`run_jobs`].
+#[command]
+pub async fn resume_session(
+    app: AppHandle,
+    session_id: String,
+    priority: Option<i32>,
+    state: State<'_, AppState>,
+) -> Result<String> {
+    let request = RunJobsRequest {
+        algorithm: AlgorithmConfigPatch {
+            rendering: None,
+            analysis: None,
+            clustering: None,
+        },
+        session_id: Some(session_id),
+        source_session_id: None,
+        override_status: None,
+        run_mode: RunMode::InPlaceChanged,
+        resume: true,
+        resource_profile: None,
+    };
+    let kind = QueuedJobKind::Pipeline { request };
+    await_job(submit_job(&app, &state, kind, priority.unwrap_or(0))?).await
+}
+
+/// Returns the stored worker resource profile, or the default one.
+#[command]
+pub async fn get_resource_profile() -> Result<ResourceProfile> {
+    read_resource_profile()
+}
+
+/// Saves the worker resource profile. Jobs started from now on use it.
+#[command]
+pub async fn set_resource_profile(profile: ResourceProfile) -> Result<()> {
+    write_resource_profile(&profile)
+}
+
+/// Queues a clustering sweep over session `session_id` and returns the sweep
+/// summary once it finishes.
+///
+/// The worker is registered under the session id, so it counts towards the
+/// queue's concurrency limit, holds back other jobs on the same session and
+/// is cancelled by [`stop_jobs`] like a pipeline run. The session itself is
+/// only read.
+#[command]
+pub async fn run_sweep(
+    app: AppHandle,
+    session_id: String,
+    grid: ClusteringSweepGrid,
+    priority: Option<i32>,
+    state: State<'_, AppState>,
+) -> Result<ClusteringSweep> {
+    let kind = QueuedJobKind::Sweep {
+        request: RunSweepRequest { session_id, grid },
+    };
+    let result = await_job(submit_job(&app, &state, kind, priority.unwrap_or(0))?).await?;
+    match result.as_str() {
+        "Cancelled" => Err(AppError::new(
+            ErrorCode::Cancelled,
+            "The sweep was cancelled",
+        )),
+        WORKER_FAILED_RESULT => Err(AppError::new(
+            ErrorCode::WorkerFailed,
+            "The sweep worker did not report a sweep",
+        )),
+        sweep_id => read_clustering_sweep(sweep_id),
     }
+}
 
-    let request_json = serde_json::to_string(&request)?;
+/// Runs a job the queue has started to completion and returns its worker's
+/// final result. Pipeline workers are keyed by the job id until their session
+/// starts; sweep workers by the swept session.
+pub(crate) fn run_queued_job(app: &AppHandle, state: &AppState, job: &QueuedJob) -> Result<String> {
     let resource_dir = app.path().resource_dir().ok();
+    match &job.kind {
+        QueuedJobKind::Pipeline { request } => run_worker(
+            app,
+            state,
+            WORKER_RUN_JOBS_ARG,
+            &serde_json::to_string(request)?,
+            resource_dir,
+            &job.job_id,
+            job.job_id.clone(),
+        ),
+        QueuedJobKind::Sweep { request } => run_worker(
+            app,
+            state,
+            WORKER_RUN_SWEEP_ARG,
+            &serde_json::to_string(request)?,
+            resource_dir,
+            &job.job_id,
+            request.session_id.clone(),
+        ),
+    }
+}
 
-    tokio::task::spawn_blocking(move || -> Result<String> {
-        let mut command = Command::new(std::env::current_exe()?);
-        command
-            .arg(WORKER_RUN_JOBS_ARG)
-            .arg(request_json)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::inherit());
+/// Runs one worker process to completion, forwarding its events to the
+/// webview, and returns its `worker_result`.
+///
+/// The child is recorded in [`AppState::current_job_children`] under
+/// `job_key` until a `session_started` event moves it to the real session ID,
+/// and is removed on completion or process exit. A failed worker's
+/// `worker_error` (see [`report_worker_error`]) becomes the returned error.
+/// Blocks on process I/O, so callers run it off the async runtime.
+///
+/// The worker's stdin stays open until the job is cancelled (see
+/// [`RunningJob::cancel`]). A cancelled job always ends with a
+/// `job_cancelled` event and a `"Cancelled"` result; if the worker had to be
+/// killed, its partial outputs are discarded here instead of by the worker.
+fn run_worker(
+    app: &AppHandle,
+    state: &AppState,
+    worker_arg: &str,
+    request_json: &str,
+    resource_dir: Option<PathBuf>,
+    job_id: &str,
+    job_key: String,
+) -> Result<String> {
+    let mut command = Command::new(std::env::current_exe()?);
+    command
+        .arg(worker_arg)
+        .arg(request_json)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit());
 
-        if let Some(resource_dir) = resource_dir {
-            command.env("FONTCLUSTER_RESOURCE_DIR", resource_dir);
-        }
+    if let Some(resource_dir) = resource_dir {
+        command.env("FONTCLUSTER_RESOURCE_DIR", resource_dir);
+    }
 
-        let mut child = command.spawn().map_err(|error| {
-            AppError::Processing(format!("Failed to spawn job worker process: {error}"))
-        })?;
-        let stdout = child
-            .stdout
-            .take()
-            .ok_or_else(|| AppError::Processing("Worker stdout was not piped".into()))?;
-        let child = Arc::new(Mutex::new(child));
-        let is_cancelled = Arc::new(std::sync::atomic::AtomicBool::new(false));
-        state.current_job_children.lock().unwrap().insert(
-            run_id.clone(),
-            RunningJob {
-                child: child.clone(),
-                is_cancelled: is_cancelled.clone(),
-            },
-        );
+    let mut child = command.spawn().map_err(|error| {
+        AppError::Processing(format!("Failed to spawn job worker process: {error}"))
+    })?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| AppError::Processing("Worker stdout was not piped".into()))?;
+    let stdin = child.stdin.take();
+    let child = Arc::new(Mutex::new(child));
+    let cancellation = CancellationToken::new();
+    state.current_job_children.lock().unwrap().insert(
+        job_key.clone(),
+        RunningJob {
+            child: child.clone(),
+            stdin: Arc::new(Mutex::new(stdin)),
+            cancellation: cancellation.clone(),
+        },
+    );
 
-        let mut result = "Failed".to_string();
-        let mut session_id: Option<String> = None;
-        for line in BufReader::new(stdout).lines() {
-            let line = line?;
-            let Ok(message) = serde_json::from_str::<WorkerEventMessage>(&line) else {
-                println!("{line}");
-                continue;
-            };
+    let mut result = WORKER_FAILED_RESULT.to_string();
+    let mut worker_error = None;
+    let mut session_id: Option<String> = None;
+    for line in BufReader::new(stdout).lines() {
+        let line = line?;
+        let Ok(message) = serde_json::from_str::<WorkerEventMessage>(&line) else {
+            println!("{line}");
+            continue;
+        };
 
-            if message.event == "worker_result" {
-                if let Some(value) = message.payload.as_str() {
-                    result = value.to_string();
-                }
-                continue;
+        if message.event == "worker_result" {
+            if let Some(value) = message.payload.as_str() {
+                result = value.to_string();
             }
+            continue;
+        }
 
-            if message.event == "session_started" {
-                if let Some(started_session_id) = message.payload.as_str() {
-                    session_id = Some(started_session_id.to_string());
-                    let mut running_jobs = state.current_job_children.lock().unwrap();
-                    if let Some(job) = running_jobs.remove(&run_id) {
-                        running_jobs.insert(started_session_id.to_string(), job);
-                    }
-                }
-                app.emit(&message.event, message.payload)?;
-                continue;
-            }
+        if message.event == "worker_error" {
+            worker_error = serde_json::from_value::<AppError>(message.payload).ok();
+            continue;
+        }
 
-            if message.event.starts_with("model_download_") {
-                let mut payload = message.payload;
-                if let (Some(session_id), Some(object)) =
-                    (session_id.as_ref(), payload.as_object_mut())
-                {
-                    object.insert("sessionId".into(), Value::String(session_id.clone()));
+        if message.event == "session_started" {
+            if let Some(started_session_id) = message.payload.as_str() {
+                session_id = Some(started_session_id.to_string());
+                let mut running_jobs = state.current_job_children.lock().unwrap();
+                if let Some(job) = running_jobs.remove(&job_key) {
+                    running_jobs.insert(started_session_id.to_string(), job);
                 }
-                app.emit(&message.event, payload)?;
-                continue;
             }
+            app.emit(&message.event, message.payload)?;
+            continue;
+        }
 
-            if is_progress_event(&message.event) {
-                let payload = if let Some(session_id) = session_id.as_ref() {
-                    json!({
-                        "sessionId": session_id,
-                        "value": message.payload,
-                    })
-                } else {
-                    message.payload
-                };
-                app.emit(&message.event, payload)?;
-                continue;
+        if message.event.starts_with("model_download_") {
+            let mut payload = message.payload;
+            if let (Some(session_id), Some(object)) = (session_id.as_ref(), payload.as_object_mut())
+            {
+                object.insert("sessionId".into(), Value::String(session_id.clone()));
             }
+            app.emit(&message.event, payload)?;
+            continue;
+        }
 
-            if message.event == "all_jobs_complete" {
-                let key = session_id.as_ref().unwrap_or(&run_id).to_string();
-                state.current_job_children.lock().unwrap().remove(&key);
-                app.emit(&message.event, message.payload)?;
-                continue;
-            }
+        if is_progress_event(&message.event) {
+            let payload = if let Some(session_id) = session_id.as_ref() {
+                json!({
+                    "sessionId": session_id,
+                    "value": message.payload,
+                })
+            } else {
+                message.payload
+            };
+            app.emit(&message.event, payload)?;
+            continue;
+        }
 
+        if message.event == "all_jobs_complete" {
+            let key = session_id.as_ref().unwrap_or(&job_key).to_string();
+            state.current_job_children.lock().unwrap().remove(&key);
             app.emit(&message.event, message.payload)?;
+            continue;
         }
 
-        let status = child.lock().unwrap().wait()?;
-        let key = session_id.as_ref().unwrap_or(&run_id).to_string();
-        state.current_job_children.lock().unwrap().remove(&key);
-        if status.success() {
-            Ok(result)
-        } else if is_cancelled.load(Ordering::Relaxed) {
-            Ok("Cancelled".into())
-        } else {
-            Err(AppError::Processing(format!(
-                "Job worker exited with status {status}"
-            )))
+        app.emit(&message.event, message.payload)?;
+    }
+
+    let status = child.lock().unwrap().wait()?;
+    let key = session_id.as_ref().unwrap_or(&job_key).to_string();
+    state.current_job_children.lock().unwrap().remove(&key);
+    // A sweep is keyed by its session, a pipeline by its session once started.
+    let job_session_id = (key != job_id).then_some(key.as_str());
+    if status.success() {
+        if result == "Cancelled" {
+            emit_job_cancelled(app, job_id, job_session_id);
         }
-    })
-    .await
-    .map_err(|error| AppError::Processing(error.to_string()))?
+        successful_worker_result(result, worker_error)
+    } else if cancellation.is_cancelled() {
+        if let Some(session_id) = session_id.as_deref() {
+            let discarded = AppState::get_session_processing_dir(session_id)
+                .and_then(|dir| discard_partial_outputs(&dir));
+            if let Err(error) = discarded {
+                eprintln!(
+                    "Failed to discard a killed job's partial outputs: {}",
+                    error
+                );
+            }
+        }
+        emit_job_cancelled(app, job_id, job_session_id);
+        Ok("Cancelled".into())
+    } else if let Some(error) = worker_error {
+        Err(error)
+    } else {
+        Err(AppError::new(
+            ErrorCode::WorkerFailed,
+            format!("Job worker exited with status {status}"),
+        ))
+    }
+}
+
+/// The outcome of a worker that exited successfully: its `worker_result`, or
+/// the `worker_error` it reported when it never sent one.
+fn successful_worker_result(result: String, worker_error: Option<AppError>) -> Result<String> {
+    if result != WORKER_FAILED_RESULT {
+        return Ok(result);
+    }
+    Err(worker_error.unwrap_or_else(|| {
+        AppError::new(
+            ErrorCode::WorkerFailed,
+            "Job worker exited without reporting a result",
+        )
+    }))
 }
 
 /// One JSON line as printed by the worker's [`StdoutEventSink`].
@@ -283,22 +463,123 @@ pub fn is_worker_run_jobs_arg(arg: &str) -> bool {
     arg == WORKER_RUN_JOBS_ARG
 }
 
-/// Worker-process entry point: deserialises the request, runs the pipeline on
-/// a single-threaded Tokio runtime, and prints the final result event.
+/// True if `arg` is the flag that selects sweep-worker mode (checked in
+/// `main`).
+pub fn is_worker_run_sweep_arg(arg: &str) -> bool {
+    arg == WORKER_RUN_SWEEP_ARG
+}
+
+/// Prints a failed worker run's error as a `worker_error` event (called in
+/// `main`), so the app process returns it with its code and details intact.
+pub fn report_worker_error(error: &AppError) {
+    let payload = serde_json::to_value(error).unwrap_or_else(|_| json!(error.to_string()));
+    let _ = StdoutEventSink::new().emit_value("worker_error", payload);
+}
+
+/// Reports a worker's new pause state as `job_paused` or `job_resumed`.
+fn emit_pause(events: &impl EventSink, session_id: Option<&str>, paused: bool) {
+    let (event, message) = if paused {
+        ("job_paused", "⏸️ Job paused")
+    } else {
+        ("job_resumed", "▶️ Job resumed")
+    };
+    println!("{message}");
+    let _ = events.emit_value(event, json!({ "sessionId": session_id }));
+}
+
+/// Sweep-worker entry point: evaluates the requested grid, emits
+/// `sweep_complete`, and reports the new sweep's id as the final result. The
+/// worker runs under the stored [`ResourceProfile`].
+///
+/// The session's model is installed (or verified) first only when its
+/// clustering config uses attribute directions, as for a clustering-only run.
+pub fn run_sweep_worker(request_json: &str) -> Result<()> {
+    let request = serde_json::from_str::<RunSweepRequest>(request_json)?;
+    let resource_profile = read_resource_profile()?;
+    resource_profile.apply_to_process()?;
+    let events = StdoutEventSink::new();
+    let cancel = CancellationToken::controlled_by_stdin({
+        let events = events.clone();
+        let session_id = request.session_id.clone();
+        move |paused| emit_pause(&events, Some(&session_id), paused)
+    });
+    let session =
+        read_session_config_from_dir(&AppState::resolve_session_dir(&request.session_id)?)?;
+    let model_bundle = if session.algorithm.clustering.uses_attribute_directions() {
+        Some(ensure_model(
+            &session.algorithm.analysis.model_id,
+            resource_profile.effective_download_connections(),
+            &events,
+        )?)
+    } else {
+        None
+    };
+    let sweep = match run_clustering_sweep(
+        &events,
+        &request.session_id,
+        &request.grid,
+        model_bundle.as_ref().map(|model| model.directory.as_path()),
+        &resource_profile,
+        &cancel,
+    ) {
+        Err(error) if error.is_cancelled() => {
+            events.emit_string("worker_result", "Cancelled".into())?;
+            return Ok(());
+        }
+        result => result?,
+    };
+    events.emit_value(
+        "sweep_complete",
+        json!({
+            "sessionId": request.session_id,
+            "sweepId": sweep.sweep_id,
+        }),
+    )?;
+    events.emit_string("worker_result", sweep.sweep_id)?;
+    Ok(())
+}
+
+/// Worker-process entry point: deserialises the request, applies its
+/// [`ResourceProfile`] (or the stored one) to the process, runs the pipeline
+/// on a single-threaded Tokio runtime, and prints the final result event. The
+/// app pauses, resumes and cancels the job through the worker's stdin; the
+/// pause state is persisted on the session as it changes.
 ///
 /// Keeping this runtime wholly inside the worker process isolates native-model
 /// failures from the UI process. Blocking model installation is delegated by
 /// [`run_jobs_pipeline`] so its blocking HTTP runtime is also created and
 /// dropped outside this async runtime.
 pub fn run_jobs_worker(request_json: &str) -> Result<()> {
-    let request = serde_json::from_str::<RunJobsRequest>(request_json)?;
+    let mut request = serde_json::from_str::<RunJobsRequest>(request_json)?;
+    let resource_profile = match request.resource_profile.take() {
+        Some(profile) => profile,
+        None => read_resource_profile()?,
+    };
+    resource_profile.apply_to_process()?;
+    request.resource_profile = Some(resource_profile);
     let state = AppState::new();
     let events = StdoutEventSink::new();
+    let cancel = CancellationToken::controlled_by_stdin({
+        let state = state.clone();
+        let events = events.clone();
+        move |paused| {
+            if let Err(error) = state.update_status(|status| status.paused = paused) {
+                eprintln!("Failed to save the pause state: {}", error);
+            }
+            let session_id = state
+                .current_session
+                .lock()
+                .unwrap()
+                .as_ref()
+                .map(|session| session.session_id.clone());
+            emit_pause(&events, session_id.as_deref(), paused);
+        }
+    });
     let runtime = tokio::runtime::Builder::new_current_thread()
         .enable_all()
         .build()
         .map_err(|error| AppError::Processing(error.to_string()))?;
-    let result = runtime.block_on(run_jobs_pipeline(events.clone(), &state, request))?;
+    let result = runtime.block_on(run_jobs_pipeline(events.clone(), &state, request, &cancel))?;
     events.emit_string("worker_result", result)?;
     Ok(())
 }
@@ -308,9 +589,16 @@ pub fn run_jobs_worker(request_json: &str) -> Result<()> {
 /// Initialises or resumes the session, then advances it through the rendering,
 /// analysis and clustering stages. Each stage is skipped if the
 /// session's [`ProcessStatus`] already covers it, so an interrupted session
-/// resumes where it left off. The session is packed into its document once it
-/// reaches `Clustered`. Returns `"Cancelled"` if cancellation is observed at
-/// any checkpoint, otherwise `"Success"`.
+/// resumes where it left off; with [`RunJobsRequest::resume`] the interrupted
+/// stage itself also keeps the fonts it already finished. The session is
+/// packed into its document once it reaches `Clustered`. Returns `"Cancelled"`
+/// if `cancel` is observed at any checkpoint, after discarding the outputs the
+/// interrupted stage left half-written; otherwise `"Success"`.
+///
+/// Each run appends to the session's [`crate::core::run_log`]: its settings,
+/// thread counts and resource limits, the model used, stage timings, dropped
+/// fonts and the outcome, so the history is packed into the document along
+/// with the rest.
 ///
 /// Model ownership is job-local. The pipeline resolves or installs one
 /// [`crate::core::ModelBundle`] before any stage that needs it, then passes the
@@ -331,8 +619,11 @@ pub async fn run_jobs_pipeline(
     events: impl EventSink,
     state: &AppState,
     request: RunJobsRequest,
+    cancel: &CancellationToken,
 ) -> Result<String> {
-    state.is_cancelled.store(false, Ordering::Relaxed);
+    let resume = request.resume;
+    let run_mode = request.run_mode;
+    let resource_profile = request.resource_profile.clone().unwrap_or_default();
 
     // Initialize or load session.
     let id = match request.run_mode {
@@ -378,20 +669,86 @@ pub async fn run_jobs_pipeline(
     };
     events.emit_string("session_started", id.clone())?;
 
+    let resume_status = {
+        let guard = state.current_session.lock().unwrap();
+        guard.as_ref().unwrap().status.process_status
+    };
+    // A pause requested before the session was loaded could not be saved.
+    state.update_status(|status| status.paused = cancel.is_paused())?;
+    let started_at = Instant::now();
+    *state.run_log.lock().unwrap() = Some(RunLog::start(&state.get_session_dir()?));
+    state.log_run_event(RunLogEvent::RunStarted {
+        session_id: id.clone(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        run_mode,
+        resume,
+        resume_status,
+        rayon_threads: rayon::current_num_threads(),
+        available_parallelism: std::thread::available_parallelism()
+            .map_or(1, |threads| threads.get()),
+        onnx_threads: resource_profile.effective_onnx_threads(),
+        resource_profile: resource_profile.clone(),
+    });
+
+    let result = match run_pipeline_stages(
+        events.clone(),
+        state,
+        id.clone(),
+        resume,
+        &resource_profile,
+        cancel,
+    )
+    .await
+    {
+        Err(error) if error.is_cancelled() => Ok("Cancelled".to_string()),
+        result => result,
+    };
+    if let Err(error) = state.update_status(|status| status.paused = false) {
+        eprintln!("Failed to save the pause state: {}", error);
+    }
+    let duration_ms = started_at.elapsed().as_millis() as u64;
+    match &result {
+        Ok(result) => state.log_run_event(RunLogEvent::RunFinished {
+            result: result.clone(),
+            duration_ms,
+        }),
+        Err(error) => state.log_run_event(RunLogEvent::RunFailed {
+            error: error.clone(),
+            duration_ms,
+        }),
+    }
+    state.run_log.lock().unwrap().take();
+
+    let result = result?;
+    if result == "Success" {
+        state.finalize_session(&id)?;
+        events.emit_string("all_jobs_complete", id)?;
+    } else {
+        println!("🛑 Run cancelled; discarding partial outputs");
+        discard_partial_outputs(&state.get_session_dir()?)?;
+    }
+    Ok(result)
+}
+
+/// Advances the active session `id` through every stage its status does not
+/// already cover; see [`run_jobs_pipeline`]. Returns `"Success"` once the
+/// session is `Clustered`, leaving packing to the caller, and a cancelled
+/// error once `cancel` is observed.
+async fn run_pipeline_stages(
+    events: impl EventSink,
+    state: &AppState,
+    id: String,
+    resume: bool,
+    resource_profile: &ResourceProfile,
+    cancel: &CancellationToken,
+) -> Result<String> {
     let (model_id, resume_status, clustering_needs_model) = {
         let guard = state.current_session.lock().unwrap();
         let session = guard.as_ref().unwrap();
         (
             session.algorithm.analysis.model_id.clone(),
             session.status.process_status,
-            session.algorithm.clustering.enable_preprocess_pca
-                && session.algorithm.clustering.enable_attribute_emphasis
-                && session
-                    .algorithm
-                    .clustering
-                    .emphasis
-                    .values()
-                    .any(|&level| level != 0),
+            session.algorithm.clustering.uses_attribute_directions(),
         )
     };
 
@@ -402,9 +759,15 @@ pub async fn run_jobs_pipeline(
     {
         let model_install_id = model_id.clone();
         let model_install_events = events.clone();
+        let download_connections = resource_profile.effective_download_connections();
         Some(
             tokio::task::spawn_blocking(move || {
-                ensure_model(&model_install_id, &model_install_events)
+                ensure_model(
+                    &model_install_id,
+                    download_connections,
+                    &model_install_events,
+                )
+                .map_err(|error| error.with_model_id(&model_install_id))
             })
             .await
             .map_err(|error| {
@@ -414,6 +777,12 @@ pub async fn run_jobs_pipeline(
     } else {
         None
     };
+    if let Some(model) = &model_bundle {
+        state.log_run_event(RunLogEvent::ModelSelected {
+            model_id: model.manifest.id.clone(),
+            model_sha256: model.manifest.model_sha256().to_string(),
+        });
+    }
 
     // When resuming from a midpoint, clear the progress of the resume stage and
     // every later stage so the UI stops showing stale results that are about to
@@ -429,10 +798,16 @@ pub async fn run_jobs_pipeline(
         guard.as_ref().unwrap().status.process_status
     };
     if status == ProcessStatus::Empty {
-        if state.is_cancelled.load(Ordering::Relaxed) {
-            return Ok("Cancelled".into());
+        cancel.checkpoint()?;
+        // Rendered markers only exist when this same rendering pass was
+        // interrupted after it reset the outputs; without any there is nothing
+        // to keep and stale samples from an earlier config must go.
+        let session_dir = state.get_session_dir()?;
+        if resume && count_font_markers(&session_dir, FontStage::Rendered) > 0 {
+            println!("⏩ Resuming sample rendering...");
+        } else {
+            state.reset_rendering_outputs()?;
         }
-        state.reset_rendering_outputs()?;
         println!("🖼️ Starting sample rendering...");
         events.emit_unit("font_rendering_start")?;
 
@@ -440,6 +815,7 @@ pub async fn run_jobs_pipeline(
             let guard = state.current_session.lock().unwrap();
             guard.as_ref().unwrap().algorithm.rendering.font_set.clone()
         };
+        let discovery_started_at = log_stage_started(state, RunStage::Discovery);
         let disc = Discoverer::new();
         let google_fonts_dir = if matches!(font_set, FontSet::SystemFonts) {
             None
@@ -447,8 +823,9 @@ pub async fn run_jobs_pipeline(
             let temp_dir =
                 tempfile::TempDir::new().map_err(|error| AppError::Io(error.to_string()))?;
             GoogleFontsDownloader::new()
-                .download_fonts_to_dir(state, temp_dir.path().to_path_buf())
-                .await?;
+                .download_fonts_to_dir(state, temp_dir.path().to_path_buf(), cancel)
+                .await
+                .map_err(|error| error.with_stage(RunStage::Discovery))?;
             Some(temp_dir)
         };
 
@@ -458,20 +835,22 @@ pub async fn run_jobs_pipeline(
                 google_fonts_dir
                     .as_ref()
                     .map(|dir| dir.path().to_path_buf()),
+                cancel,
             )
-            .await?;
+            .await
+            .map_err(|error| error.with_stage(RunStage::Discovery))?;
 
-        if state.is_cancelled.load(Ordering::Relaxed) {
-            return Ok("Cancelled".into());
-        }
+        cancel.checkpoint()?;
+        log_stage_completed(state, RunStage::Discovery, discovery_started_at);
+        let rendering_started_at = log_stage_started(state, RunStage::Rendering);
         let renderer = SampleRenderer::new();
         renderer
-            .render_all(&events, state, discovery.render_sources)
-            .await?;
+            .render_all(&events, state, discovery.render_sources, cancel)
+            .await
+            .map_err(|error| error.with_stage(RunStage::Rendering))?;
 
-        if state.is_cancelled.load(Ordering::Relaxed) {
-            return Ok("Cancelled".into());
-        }
+        cancel.checkpoint()?;
+        log_stage_completed(state, RunStage::Rendering, rendering_started_at);
         events.emit_string("font_rendering_complete", id.clone())?;
     }
 
@@ -481,20 +860,22 @@ pub async fn run_jobs_pipeline(
         guard.as_ref().unwrap().status.process_status
     };
     if status == ProcessStatus::Rendered {
-        if state.is_cancelled.load(Ordering::Relaxed) {
-            return Ok("Cancelled".into());
-        }
+        cancel.checkpoint()?;
         println!("📐 Starting analysis...");
         events.emit_unit("analysis_start")?;
         let model = model_bundle.as_ref().ok_or_else(|| {
             AppError::Processing("Analysis requires a validated model bundle".into())
         })?;
-        let analyzer = Analyzer::new(model)?;
-        analyzer.analyze_all(&events, state).await?;
+        let analysis_started_at = log_stage_started(state, RunStage::Analysis);
+        let analyzer = Analyzer::new(model, resource_profile)
+            .map_err(|error| error.with_stage(RunStage::Analysis))?;
+        analyzer
+            .analyze_all(&events, state, resume, cancel)
+            .await
+            .map_err(|error| error.with_stage(RunStage::Analysis))?;
 
-        if state.is_cancelled.load(Ordering::Relaxed) {
-            return Ok("Cancelled".into());
-        }
+        cancel.checkpoint()?;
+        log_stage_completed(state, RunStage::Analysis, analysis_started_at);
         events.emit_string("analysis_complete", id.clone())?;
     }
 
@@ -504,22 +885,26 @@ pub async fn run_jobs_pipeline(
         guard.as_ref().unwrap().status.process_status
     };
     if status == ProcessStatus::Analyzed {
-        if state.is_cancelled.load(Ordering::Relaxed) {
-            return Ok("Cancelled".into());
-        }
+        cancel.checkpoint()?;
         println!("✨ Starting clustering...");
         events.emit_unit("clustering_start")?;
-        clusterer::cluster_all(&events, state, model_bundle.as_ref()).await?;
+        let clustering_started_at = log_stage_started(state, RunStage::Clustering);
+        clusterer::cluster_all(
+            &events,
+            state,
+            model_bundle.as_ref(),
+            resource_profile,
+            cancel,
+        )
+        .await
+        .map_err(|error| error.with_stage(RunStage::Clustering))?;
 
-        if state.is_cancelled.load(Ordering::Relaxed) {
-            return Ok("Cancelled".into());
-        }
+        cancel.checkpoint()?;
+        log_stage_completed(state, RunStage::Clustering, clustering_started_at);
         events.emit_string("clustering_complete", id.clone())?;
     }
 
-    if state.is_cancelled.load(Ordering::Relaxed) {
-        return Ok("Cancelled".into());
-    }
+    cancel.checkpoint()?;
 
     let final_status = {
         let guard = state.current_session.lock().unwrap();
@@ -530,40 +915,125 @@ pub async fn run_jobs_pipeline(
             "Processing stopped before clustering completed (status: {final_status:?})"
         )));
     }
-    state.finalize_session(&id)?;
-
-    events.emit_string("all_jobs_complete", id)?;
     Ok("Success".into())
 }
 
-/// Cancels running jobs and kills their worker processes.
+/// Records the start of `stage` in the run log and returns when it started.
+fn log_stage_started(state: &AppState, stage: RunStage) -> Instant {
+    state.log_run_event(RunLogEvent::StageStarted { stage });
+    Instant::now()
+}
+
+/// Records that `stage`, begun at `started_at`, completed.
+fn log_stage_completed(state: &AppState, stage: RunStage, started_at: Instant) {
+    state.log_run_event(RunLogEvent::StageCompleted {
+        stage,
+        duration_ms: started_at.elapsed().as_millis() as u64,
+    });
+}
+
+/// Cancels jobs and stops their worker processes.
 ///
-/// Cancels the worker for `session_id` if given, otherwise every running
-/// worker. Each job's cancellation flag is set before the process is killed so
-/// it can report `"Cancelled"` cleanly, and `jobs_cancelled` is emitted.
-/// Removed jobs are no longer addressable through
-/// [`AppState::current_job_children`] after this call.
+/// Cancels the worker for `session_id` and the queued jobs targeting it if
+/// given, otherwise every running worker and the whole queue. Each worker is
+/// asked to stop through its [`CancellationToken`] and stops at its next
+/// checkpoint, discarding its partial outputs; one still running after
+/// [`WORKER_CANCEL_GRACE`] is killed. Every cancelled job, queued or running,
+/// ends with a `job_cancelled` event, and `jobs_cancelled` is emitted once.
+/// Running jobs stay in [`AppState::current_job_children`] until their worker
+/// exits, so their sessions remain busy while they clean up.
 #[command]
 pub fn stop_jobs(
     app: AppHandle,
     state: State<'_, AppState>,
     session_id: Option<String>,
 ) -> Result<()> {
-    let jobs = {
-        let mut running_jobs = state.current_job_children.lock().unwrap();
-        match session_id.as_ref() {
-            Some(session_id) => running_jobs
-                .remove(session_id)
-                .into_iter()
-                .collect::<Vec<_>>(),
-            None => running_jobs.drain().map(|(_, job)| job).collect::<Vec<_>>(),
+    let jobs = running_jobs(&state, session_id.as_deref());
+
+    let cancelled_queued = {
+        let mut queue = state.job_queue.lock().unwrap();
+        let cancelled = match session_id.as_deref() {
+            Some(session_id) => {
+                queue.cancel_queued(|job| job.protected_session_id() == Some(session_id))
+            }
+            None => queue.cancel_queued(|_| true),
+        };
+        if !cancelled.is_empty() {
+            queue.save()?;
         }
+        cancelled
     };
+    for job in &cancelled_queued {
+        emit_job_cancelled(&app, &job.job_id, job.protected_session_id());
+    }
+    if !cancelled_queued.is_empty() {
+        emit_job_queue(&app, &state);
+    }
 
     for job in jobs {
-        job.is_cancelled.store(true, Ordering::Relaxed);
-        let _ = job.child.lock().unwrap().kill();
+        job.cancel(WORKER_CANCEL_GRACE);
     }
     app.emit("jobs_cancelled", session_id)?;
     Ok(())
 }
+
+/// Pauses running jobs at their next safe checkpoint: between downloads,
+/// render tasks, analysis batches, sweep variants and stages.
+///
+/// Pauses the worker for `session_id` if given, otherwise every running
+/// worker. Each worker saves the paused state in its session's
+/// [`ProcessingStatus`](crate::config::ProcessingStatus) and emits
+/// `job_paused`. A paused job keeps its place and its concurrency slot in the
+/// queue, and can still be stopped with [`stop_jobs`].
+#[command]
+pub fn pause_jobs(state: State<'_, AppState>, session_id: Option<String>) -> Result<()> {
+    for job in running_jobs(&state, session_id.as_deref()) {
+        job.pause()?;
+    }
+    Ok(())
+}
+
+/// Resumes paused jobs, like [`pause_jobs`] in reverse; each worker emits
+/// `job_resumed`.
+#[command]
+pub fn resume_jobs(state: State<'_, AppState>, session_id: Option<String>) -> Result<()> {
+    for job in running_jobs(&state, session_id.as_deref()) {
+        job.resume()?;
+    }
+    Ok(())
+}
+
+/// The running job for `session_id`, or every running job.
+fn running_jobs(state: &AppState, session_id: Option<&str>) -> Vec<RunningJob> {
+    let running_jobs = state.current_job_children.lock().unwrap();
+    match session_id {
+        Some(session_id) => running_jobs.get(session_id).cloned().into_iter().collect(),
+        None => running_jobs.values().cloned().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn worker_without_a_result_returns_its_error() {
+        assert_eq!(
+            successful_worker_result("Success".into(), None).unwrap(),
+            "Success"
+        );
+        let sweep_id = uuid::Uuid::now_v7().to_string();
+        assert_eq!(
+            successful_worker_result(sweep_id.clone(), None).unwrap(),
+            sweep_id
+        );
+
+        let reported = AppError::new(ErrorCode::SessionNotFound, "Session gone");
+        let error =
+            successful_worker_result(WORKER_FAILED_RESULT.into(), Some(reported)).unwrap_err();
+        assert_eq!(error.code(), ErrorCode::SessionNotFound);
+
+        let error = successful_worker_result(WORKER_FAILED_RESULT.into(), None).unwrap_err();
+        assert_eq!(error.code(), ErrorCode::WorkerFailed);
+    }
+}
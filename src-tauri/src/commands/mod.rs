@@ -1,21 +1,28 @@
 //! Tauri command handlers invoked from the webview.
 //!
 //! Each submodule groups the commands for one feature area: [`font`] (browser
-//! and previews), [`jobs`] (running/stopping the pipeline), [`plugin`] (the
-//! plugin bridge) and [`session`] (session lifecycle). [`progress`] holds shared
-//! progress-reporting helpers rather than commands. The handlers are
-//! registered in [`crate::run`].
+//! and previews), [`jobs`] (running, pausing and stopping the pipeline, and
+//! worker resource limits), [`model`] (the model catalog and installed
+//! models), [`queue`] (the job queue), [`plugin`] (the plugin bridge),
+//! [`session`] (session lifecycle) and [`storage`] (disk usage and garbage
+//! collection).
+//! [`progress`] holds shared progress-reporting helpers rather than commands.
+//! The handlers are registered in [`crate::run`].
 
 pub mod font;
 pub mod jobs;
 pub mod model;
 pub mod plugin;
 pub mod progress;
+pub mod queue;
 pub mod session;
+pub mod storage;
 
 pub use font::*;
 pub use jobs::*;
 pub use model::*;
 pub use plugin::*;
 pub use progress::*;
+pub use queue::*;
 pub use session::*;
+pub use storage::*;
@@ -1,7 +1,32 @@
-//! Model-catalog commands invoked by algorithm options.
+//! Model-catalog commands invoked by algorithm options, and management of the
+//! installed models.
 
-use crate::core::{list_models as read_model_catalog, ModelCatalogResponse};
-use crate::error::{AppError, Result};
+use crate::commands::session::collect_stored_sessions;
+use crate::core::{
+    delete_model as remove_installed_model, import_local_model as install_local_model,
+    list_models as read_model_catalog, model_storage_summary, read_model_source,
+    verify_model as verify_installed_model, write_model_source, AppState, ModelAvailability,
+    ModelCatalogEntry, ModelCatalogResponse, ModelSource, ModelStorageSummary, ModelVerification,
+};
+use crate::error::{AppError, ErrorCode, Result};
+use serde::Serialize;
+use std::path::PathBuf;
+use tauri::State;
+
+/// Outcome of a [`delete_model`] request.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelDeletion {
+    pub model_id: String,
+    /// `false` when the model was kept because sessions reference it and the
+    /// deletion was not forced.
+    pub deleted: bool,
+    /// Bytes the model occupied; zero when it was kept.
+    pub freed_bytes: u64,
+    /// Ids of the stored sessions whose analysis uses the model. A published
+    /// model is downloaded again when one of them is re-run.
+    pub referencing_sessions: Vec<String>,
+}
 
 /// Reads local availability immediately and augments it with published model
 /// releases without blocking Tauri's async command thread.
@@ -11,3 +36,170 @@ pub async fn list_models() -> Result<ModelCatalogResponse> {
         .await
         .map_err(|error| AppError::Processing(error.to_string()))
 }
+
+/// Imports the model bundle in the directory or zip archive at `path` under
+/// the `local:` namespace and returns its catalog entry. Hashing and copying
+/// a large model runs off the async command thread.
+#[tauri::command]
+pub async fn import_local_model(path: PathBuf) -> Result<ModelCatalogEntry> {
+    let bundle = tokio::task::spawn_blocking(move || install_local_model(&path))
+        .await
+        .map_err(|error| AppError::Processing(error.to_string()))??;
+    Ok(ModelCatalogEntry {
+        id: bundle.manifest.id,
+        name: bundle.manifest.name,
+        parameter_count: bundle.manifest.parameter_count,
+        download_size: 0,
+        availability: ModelAvailability::Available,
+    })
+}
+
+/// Returns where published models are listed and downloaded from.
+#[tauri::command]
+pub fn get_model_source() -> Result<ModelSource> {
+    read_model_source()
+}
+
+/// Saves the model source; the next catalog request lists its releases.
+#[tauri::command]
+pub fn set_model_source(source: ModelSource) -> Result<()> {
+    write_model_source(&source)
+}
+
+/// Re-hashes the installed model's payloads against its manifest checksums.
+#[tauri::command]
+pub async fn verify_model(model_id: String) -> Result<ModelVerification> {
+    tokio::task::spawn_blocking(move || verify_installed_model(&model_id))
+        .await
+        .map_err(|error| AppError::Processing(error.to_string()))?
+}
+
+/// Deletes an installed model. When stored sessions reference it, the model
+/// is kept and their ids returned as a warning unless `force` is set. A model
+/// that a running or queued job uses, through its session or its request, is
+/// never deleted.
+#[tauri::command]
+pub async fn delete_model(
+    model_id: String,
+    force: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<ModelDeletion> {
+    let force = force.unwrap_or(false);
+    let state = state.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let referencing_sessions: Vec<String> = collect_stored_sessions()?
+            .into_iter()
+            .filter(|session| session.algorithm.analysis.model_id == model_id)
+            .map(|session| session.session_id)
+            .collect();
+        let busy_session_ids = state.busy_session_ids();
+        if let Some(session_id) = referencing_sessions
+            .iter()
+            .find(|session_id| busy_session_ids.contains(*session_id))
+        {
+            return Err(AppError::new(
+                ErrorCode::SessionBusy,
+                format!("Model '{model_id}' is in use by a running or queued job"),
+            )
+            .with_model_id(&model_id)
+            .with_session_id(session_id));
+        }
+        if state.job_queue.lock().unwrap().requests_model(&model_id) {
+            return Err(AppError::new(
+                ErrorCode::SessionBusy,
+                format!("Model '{model_id}' is requested by a running or queued job"),
+            )
+            .with_model_id(&model_id));
+        }
+        delete_unless_referenced(
+            model_id,
+            referencing_sessions,
+            force,
+            remove_installed_model,
+        )
+    })
+    .await
+    .map_err(|error| AppError::Processing(error.to_string()))?
+}
+
+/// Deletes `model_id` with `remove` unless `referencing_sessions` is
+/// non-empty and the deletion is not forced.
+fn delete_unless_referenced(
+    model_id: String,
+    referencing_sessions: Vec<String>,
+    force: bool,
+    remove: impl FnOnce(&str) -> Result<u64>,
+) -> Result<ModelDeletion> {
+    if !referencing_sessions.is_empty() && !force {
+        return Ok(ModelDeletion {
+            model_id,
+            deleted: false,
+            freed_bytes: 0,
+            referencing_sessions,
+        });
+    }
+    let freed_bytes = remove(&model_id)?;
+    Ok(ModelDeletion {
+        model_id,
+        deleted: true,
+        freed_bytes,
+        referencing_sessions,
+    })
+}
+
+/// Measures the disk usage of every installed model.
+#[tauri::command]
+pub async fn get_model_storage() -> Result<ModelStorageSummary> {
+    tokio::task::spawn_blocking(model_storage_summary)
+        .await
+        .map_err(|error| AppError::Processing(error.to_string()))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sessions() -> Vec<String> {
+        vec!["a".to_string(), "b".to_string()]
+    }
+
+    #[test]
+    fn referenced_model_is_kept_unless_forced() {
+        let deletion = delete_unless_referenced("model".into(), sessions(), false, |_| {
+            panic!("a referenced model must not be removed")
+        })
+        .unwrap();
+        assert!(!deletion.deleted);
+        assert_eq!(deletion.freed_bytes, 0);
+        assert_eq!(deletion.referencing_sessions, sessions());
+    }
+
+    #[test]
+    fn forced_deletion_reports_the_freed_bytes() {
+        let mut removed = None;
+        let deletion = delete_unless_referenced("model".into(), sessions(), true, |model_id| {
+            removed = Some(model_id.to_string());
+            Ok(1024)
+        })
+        .unwrap();
+        assert_eq!(removed.as_deref(), Some("model"));
+        assert_eq!(
+            serde_json::to_value(&deletion).unwrap(),
+            json!({
+                "modelId": "model",
+                "deleted": true,
+                "freedBytes": 1024,
+                "referencingSessions": ["a", "b"],
+            })
+        );
+    }
+
+    #[test]
+    fn unreferenced_model_is_deleted_without_force() {
+        let deletion =
+            delete_unless_referenced("model".into(), Vec::new(), false, |_| Ok(7)).unwrap();
+        assert!(deletion.deleted);
+        assert_eq!(deletion.freed_bytes, 7);
+    }
+}
@@ -0,0 +1,201 @@
+//! Job queue dispatch and the commands that inspect and reorder it.
+//!
+//! [`submit_job`] adds a pipeline run or sweep to the [`JobQueue`] in
+//! [`AppState`], and [`pump_job_queue`] starts whatever the queue allows, each
+//! job on its own thread driving a worker process (see
+//! [`run_queued_job`]). Every change is saved and emitted to the webview as
+//! `job_queue_changed`; each finished job also emits `job_finished`, and a
+//! cancelled one `job_cancelled`.
+
+use crate::commands::jobs::run_queued_job;
+use crate::core::{AppState, JobQueue, JobQueueSnapshot, QueuedJobKind};
+use crate::error::{AppError, Result};
+use serde_json::json;
+use std::collections::HashSet;
+use std::sync::mpsc::{self, Receiver};
+use tauri::{command, AppHandle, Emitter, Manager, State};
+
+/// Queues a job and starts it right away if the queue allows. The returned
+/// receiver yields the job's result once it finishes (`"Cancelled"` if it is
+/// removed while waiting); see [`await_job`].
+pub(crate) fn submit_job(
+    app: &AppHandle,
+    state: &AppState,
+    kind: QueuedJobKind,
+    priority: i32,
+) -> Result<Receiver<Result<String>>> {
+    let (sender, receiver) = mpsc::channel();
+    edit_job_queue(app, state, |queue| {
+        queue.submit(kind, priority, Some(sender));
+        Ok(())
+    })?;
+    Ok(receiver)
+}
+
+/// Waits, off the async runtime, for a submitted job's result.
+pub(crate) async fn await_job(receiver: Receiver<Result<String>>) -> Result<String> {
+    tokio::task::spawn_blocking(move || receiver.recv())
+        .await
+        .map_err(|error| AppError::Processing(error.to_string()))?
+        .map_err(|_| AppError::Processing("The job was dropped without a result".into()))?
+}
+
+/// Loads the queue saved by the previous launch into `app`'s state and starts
+/// its jobs. Their results are only reported through `job_finished`.
+pub fn restore_job_queue(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    match JobQueue::load() {
+        Ok(queue) => {
+            if !queue.queued.is_empty() {
+                println!("📋 Restored {} queued job(s)", queue.queued.len());
+            }
+            *state.job_queue.lock().unwrap() = queue;
+        }
+        Err(error) => eprintln!("Failed to restore the job queue: {}", error),
+    }
+    pump_job_queue(app, &state);
+}
+
+/// Starts every job the queue currently allows.
+pub(crate) fn pump_job_queue(app: &AppHandle, state: &AppState) {
+    let busy_session_ids = state
+        .current_job_children
+        .lock()
+        .unwrap()
+        .keys()
+        .cloned()
+        .collect::<HashSet<_>>();
+    let started = {
+        let mut queue = state.job_queue.lock().unwrap();
+        let mut started = Vec::new();
+        while let Some(job) = queue.start_next(&busy_session_ids) {
+            started.push(job);
+        }
+        if !started.is_empty() {
+            if let Err(error) = queue.save() {
+                eprintln!("Failed to save the job queue: {}", error);
+            }
+        }
+        started
+    };
+    if started.is_empty() {
+        return;
+    }
+    emit_job_queue(app, state);
+
+    for job in started {
+        let app = app.clone();
+        let state = state.clone();
+        std::thread::spawn(move || {
+            println!("▶️ Starting queued job {}", job.job_id);
+            let result = run_queued_job(&app, &state, &job);
+            let waiter = state.job_queue.lock().unwrap().finish(&job.job_id);
+            let _ = app.emit(
+                "job_finished",
+                json!({
+                    "jobId": job.job_id,
+                    "result": result.as_ref().ok(),
+                    "error": result.as_ref().err(),
+                }),
+            );
+            if let Some(waiter) = waiter {
+                let _ = waiter.send(result);
+            }
+            emit_job_queue(&app, &state);
+            pump_job_queue(&app, &state);
+        });
+    }
+}
+
+/// Applies `edit` to the queue, saves it, tells the webview and starts
+/// whatever the edit made startable.
+fn edit_job_queue<T>(
+    app: &AppHandle,
+    state: &AppState,
+    edit: impl FnOnce(&mut JobQueue) -> Result<T>,
+) -> Result<T> {
+    let value = {
+        let mut queue = state.job_queue.lock().unwrap();
+        let value = edit(&mut queue)?;
+        queue.save()?;
+        value
+    };
+    emit_job_queue(app, state);
+    pump_job_queue(app, state);
+    Ok(value)
+}
+
+/// Emits `job_cancelled`, the terminal event of a job that was cancelled
+/// while waiting or running. `session_id` is the job's session, when known.
+pub(crate) fn emit_job_cancelled(app: &AppHandle, job_id: &str, session_id: Option<&str>) {
+    let _ = app.emit(
+        "job_cancelled",
+        json!({
+            "jobId": job_id,
+            "sessionId": session_id,
+        }),
+    );
+}
+
+/// Emits `job_queue_changed` with the current queue state.
+pub(crate) fn emit_job_queue(app: &AppHandle, state: &AppState) {
+    let snapshot = state.job_queue.lock().unwrap().snapshot();
+    let _ = app.emit("job_queue_changed", snapshot);
+}
+
+/// Returns the waiting and running jobs and the concurrency limit.
+#[command]
+pub async fn get_job_queue(state: State<'_, AppState>) -> Result<JobQueueSnapshot> {
+    Ok(state.job_queue.lock().unwrap().snapshot())
+}
+
+/// Changes a waiting job's priority; higher runs first.
+#[command]
+pub async fn set_job_priority(
+    app: AppHandle,
+    job_id: String,
+    priority: i32,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    edit_job_queue(&app, &state, |queue| queue.set_priority(&job_id, priority))
+}
+
+/// Reorders the waiting jobs; `job_ids` must list each exactly once. The
+/// order decides between jobs of equal priority.
+#[command]
+pub async fn reorder_job_queue(
+    app: AppHandle,
+    job_ids: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    edit_job_queue(&app, &state, |queue| queue.reorder(&job_ids))
+}
+
+/// Drops a waiting job. Returns `false` when it is no longer waiting (it has
+/// started or finished); use `stop_jobs` to cancel a running one.
+#[command]
+pub async fn remove_queued_job(
+    app: AppHandle,
+    job_id: String,
+    state: State<'_, AppState>,
+) -> Result<bool> {
+    let cancelled = edit_job_queue(&app, &state, |queue| {
+        Ok(queue.cancel_queued(|job| job.job_id == job_id))
+    })?;
+    for job in &cancelled {
+        emit_job_cancelled(&app, &job.job_id, job.protected_session_id());
+    }
+    Ok(!cancelled.is_empty())
+}
+
+/// Sets how many jobs may run at once.
+#[command]
+pub async fn set_job_concurrency(
+    app: AppHandle,
+    max_concurrent: usize,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    edit_job_queue(&app, &state, |queue| {
+        queue.set_max_concurrent(max_concurrent)
+    })
+}
@@ -1,37 +1,30 @@
 //! Session-management commands: creating, listing, inspecting and deleting
-//! sessions, plus enforcing the recent-history cap.
+//! sessions, importing and exporting session documents, comparing two
+//! sessions' clusterings, reading clustering sweep results, editing and
+//! searching session metadata (tags, notes, flags, folders), listing and
+//! discarding interrupted sessions, reading a session's run log and
+//! dropped-font report, plus applying the storage policy to the history.
 //!
 //! Sessions live either as packed documents or as live processing
 //! directories; [`collect_stored_sessions`] unifies both views, de-duplicating
 //! by id so an in-progress session shadows its older packed copy.
 
+use crate::commands::storage::collect_session_garbage;
 use crate::config::{DendrogramData, FontData, ProcessStatus, SessionConfig};
 use crate::core::{
-    is_session_document_path, load_dendrogram, read_session_config_from_dir,
-    read_session_config_from_document, AppState,
+    find_interrupted_sessions, is_session_document_path, list_clustering_sweeps, load_dendrogram,
+    read_clustering_sweep, read_dropped_fonts, read_migration_report, read_run_log,
+    read_session_config_from_dir, read_session_config_from_document, read_storage_policy, AppState,
+    ClusteringSweep, DroppedFont, EmbeddedFontReport, InterruptedSession, MigrationRun,
+    RunLogEntry, SessionComparison,
 };
-use crate::error::Result;
+use crate::error::{AppError, ErrorCode, Result};
+use serde::Deserialize;
 use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::{command, State};
 
-/// How many recent sessions to keep; older ones are pruned on history fetch.
-const SESSION_HISTORY_LIMIT: usize = 20;
-
-/// Where a discovered stored session physically lives, so it can be deleted
-/// with the right filesystem call when pruned.
-enum StoredSessionLocation {
-    Document(PathBuf),
-    Processing(PathBuf),
-}
-
-/// A stored session's config paired with its on-disk location.
-struct StoredSession {
-    session: SessionConfig,
-    location: StoredSessionLocation,
-}
-
 /// Everything the webview needs to display a session in one round-trip.
 #[derive(serde::Serialize)]
 pub struct SessionPayload {
@@ -73,20 +66,21 @@ pub async fn load_session(
     })
 }
 
-/// Returns recent sessions newest first, pruning anything past the history cap
-/// as a side effect.
+/// Returns recent sessions newest first, deleting whatever the
+/// [`StoragePolicy`](crate::core::StoragePolicy) no longer keeps as a side
+/// effect.
 #[command]
 pub async fn get_session_history(state: State<'_, AppState>) -> Result<Vec<SessionConfig>> {
     let mut sessions = collect_stored_sessions()?;
-    sessions.sort_by(|a, b| b.session.modified_at.cmp(&a.session.modified_at));
-    prune_session_history(&mut sessions, &state)?;
-    Ok(sessions.into_iter().map(|stored| stored.session).collect())
+    sessions.sort_by(|a, b| b.modified_at.cmp(&a.modified_at));
+    collect_session_garbage(&mut sessions, &state, &read_storage_policy()?, false)?;
+    Ok(sessions)
 }
 
 /// Gathers sessions from both stored documents and processing directories,
 /// keyed by id so a live processing copy shadows the packed one.
-fn collect_stored_sessions() -> Result<Vec<StoredSession>> {
-    let mut sessions: HashMap<String, StoredSession> = HashMap::new();
+pub(crate) fn collect_stored_sessions() -> Result<Vec<SessionConfig>> {
+    let mut sessions: HashMap<String, SessionConfig> = HashMap::new();
 
     let generated_dir = AppState::get_generated_dir()?;
     if generated_dir.exists() {
@@ -96,13 +90,7 @@ fn collect_stored_sessions() -> Result<Vec<StoredSession>> {
                 continue;
             }
             if let Ok(session) = read_session_config_from_document(&path) {
-                sessions.insert(
-                    session.session_id.clone(),
-                    StoredSession {
-                        session,
-                        location: StoredSessionLocation::Document(path),
-                    },
-                );
+                sessions.insert(session.session_id.clone(), session);
             }
         }
     }
@@ -122,13 +110,7 @@ fn collect_stored_sessions() -> Result<Vec<StoredSession>> {
                 continue;
             }
             if let Ok(session) = read_session_config_from_dir(&path) {
-                sessions.insert(
-                    session.session_id.clone(),
-                    StoredSession {
-                        session,
-                        location: StoredSessionLocation::Processing(path),
-                    },
-                );
+                sessions.insert(session.session_id.clone(), session);
             }
         }
     }
@@ -136,59 +118,168 @@ fn collect_stored_sessions() -> Result<Vec<StoredSession>> {
     Ok(sessions.into_values().collect())
 }
 
-/// Deletes sessions beyond [`SESSION_HISTORY_LIMIT`] from disk and from
-/// `sessions`, expecting the slice to be sorted newest-first.
-///
-/// The active session and any with a running job are always kept, even if they
-/// fall outside the limit.
-fn prune_session_history(sessions: &mut Vec<StoredSession>, state: &AppState) -> Result<()> {
-    if sessions.len() <= SESSION_HISTORY_LIMIT {
-        return Ok(());
-    }
+/// Sets a session's user-given title. An empty `new_title` clears it, letting
+/// the UI fall back to the rendering sample text.
+#[command]
+pub async fn update_session_title(
+    session_id: String,
+    new_title: String,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    state.set_session_title(&session_id, &new_title)
+}
 
-    let current_session_id = state
-        .current_session
-        .lock()
-        .unwrap()
-        .as_ref()
-        .map(|session| session.session_id.clone());
-    let running_session_ids = state
-        .current_job_children
-        .lock()
-        .unwrap()
-        .keys()
-        .cloned()
-        .collect::<HashSet<_>>();
-
-    let mut retained = Vec::with_capacity(SESSION_HISTORY_LIMIT);
-    for (index, stored) in std::mem::take(sessions).into_iter().enumerate() {
-        let session_id = &stored.session.session_id;
-        let is_protected = current_session_id.as_ref() == Some(session_id)
-            || running_session_ids.contains(session_id);
-        if index < SESSION_HISTORY_LIMIT || is_protected {
-            retained.push(stored);
-            continue;
-        }
+/// A partial edit of a session's user metadata; `None` fields are left as
+/// they are.
+#[derive(Debug, Default, Deserialize)]
+pub struct SessionMetadataPatch {
+    title: Option<String>,
+    tags: Option<Vec<String>>,
+    notes: Option<String>,
+    pinned: Option<bool>,
+    favorite: Option<bool>,
+    folder: Option<String>,
+}
 
-        match &stored.location {
-            StoredSessionLocation::Document(path) => fs::remove_file(path)?,
-            StoredSessionLocation::Processing(path) => fs::remove_dir_all(path)?,
+impl SessionMetadataPatch {
+    /// Writes the supplied fields into `session`, normalising tags and the
+    /// folder path.
+    fn apply(&self, session: &mut SessionConfig) {
+        if let Some(title) = &self.title {
+            session.title = title.clone();
+        }
+        if let Some(tags) = &self.tags {
+            session.tags = normalize_tags(tags);
+        }
+        if let Some(notes) = &self.notes {
+            session.notes = notes.clone();
+        }
+        if let Some(pinned) = self.pinned {
+            session.pinned = pinned;
+        }
+        if let Some(favorite) = self.favorite {
+            session.favorite = favorite;
+        }
+        if let Some(folder) = &self.folder {
+            session.folder = normalize_folder(folder);
         }
     }
+}
 
-    *sessions = retained;
-    Ok(())
+/// Trims tags and drops empty and duplicate (case-insensitively) ones,
+/// keeping the first spelling in the given order.
+fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    tags.iter()
+        .map(|tag| tag.trim())
+        .filter(|tag| !tag.is_empty() && seen.insert(tag.to_lowercase()))
+        .map(str::to_string)
+        .collect()
 }
 
-/// Sets a session's user-given title. An empty `new_title` clears it, letting
-/// the UI fall back to the rendering sample text.
+/// Trims each segment of a `/`-separated folder path and drops empty ones, so
+/// `" Fonts//Serif/ "` becomes `"Fonts/Serif"`.
+fn normalize_folder(folder: &str) -> String {
+    folder
+        .split('/')
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Edits a session's user metadata and returns its updated config.
 #[command]
-pub async fn update_session_title(
+pub async fn update_session_metadata(
     session_id: String,
-    new_title: String,
+    patch: SessionMetadataPatch,
     state: State<'_, AppState>,
-) -> Result<()> {
-    state.set_session_title(&session_id, &new_title)
+) -> Result<SessionConfig> {
+    state.update_session_metadata(&session_id, |session| patch.apply(session))
+}
+
+/// Filters for [`search_sessions`]; every supplied filter must match.
+#[derive(Debug, Default, Deserialize)]
+pub struct SessionSearchQuery {
+    /// Whitespace-separated terms, each of which must appear
+    /// (case-insensitively) in the title, sample text, notes, model id, font
+    /// set, folder or a tag.
+    #[serde(default)]
+    text: String,
+    /// Tags the session must all carry (case-insensitive).
+    #[serde(default)]
+    tags: Vec<String>,
+    /// Folder the session must be filed in, directly or in a subfolder.
+    #[serde(default)]
+    folder: Option<String>,
+    #[serde(default)]
+    pinned: Option<bool>,
+    #[serde(default)]
+    favorite: Option<bool>,
+}
+
+impl SessionSearchQuery {
+    fn matches(&self, session: &SessionConfig) -> bool {
+        if self.pinned.is_some_and(|pinned| pinned != session.pinned)
+            || self
+                .favorite
+                .is_some_and(|favorite| favorite != session.favorite)
+        {
+            return false;
+        }
+        if let Some(folder) = &self.folder {
+            let folder = normalize_folder(folder);
+            let is_inside = folder.is_empty()
+                || session.folder == folder
+                || session
+                    .folder
+                    .strip_prefix(&folder)
+                    .is_some_and(|rest| rest.starts_with('/'));
+            if !is_inside {
+                return false;
+            }
+        }
+        let session_tags: Vec<String> = session.tags.iter().map(|tag| tag.to_lowercase()).collect();
+        if !self
+            .tags
+            .iter()
+            .all(|tag| session_tags.contains(&tag.trim().to_lowercase()))
+        {
+            return false;
+        }
+
+        let font_set = serde_json::to_value(&session.algorithm.rendering.font_set)
+            .ok()
+            .and_then(|value| value.as_str().map(|name| name.replace('_', " ")))
+            .unwrap_or_default();
+        let haystack = [
+            session.title.as_str(),
+            session.algorithm.rendering.text.as_str(),
+            session.notes.as_str(),
+            session.algorithm.analysis.model_id.as_str(),
+            font_set.as_str(),
+            session.folder.as_str(),
+        ]
+        .into_iter()
+        .chain(session.tags.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .to_lowercase();
+        self.text
+            .split_whitespace()
+            .all(|term| haystack.contains(&term.to_lowercase()))
+    }
+}
+
+/// Returns the stored sessions matching `query`, newest first.
+#[command]
+pub async fn search_sessions(query: SessionSearchQuery) -> Result<Vec<SessionConfig>> {
+    let mut sessions: Vec<SessionConfig> = collect_stored_sessions()?
+        .into_iter()
+        .filter(|session| query.matches(session))
+        .collect();
+    sessions.sort_by(|a, b| b.modified_at.cmp(&a.modified_at));
+    Ok(sessions)
 }
 
 /// Returns the ids of sessions that currently have a running job.
@@ -198,6 +289,52 @@ pub async fn get_running_session_ids(state: State<'_, AppState>) -> Result<Vec<S
     Ok(running_jobs.keys().cloned().collect())
 }
 
+/// Lists the sessions whose job was killed, cancelled or failed before
+/// clustering completed, so the UI can offer to resume or discard them.
+/// Sessions with a running or queued job are left out.
+#[command]
+pub async fn get_interrupted_sessions(
+    state: State<'_, AppState>,
+) -> Result<Vec<InterruptedSession>> {
+    find_interrupted_sessions(&state.busy_session_ids())
+}
+
+/// Drops an interrupted session's working directory instead of resuming it,
+/// falling back to its last completed document if it has one.
+#[command]
+pub async fn discard_interrupted_session(
+    session_id: String,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    if state
+        .current_job_children
+        .lock()
+        .unwrap()
+        .contains_key(&session_id)
+    {
+        return Err(session_busy(
+            &session_id,
+            "This session has a processing job running",
+        ));
+    }
+    crate::core::discard_interrupted_session(&session_id)
+}
+
+/// Returns every entry of a session's run log, oldest first: stage timings,
+/// dropped fonts and their errors, and the model and thread counts of each
+/// run.
+#[command]
+pub async fn get_run_log(session_id: String) -> Result<Vec<RunLogEntry>> {
+    read_run_log(&AppState::resolve_session_dir(&session_id)?)
+}
+
+/// Lists the fonts the session's pipeline excluded, with the stage that
+/// excluded each and why.
+#[command]
+pub async fn get_dropped_fonts(session_id: String) -> Result<Vec<DroppedFont>> {
+    read_dropped_fonts(&AppState::resolve_session_dir(&session_id)?)
+}
+
 /// Returns the most recently modified completed (`Clustered`) session.
 ///
 /// When there are no sessions at all, seeds the bundled example session and
@@ -209,7 +346,6 @@ pub async fn get_latest_session_id(app: tauri::AppHandle) -> Result<Option<Strin
 
     let latest = sessions
         .into_iter()
-        .map(|stored| stored.session)
         .filter(|session| session.status.process_status == ProcessStatus::Clustered)
         .max_by_key(|session| session.modified_at)
         .map(|session| session.session_id);
@@ -225,23 +361,237 @@ pub async fn get_latest_session_id(app: tauri::AppHandle) -> Result<Option<Strin
 /// Deletes a session's document and working/view directories.
 ///
 /// Returns `true` if a document or processing directory was removed (i.e. the
-/// session actually existed).
-#[command]
-pub async fn delete_session(session_id: String) -> Result<bool> {
-    let mut deleted = false;
-    let document_path = AppState::get_session_document_path(&session_id)?;
-    if document_path.exists() {
-        fs::remove_file(&document_path)?;
-        deleted = true;
+/// session actually existed). Refused while a job is running on, or waiting
+/// for, the session.
+#[command]
+pub async fn delete_session(session_id: String, state: State<'_, AppState>) -> Result<bool> {
+    if state.busy_session_ids().contains(&session_id) {
+        return Err(session_busy(
+            &session_id,
+            "This session has a processing job running or queued; delete it once the job finishes",
+        ));
+    }
+    AppState::delete_session_files(&session_id)
+}
+
+/// Imports a `.fontclusterdoc` from `path` into the session store and returns
+/// its config (with a fresh id if the original one was already taken).
+#[command]
+pub async fn import_session_document(path: String) -> Result<SessionConfig> {
+    AppState::import_session_document(Path::new(&path))
+}
+
+/// Writes session `session_id` as a `.fontclusterdoc` at `path`.
+///
+/// With `portable`, the session's system fonts are embedded as subsets so the
+/// recipient can preview them without having them installed; the returned
+/// report lists which fonts were embedded and which were skipped.
+///
+/// Refused while a job is running on the session, since its processing
+/// directory may be half-written.
+#[command]
+pub async fn export_session_document(
+    session_id: String,
+    path: String,
+    portable: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Option<EmbeddedFontReport>> {
+    if state
+        .current_job_children
+        .lock()
+        .unwrap()
+        .contains_key(&session_id)
+    {
+        return Err(session_busy(
+            &session_id,
+            "This session has a processing job running; export it once the job finishes",
+        ));
+    }
+    let portable = portable.unwrap_or(false);
+    tokio::task::spawn_blocking(move || {
+        AppState::export_session_document(&session_id, Path::new(&path), portable)
+    })
+    .await
+    .map_err(|e| crate::error::AppError::Processing(e.to_string()))?
+}
+
+/// Returns every recorded startup migration run, oldest first: which stored
+/// sessions were upgraded, quarantined or skipped, and why.
+#[command]
+pub async fn get_migration_report() -> Result<Vec<MigrationRun>> {
+    read_migration_report()
+}
+
+/// Compares the clusterings of sessions `session_a` and `session_b` over the
+/// fonts they share.
+#[command]
+pub async fn compare_sessions(session_a: String, session_b: String) -> Result<SessionComparison> {
+    tokio::task::spawn_blocking(move || {
+        crate::core::comparison::compare_sessions(&session_a, &session_b)
+    })
+    .await
+    .map_err(|e| crate::error::AppError::Processing(e.to_string()))?
+}
+
+/// Returns the summary of clustering sweep `sweep_id`.
+#[command]
+pub async fn get_clustering_sweep(sweep_id: String) -> Result<ClusteringSweep> {
+    read_clustering_sweep(&sweep_id)
+}
+
+/// Lists the clustering sweeps run on session `session_id`, newest first.
+#[command]
+pub async fn list_session_sweeps(session_id: String) -> Result<Vec<ClusteringSweep>> {
+    list_clustering_sweeps(&session_id)
+}
+
+/// The error for an operation refused because `session_id` has a job running.
+fn session_busy(session_id: &str, message: &str) -> AppError {
+    AppError::new(ErrorCode::SessionBusy, message).with_session_id(session_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AlgorithmConfig, ProcessingStatus};
+    use chrono::Utc;
+
+    fn session() -> SessionConfig {
+        SessionConfig {
+            app_version: String::new(),
+            modified_app_version: String::new(),
+            schema_version: 1,
+            session_id: "session".to_string(),
+            title: "Humanist sans".to_string(),
+            tags: vec!["Body".to_string(), "Draft".to_string()],
+            notes: "Compare against the display cut".to_string(),
+            pinned: true,
+            favorite: false,
+            folder: "Fonts/Serif".to_string(),
+            created_at: Utc::now(),
+            modified_at: Utc::now(),
+            discovered_fonts: HashMap::new(),
+            algorithm: AlgorithmConfig::default(),
+            status: ProcessingStatus::default(),
+        }
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn normalizes_tags_by_trimming_and_dropping_duplicates() {
+        let tags = strings(&[" Serif ", "serif", "", "  ", "Display", "SERIF", "display "]);
+        assert_eq!(normalize_tags(&tags), strings(&["Serif", "Display"]));
+    }
+
+    #[test]
+    fn normalizes_folder_separators() {
+        assert_eq!(normalize_folder(" Fonts//Serif/ "), "Fonts/Serif");
+        assert_eq!(normalize_folder("/Fonts/ Old Style /"), "Fonts/Old Style");
+        assert_eq!(normalize_folder(" / "), "");
+    }
+
+    #[test]
+    fn patch_leaves_unspecified_fields_unchanged() {
+        let mut patched = session();
+        SessionMetadataPatch::default().apply(&mut patched);
+        let original = session();
+        assert_eq!(patched.title, original.title);
+        assert_eq!(patched.tags, original.tags);
+        assert_eq!(patched.notes, original.notes);
+        assert_eq!(patched.pinned, original.pinned);
+        assert_eq!(patched.favorite, original.favorite);
+        assert_eq!(patched.folder, original.folder);
+
+        let patch = SessionMetadataPatch {
+            tags: Some(strings(&[" Text ", "text"])),
+            favorite: Some(true),
+            folder: Some("Fonts//Sans/".to_string()),
+            ..SessionMetadataPatch::default()
+        };
+        patch.apply(&mut patched);
+        assert_eq!(patched.title, original.title);
+        assert_eq!(patched.notes, original.notes);
+        assert!(patched.pinned);
+        assert_eq!(patched.tags, strings(&["Text"]));
+        assert!(patched.favorite);
+        assert_eq!(patched.folder, "Fonts/Sans");
     }
-    let processing_dir = AppState::get_session_processing_dir(&session_id)?;
-    if processing_dir.exists() {
-        fs::remove_dir_all(&processing_dir)?;
-        deleted = true;
+
+    #[test]
+    fn search_matches_each_filter() {
+        let session = session();
+        let matches = |query: SessionSearchQuery| query.matches(&session);
+
+        assert!(matches(SessionSearchQuery::default()));
+
+        assert!(matches(SessionSearchQuery {
+            pinned: Some(true),
+            ..SessionSearchQuery::default()
+        }));
+        assert!(!matches(SessionSearchQuery {
+            pinned: Some(false),
+            ..SessionSearchQuery::default()
+        }));
+        assert!(!matches(SessionSearchQuery {
+            favorite: Some(true),
+            ..SessionSearchQuery::default()
+        }));
+
+        for folder in ["Fonts", "Fonts/Serif", " Fonts//Serif/ ", ""] {
+            assert!(matches(SessionSearchQuery {
+                folder: Some(folder.to_string()),
+                ..SessionSearchQuery::default()
+            }));
+        }
+        for folder in ["Font", "Fonts/Ser", "Fonts/Serif/Old"] {
+            assert!(!matches(SessionSearchQuery {
+                folder: Some(folder.to_string()),
+                ..SessionSearchQuery::default()
+            }));
+        }
+
+        assert!(matches(SessionSearchQuery {
+            tags: strings(&[" body", "DRAFT"]),
+            ..SessionSearchQuery::default()
+        }));
+        assert!(!matches(SessionSearchQuery {
+            tags: strings(&["body", "final"]),
+            ..SessionSearchQuery::default()
+        }));
+
+        assert!(matches(SessionSearchQuery {
+            text: "HUMANIST display".to_string(),
+            ..SessionSearchQuery::default()
+        }));
+        assert!(matches(SessionSearchQuery {
+            text: "draft serif".to_string(),
+            ..SessionSearchQuery::default()
+        }));
+        assert!(!matches(SessionSearchQuery {
+            text: "humanist grotesque".to_string(),
+            ..SessionSearchQuery::default()
+        }));
     }
-    let current_dir = AppState::get_session_current_dir(&session_id)?;
-    if current_dir.exists() {
-        fs::remove_dir_all(&current_dir)?;
+
+    #[test]
+    fn search_requires_every_filter_to_match() {
+        let session = session();
+        let query = |favorite| SessionSearchQuery {
+            text: "humanist".to_string(),
+            tags: strings(&["body"]),
+            folder: Some("Fonts".to_string()),
+            pinned: Some(true),
+            favorite,
+        };
+        assert!(query(None).matches(&session));
+        assert!(query(Some(false)).matches(&session));
+        assert!(!query(Some(true)).matches(&session));
+
+        let mut query = query(None);
+        query.folder = Some("Fonts/Sans".to_string());
+        assert!(!query.matches(&session));
     }
-    Ok(deleted)
 }
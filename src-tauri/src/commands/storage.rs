@@ -0,0 +1,244 @@
+//! Storage commands: the disk usage report, the session retention policy and
+//! on-demand garbage collection.
+//!
+//! [`collect_session_garbage`] is the single place sessions are deleted by
+//! policy; the history fetch runs it too, so the policy also applies without
+//! an explicit collection.
+
+use crate::commands::session::collect_stored_sessions;
+use crate::config::SessionConfig;
+use crate::core::{
+    read_storage_policy, session_storage_bytes, storage_report, write_storage_policy, AppState,
+    StoragePolicy, StorageReport,
+};
+use crate::error::{AppError, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+use tauri::{command, AppHandle, Manager, State};
+
+/// Outcome of a garbage collection run.
+#[derive(Debug, Clone, Serialize)]
+pub struct GcReport {
+    /// Ids of the sessions deleted (or, on a dry run, that would be).
+    pub deleted: Vec<String>,
+    /// Bytes the deleted sessions occupied.
+    pub freed_bytes: u64,
+    pub dry_run: bool,
+}
+
+/// Measures the disk usage of sessions, models, previews, sweeps and
+/// quarantined sessions.
+#[command]
+pub async fn get_storage_report(app: AppHandle) -> Result<StorageReport> {
+    let preview_cache_dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| AppError::Io(format!("Failed to resolve app cache dir: {e}")))?
+        .join("font-previews");
+    tokio::task::spawn_blocking(move || storage_report(&preview_cache_dir))
+        .await
+        .map_err(|e| AppError::Processing(e.to_string()))?
+}
+
+/// Returns the stored retention policy, or the default one.
+#[command]
+pub async fn get_storage_policy() -> Result<StoragePolicy> {
+    read_storage_policy()
+}
+
+/// Saves the retention policy. It takes effect on the next collection.
+#[command]
+pub async fn set_storage_policy(policy: StoragePolicy) -> Result<()> {
+    write_storage_policy(&policy)
+}
+
+/// Applies the stored retention policy now. A `dry_run` only reports what
+/// would be deleted.
+#[command]
+pub async fn run_storage_gc(dry_run: Option<bool>, state: State<'_, AppState>) -> Result<GcReport> {
+    let dry_run = dry_run.unwrap_or(false);
+    let state = state.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let mut sessions = collect_stored_sessions()?;
+        sessions.sort_by(|a, b| b.modified_at.cmp(&a.modified_at));
+        collect_session_garbage(&mut sessions, &state, &read_storage_policy()?, dry_run)
+    })
+    .await
+    .map_err(|e| AppError::Processing(e.to_string()))?
+}
+
+/// Deletes the sessions `policy` no longer keeps from `sessions` (sorted
+/// newest first) and from disk, leaving the retained ones in place.
+///
+/// Pinned and favourite sessions, the active session and sessions with a
+/// running or waiting job (see [`AppState::busy_session_ids`]) are always
+/// kept and don't count towards `keep_latest`. With
+/// `dry_run` nothing is deleted and `sessions` is left untouched.
+pub(crate) fn collect_session_garbage(
+    sessions: &mut Vec<SessionConfig>,
+    state: &AppState,
+    policy: &StoragePolicy,
+    dry_run: bool,
+) -> Result<GcReport> {
+    let current_session_id = state
+        .current_session
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|session| session.session_id.clone());
+    let mut protected_session_ids = state.busy_session_ids();
+    protected_session_ids.extend(current_session_id);
+    let expired = expired_sessions(sessions, policy, &protected_session_ids, Utc::now());
+
+    let mut report = GcReport {
+        deleted: Vec::new(),
+        freed_bytes: 0,
+        dry_run,
+    };
+    if expired.is_empty() {
+        return Ok(report);
+    }
+
+    for session_id in sessions
+        .iter()
+        .map(|session| &session.session_id)
+        .filter(|session_id| expired.contains(*session_id))
+    {
+        let bytes = session_storage_bytes(session_id)?;
+        if !dry_run && !AppState::delete_session_files(session_id)? {
+            continue;
+        }
+        report.freed_bytes += bytes;
+        report.deleted.push(session_id.clone());
+    }
+    if !dry_run {
+        sessions.retain(|session| !expired.contains(&session.session_id));
+        println!(
+            "🧹 Collected {} session(s), freed {} bytes",
+            report.deleted.len(),
+            report.freed_bytes
+        );
+    }
+    Ok(report)
+}
+
+/// Ids of the sessions in `sessions` (sorted newest first) that `policy` no
+/// longer keeps as of `now`. Pinned, favourite and `protected` sessions are
+/// kept and don't count towards `keep_latest`.
+fn expired_sessions(
+    sessions: &[SessionConfig],
+    policy: &StoragePolicy,
+    protected: &HashSet<String>,
+    now: DateTime<Utc>,
+) -> HashSet<String> {
+    let cutoff = policy
+        .max_age_days
+        .map(|days| now - Duration::days(i64::from(days)));
+    let mut expired = HashSet::new();
+    let mut rank = 0;
+    for session in sessions {
+        if session.pinned || session.favorite || protected.contains(&session.session_id) {
+            continue;
+        }
+        rank += 1;
+        let is_over_count = policy.keep_latest.is_some_and(|keep| rank > keep);
+        let is_too_old = cutoff.is_some_and(|cutoff| session.modified_at < cutoff);
+        if is_over_count || is_too_old {
+            expired.insert(session.session_id.clone());
+        }
+    }
+    expired
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AlgorithmConfig, ProcessingStatus};
+    use std::collections::HashMap;
+
+    /// Sessions `s0`, `s1`, … modified one day apart, newest first.
+    fn sessions(count: usize, now: DateTime<Utc>) -> Vec<SessionConfig> {
+        (0..count)
+            .map(|index| {
+                let modified_at = now - Duration::days(index as i64);
+                SessionConfig {
+                    app_version: String::new(),
+                    modified_app_version: String::new(),
+                    schema_version: 1,
+                    session_id: format!("s{index}"),
+                    title: String::new(),
+                    tags: Vec::new(),
+                    notes: String::new(),
+                    pinned: false,
+                    favorite: false,
+                    folder: String::new(),
+                    created_at: modified_at,
+                    modified_at,
+                    discovered_fonts: HashMap::new(),
+                    algorithm: AlgorithmConfig::default(),
+                    status: ProcessingStatus::default(),
+                }
+            })
+            .collect()
+    }
+
+    fn ids(expired: HashSet<String>) -> Vec<String> {
+        let mut ids: Vec<String> = expired.into_iter().collect();
+        ids.sort();
+        ids
+    }
+
+    fn policy(keep_latest: Option<usize>, max_age_days: Option<u32>) -> StoragePolicy {
+        StoragePolicy {
+            keep_latest,
+            max_age_days,
+        }
+    }
+
+    #[test]
+    fn keeps_the_latest_sessions() {
+        let now = Utc::now();
+        let sessions = sessions(5, now);
+        let expired = expired_sessions(&sessions, &policy(Some(3), None), &HashSet::new(), now);
+        assert_eq!(ids(expired), ["s3", "s4"]);
+
+        let unbounded = expired_sessions(&sessions, &policy(None, None), &HashSet::new(), now);
+        assert!(unbounded.is_empty());
+    }
+
+    #[test]
+    fn deletes_sessions_older_than_max_age() {
+        let now = Utc::now();
+        let sessions = sessions(5, now);
+        let expired = expired_sessions(&sessions, &policy(None, Some(2)), &HashSet::new(), now);
+        assert_eq!(ids(expired), ["s3", "s4"]);
+
+        // Either limit is enough to delete a session.
+        let expired = expired_sessions(&sessions, &policy(Some(4), Some(3)), &HashSet::new(), now);
+        assert_eq!(ids(expired), ["s4"]);
+    }
+
+    #[test]
+    fn flagged_sessions_are_kept_and_not_counted() {
+        let now = Utc::now();
+        let mut sessions = sessions(5, now);
+        sessions[0].pinned = true;
+        sessions[4].favorite = true;
+        let expired = expired_sessions(&sessions, &policy(Some(2), None), &HashSet::new(), now);
+        assert_eq!(ids(expired), ["s3"]);
+    }
+
+    #[test]
+    fn active_and_running_sessions_are_kept_and_not_counted() {
+        let now = Utc::now();
+        let sessions = sessions(5, now);
+        let protected = HashSet::from(["s0".to_string(), "s4".to_string()]);
+        let expired = expired_sessions(&sessions, &policy(Some(2), None), &protected, now);
+        // s0 doesn't use up a slot, so s1 and s2 are the two kept.
+        assert_eq!(ids(expired), ["s3"]);
+
+        let expired = expired_sessions(&sessions, &policy(None, Some(1)), &protected, now);
+        assert_eq!(ids(expired), ["s2", "s3"]);
+    }
+}
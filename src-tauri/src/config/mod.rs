@@ -23,17 +23,39 @@ pub const DEFAULT_FONT_SIZE: f32 = 224.0;
 ///
 /// This is the source of truth saved as `config.json`; `app_version` records
 /// the version that created the session while `modified_app_version` tracks
-/// the version of the last write, which together drive migration pruning.
+/// the version of the last write. `schema_version` identifies the on-disk
+/// layout that [`crate::core::migrations`] upgrades from.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionConfig {
     pub app_version: String,
     pub modified_app_version: String,
+    /// On-disk layout version. `0` when read from a session that predates
+    /// the field; startup migration stamps every stored session.
+    #[serde(default)]
+    pub schema_version: u32,
     pub session_id: String,
     /// User-given display name; empty means "untitled" and the UI falls back
     /// to the rendering sample text. Defaults for sessions written before the
     /// field existed.
     #[serde(default)]
     pub title: String,
+    /// User-given labels for filtering the history, trimmed and unique.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Free-text notes about the run.
+    #[serde(default)]
+    pub notes: String,
+    /// Pinned sessions are listed first in the history and are never pruned
+    /// from it.
+    #[serde(default)]
+    pub pinned: bool,
+    /// Marked as a favourite; like pinned sessions, never pruned.
+    #[serde(default)]
+    pub favorite: bool,
+    /// `/`-separated folder path the session is filed under; empty means the
+    /// top level.
+    #[serde(default)]
+    pub folder: String,
     pub created_at: DateTime<Utc>,
     pub modified_at: DateTime<Utc>,
     pub discovered_fonts: HashMap<i32, Vec<String>>,
@@ -81,6 +103,25 @@ pub struct RenderingConfig {
     pub font_set: FontSet,
     /// Rendering size in pixels.
     pub font_size: f32,
+    /// Writing direction of the sample text. Defaulted so sessions saved
+    /// before direction support keep rendering left-to-right (`Auto` resolves
+    /// to that for Latin text).
+    #[serde(default)]
+    pub direction: TextDirection,
+    /// OpenType feature settings applied when shaping, e.g. `smcp`, `-liga`
+    /// or `salt=2` (see [`crate::rendering::parse_feature_setting`]). Empty
+    /// for older sessions, which leaves swash's default features in place.
+    #[serde(default)]
+    pub features: Vec<String>,
+    /// BCP-47 language tag (e.g. `tr`, `zh-Hant`) selecting the face's
+    /// language system so `locl` variants apply. `None` shapes with the
+    /// default language system.
+    #[serde(default)]
+    pub language: Option<String>,
+    /// How the rendered glyphs are framed. Older sessions were always
+    /// `Cropped`.
+    #[serde(default)]
+    pub canvas: SampleCanvas,
 }
 
 impl Default for RenderingConfig {
@@ -90,10 +131,48 @@ impl Default for RenderingConfig {
             weights: vec![400],
             font_set: FontSet::default(),
             font_size: DEFAULT_FONT_SIZE,
+            direction: TextDirection::default(),
+            features: Vec::new(),
+            language: None,
+            canvas: SampleCanvas::default(),
         }
     }
 }
 
+/// Writing direction used to lay out sample text.
+///
+/// `Auto` picks the paragraph direction from the first strong character and
+/// reorders mixed left-to-right/right-to-left runs; the explicit horizontal
+/// variants only fix the paragraph direction. `TopToBottom` sets the text in
+/// vertical columns using the face's vertical metrics and `vert`/`vrt2`
+/// alternates.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum TextDirection {
+    #[default]
+    Auto,
+    LeftToRight,
+    RightToLeft,
+    TopToBottom,
+}
+
+/// Framing of the rendered sample image.
+///
+/// `Cropped` trims the image to the ink, so the analyzer's resize gives each
+/// font a different effective scale. `Normalized` renders onto a fixed
+/// `font_size`-pixel square, scaling each face so its OS/2 cap height covers
+/// the same fraction of the canvas and setting every face on the same
+/// baseline. Text that overflows the canvas is clipped, which the run log
+/// records per font
+/// ([`RunLogEvent::SampleClipped`](crate::core::run_log::RunLogEvent)).
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SampleCanvas {
+    #[default]
+    Cropped,
+    Normalized,
+}
+
 /// Corpus of fonts a session draws from.
 ///
 /// `SystemFonts` enumerates fonts installed on the machine; the `GoogleFonts*`
@@ -162,6 +241,26 @@ pub struct ClusteringConfig {
     /// beside the model.
     #[serde(default)]
     pub emphasis: BTreeMap<String, i8>,
+    /// Per-metric weights for typographic axes appended to the clustering
+    /// features (e.g. `{"width": 1.0}` to group "same vibe but narrower").
+    /// Each active metric is standardised across the session's fonts and
+    /// scaled by `reference * weight`, with `reference` the same typical
+    /// feature-axis spread the attribute emphasis uses (see
+    /// [`crate::core::clusterer`]). A weight of `0` or a missing key leaves
+    /// the metric out. Empty by default so older sessions load unchanged.
+    #[serde(default)]
+    pub metric_weights: BTreeMap<FontMetricKey, f32>,
+}
+
+impl ClusteringConfig {
+    /// Whether building this config's features reads the model's
+    /// `attribute_directions.json`: attribute emphasis is enabled, applies
+    /// (PCA preprocessing is on) and has at least one nonzero level.
+    pub fn uses_attribute_directions(&self) -> bool {
+        self.enable_preprocess_pca
+            && self.enable_attribute_emphasis
+            && self.emphasis.values().any(|&level| level != 0)
+    }
 }
 
 /// Serde fallback for [`ClusteringConfig::enable_preprocess_pca`]: sessions
@@ -202,6 +301,7 @@ impl Default for ClusteringConfig {
             target_cluster_count: 0,
             enable_attribute_emphasis: false,
             emphasis: BTreeMap::new(),
+            metric_weights: BTreeMap::new(),
         }
     }
 }
@@ -235,6 +335,9 @@ pub struct ProcessingStatus {
     #[serde(default)]
     pub clustering_stats: ClusteringStats,
     pub progress: ProcessingProgress,
+    /// True while the session's running job is paused (see `pause_jobs`).
+    #[serde(default)]
+    pub paused: bool,
 }
 
 /// By-product statistics of a single clustering run, persisted alongside the
@@ -424,6 +527,67 @@ pub struct FontMetadata {
     pub weight: i32,
     pub weights: Vec<String>,
     pub font_index: u32,
+    /// Measured geometry of the face. Empty for fonts discovered before
+    /// metrics were extracted.
+    #[serde(default)]
+    pub metrics: FontMetrics,
+}
+
+/// Typographic measurements of a font face.
+///
+/// Everything except `stroke_contrast` is read from the font tables during
+/// discovery; ratios are relative to the em so faces of different
+/// `unitsPerEm` compare directly. `stroke_contrast` is estimated from the
+/// rendered sample and so only exists once the rendering stage has run.
+/// Fields a face doesn't declare are `None`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct FontMetrics {
+    /// OS/2 `sxHeight` / `unitsPerEm`.
+    pub x_height_ratio: Option<f32>,
+    /// OS/2 `sCapHeight` / `unitsPerEm`.
+    pub cap_height_ratio: Option<f32>,
+    /// OS/2 `xAvgCharWidth` / `unitsPerEm`; a proxy for the face's width.
+    pub average_advance_ratio: Option<f32>,
+    /// `post` italic angle in degrees counter-clockwise from vertical, so
+    /// right-leaning italics are negative.
+    pub italic_angle: Option<f32>,
+    /// OS/2 `usWidthClass`, 1 (ultra-condensed) to 9 (ultra-expanded).
+    pub width_class: Option<u16>,
+    /// OS/2 `sFamilyClass` as `[class, subclass]` (IBM font classification).
+    pub family_class: Option<[u8; 2]>,
+    /// OS/2 PANOSE classification digits.
+    pub panose: Option<[u8; 10]>,
+    /// Ratio of the thickest to the thinnest strokes in the rendered sample
+    /// (~1 for monoline designs, higher for high-contrast serifs).
+    pub stroke_contrast: Option<f32>,
+}
+
+/// A scalar font measurement usable as a sort/filter key or as an extra
+/// clustering axis.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum FontMetricKey {
+    XHeight,
+    CapHeight,
+    Width,
+    Weight,
+    Contrast,
+    ItalicAngle,
+}
+
+impl FontMetricKey {
+    /// Reads this metric from `meta`, or `None` when the face lacks it.
+    pub fn value(self, meta: &FontMetadata) -> Option<f32> {
+        let metrics = &meta.metrics;
+        match self {
+            Self::XHeight => metrics.x_height_ratio,
+            Self::CapHeight => metrics.cap_height_ratio,
+            Self::Width => metrics.average_advance_ratio,
+            Self::Weight => Some(meta.weight as f32),
+            Self::Contrast => metrics.stroke_contrast,
+            Self::ItalicAngle => metrics.italic_angle,
+        }
+    }
 }
 
 /// Where a font face originated, which decides how it is re-loaded for
@@ -496,5 +660,9 @@ impl FontMetadata {
 pub struct RenderConfig {
     pub text: String,
     pub font_size: f32,
+    pub direction: TextDirection,
+    pub features: Vec<String>,
+    pub language: Option<String>,
+    pub canvas: SampleCanvas,
     pub output_dir: PathBuf,
 }
@@ -2,14 +2,28 @@
 //! embedding vector with an ONNX vision model.
 //!
 //! A single [`Analyzer`] owns one ONNX [`Session`] guarded by a
-//! mutex. Images are preprocessed in parallel with [`rayon`], run through the
-//! model in fixed-size batches, and the resulting embedding for each image is
+//! mutex. Images are preprocessed in parallel with [`rayon`] into the input the
+//! bundle's manifest declares ([`ModelInputSpec`]), run through the model in
+//! fixed-size batches, and the declared embedding output for each image is
 //! written next to it as `vector.bin`. ONNX Runtime's default CPU execution
-//! provider is used on every platform.
+//! provider is used on every platform, with the intra-op threads of the job's
+//! [`ResourceProfile`]; while the worker's memory keeps growing past the
+//! profile's ceiling the batch size is halved, down to a single image. A resumed analysis keeps
+//! the vectors an interrupted run already finished (see
+//! [`crate::core::resume`]). Images that fail are left out of the stage and
+//! recorded in the session's [`crate::core::dropped_fonts`] report.
 
 use crate::commands::progress::progress_events;
 use crate::config::ProgressStage;
-use crate::core::{AppState, EventSink, ModelBundle};
+use crate::core::dropped_fonts::{record_dropped_fonts, reset_dropped_fonts, DroppedFont};
+use crate::core::resources::{
+    memory_limit_error, resident_memory_bytes, BatchSizeGovernor, ResourceProfile,
+};
+use crate::core::resume::{clear_font_markers, is_font_complete, mark_font_complete, FontStage};
+use crate::core::{
+    AppState, CancellationToken, EventSink, ModelBundle, ModelInputSpec, ModelOutputSpec,
+    RunLogEvent, RunStage,
+};
 use crate::error::{AppError, Result};
 use bytemuck;
 use image::imageops::{replace, FilterType};
@@ -22,21 +36,24 @@ use ort::{
 use rayon::prelude::*;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::{atomic::Ordering, Mutex};
+use std::sync::Mutex;
 
 const MODEL_FILE_NAME: &str = "model.onnx";
-const DEFAULT_INPUT_SIZE: u32 = 224;
-const MODEL_BATCH_DIMENSION_NAME: &str = "batch_size";
-const MODEL_BATCH_SIZE: usize = 8;
-const MODEL_OUTPUT_DIMENSIONS: usize = 512;
-const PREFERRED_EMBEDDING_OUTPUT_NAME: &str = "embedding";
+/// Analysed images between progress updates.
+const PROGRESS_FLUSH_IMAGES: usize = 32;
 
-/// Owns the loaded ONNX model and the preprocessing spec it expects.
+/// Owns the loaded ONNX model and the input and output its manifest declares.
 pub struct Analyzer {
     /// The ONNX session. Behind a mutex because [`Session::run`] needs `&mut`
     /// while [`Analyzer`] is shared across the batch loop.
     session: Mutex<Session>,
-    spec: ModelSpec,
+    input: ModelInputSpec,
+    output: ModelOutputSpec,
+    /// Images per batch the session was first built for.
+    batch_size: usize,
+    /// Kept to rebuild the session with a smaller batch.
+    model_path: PathBuf,
+    resource_profile: ResourceProfile,
 }
 
 /// A preprocessed image tensor together with the path it was loaded from, so
@@ -50,7 +67,8 @@ struct PreparedImage {
 /// plus how many were dropped, so progress totals can be adjusted.
 struct BatchResult {
     prepared_images: Vec<PreparedImage>,
-    failed_count: usize,
+    /// Images that failed preprocessing, with the error that dropped them.
+    failures: Vec<(PathBuf, AppError)>,
 }
 
 impl Analyzer {
@@ -59,37 +77,83 @@ impl Analyzer {
     /// Bundle resolution, download, and checksum verification belong to the
     /// job preparation stage. Accepting [`ModelBundle`] here keeps inference
     /// focused on model execution and prevents a second full-file checksum of
-    /// large ONNX assets when analysis begins.
-    pub fn new(model: &ModelBundle) -> Result<Self> {
+    /// large ONNX assets when analysis begins. The session runs with the
+    /// ONNX threads of `resource_profile`, whose memory ceiling analysis
+    /// enforces, and its batch size, falling back to the manifest's.
+    pub fn new(model: &ModelBundle, resource_profile: &ResourceProfile) -> Result<Self> {
         let model_path = model.directory.join(MODEL_FILE_NAME);
+        let input = model.manifest.input.clone();
+        let batch_size = resource_profile.batch_size.unwrap_or(input.batch_size);
+        let session = load_session(
+            &model_path,
+            resource_profile.effective_onnx_threads(),
+            &input.batch_dimension,
+            batch_size,
+        )?;
 
         Ok(Self {
-            session: Mutex::new(load_session(&model_path)?),
-            spec: ModelSpec {
-                input_size: DEFAULT_INPUT_SIZE,
-                output_dimensions: MODEL_OUTPUT_DIMENSIONS,
-            },
+            session: Mutex::new(session),
+            input,
+            output: model.manifest.output.clone(),
+            batch_size,
+            model_path,
+            resource_profile: resource_profile.clone(),
         })
     }
 
     /// Embeds every sample image in the active session.
     ///
-    /// Processes images in batches of [`MODEL_BATCH_SIZE`], reporting progress
+    /// Processes images in batches of the configured size, reporting progress
     /// through `events`/`state` and writing each embedding to `vector.bin`.
+    /// Whenever the worker's resident memory exceeds the resource profile's
+    /// ceiling after a batch and has grown since the last reduction (see
+    /// [`BatchSizeGovernor`]), later batches are half as large; once a single
+    /// image still outgrows it, analysis fails with an
+    /// [`ErrorCode::ResourceLimit`](crate::error::ErrorCode::ResourceLimit)
+    /// error, keeping the vectors already written for a resume.
     /// Images that fail to decode or infer are dropped from the denominator
-    /// rather than failing the whole run. Returns early (leaving status
-    /// unchanged) if the job is cancelled mid-way.
-    pub async fn analyze_all(&self, events: &impl EventSink, state: &AppState) -> Result<()> {
+    /// and recorded in the dropped-font report rather than failing the whole
+    /// run. With `resume`, fonts an interrupted
+    /// run already marked analysed keep their vectors and are not re-embedded.
+    /// Each batch waits while `cancel` is paused; fails with a cancelled error
+    /// (leaving status unchanged) once it is cancelled between batches.
+    pub async fn analyze_all(
+        &self,
+        events: &impl EventSink,
+        state: &AppState,
+        resume: bool,
+        cancel: &CancellationToken,
+    ) -> Result<()> {
         let session_dir = state.get_session_dir()?;
+        reset_dropped_fonts(&session_dir, RunStage::Analysis)?;
         let samples_dir = session_dir.join("samples");
-        let png_files = collect_sample_paths(session_dir).await?;
+        let mut png_files = collect_sample_paths(session_dir.clone()).await?;
 
         println!("🔍 Analyzer: Found {} images to process", png_files.len());
-        // A re-analysis may switch to another 512-dimensional embedding
-        // space. Remove every previous vector first so a failed batch cannot
-        // silently leave a mixture of old and new model outputs.
+        let is_resumed = |path: &PathBuf| {
+            resume
+                && sample_safe_name(path).is_some_and(|safe_name| {
+                    is_font_complete(&session_dir, &safe_name, FontStage::Analyzed)
+                })
+        };
+        let total_count = png_files.len();
+        png_files.retain(|path| !is_resumed(path));
+        let resumed_count = total_count - png_files.len();
+        if resumed_count > 0 {
+            println!("⏩ Resuming: {} images already analysed", resumed_count);
+        } else {
+            clear_font_markers(&session_dir, FontStage::Analyzed)?;
+        }
+        // A re-analysis may switch to another model's embedding space, of the
+        // same or another dimensionality. Remove every previous vector not
+        // kept for a resume first so a failed batch cannot silently leave a
+        // mixture of old and new model outputs.
         for entry in fs::read_dir(samples_dir)? {
-            let vector_path = entry?.path().join("vector.bin");
+            let font_dir = entry?.path();
+            if is_resumed(&font_dir.join("sample.png")) {
+                continue;
+            }
+            let vector_path = font_dir.join("vector.bin");
             if vector_path.exists() {
                 fs::remove_file(&vector_path).map_err(|error| {
                     AppError::Io(format!(
@@ -100,7 +164,7 @@ impl Analyzer {
             }
         }
 
-        if png_files.is_empty() {
+        if total_count == 0 {
             return Err(AppError::Processing(
                 "Analysis produced no embeddings because no sample images were rendered".into(),
             ));
@@ -111,19 +175,32 @@ impl Analyzer {
             events,
             state,
             ProgressStage::Analysis,
-            png_files.len() as i32,
+            total_count as i32,
         );
+        if resumed_count > 0 {
+            progress_events::increase_numerator(
+                events,
+                state,
+                ProgressStage::Analysis,
+                resumed_count as i32,
+            );
+        }
 
         println!(
             "🚀 Analyzer: running ONNX inference with batch size {}",
-            MODEL_BATCH_SIZE
+            self.batch_size
         );
 
         let mut pending_progress = 0;
-        let mut processed_total = 0;
+        let mut processed_total = resumed_count;
         let mut first_inference_error = None;
-        for chunk in png_files.chunks(MODEL_BATCH_SIZE) {
-            if state.is_cancelled.load(Ordering::Relaxed) {
+        let mut batch_size = self.batch_size;
+        let mut governor = BatchSizeGovernor::new(&self.resource_profile);
+        let mut next_index = 0;
+        while next_index < png_files.len() {
+            let chunk = &png_files[next_index..(next_index + batch_size).min(png_files.len())];
+            next_index += chunk.len();
+            if cancel.checkpoint().is_err() {
                 if pending_progress > 0 {
                     progress_events::increase_numerator(
                         events,
@@ -132,19 +209,25 @@ impl Analyzer {
                         pending_progress as i32,
                     );
                 }
-                return Ok(());
+                return cancel.check();
             }
 
-            let batch = prepare_batch(chunk, &self.spec);
-            if batch.failed_count > 0 {
+            let batch = prepare_batch(chunk, &self.input);
+            if !batch.failures.is_empty() {
+                let dropped = batch
+                    .failures
+                    .iter()
+                    .map(|(path, reason)| drop_font(state, &session_dir, path, reason))
+                    .collect();
+                record_dropped_fonts(&session_dir, dropped)?;
                 progress_events::decrease_denominator(
                     events,
                     state,
                     ProgressStage::Analysis,
-                    batch.failed_count as i32,
+                    batch.failures.len() as i32,
                 );
             }
-            if state.is_cancelled.load(Ordering::Relaxed) {
+            if cancel.is_cancelled() {
                 if pending_progress > 0 {
                     progress_events::increase_numerator(
                         events,
@@ -153,19 +236,23 @@ impl Analyzer {
                         pending_progress as i32,
                     );
                 }
-                return Ok(());
+                return cancel.check();
             }
 
             if batch.prepared_images.is_empty() {
                 continue;
             }
 
-            let prepared_count = batch.prepared_images.len();
-            match self.process_prepared_images(batch.prepared_images) {
+            let prepared_paths: Vec<PathBuf> = batch
+                .prepared_images
+                .iter()
+                .map(|prepared| prepared.path.clone())
+                .collect();
+            match self.process_prepared_images(&session_dir, batch.prepared_images, batch_size) {
                 Ok(processed_count) => {
                     processed_total += processed_count;
                     pending_progress += processed_count;
-                    if pending_progress >= MODEL_BATCH_SIZE * 4 {
+                    if pending_progress >= PROGRESS_FLUSH_IMAGES {
                         progress_events::increase_numerator(
                             events,
                             state,
@@ -177,6 +264,11 @@ impl Analyzer {
                 }
                 Err(e) => {
                     println!("❌ Analysis failed for batch: {}", e);
+                    let dropped = prepared_paths
+                        .iter()
+                        .map(|path| drop_font(state, &session_dir, path, &e))
+                        .collect();
+                    record_dropped_fonts(&session_dir, dropped)?;
                     if first_inference_error.is_none() {
                         first_inference_error = Some(e.to_string());
                     }
@@ -184,10 +276,23 @@ impl Analyzer {
                         events,
                         state,
                         ProgressStage::Analysis,
-                        prepared_count as i32,
+                        prepared_paths.len() as i32,
                     );
                 }
             }
+
+            if next_index < png_files.len() && governor.should_reduce(resident_memory_bytes()) {
+                if pending_progress > 0 {
+                    progress_events::increase_numerator(
+                        events,
+                        state,
+                        ProgressStage::Analysis,
+                        pending_progress as i32,
+                    );
+                    pending_progress = 0;
+                }
+                batch_size = self.reduce_batch_size(batch_size)?;
+            }
         }
 
         if pending_progress > 0 {
@@ -199,9 +304,7 @@ impl Analyzer {
             );
         }
 
-        if state.is_cancelled.load(Ordering::Relaxed) {
-            return Ok(());
-        }
+        cancel.check()?;
         if processed_total == 0 {
             return Err(AppError::Processing(format!(
                 "Analysis produced no embeddings{}",
@@ -211,23 +314,69 @@ impl Analyzer {
             )));
         }
 
+        clear_font_markers(&session_dir, FontStage::Analyzed)?;
         state.update_status(|s| s.process_status = crate::config::ProcessStatus::Analyzed)?;
         Ok(())
     }
 
-    /// Runs inference for one prepared batch and persists every embedding,
-    /// returning how many images were written.
-    fn process_prepared_images(&self, prepared_images: Vec<PreparedImage>) -> Result<usize> {
+    /// Rebuilds the ONNX session for half of `batch_size` images and returns
+    /// the new size, or fails once a single image still outgrows the ceiling.
+    fn reduce_batch_size(&self, batch_size: usize) -> Result<usize> {
+        if batch_size == 1 {
+            return Err(memory_limit_error(
+                &self.resource_profile,
+                "Analysing a single image",
+            ));
+        }
+        let batch_size = batch_size / 2;
+        println!(
+            "⚠️ Analyzer: over the {} MiB memory ceiling; reducing the batch size to {}",
+            self.resource_profile.memory_ceiling_mb.unwrap_or_default(),
+            batch_size
+        );
+        let mut session = self
+            .session
+            .lock()
+            .expect("ONNX session mutex should not be poisoned");
+        *session = load_session(
+            &self.model_path,
+            self.resource_profile.effective_onnx_threads(),
+            &self.input.batch_dimension,
+            batch_size,
+        )?;
+        Ok(batch_size)
+    }
+
+    /// Runs inference for one prepared batch of a session built for
+    /// `batch_size` images, persists every embedding and marks its font
+    /// analysed, returning how many images were written.
+    fn process_prepared_images(
+        &self,
+        session_dir: &Path,
+        prepared_images: Vec<PreparedImage>,
+        batch_size: usize,
+    ) -> Result<usize> {
         let prepared_count = prepared_images.len();
-        let features = self.run_batch_inference(&prepared_images)?;
+        let safe_names: Vec<String> = prepared_images
+            .iter()
+            .filter_map(|prepared| sample_safe_name(&prepared.path))
+            .collect();
+        let features = self.run_batch_inference(&prepared_images, batch_size)?;
         write_feature_vectors(prepared_images, features)?;
+        for safe_name in safe_names {
+            mark_font_complete(session_dir, &safe_name, FontStage::Analyzed)?;
+        }
         Ok(prepared_count)
     }
 
-    /// Packs the batch into a single tensor, runs the model, and returns the
-    /// embedding vector for each input.
-    fn run_batch_inference(&self, prepared_images: &[PreparedImage]) -> Result<Vec<Vec<f32>>> {
-        let tensor = tensor_from_inputs(prepared_images)?;
+    /// Packs the batch into a single tensor of `batch_size` rows, runs the
+    /// model, and returns the embedding vector for each input.
+    fn run_batch_inference(
+        &self,
+        prepared_images: &[PreparedImage],
+        batch_size: usize,
+    ) -> Result<Vec<Vec<f32>>> {
+        let tensor = tensor_from_inputs(prepared_images, batch_size)?;
         let mut session = self
             .session
             .lock()
@@ -236,12 +385,18 @@ impl Analyzer {
             .run(inputs![tensor])
             .map_err(|err| AppError::Processing(err.to_string()))?;
 
-        extract_embeddings(&outputs, prepared_images.len(), self.spec.output_dimensions)
+        extract_embeddings(&outputs, &self.output, prepared_images.len())
     }
 }
 
-/// Builds and commits an ONNX session for the model at `model_path`.
-fn load_session(model_path: &Path) -> Result<Session> {
+/// Builds and commits an ONNX session for the model at `model_path`, running
+/// on `intra_threads` threads with its `batch_dimension` fixed to `batch_size`.
+fn load_session(
+    model_path: &Path,
+    intra_threads: usize,
+    batch_dimension: &str,
+    batch_size: usize,
+) -> Result<Session> {
     println!(
         "🚀 Analyzer: loading ONNX model from {}",
         model_path.display()
@@ -252,10 +407,10 @@ fn load_session(model_path: &Path) -> Result<Session> {
         .with_optimization_level(GraphOptimizationLevel::Level3)
         .map_err(|err| AppError::Processing(err.to_string()))?;
     builder = builder
-        .with_intra_threads(std::thread::available_parallelism().map_or(1, |threads| threads.get()))
+        .with_intra_threads(intra_threads)
         .map_err(|err| AppError::Processing(err.to_string()))?;
     builder = builder
-        .with_dimension_override(MODEL_BATCH_DIMENSION_NAME, MODEL_BATCH_SIZE as i64)
+        .with_dimension_override(batch_dimension, batch_size as i64)
         .map_err(|err| AppError::Processing(err.to_string()))?;
 
     let session = builder
@@ -302,35 +457,57 @@ async fn collect_sample_paths(session_dir: PathBuf) -> Result<Vec<PathBuf>> {
     })
 }
 
-/// Preprocesses a chunk of images in parallel, logging and counting any that
-/// fail rather than aborting the batch.
-fn prepare_batch(paths: &[PathBuf], spec: &ModelSpec) -> BatchResult {
+/// The `safe_name` of the font a `samples/<safe_name>/sample.png` path belongs
+/// to.
+fn sample_safe_name(path: &Path) -> Option<String> {
+    Some(path.parent()?.file_name()?.to_str()?.to_string())
+}
+
+/// Records in the run log that analysis dropped the font of `sample_path`,
+/// returning its dropped-font report entry.
+fn drop_font(
+    state: &AppState,
+    session_dir: &Path,
+    sample_path: &Path,
+    reason: &AppError,
+) -> DroppedFont {
+    let safe_name = sample_safe_name(sample_path).unwrap_or_default();
+    state.log_run_event(RunLogEvent::FontFailed {
+        stage: RunStage::Analysis,
+        safe_name: safe_name.clone(),
+        reason: reason.clone(),
+    });
+    DroppedFont::kept_font(session_dir, RunStage::Analysis, &safe_name, reason.clone())
+}
+
+/// Preprocesses a chunk of images in parallel, logging and collecting any
+/// that fail rather than aborting the batch.
+fn prepare_batch(paths: &[PathBuf], spec: &ModelInputSpec) -> BatchResult {
     let mut prepared_images = Vec::new();
-    let mut failed_count = 0;
+    let mut failures = Vec::new();
 
     for result in preprocess_images(paths, spec) {
         match result {
             Ok(prepared) => prepared_images.push(prepared),
             Err((path, e)) => {
-                failed_count += 1;
                 println!("❌ Analysis failed for {:?}: {}", path, e);
+                failures.push((path, e));
             }
         }
     }
 
     BatchResult {
         prepared_images,
-        failed_count,
+        failures,
     }
 }
 
-/// Stacks per-image `[1, C, H, W]` tensors into one `[MODEL_BATCH_SIZE, …]`
-/// tensor.
+/// Stacks per-image `[1, C, H, W]` tensors into one `[batch_size, …]` tensor.
 ///
 /// All inputs must share the leading image's shape. The batch dimension is
-/// always [`MODEL_BATCH_SIZE`] (matching the dimension override applied when
-/// the session was built); a short final batch leaves the unused rows zeroed.
-fn tensor_from_inputs(prepared_images: &[PreparedImage]) -> Result<Tensor<f32>> {
+/// always `batch_size` (matching the dimension override applied when the
+/// session was built); a short final batch leaves the unused rows zeroed.
+fn tensor_from_inputs(prepared_images: &[PreparedImage], batch_size: usize) -> Result<Tensor<f32>> {
     let first_input = prepared_images
         .first()
         .ok_or_else(|| AppError::Processing("Cannot run inference with an empty batch".into()))?;
@@ -342,7 +519,14 @@ fn tensor_from_inputs(prepared_images: &[PreparedImage]) -> Result<Tensor<f32>>
         )));
     }
 
-    let mut input = Array4::<f32>::zeros((MODEL_BATCH_SIZE, shape[1], shape[2], shape[3]));
+    if prepared_images.len() > batch_size {
+        return Err(AppError::Processing(format!(
+            "Batch of {} images exceeds the session's batch size {batch_size}",
+            prepared_images.len()
+        )));
+    }
+
+    let mut input = Array4::<f32>::zeros((batch_size, shape[1], shape[2], shape[3]));
     for (batch_index, prepared) in prepared_images.iter().enumerate() {
         if prepared.input.shape() != shape {
             return Err(AppError::Processing(format!(
@@ -403,7 +587,7 @@ fn write_feature_vector(path: PathBuf, feature: &[f32]) -> Result<()> {
 /// Preprocesses `paths` in parallel, pairing each failure with its path.
 fn preprocess_images(
     paths: &[PathBuf],
-    spec: &ModelSpec,
+    spec: &ModelInputSpec,
 ) -> Vec<std::result::Result<PreparedImage, (PathBuf, AppError)>> {
     paths
         .par_iter()
@@ -418,22 +602,15 @@ fn preprocess_images(
         .collect()
 }
 
-/// The preprocessing the model expects: square grayscale input size.
-#[derive(Debug, Clone)]
-struct ModelSpec {
-    input_size: u32,
-    output_dimensions: usize,
-}
-
 /// Loads and preprocesses one image into the model's NCHW input tensor.
 ///
 /// The renderer writes a white coverage mask in the PNG alpha channel. This
-/// converts that mask to black ink on a white background, producing the
-/// `[0, 1]` grayscale input expected by the model API.
-fn preprocess_image(path: &Path, spec: &ModelSpec) -> Result<Array4<f32>> {
+/// converts that mask to black ink on a white background, which is scaled to
+/// `[0, 1]` and normalised into every channel `spec` declares.
+fn preprocess_image(path: &Path, spec: &ModelInputSpec) -> Result<Array4<f32>> {
     let image = image::open(path)
         .map_err(|e| AppError::Image(format!("Failed to open image {}: {}", path.display(), e)))?;
-    let resized = image.resize(spec.input_size, spec.input_size, FilterType::CatmullRom);
+    let resized = image.resize(spec.size, spec.size, FilterType::CatmullRom);
     let (width, height, gray_pixels) = match resized {
         image::DynamicImage::ImageLumaA8(la8) => {
             let pixels = la8
@@ -456,21 +633,21 @@ fn preprocess_image(path: &Path, spec: &ModelSpec) -> Result<Array4<f32>> {
     let gray = image::GrayImage::from_raw(width, height, gray_pixels)
         .expect("Mask conversion should preserve pixel count");
 
-    let processed = center_in_square(&gray, spec.input_size);
+    let processed = center_in_square(&gray, spec.size);
 
-    if processed.width() != spec.input_size || processed.height() != spec.input_size {
+    if processed.width() != spec.size || processed.height() != spec.size {
         return Err(AppError::Processing(format!(
             "Preprocess result is {}x{}, expected {}x{}",
             processed.width(),
             processed.height(),
-            spec.input_size,
-            spec.input_size
+            spec.size,
+            spec.size
         )));
     }
 
-    let mut input =
-        Array4::<f32>::zeros((1, 1, spec.input_size as usize, spec.input_size as usize));
-    fill_nchw_input(&processed, &mut input)?;
+    let size = spec.size as usize;
+    let mut input = Array4::<f32>::zeros((1, spec.channels.count(), size, size));
+    fill_nchw_input(&processed, spec, &mut input)?;
 
     Ok(input)
 }
@@ -496,17 +673,25 @@ fn center_in_square(source: &image::GrayImage, target_size: u32) -> image::GrayI
 
 /// Fills an NCHW tensor from a grayscale image.
 ///
-/// Pixel values are scaled to `[0, 1]`; any further channel adaptation and
-/// normalisation are intentionally owned by the ONNX graph.
-fn fill_nchw_input(processed: &image::GrayImage, input: &mut Array4<f32>) -> Result<()> {
+/// Pixel values are scaled to `[0, 1]` and normalised with each channel's
+/// mean and standard deviation from `spec`; every channel gets the same
+/// grayscale plane.
+fn fill_nchw_input(
+    processed: &image::GrayImage,
+    spec: &ModelInputSpec,
+    input: &mut Array4<f32>,
+) -> Result<()> {
     let plane_len = processed.width() as usize * processed.height() as usize;
     let input_slice = input
         .as_slice_mut()
         .expect("Input tensor should be contiguous");
     let pixels = processed.as_raw();
 
-    for (destination, pixel) in input_slice.iter_mut().take(plane_len).zip(pixels) {
-        *destination = *pixel as f32 / 255.0;
+    for (channel, plane) in input_slice.chunks_exact_mut(plane_len).enumerate() {
+        let (mean, std) = spec.normalisation(channel);
+        for (destination, pixel) in plane.iter_mut().zip(pixels) {
+            *destination = (*pixel as f32 / 255.0 - mean) / std;
+        }
     }
 
     Ok(())
@@ -514,23 +699,18 @@ fn fill_nchw_input(processed: &image::GrayImage, input: &mut Array4<f32>) -> Res
 
 /// Pulls the embedding output out of the model's results.
 ///
-/// Looks up the `embedding` output, validates that it is a 2-D
-/// `[batch, features]` tensor with at least `expected_count` rows, and returns
-/// the first `expected_count` rows as owned vectors (dropping the padding rows
-/// from any short final batch).
+/// Looks up the output `spec` names, validates that it is a 2-D
+/// `[batch, features]` tensor with `spec.dimensions` features and at least
+/// `expected_count` rows, and returns the first `expected_count` rows as owned
+/// vectors (dropping the padding rows from any short final batch).
 fn extract_embeddings(
     outputs: &ort::session::SessionOutputs<'_>,
+    spec: &ModelOutputSpec,
     expected_count: usize,
-    expected_dimensions: usize,
 ) -> Result<Vec<Vec<f32>>> {
-    let output = outputs
-        .get(PREFERRED_EMBEDDING_OUTPUT_NAME)
-        .ok_or_else(|| {
-            AppError::Processing(format!(
-                "Model output '{}' was not found",
-                PREFERRED_EMBEDDING_OUTPUT_NAME
-            ))
-        })?;
+    let output = outputs.get(spec.name.as_str()).ok_or_else(|| {
+        AppError::Processing(format!("Model output '{}' was not found", spec.name))
+    })?;
 
     let array = output
         .try_extract_array::<f32>()
@@ -543,19 +723,19 @@ fn extract_embeddings(
     if shape.len() != 2 {
         return Err(AppError::Processing(format!(
             "Output '{}' must be 2D [batch, features], got {:?}",
-            PREFERRED_EMBEDDING_OUTPUT_NAME, shape
+            spec.name, shape
         )));
     }
     if shape[0] < expected_count {
         return Err(AppError::Processing(format!(
             "Output '{}' batch size {} is smaller than expected {}",
-            PREFERRED_EMBEDDING_OUTPUT_NAME, shape[0], expected_count
+            spec.name, shape[0], expected_count
         )));
     }
-    if shape[1] != expected_dimensions {
+    if shape[1] != spec.dimensions {
         return Err(AppError::Processing(format!(
             "Output '{}' has {} features, expected {}",
-            PREFERRED_EMBEDDING_OUTPUT_NAME, shape[1], expected_dimensions
+            spec.name, shape[1], spec.dimensions
         )));
     }
 
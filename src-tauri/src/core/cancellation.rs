@@ -0,0 +1,251 @@
+//! Cooperative, per-job cancellation and pausing.
+//!
+//! Every job owns a [`CancellationToken`] that is handed down to each stage it
+//! runs. Stages check it between units of work and inside their long-running
+//! loops — linkage replay and leaf ordering, the parallel Google Fonts
+//! download, rendering and analysis batches — and stop with an
+//! [`ErrorCode::Cancelled`] error. Work that can't be interrupted, such as the
+//! hierarchical linkage itself, runs through [`CancellationToken::run`],
+//! which stops waiting for it on cancellation. The job reports the error as
+//! its `"Cancelled"`
+//! result after discarding the partial outputs (see
+//! [`discard_partial_outputs`](crate::core::resume::discard_partial_outputs)).
+//!
+//! The same token pauses a job: its safe checkpoints (see
+//! [`CancellationToken::checkpoint`]) — between downloads, render tasks,
+//! analysis batches and stages — block while it is paused.
+//!
+//! Jobs run in worker processes, so the app controls one through the worker's
+//! stdin (see [`CancellationToken::controlled_by_stdin`]): a
+//! [`PAUSE_COMMAND`] or [`RESUME_COMMAND`] line pauses or resumes it, and
+//! closing the pipe cancels it. The same signal stops a worker whose app
+//! process has gone away.
+
+use crate::error::{AppError, ErrorCode, Result};
+use std::io::{self, BufRead};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+/// Worker stdin line that pauses the job.
+pub const PAUSE_COMMAND: &str = "pause";
+/// Worker stdin line that resumes a paused job.
+pub const RESUME_COMMAND: &str = "resume";
+
+/// How often [`CancellationToken::run`] looks for a cancellation.
+const RUN_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Shared flags telling one job's stages to pause or stop. Clones observe the
+/// same state.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<TokenState>);
+
+#[derive(Debug, Default)]
+struct TokenState {
+    cancelled: AtomicBool,
+    paused: Mutex<bool>,
+    /// Wakes the checkpoints blocked on `paused`.
+    unpaused: Condvar,
+}
+
+impl CancellationToken {
+    /// Creates a token that is neither cancelled nor paused.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a token driven by this process's stdin: pause and resume
+    /// command lines toggle the pause, calling `on_pause` with the new state,
+    /// and end of file — the app process closing the pipe or exiting —
+    /// cancels it.
+    pub fn controlled_by_stdin(on_pause: impl Fn(bool) + Send + 'static) -> Self {
+        let token = Self::new();
+        let controlled = token.clone();
+        std::thread::spawn(move || {
+            for line in io::stdin().lock().lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                match line.trim() {
+                    PAUSE_COMMAND => {
+                        controlled.pause();
+                        on_pause(true);
+                    }
+                    RESUME_COMMAND => {
+                        controlled.resume();
+                        on_pause(false);
+                    }
+                    _ => {}
+                }
+            }
+            controlled.cancel();
+        });
+        token
+    }
+
+    /// Cancels the job; every clone observes it from now on, and paused
+    /// checkpoints wake up to fail.
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::Relaxed);
+        let _paused = self.0.paused.lock().unwrap();
+        self.0.unpaused.notify_all();
+    }
+
+    /// True once the job has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Fails with an [`ErrorCode::Cancelled`] error once the token is
+    /// cancelled.
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            Err(AppError::new(ErrorCode::Cancelled, "The job was cancelled"))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Pauses the job at its next checkpoint.
+    pub fn pause(&self) {
+        *self.0.paused.lock().unwrap() = true;
+    }
+
+    /// Lets the checkpoints of a paused job continue.
+    pub fn resume(&self) {
+        *self.0.paused.lock().unwrap() = false;
+        self.0.unpaused.notify_all();
+    }
+
+    /// True while the job is paused.
+    pub fn is_paused(&self) -> bool {
+        *self.0.paused.lock().unwrap()
+    }
+
+    /// A safe point to suspend the job: blocks while the token is paused, then
+    /// fails like [`check`](Self::check) if it was cancelled meanwhile.
+    pub fn checkpoint(&self) -> Result<()> {
+        let mut paused = self.0.paused.lock().unwrap();
+        while *paused && !self.is_cancelled() {
+            paused = self.0.unpaused.wait(paused).unwrap();
+        }
+        drop(paused);
+        self.check()
+    }
+
+    /// Runs `work`, which has no checkpoints of its own, on a helper thread
+    /// and waits for it unless the token is cancelled first. A cancelled run
+    /// returns at once with the [`check`](Self::check) error; the helper
+    /// thread finishes in the background and its result is dropped.
+    pub fn run<T: Send + 'static>(&self, work: impl FnOnce() -> T + Send + 'static) -> Result<T> {
+        self.check()?;
+        let (sender, receiver) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("cancellable-work".into())
+            .spawn(move || {
+                // The receiver is gone once the run was cancelled.
+                let _ = sender.send(work());
+            })
+            .map_err(|e| AppError::Processing(format!("Failed to spawn worker thread: {e}")))?;
+        loop {
+            match receiver.recv_timeout(RUN_POLL_INTERVAL) {
+                Ok(value) => return Ok(value),
+                Err(RecvTimeoutError::Timeout) => self.check()?,
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(AppError::Processing("Worker thread panicked".into()))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    /// Runs `token.checkpoint()` on another thread, reporting when it returns.
+    fn spawn_checkpoint(token: &CancellationToken) -> mpsc::Receiver<Result<()>> {
+        let (sender, receiver) = mpsc::channel();
+        let token = token.clone();
+        std::thread::spawn(move || sender.send(token.checkpoint()).unwrap());
+        receiver
+    }
+
+    #[test]
+    fn checkpoint_passes_while_running() {
+        let token = CancellationToken::new();
+        assert!(token.checkpoint().is_ok());
+        assert!(!token.is_paused());
+    }
+
+    #[test]
+    fn checkpoint_blocks_while_paused_until_resumed() {
+        let token = CancellationToken::new();
+        token.pause();
+        assert!(token.is_paused());
+        let checkpoint = spawn_checkpoint(&token);
+        assert!(checkpoint.recv_timeout(Duration::from_millis(100)).is_err());
+
+        token.resume();
+        let result = checkpoint.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn cancel_wakes_a_paused_checkpoint() {
+        let token = CancellationToken::new();
+        token.pause();
+        let checkpoint = spawn_checkpoint(&token);
+        assert!(checkpoint.recv_timeout(Duration::from_millis(100)).is_err());
+
+        token.cancel();
+        let result = checkpoint.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(result.unwrap_err().is_cancelled());
+    }
+
+    #[test]
+    fn run_returns_the_result_of_the_work() {
+        let token = CancellationToken::new();
+        assert_eq!(token.run(|| 6 * 7).unwrap(), 42);
+    }
+
+    #[test]
+    fn run_stops_waiting_once_cancelled() {
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        let (release, released) = mpsc::channel::<()>();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            canceller.cancel();
+        });
+
+        let started = Instant::now();
+        // The work only finishes once released, after the run has returned.
+        let result = token.run(move || released.recv().is_ok());
+        assert!(result.unwrap_err().is_cancelled());
+        assert!(started.elapsed() < Duration::from_secs(5));
+        drop(release);
+    }
+
+    #[test]
+    fn run_reports_a_panicking_work() {
+        let token = CancellationToken::new();
+        let error = token.run(|| -> u32 { panic!("boom") }).unwrap_err();
+        assert!(!error.is_cancelled());
+    }
+
+    #[test]
+    fn run_does_not_start_when_already_cancelled() {
+        let token = CancellationToken::new();
+        token.cancel();
+        let started = Arc::new(AtomicBool::new(false));
+        let flag = started.clone();
+        assert!(token
+            .run(move || flag.store(true, Ordering::Relaxed))
+            .is_err());
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(!started.load(Ordering::Relaxed));
+    }
+}
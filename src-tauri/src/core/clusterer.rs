@@ -4,21 +4,27 @@
 //! largest pairwise distance is 1, and fed to agglomerative (hierarchical)
 //! clustering via [`kodama`]. The dendrogram is cut by either a target cluster
 //! count or a distance threshold (see [`ClusteringConfig`]), and the resulting
-//! label is stored on each font.
+//! label is stored on each font. Typographic metrics from the per-font
+//! metadata can be appended as extra weighted axes (see [`append_metric_axes`]).
+//! The pairwise distance matrix grows with the square of the font count, so it
+//! is checked against the job's memory ceiling before it is built (see
+//! [`ensure_distance_matrix_fits`]).
 
 use crate::commands::progress::progress_events;
 use crate::config::{
     ClusterStat, ClusteringConfig, ClusteringData, ClusteringMethod, ClusteringStats, ComputedData,
-    DendrogramData, DendrogramMerge, ProgressStage,
+    DendrogramData, DendrogramMerge, FontMetricKey, ProgressStage,
 };
 use crate::core::optimal_leaf_ordering::{optimize_leaf_order, ordered_leaves};
 use crate::core::session::{
     load_computed_data, load_font_metadata, load_sample_vectors, save_computed_data,
     save_dendrogram,
 };
-use crate::core::{AppState, EventSink, ModelBundle};
+use crate::core::{
+    ensure_allocation_fits, AppState, CancellationToken, EventSink, ModelBundle, ResourceProfile,
+};
 use crate::error::{AppError, Result};
-use kodama::{linkage, Method as KodamaMethod};
+use kodama::{linkage, Method as KodamaMethod, Step};
 use ndarray::{concatenate, Array1, Array2, Axis};
 use petal_decomposition::PcaBuilder;
 use std::collections::{BTreeMap, HashMap};
@@ -29,7 +35,12 @@ use std::path::Path;
 /// Reads the embeddings, reduces/rescales them, runs agglomerative
 /// clustering, writes each font's cluster index, and records the cluster and
 /// sample counts on the session status. Returns an error when there are no
-/// analysed vectors to cluster.
+/// analysed vectors to cluster, and a cancelled error once `cancel` is
+/// observed; cancellation is honoured during linkage and checked throughout
+/// linkage replay and leaf ordering, before any new output is written.
+/// Fails with an [`ErrorCode::ResourceLimit`](crate::error::ErrorCode::ResourceLimit)
+/// error when the distance matrix would exceed the memory ceiling of
+/// `resource_profile`.
 ///
 /// `model` is deliberately optional: ordinary clustering consumes only the
 /// vectors saved by analysis, while active attribute emphasis additionally
@@ -40,6 +51,8 @@ pub async fn cluster_all(
     events: &impl EventSink,
     state: &AppState,
     model: Option<&ModelBundle>,
+    resource_profile: &ResourceProfile,
+    cancel: &CancellationToken,
 ) -> Result<()> {
     let session_dir = state.get_session_dir()?;
 
@@ -89,16 +102,7 @@ pub async fn cluster_all(
             .map(|s| s.algorithm.clustering.clone())
             .ok_or_else(|| AppError::Processing("No active session".into()))?
     };
-    let enable_preprocess_pca = config.enable_preprocess_pca;
-    let preprocessing_dimensions = config.preprocessing_dimensions;
-    // The enable switch gates the whole feature: when off, hand the feature
-    // builder an empty map so it takes the plain no-emphasis path, while the
-    // stored levels stay untouched in the session.
-    let emphasis = if config.enable_attribute_emphasis {
-        config.emphasis.clone()
-    } else {
-        BTreeMap::new()
-    };
+    let feature_config = config.clone();
     let model_directory = model.map(|model| model.directory.clone());
     let session_dir_for_first = session_dir.clone();
 
@@ -107,28 +111,20 @@ pub async fn cluster_all(
         scatter,
         ids,
     } = tokio::task::spawn_blocking(move || -> Result<ClusterInputs> {
-        let (vectors, ids) = load_sample_vectors(&session_dir_for_first)?;
-        if vectors.is_empty() {
+        let (data, ids) = load_vector_matrix(&session_dir_for_first)?;
+        if data.is_empty() {
             return Ok(ClusterInputs {
-                points: Array2::zeros((0, 0)),
+                points: data,
                 scatter: Vec::new(),
                 ids,
             });
         }
 
-        let n_samples = vectors.len();
-        let n_features = vectors[0].len();
-        let data = Array2::from_shape_vec(
-            (n_samples, n_features),
-            vectors.into_iter().flatten().collect(),
-        )
-        .map_err(|e| AppError::Processing(e.to_string()))?;
-
-        let points = build_cluster_features(
+        let points = cluster_features(
             data,
-            enable_preprocess_pca,
-            preprocessing_dimensions,
-            &emphasis,
+            &ids,
+            &session_dir_for_first,
+            &feature_config,
             model_directory.as_deref(),
         )?;
         let scatter = scatter_projection(&points)?;
@@ -148,13 +144,18 @@ pub async fn cluster_all(
         ));
     }
 
+    cancel.check()?;
     let n_samples = points.nrows();
+    ensure_distance_matrix_fits(resource_profile, n_samples)?;
     // Linkage plus leaf ordering is CPU-bound (up to O(n³)); run it off the
     // async runtime like the other heavy stages.
+    let cancel_blocking = cancel.clone();
     let (labels, join_heights, leaf_angles, merges, stats) =
-        tokio::task::spawn_blocking(move || agglomerative_clustering(points, &config))
-            .await
-            .map_err(|e| AppError::Processing(e.to_string()))??;
+        tokio::task::spawn_blocking(move || {
+            agglomerative_clustering(points, &config, &cancel_blocking)
+        })
+        .await
+        .map_err(|e| AppError::Processing(e.to_string()))??;
     let n_clusters = stats.clusters.len();
 
     progress_events::reset_progress(events, state, ProgressStage::Clustering);
@@ -236,6 +237,67 @@ struct ClusterInputs {
     ids: Vec<String>,
 }
 
+/// Reads the session's analysed vectors as one row per font, with the font ids
+/// in row order. The matrix is empty when no font has been analysed.
+pub(crate) fn load_vector_matrix(session_dir: &Path) -> Result<(Array2<f32>, Vec<String>)> {
+    let (vectors, ids) = load_sample_vectors(session_dir)?;
+    if vectors.is_empty() {
+        return Ok((Array2::zeros((0, 0)), ids));
+    }
+    let n_samples = vectors.len();
+    let n_features = vectors[0].len();
+    let data = Array2::from_shape_vec(
+        (n_samples, n_features),
+        vectors.into_iter().flatten().collect(),
+    )
+    .map_err(|e| AppError::Processing(e.to_string()))?;
+    Ok((data, ids))
+}
+
+/// Builds the clustering feature matrix for `config` from the analysed
+/// vectors in `data` (rows in `ids` order): the PCA/emphasis features of
+/// [`build_cluster_features`] followed by any weighted metric axes.
+pub(crate) fn cluster_features(
+    data: Array2<f32>,
+    ids: &[String],
+    session_dir: &Path,
+    config: &ClusteringConfig,
+    model_directory: Option<&Path>,
+) -> Result<Array2<f32>> {
+    // The enable switch gates the whole feature: when off, hand the feature
+    // builder an empty map so it takes the plain no-emphasis path, while the
+    // stored levels stay untouched in the session.
+    let emphasis = if config.enable_attribute_emphasis {
+        config.emphasis.clone()
+    } else {
+        BTreeMap::new()
+    };
+    let points = build_cluster_features(
+        data,
+        config.enable_preprocess_pca,
+        config.preprocessing_dimensions,
+        &emphasis,
+        model_directory,
+    )?;
+
+    let metric_weights = active_metric_weights(&config.metric_weights);
+    if metric_weights.is_empty() {
+        return Ok(points);
+    }
+    let metadata = ids
+        .iter()
+        .map(|id| load_font_metadata(session_dir, id))
+        .collect::<Result<Vec<_>>>()?;
+    let columns = metric_weights
+        .iter()
+        .map(|&(key, weight)| {
+            let values = metadata.iter().map(|meta| key.value(meta)).collect();
+            (key, weight, values)
+        })
+        .collect::<Vec<_>>();
+    append_metric_axes(points, &columns)
+}
+
 /// `(attribute-name, level)` pairs for the non-zero emphasis axes.
 ///
 /// Iteration order is the map's key order (`BTreeMap` iterates sorted), so the
@@ -250,6 +312,81 @@ fn active_emphasis(emphasis: &BTreeMap<String, i8>) -> Vec<(String, i8)> {
         .collect()
 }
 
+/// Largest accepted metric weight; keeps a hand-edited `config.json` from
+/// letting one metric swamp the embedding entirely.
+const MAX_METRIC_WEIGHT: f32 = 16.0;
+
+/// `(metric, weight)` pairs for the positive metric weights, in key order.
+fn active_metric_weights(weights: &BTreeMap<FontMetricKey, f32>) -> Vec<(FontMetricKey, f32)> {
+    weights
+        .iter()
+        .filter(|(_, &weight)| weight.is_finite() && weight > 0.0)
+        .map(|(&key, &weight)| (key, weight.min(MAX_METRIC_WEIGHT)))
+        .collect()
+}
+
+/// Appends one typographic-metric axis per `(metric, weight, values)` column.
+///
+/// `values` holds each row's measurement in `points` row order. Like the
+/// attribute-emphasis axes in [`build_cluster_features`], each column is
+/// standardised and then scaled by `reference * weight`, `reference` being the
+/// mean spread of the existing feature columns, so a weight of `1` makes the
+/// metric about as influential as a typical visual axis. Fonts missing the
+/// metric sit at the mean (zero after standardisation) so they are neither
+/// pulled nor pushed along it. A metric with no spread at all (or no values)
+/// carries no information and is skipped with a warning.
+fn append_metric_axes(
+    points: Array2<f32>,
+    columns: &[(FontMetricKey, f32, Vec<Option<f32>>)],
+) -> Result<Array2<f32>> {
+    let n_samples = points.nrows();
+    if n_samples < 2 {
+        return Ok(points);
+    }
+    let reference = ((0..points.ncols())
+        .map(|column| column_std(&points, column))
+        .sum::<f32>()
+        / points.ncols().max(1) as f32)
+        .max(1e-6);
+
+    let mut axes = Vec::new();
+    for (key, weight, values) in columns {
+        let known: Vec<f32> = values.iter().flatten().copied().collect();
+        if values.len() != n_samples || known.is_empty() {
+            println!("⚠️ Clusterer: metric {key:?} unavailable, skipped");
+            continue;
+        }
+        let mean = known.iter().sum::<f32>() / known.len() as f32;
+        let std = (known
+            .iter()
+            .map(|value| (value - mean).powi(2))
+            .sum::<f32>()
+            / known.len() as f32)
+            .sqrt();
+        if std <= 1e-6 {
+            println!("⚠️ Clusterer: metric {key:?} is constant across fonts, skipped");
+            continue;
+        }
+        let scale = reference * weight / std;
+        axes.push(Array1::from_iter(
+            values
+                .iter()
+                .map(|value| value.map_or(0.0, |value| (value - mean) * scale)),
+        ));
+    }
+    if axes.is_empty() {
+        return Ok(points);
+    }
+
+    let mut views = vec![points.view()];
+    let axes: Vec<Array2<f32>> = axes
+        .into_iter()
+        .map(|axis| axis.insert_axis(Axis(1)))
+        .collect();
+    views.extend(axes.iter().map(|axis| axis.view()));
+    concatenate(Axis(1), &views).map_err(|e| AppError::Processing(e.to_string()))
+}
+
 /// Builds the feature matrix fed to clustering, honouring attribute emphasis.
 ///
 /// When PCA preprocessing is disabled, the analyzer embeddings pass through
@@ -547,9 +684,14 @@ fn pca_embedding(data: Array2<f32>, dimensions: usize) -> Result<Array2<f32>> {
 /// their smallest member index for stable, deterministic ids. The stats are a
 /// free by-product of the replay (per-cluster size/centroid/diameter, the cut
 /// height, and the full merge-height sequence).
+///
+/// `cancel` is checked at every replayed merge and throughout leaf ordering;
+/// [`kodama::linkage`] itself can't be interrupted, so a cancelled job stops
+/// waiting for it (see [`CancellationToken::run`]).
 fn agglomerative_clustering(
     points: Array2<f32>,
     config: &ClusteringConfig,
+    cancel: &CancellationToken,
 ) -> Result<(
     Vec<i32>,
     Vec<f32>,
@@ -575,33 +717,16 @@ fn agglomerative_clustering(
         return Ok((vec![0], vec![0.0], vec![0.0], Vec::new(), stats));
     }
 
-    let mut condensed = Vec::with_capacity((n * (n - 1)) / 2);
-
-    for i in 0..n {
-        for j in (i + 1)..n {
-            condensed.push(point_distance(&points, i, j));
-        }
-    }
-
-    // Unit-diameter rescale: points and pairwise distances divided by the
-    // largest pairwise distance, so downstream heights/centroids stay in one
-    // consistent space (identical points leave everything at scale 1).
-    let max_distance = condensed.iter().copied().fold(0.0f32, f32::max);
-    let points = if max_distance > 0.0 {
-        for distance in &mut condensed {
-            *distance /= max_distance;
-        }
-        points.mapv_into(|value| value / max_distance)
-    } else {
-        points
-    };
+    let (points, condensed) = unit_diameter_distances(points);
 
     // `kodama` uses the condensed matrix as mutable workspace. Keep the
     // original normalized leaf distances for the post-linkage leaf ordering.
     let leaf_distances = condensed;
+    // `linkage` has no checkpoints, so it runs on a helper thread that a
+    // cancelled job stops waiting for.
     let mut linkage_distances = leaf_distances.clone();
-    let dendrogram = linkage(&mut linkage_distances, n, kodama_method(config.method));
-    drop(linkage_distances);
+    let method = kodama_method(config.method);
+    let dendrogram = cancel.run(move || linkage(&mut linkage_distances, n, method))?;
     // Every merge (full tree), plus per-leaf the height at which each point is
     // first absorbed — its isolation. A leaf is a direct operand of exactly
     // one merge, so this fills every entry in one pass.
@@ -617,6 +742,7 @@ fn agglomerative_clustering(
     let mut sizes: Vec<usize> = vec![1; n];
     let mut representatives: Vec<usize> = (0..n).collect();
     for step in dendrogram.steps() {
+        cancel.check()?;
         merge_heights.push(step.dissimilarity);
         let (left, right) = (step.cluster1, step.cluster2);
         let total = (sizes[left] + sizes[right]) as f32;
@@ -649,11 +775,115 @@ fn agglomerative_clustering(
             join_heights[right] = step.dissimilarity;
         }
     }
-    optimize_leaf_order(&mut merges, &leaf_distances, n);
+    optimize_leaf_order(&mut merges, &leaf_distances, n, cancel)?;
     let mut leaf_angles = vec![0.0f32; n];
     for (rank, leaf) in ordered_leaves(&merges, n).into_iter().enumerate() {
         leaf_angles[leaf] = std::f32::consts::TAU * (rank as f32 + 0.5) / n as f32;
     }
+    let DendrogramCut {
+        clusters: active_clusters,
+        node_height,
+        cut_height,
+    } = cut_dendrogram(dendrogram.steps(), n, config);
+    let labels = leaf_labels(&active_clusters, n);
+
+    let color_indices = assign_color_indices(&active_clusters, &merges, n);
+
+    let cluster_stats = active_clusters
+        .iter()
+        .zip(&color_indices)
+        .map(|((node, members), color_index)| ClusterStat {
+            size: members.len(),
+            centroid: points
+                .select(Axis(0), members)
+                .mean_axis(Axis(0))
+                .map(|centroid| centroid.to_vec())
+                .unwrap_or_default(),
+            diameter: node_height[*node],
+            // A cut cluster is a subtree and therefore occupies one contiguous
+            // interval in the final left-first order. Its mean leaf position
+            // is the center direction of that interval.
+            cluster_angle: members
+                .iter()
+                .map(|member| leaf_angles[*member])
+                .sum::<f32>()
+                / members.len() as f32,
+            color_index: *color_index,
+        })
+        .collect();
+
+    Ok((
+        labels,
+        join_heights,
+        leaf_angles,
+        merges,
+        ClusteringStats {
+            clusters: cluster_stats,
+            cut_height,
+            merge_heights,
+        },
+    ))
+}
+
+/// Fails with an [`ErrorCode::ResourceLimit`](crate::error::ErrorCode::ResourceLimit)
+/// error when the condensed distance matrix over `n` points, together with
+/// the copy linkage consumes as its workspace, would exceed the memory
+/// ceiling of `profile`.
+pub(crate) fn ensure_distance_matrix_fits(profile: &ResourceProfile, n: usize) -> Result<()> {
+    ensure_allocation_fits(profile, distance_matrix_bytes(n), "Clustering")
+}
+
+/// Bytes of a condensed `f32` distance matrix over `n` points and its linkage
+/// workspace.
+fn distance_matrix_bytes(n: usize) -> u64 {
+    let distances = n as u64 * (n as u64).saturating_sub(1) / 2;
+    distances.saturating_mul(2 * std::mem::size_of::<f32>() as u64)
+}
+
+/// Pairwise Euclidean distances of `points` in condensed (upper-triangle,
+/// row-major) order, with points and distances uniformly rescaled so the
+/// largest pairwise distance is 1 — downstream heights/centroids stay in one
+/// consistent space (identical points leave everything at scale 1).
+pub(crate) fn unit_diameter_distances(points: Array2<f32>) -> (Array2<f32>, Vec<f32>) {
+    let n = points.nrows();
+    let mut condensed = Vec::with_capacity((n * n.saturating_sub(1)) / 2);
+    for i in 0..n {
+        for j in (i + 1)..n {
+            condensed.push(point_distance(&points, i, j));
+        }
+    }
+
+    let max_distance = condensed.iter().copied().fold(0.0f32, f32::max);
+    if max_distance > 0.0 {
+        for distance in &mut condensed {
+            *distance /= max_distance;
+        }
+        (points.mapv_into(|value| value / max_distance), condensed)
+    } else {
+        (points, condensed)
+    }
+}
+
+/// The clusters left after cutting a linkage with the stop criteria of a
+/// [`ClusteringConfig`] (see [`agglomerative_clustering`]).
+pub(crate) struct DendrogramCut {
+    /// `(node, members)` of every remaining cluster, ordered by smallest
+    /// member so a cluster's position is its label.
+    pub clusters: Vec<(usize, Vec<usize>)>,
+    /// Linkage height at which each node formed; leaves and unapplied merges
+    /// stay 0.
+    pub node_height: Vec<f32>,
+    /// Height of the last applied merge.
+    pub cut_height: f32,
+}
+
+/// Replays the `n`-leaf linkage `steps` in order until a stop criterion of
+/// `config` is hit.
+pub(crate) fn cut_dendrogram(
+    steps: &[Step<f32>],
+    n: usize,
+    config: &ClusteringConfig,
+) -> DendrogramCut {
     let mut active_count = n;
     let target_cluster_count =
         (config.target_cluster_count > 0).then(|| config.target_cluster_count.clamp(1, n));
@@ -661,7 +891,6 @@ fn agglomerative_clustering(
 
     let mut clusters = vec![Vec::new(); (2 * n) - 1];
     let mut active = vec![false; (2 * n) - 1];
-    // Linkage height at which each internal node formed; leaves stay 0.
     let mut node_height = vec![0.0f32; (2 * n) - 1];
     let mut cut_height = 0.0f32;
     for i in 0..n {
@@ -669,7 +898,7 @@ fn agglomerative_clustering(
         active[i] = true;
     }
 
-    for (step_index, step) in dendrogram.steps().iter().enumerate() {
+    for (step_index, step) in steps.iter().enumerate() {
         if let Some(target_cluster_count) = target_cluster_count {
             if active_count <= target_cluster_count {
                 break;
@@ -704,53 +933,27 @@ fn agglomerative_clustering(
         .iter()
         .enumerate()
         .filter(|(_, is_active)| **is_active)
-        .map(|(node, _)| (node, clusters[node].clone()))
+        .map(|(node, _)| (node, std::mem::take(&mut clusters[node])))
         .collect::<Vec<_>>();
     active_clusters.sort_by_key(|(_, members)| members.iter().copied().min().unwrap_or(usize::MAX));
 
+    DendrogramCut {
+        clusters: active_clusters,
+        node_height,
+        cut_height,
+    }
+}
+
+/// Per-leaf cluster labels of `n` leaves grouped into `clusters`, numbered in
+/// slice order.
+pub(crate) fn leaf_labels(clusters: &[(usize, Vec<usize>)], n: usize) -> Vec<i32> {
     let mut labels = vec![-1; n];
-    for (cluster_id, (_, members)) in active_clusters.iter().enumerate() {
+    for (cluster_id, (_, members)) in clusters.iter().enumerate() {
         for point_index in members {
             labels[*point_index] = cluster_id as i32;
         }
     }
-
-    let color_indices = assign_color_indices(&active_clusters, &merges, n);
-
-    let cluster_stats = active_clusters
-        .iter()
-        .zip(&color_indices)
-        .map(|((node, members), color_index)| ClusterStat {
-            size: members.len(),
-            centroid: points
-                .select(Axis(0), members)
-                .mean_axis(Axis(0))
-                .map(|centroid| centroid.to_vec())
-                .unwrap_or_default(),
-            diameter: node_height[*node],
-            // A cut cluster is a subtree and therefore occupies one contiguous
-            // interval in the final left-first order. Its mean leaf position
-            // is the center direction of that interval.
-            cluster_angle: members
-                .iter()
-                .map(|member| leaf_angles[*member])
-                .sum::<f32>()
-                / members.len() as f32,
-            color_index: *color_index,
-        })
-        .collect();
-
-    Ok((
-        labels,
-        join_heights,
-        leaf_angles,
-        merges,
-        ClusteringStats {
-            clusters: cluster_stats,
-            cut_height,
-            merge_heights,
-        },
-    ))
+    labels
 }
 
 /// Number of distinct cluster colors the UI palette provides; must stay in
@@ -866,7 +1069,7 @@ fn point_distance(points: &Array2<f32>, left: usize, right: usize) -> f32 {
 
 /// Maps the config's [`ClusteringMethod`] onto the equivalent
 /// [`kodama::Method`].
-fn kodama_method(method: ClusteringMethod) -> KodamaMethod {
+pub(crate) fn kodama_method(method: ClusteringMethod) -> KodamaMethod {
     match method {
         ClusteringMethod::Single => KodamaMethod::Single,
         ClusteringMethod::Complete => KodamaMethod::Complete,
@@ -882,6 +1085,26 @@ fn kodama_method(method: ClusteringMethod) -> KodamaMethod {
 mod tests {
     use super::*;
 
+    #[test]
+    fn distance_matrix_counts_both_condensed_copies() {
+        assert_eq!(distance_matrix_bytes(0), 0);
+        assert_eq!(distance_matrix_bytes(1), 0);
+        assert_eq!(distance_matrix_bytes(4), 6 * 4 * 2);
+        assert_eq!(distance_matrix_bytes(100_000), 4_999_950_000 * 8);
+    }
+
+    #[test]
+    fn distance_matrix_over_the_ceiling_is_rejected() {
+        let profile = ResourceProfile {
+            memory_ceiling_mb: Some(1),
+            ..ResourceProfile::default()
+        };
+        // 1,000 fonts need about 4 MB of distances alone.
+        let error = ensure_distance_matrix_fits(&profile, 1_000).unwrap_err();
+        assert_eq!(error.code(), crate::error::ErrorCode::ResourceLimit);
+        assert!(ensure_distance_matrix_fits(&ResourceProfile::default(), 1_000).is_ok());
+    }
+
     #[test]
     fn cluster_colors_follow_the_final_oriented_ring_order() {
         let leaf_count = 10;
@@ -1050,6 +1273,53 @@ mod tests {
         assert!(basis.row(0).dot(&basis.row(1)).abs() < 1e-5);
     }
 
+    /// Metric axes are standardised against the typical feature spread and
+    /// scaled by their weight; fonts missing the metric sit at zero.
+    #[test]
+    fn metric_axes_are_standardised_weighted_and_impute_missing() {
+        let points =
+            Array2::from_shape_vec((4, 2), vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0]).unwrap();
+        let reference = (column_std(&points, 0) + column_std(&points, 1)) / 2.0;
+        let columns = vec![
+            (
+                FontMetricKey::Width,
+                2.0,
+                vec![Some(0.4), Some(0.6), Some(0.4), Some(0.6)],
+            ),
+            (
+                FontMetricKey::Contrast,
+                1.0,
+                vec![Some(1.0), None, Some(3.0), None],
+            ),
+            (FontMetricKey::ItalicAngle, 1.0, vec![Some(0.0); 4]),
+        ];
+
+        let out = append_metric_axes(points, &columns).unwrap();
+
+        // The constant italic angle carries no information and is dropped.
+        assert_eq!(out.ncols(), 4);
+        let width_std = column_std(&out, 2);
+        assert!((width_std - 2.0 * reference).abs() < 1e-4);
+        assert!(out.column(2).sum().abs() < 1e-4);
+        assert_eq!(out[(1, 3)], 0.0);
+        assert_eq!(out[(3, 3)], 0.0);
+        assert!((out[(0, 3)] + out[(2, 3)]).abs() < 1e-4);
+    }
+
+    #[test]
+    fn metric_weights_drop_inactive_and_clamp() {
+        let weights = BTreeMap::from([
+            (FontMetricKey::Width, 0.0),
+            (FontMetricKey::Contrast, -1.0),
+            (FontMetricKey::XHeight, 100.0),
+            (FontMetricKey::Weight, f32::NAN),
+        ]);
+        assert_eq!(
+            active_metric_weights(&weights),
+            vec![(FontMetricKey::XHeight, MAX_METRIC_WEIGHT)]
+        );
+    }
+
     /// A non-zero level appends exactly one standardised attribute axis whose
     /// scale is `reference * 2^level`. The reference and standardisation are
     /// identical across levels, so the appended column is zero-mean and a
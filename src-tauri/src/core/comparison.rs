@@ -0,0 +1,263 @@
+//! Comparing the clusterings of two sessions.
+//!
+//! Reruns with a different model or linkage regroup the same fonts, so two
+//! sessions are compared through the `k` labels of the fonts they share,
+//! matched by `safe_name`. The partition agreement is summarised by the
+//! adjusted Rand index and normalised mutual information; the contingency
+//! table and the fonts whose cluster-mates changed most show where they
+//! disagree.
+
+use crate::core::session::{load_dendrogram, load_font_data};
+use crate::core::AppState;
+use crate::error::{AppError, Result};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// How many of the most-moved fonts a comparison lists.
+const MOVED_FONT_LIMIT: usize = 50;
+
+/// Agreement between the clusterings of two sessions.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionComparison {
+    pub session_a: String,
+    pub session_b: String,
+    /// Number of clustered fonts present in both sessions; every statistic
+    /// below is computed over these alone.
+    pub shared_fonts: usize,
+    /// Clustered fonts only session `a` has.
+    pub only_in_a: Vec<String>,
+    /// Clustered fonts only session `b` has.
+    pub only_in_b: Vec<String>,
+    /// Adjusted Rand index of the two partitions: `1.0` when identical up to
+    /// relabelling, around `0.0` for chance agreement.
+    pub adjusted_rand_index: f64,
+    /// Mutual information normalised by the mean of both entropies, in
+    /// `[0, 1]`.
+    pub normalized_mutual_information: f64,
+    /// Cluster labels of session `a`, one per contingency row.
+    pub clusters_a: Vec<i32>,
+    /// Cluster labels of session `b`, one per contingency column.
+    pub clusters_b: Vec<i32>,
+    /// `contingency[i][j]` counts the shared fonts in cluster `clusters_a[i]`
+    /// of session `a` and cluster `clusters_b[j]` of session `b`.
+    pub contingency: Vec<Vec<usize>>,
+    /// Fonts whose cluster-mates changed most, most moved first.
+    pub moved_fonts: Vec<MovedFont>,
+}
+
+/// A font whose cluster-mates differ between the two sessions.
+#[derive(Debug, Clone, Serialize)]
+pub struct MovedFont {
+    pub safe_name: String,
+    pub k_a: i32,
+    pub k_b: i32,
+    /// Jaccard distance between the font's cluster-mates in either session:
+    /// `0.0` when it kept exactly the same company, `1.0` when it shares none.
+    pub displacement: f64,
+}
+
+/// Compares the clusterings of sessions `session_a` and `session_b`.
+pub fn compare_sessions(session_a: &str, session_b: &str) -> Result<SessionComparison> {
+    let labels_a = read_cluster_labels(&AppState::resolve_session_dir(session_a)?, session_a)?;
+    let labels_b = read_cluster_labels(&AppState::resolve_session_dir(session_b)?, session_b)?;
+
+    let shared: Vec<(&String, i32, i32)> = labels_a
+        .iter()
+        .filter_map(|(safe_name, &k_a)| labels_b.get(safe_name).map(|&k_b| (safe_name, k_a, k_b)))
+        .collect();
+    if shared.is_empty() {
+        return Err(AppError::Processing(format!(
+            "Sessions {} and {} share no clustered fonts",
+            session_a, session_b
+        )));
+    }
+
+    let pairs: Vec<(i32, i32)> = shared.iter().map(|&(_, k_a, k_b)| (k_a, k_b)).collect();
+    let table = ContingencyTable::new(&pairs);
+
+    let mut moved_fonts: Vec<MovedFont> = shared
+        .iter()
+        .map(|&(safe_name, k_a, k_b)| MovedFont {
+            safe_name: safe_name.clone(),
+            k_a,
+            k_b,
+            displacement: table.displacement(k_a, k_b),
+        })
+        .filter(|font| font.displacement > 0.0)
+        .collect();
+    moved_fonts.sort_by(|a, b| {
+        b.displacement
+            .total_cmp(&a.displacement)
+            .then_with(|| a.safe_name.cmp(&b.safe_name))
+    });
+    moved_fonts.truncate(MOVED_FONT_LIMIT);
+
+    Ok(SessionComparison {
+        session_a: session_a.to_string(),
+        session_b: session_b.to_string(),
+        shared_fonts: shared.len(),
+        only_in_a: labels_a
+            .keys()
+            .filter(|safe_name| !labels_b.contains_key(*safe_name))
+            .cloned()
+            .collect(),
+        only_in_b: labels_b
+            .keys()
+            .filter(|safe_name| !labels_a.contains_key(*safe_name))
+            .cloned()
+            .collect(),
+        adjusted_rand_index: table.adjusted_rand_index(),
+        normalized_mutual_information: table.normalized_mutual_information(),
+        clusters_a: table.clusters_a.clone(),
+        clusters_b: table.clusters_b.clone(),
+        contingency: table.counts,
+        moved_fonts,
+    })
+}
+
+/// Reads the `k` label of every font in the session's dendrogram.
+fn read_cluster_labels(session_dir: &Path, session_id: &str) -> Result<BTreeMap<String, i32>> {
+    let dendrogram = load_dendrogram(session_dir).map_err(|_| {
+        AppError::Processing(format!("Session {} has not been clustered", session_id))
+    })?;
+    Ok(dendrogram
+        .ids
+        .into_iter()
+        .filter_map(|safe_name| {
+            let k = load_font_data(session_dir, &safe_name)
+                .ok()?
+                .computed?
+                .clustering?
+                .k;
+            Some((safe_name, k))
+        })
+        .collect())
+}
+
+/// Cross-tabulation of two labellings of the same items.
+struct ContingencyTable {
+    clusters_a: Vec<i32>,
+    clusters_b: Vec<i32>,
+    counts: Vec<Vec<usize>>,
+    row_sums: Vec<usize>,
+    column_sums: Vec<usize>,
+    total: usize,
+}
+
+impl ContingencyTable {
+    fn new(pairs: &[(i32, i32)]) -> Self {
+        let clusters_a: Vec<i32> = pairs
+            .iter()
+            .map(|&(k_a, _)| k_a)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let clusters_b: Vec<i32> = pairs
+            .iter()
+            .map(|&(_, k_b)| k_b)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let mut counts = vec![vec![0; clusters_b.len()]; clusters_a.len()];
+        for &(k_a, k_b) in pairs {
+            let row = clusters_a.binary_search(&k_a).unwrap();
+            let column = clusters_b.binary_search(&k_b).unwrap();
+            counts[row][column] += 1;
+        }
+        let row_sums = counts.iter().map(|row| row.iter().sum()).collect();
+        let column_sums = (0..clusters_b.len())
+            .map(|column| counts.iter().map(|row| row[column]).sum())
+            .collect();
+        Self {
+            clusters_a,
+            clusters_b,
+            counts,
+            row_sums,
+            column_sums,
+            total: pairs.len(),
+        }
+    }
+
+    fn adjusted_rand_index(&self) -> f64 {
+        let pairs = |n: usize| (n * n.saturating_sub(1) / 2) as f64;
+        let index: f64 = self.counts.iter().flatten().map(|&n| pairs(n)).sum();
+        let rows: f64 = self.row_sums.iter().map(|&n| pairs(n)).sum();
+        let columns: f64 = self.column_sums.iter().map(|&n| pairs(n)).sum();
+        let expected = rows * columns / pairs(self.total).max(1.0);
+        let max = (rows + columns) / 2.0;
+        if max == expected {
+            // Both partitions are trivial (all singletons or one cluster) in
+            // the same way; they agree perfectly.
+            return 1.0;
+        }
+        (index - expected) / (max - expected)
+    }
+
+    fn normalized_mutual_information(&self) -> f64 {
+        let total = self.total as f64;
+        let entropy = |sums: &[usize]| -> f64 {
+            sums.iter()
+                .filter(|&&n| n > 0)
+                .map(|&n| {
+                    let p = n as f64 / total;
+                    -p * p.ln()
+                })
+                .sum()
+        };
+        let entropy_a = entropy(&self.row_sums);
+        let entropy_b = entropy(&self.column_sums);
+        if entropy_a + entropy_b == 0.0 {
+            return 1.0;
+        }
+        let mut mutual_information = 0.0;
+        for (row, row_counts) in self.counts.iter().enumerate() {
+            for (column, &n) in row_counts.iter().enumerate() {
+                if n == 0 {
+                    continue;
+                }
+                let n = n as f64;
+                let independent = self.row_sums[row] as f64 * self.column_sums[column] as f64;
+                mutual_information += n / total * (n * total / independent).ln();
+            }
+        }
+        (mutual_information / ((entropy_a + entropy_b) / 2.0)).clamp(0.0, 1.0)
+    }
+
+    /// Jaccard distance between the cluster-mates (excluding the font
+    /// itself) of a font labelled `k_a` in one session and `k_b` in the other.
+    fn displacement(&self, k_a: i32, k_b: i32) -> f64 {
+        let row = self.clusters_a.binary_search(&k_a).unwrap();
+        let column = self.clusters_b.binary_search(&k_b).unwrap();
+        let both = self.counts[row][column] - 1;
+        let union = (self.row_sums[row] - 1) + (self.column_sums[column] - 1) - both;
+        if union == 0 {
+            return 0.0;
+        }
+        1.0 - both as f64 / union as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relabelled_partitions_agree_perfectly() {
+        let table = ContingencyTable::new(&[(0, 2), (0, 2), (1, 0), (1, 0), (2, 1)]);
+
+        assert!((table.adjusted_rand_index() - 1.0).abs() < 1e-12);
+        assert!((table.normalized_mutual_information() - 1.0).abs() < 1e-12);
+        assert_eq!(table.displacement(0, 2), 0.0);
+    }
+
+    #[test]
+    fn split_cluster_matches_reference_scores() {
+        let table = ContingencyTable::new(&[(0, 0), (0, 0), (1, 1), (1, 2)]);
+
+        assert!((table.adjusted_rand_index() - 4.0 / 7.0).abs() < 1e-12);
+        assert!((table.normalized_mutual_information() - 0.8).abs() < 1e-12);
+        assert_eq!(table.counts, vec![vec![2, 0, 0], vec![0, 1, 1]]);
+        assert_eq!(table.displacement(1, 1), 1.0);
+    }
+}
@@ -5,16 +5,20 @@
 //! grouped into families, and for each requested weight the closest available
 //! face is selected. The metadata is written to disk as it is discovered and a
 //! [`FontRenderSource`] is returned for each kept font so the renderer can
-//! later reopen exactly the right face.
-
-use crate::config::FontSource;
-use crate::core::AppState;
-use crate::error::{AppError, Result};
+//! later reopen exactly the right face. Every rejected face and unmatched
+//! weight is recorded in the session's [`crate::core::dropped_fonts`] report.
+
+use crate::config::{FontMetrics, FontSource};
+use crate::core::dropped_fonts::{record_dropped_fonts, reset_dropped_fonts, DroppedFont};
+use crate::core::resume::{is_font_complete, FontStage};
+use crate::core::{AppState, CancellationToken, RunStage};
+use crate::error::{AppError, ErrorCode, Result};
 use fontdb::{FaceInfo, Source};
 use rayon::prelude::*;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use swash::{FontRef, StringId};
+use swash::scale::ScaleContext;
+use swash::{tag_from_bytes, FontRef, StringId};
 
 /// Where to reopen a discovered font face for rendering: a file path plus the
 /// face index within that (possibly multi-face) file.
@@ -22,6 +26,7 @@ use swash::{FontRef, StringId};
 pub struct FontRenderSource {
     pub path: PathBuf,
     pub font_index: u32,
+    pub metrics: FontMetrics,
 }
 
 /// Output of a discovery run.
@@ -30,6 +35,8 @@ pub struct DiscoveryResult {
     pub discovered_fonts: HashMap<i32, Vec<String>>,
     /// How to reopen each kept font, keyed by `safe_name`.
     pub render_sources: HashMap<String, FontRenderSource>,
+    /// Faces and requested weights discovery excluded.
+    pub dropped_fonts: Vec<DroppedFont>,
 }
 
 /// Raw, per-face metadata pulled straight from a font's name table before
@@ -149,12 +156,68 @@ impl Discoverer {
             .unwrap_or_else(|| "Regular".to_string())
     }
 
+    /// Reads the table-derived [`FontMetrics`] of a face.
+    ///
+    /// Ratios come from swash's normalised metrics; when an old OS/2 table
+    /// doesn't declare the x-height or cap height, the top of the `x`/`H`
+    /// outline is measured instead. The width class, family class and PANOSE
+    /// are read straight from the OS/2 table and the italic angle from `post`.
+    /// `stroke_contrast` is left for the rendering stage.
+    fn extract_metrics(font: &FontRef<'_>) -> FontMetrics {
+        let metrics = font.metrics(&[]);
+        let units_per_em = f32::from(metrics.units_per_em.max(1));
+        let ratio = |value: f32| (value > 0.0).then(|| value / units_per_em);
+        let outline_top = |ch: char| {
+            let glyph_id = font.charmap().map(ch);
+            if glyph_id == 0 {
+                return None;
+            }
+            let mut scale_context = ScaleContext::new();
+            let outline = scale_context
+                .builder(*font)
+                .build()
+                .scale_outline(glyph_id)?;
+            Some(outline.bounds().max.y)
+        };
+        let x_height = ratio(metrics.x_height).or_else(|| outline_top('x').and_then(ratio));
+        let cap_height = ratio(metrics.cap_height).or_else(|| outline_top('H').and_then(ratio));
+        let os2 = font.table(tag_from_bytes(b"OS/2"));
+        let read_u16 = |table: &[u8], offset: usize| {
+            table
+                .get(offset..offset + 2)
+                .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+        };
+        let italic_angle = font
+            .table(tag_from_bytes(b"post"))
+            .and_then(|post| post.get(4..8))
+            .map(|bytes| i32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .map(|fixed| fixed as f32 / 65536.0);
+
+        FontMetrics {
+            x_height_ratio: x_height,
+            cap_height_ratio: cap_height,
+            average_advance_ratio: ratio(metrics.average_width),
+            italic_angle,
+            width_class: os2
+                .and_then(|table| read_u16(table, 6))
+                .filter(|class| (1..=9).contains(class)),
+            family_class: os2
+                .and_then(|table| table.get(30..32))
+                .map(|bytes| [bytes[0], bytes[1]]),
+            panose: os2
+                .and_then(|table| table.get(32..42))
+                .and_then(|bytes| bytes.try_into().ok())
+                .filter(|panose: &[u8; 10]| panose[0] != 0),
+            stroke_contrast: None,
+        }
+    }
+
     /// Parses one face and extracts its [`ExtractedMeta`].
     ///
     /// Returns an error (so the caller skips the face) when the font is
-    /// missing a glyph for any character in `target_text`, or when it is an
-    /// internal/system fallback font such as `LastResort` or a dot-prefixed
-    /// family.
+    /// missing a glyph for any character in `target_text` (as
+    /// [`AppError::MissingGlyph`]), or when it is an internal/system fallback
+    /// font such as `LastResort` or a dot-prefixed family.
     pub fn analyze_font_data(
         data: &[u8],
         index: u32,
@@ -168,10 +231,7 @@ impl Discoverer {
         for ch in target_text.chars() {
             let gid = font.charmap().map(ch);
             if gid == 0 && ch != '\0' && ch != '\u{FFFD}' {
-                return Err(AppError::Font(format!(
-                    "Font fallback detected for character '{}' (missing in cmap)",
-                    ch
-                )));
+                return Err(AppError::MissingGlyph(ch));
             }
         }
 
@@ -222,20 +282,56 @@ impl Discoverer {
             available_weights: Vec::new(),
             path,
             font_index: index,
+            metrics: Self::extract_metrics(&font),
         })
     }
 
+    /// Parses one face from the font database, for [`Self::analyze_font_data`].
+    fn extract_face(
+        db: &fontdb::Database,
+        face: &FaceInfo,
+        target_text: &str,
+    ) -> Result<ExtractedMeta> {
+        let path = Self::source_path(&face.source)?;
+        db.with_face_data(face.id, |data, index| {
+            Self::analyze_font_data(data, index, target_text, path, face)
+        })
+        .ok_or_else(|| AppError::Font("The font data could not be loaded".into()))?
+    }
+
+    /// The report entry for a face discovery rejected.
+    fn rejected_face(face: &FaceInfo, error: AppError) -> DroppedFont {
+        DroppedFont {
+            stage: RunStage::Discovery,
+            family_name: face
+                .families
+                .first()
+                .map(|(name, _)| name.clone())
+                .unwrap_or_else(|| face.post_script_name.clone()),
+            face_name: Some(face.post_script_name.clone()),
+            weight: None,
+            missing_weights: Vec::new(),
+            safe_name: None,
+            path: Self::source_path(&face.source).ok(),
+            reason: error,
+        }
+    }
+
     /// Discovers, groups and weight-matches fonts for the active session.
     ///
     /// Pass `google_fonts_dir` when the session uses a Google Fonts corpus
     /// (the directory the fonts were downloaded into); pass `None` to discover
     /// the system fonts. Metadata for each kept font is written to the session
-    /// directory as a side effect, and the session's `discovered_fonts` map is
-    /// updated before returning.
+    /// directory as a side effect, the excluded faces and weights replace the
+    /// session's dropped-font report, and the session's `discovered_fonts`
+    /// map is updated before returning. Fails with
+    /// [`ErrorCode::NoFontsMatched`] when no font is kept at all, and with a
+    /// cancelled error once `cancel` is observed.
     pub async fn discover_fonts(
         &self,
         state: &AppState,
         google_fonts_dir: Option<PathBuf>,
+        cancel: &CancellationToken,
     ) -> Result<DiscoveryResult> {
         let (text, target_weights, session_id, font_set) = {
             let guard = state.current_session.lock().unwrap();
@@ -249,6 +345,7 @@ impl Discoverer {
             )
         };
         let session_dir = AppState::get_session_processing_dir(&session_id)?;
+        reset_dropped_fonts(&session_dir, RunStage::Discovery)?;
 
         let is_google_fonts = !matches!(font_set, crate::config::FontSet::SystemFonts);
         let mut db = fontdb::Database::new();
@@ -270,29 +367,31 @@ impl Discoverer {
         println!("🔍 Found {} font faces", font_faces.len());
 
         // Parsing every face is CPU-bound, so move the owned inputs onto a
-        // blocking thread. `is_cancelled` is the only value that must be
-        // re-derived here, since `state` itself is only borrowed.
-        let is_cancelled = state.is_cancelled.clone();
+        // blocking thread. `cancel` is cloned since it is only borrowed.
+        let cancel_blocking = cancel.clone();
         let discovered = tokio::task::spawn_blocking(move || -> Result<DiscoveryResult> {
-            let all_metas = font_faces
+            let extracted = font_faces
                 .into_par_iter()
                 .filter_map(|face| {
-                    if is_cancelled.load(std::sync::atomic::Ordering::Relaxed) {
+                    if cancel_blocking.is_cancelled() {
                         return None;
                     }
-                    let path = Self::source_path(&face.source).ok()?;
-                    db.with_face_data(face.id, |data, index| {
-                        Self::analyze_font_data(data, index, &text, path, &face)
-                    })?
-                    .ok()
+                    Some(
+                        Self::extract_face(&db, &face, &text)
+                            .map_err(|error| Self::rejected_face(&face, error)),
+                    )
                 })
                 .collect::<Vec<_>>();
 
-            if is_cancelled.load(std::sync::atomic::Ordering::Relaxed) {
-                return Ok(DiscoveryResult {
-                    discovered_fonts: HashMap::new(),
-                    render_sources: HashMap::new(),
-                });
+            cancel_blocking.check()?;
+
+            let mut all_metas = Vec::new();
+            let mut dropped_fonts = Vec::new();
+            for result in extracted {
+                match result {
+                    Ok(meta) => all_metas.push(meta),
+                    Err(dropped) => dropped_fonts.push(dropped),
+                }
             }
 
             println!(
@@ -314,14 +413,16 @@ impl Discoverer {
             let target_weights_ref = &target_weights;
             let session_dir_ref = &session_dir;
 
-            let discovered_pairs: Vec<(i32, String, String, FontRenderSource)> = families
+            let family_results: Vec<(Vec<_>, Vec<DroppedFont>)> = families
                 .into_par_iter()
                 .map(|(family_name, family_metas)| {
-                    if is_cancelled.load(std::sync::atomic::Ordering::Relaxed) {
-                        return Vec::new();
+                    if cancel_blocking.is_cancelled() {
+                        return (Vec::new(), Vec::new());
                     }
 
                     let mut local_discovered = Vec::new();
+                    let mut local_dropped = Vec::new();
+                    let mut missing_weights = Vec::new();
                     let available_weights: Vec<String> = family_metas
                         .iter()
                         .map(|m| format!("Weight({})", m.actual_weight))
@@ -376,17 +477,36 @@ impl Discoverer {
                                 weight: tw,
                                 weights: available_weights.clone(),
                                 font_index: meta.font_index,
+                                metrics: meta.metrics.clone(),
                             };
                             let render_source = FontRenderSource {
                                 path: meta.path.clone(),
                                 font_index: meta.font_index,
                             };
 
-                            if let Err(e) = crate::core::session::save_font_metadata(
+                            // A font an interrupted run already rendered keeps
+                            // its metadata, which carries the stroke contrast
+                            // measured from its sample.
+                            let saved = if is_font_complete(
                                 session_dir_ref,
-                                &font_meta,
+                                &font_meta.safe_name,
+                                FontStage::Rendered,
                             ) {
+                                Ok(())
+                            } else {
+                                crate::core::session::save_font_metadata(
+                                    session_dir_ref,
+                                    &font_meta,
+                                )
+                            };
+                            if let Err(e) = saved {
                                 eprintln!("Failed to save font metadata: {}", e);
+                                local_dropped.push(DroppedFont::weighted_font(
+                                    RunStage::Discovery,
+                                    &family_name,
+                                    tw,
+                                    e.with_font_name(&family_name),
+                                ));
                             } else {
                                 local_discovered.push((
                                     tw,
@@ -395,11 +515,34 @@ impl Discoverer {
                                     render_source,
                                 ));
                             }
+                        } else {
+                            missing_weights.push(tw);
                         }
                     }
-                    local_discovered
+                    if !missing_weights.is_empty() {
+                        let missing = missing_weights
+                            .iter()
+                            .map(|weight| weight.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        let available = family_metas
+                            .iter()
+                            .map(|m| m.actual_weight.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        let message = format!(
+                            "No face within the weight window of {} (available: {})",
+                            missing, available
+                        );
+                        local_dropped.push(DroppedFont::missing_weights(
+                            &family_name,
+                            missing_weights,
+                            AppError::new(ErrorCode::NoWeightMatch, message)
+                                .with_font_name(&family_name),
+                        ));
+                    }
+                    (local_discovered, local_dropped)
                 })
-                .flatten()
                 .collect();
 
             let mut discovered = HashMap::new();
@@ -407,26 +550,25 @@ impl Discoverer {
             for w in &target_weights {
                 discovered.insert(*w, Vec::new());
             }
-            for (tw, family_name, safe_name, render_source) in discovered_pairs {
-                if let Some(list) = discovered.get_mut(&tw) {
-                    list.push(family_name);
+            for (discovered_pairs, family_dropped) in family_results {
+                for (tw, family_name, safe_name, render_source) in discovered_pairs {
+                    if let Some(list) = discovered.get_mut(&tw) {
+                        list.push(family_name);
+                    }
+                    render_sources.insert(safe_name, render_source);
                 }
-                render_sources.insert(safe_name, render_source);
+                dropped_fonts.extend(family_dropped);
             }
             Ok(DiscoveryResult {
                 discovered_fonts: discovered,
                 render_sources,
+                dropped_fonts,
             })
         })
         .await
         .map_err(|e| AppError::Processing(e.to_string()))??;
 
-        if state
-            .is_cancelled
-            .load(std::sync::atomic::Ordering::Relaxed)
-        {
-            return Ok(discovered);
-        }
+        cancel.check()?;
 
         let total_discovered: usize = discovered.discovered_fonts.values().map(|v| v.len()).sum();
         println!(
@@ -436,11 +578,83 @@ impl Discoverer {
         for (w, list) in &discovered.discovered_fonts {
             println!("   Weight {}: {} families", w, list.len());
         }
+        if !discovered.dropped_fonts.is_empty() {
+            println!(
+                "   Excluded {} faces or families",
+                discovered.dropped_fonts.len()
+            );
+        }
+        record_dropped_fonts(
+            &AppState::get_session_processing_dir(&session_id)?,
+            discovered.dropped_fonts.clone(),
+        )?;
 
         state.update_session(|session| {
             session.discovered_fonts = discovered.discovered_fonts.clone();
         })?;
 
+        if total_discovered == 0 {
+            return Err(AppError::new(
+                ErrorCode::NoFontsMatched,
+                "No font has a glyph for every character of the sample text at the requested \
+                 weights; see the dropped-font report",
+            )
+            .with_stage(RunStage::Discovery));
+        }
         Ok(discovered)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal TrueType face; see `tests/fixtures/make_fixture_font.py`.
+    const FIXTURE_FONT: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/FixtureSans.ttf"
+    );
+
+    #[test]
+    fn extracts_metrics_from_os2_and_post() {
+        let data = std::fs::read(FIXTURE_FONT).unwrap();
+        let font = FontRef::from_index(&data, 0).unwrap();
+        let metrics = Discoverer::extract_metrics(&font);
+
+        assert_eq!(metrics.x_height_ratio, Some(0.5));
+        assert_eq!(metrics.cap_height_ratio, Some(0.7));
+        assert_eq!(metrics.average_advance_ratio, Some(0.5));
+        assert_eq!(metrics.italic_angle, Some(0.0));
+        assert_eq!(metrics.width_class, Some(5));
+        assert_eq!(metrics.family_class, Some([0, 0]));
+        // An all-zero PANOSE means "any" and is not reported.
+        assert_eq!(metrics.panose, None);
+        // Contrast comes from the rendered sample, not the font tables.
+        assert_eq!(metrics.stroke_contrast, None);
+    }
+
+    #[test]
+    fn falls_back_to_outline_heights_without_os2_values() {
+        let mut data = std::fs::read(FIXTURE_FONT).unwrap();
+        // Zero sxHeight and sCapHeight (OS/2 v2+ offsets 86 and 88).
+        let os2 = table_offset(&data, b"OS/2");
+        data[os2 + 86..os2 + 90].fill(0);
+        let font = FontRef::from_index(&data, 0).unwrap();
+        let metrics = Discoverer::extract_metrics(&font);
+
+        // The fixture has no `x`, so only the cap height can be measured.
+        assert_eq!(metrics.x_height_ratio, None);
+        assert_eq!(metrics.cap_height_ratio, Some(0.7));
+    }
+
+    fn table_offset(data: &[u8], tag: &[u8; 4]) -> usize {
+        let num_tables = u16::from_be_bytes([data[4], data[5]]) as usize;
+        (0..num_tables)
+            .map(|index| 12 + index * 16)
+            .find(|record| &data[*record..*record + 4] == tag)
+            .map(|record| {
+                u32::from_be_bytes(data[record + 8..record + 12].try_into().unwrap()) as usize
+            })
+            .unwrap()
+    }
+}
@@ -0,0 +1,286 @@
+//! The report of fonts a session's pipeline excluded, and why.
+//!
+//! Fonts leave the pipeline at several points: discovery rejects faces it
+//! cannot read, that lack a glyph of the sample text or that are internal
+//! system fallbacks, and skips requested weights a family has no face close
+//! enough to; rendering and analysis drop fonts that fail. Each exclusion is
+//! saved as a [`DroppedFont`] in the session's `dropped_fonts.json`, so the
+//! report describes the session's current outputs and travels with its
+//! document. A stage that runs again first clears its own entries and those
+//! of every later stage, keeping the entries of the stages it reuses.
+
+use crate::config::FontMetadata;
+use crate::core::run_log::RunStage;
+use crate::core::session::load_font_metadata;
+use crate::error::{AppError, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the dropped-font report inside a session directory/document.
+pub const DROPPED_FONTS_FILE: &str = "dropped_fonts.json";
+
+/// One font or face a pipeline stage excluded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DroppedFont {
+    pub stage: RunStage,
+    /// Family the font belongs to; for a face discovery could not read, the
+    /// family its font database entry names.
+    pub family_name: String,
+    /// PostScript name of the rejected face, for faces discovery rejected.
+    #[serde(default)]
+    pub face_name: Option<String>,
+    /// Requested weight the exclusion concerns; `None` when discovery
+    /// rejected the face for every weight or skipped `missing_weights`.
+    #[serde(default)]
+    pub weight: Option<i32>,
+    /// Requested weights the family has no face close enough to, for the
+    /// single entry discovery records per such family.
+    #[serde(default)]
+    pub missing_weights: Vec<i32>,
+    /// `safe_name` of a font discovery kept before a later stage dropped it.
+    #[serde(default)]
+    pub safe_name: Option<String>,
+    /// Font file the face came from, when known.
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+    /// The error that excluded the font.
+    pub reason: AppError,
+}
+
+impl DroppedFont {
+    /// A font discovery kept under `safe_name` that `stage` then dropped. The
+    /// family and weight come from its saved metadata when it is readable.
+    pub fn kept_font(
+        session_dir: &Path,
+        stage: RunStage,
+        safe_name: &str,
+        reason: AppError,
+    ) -> Self {
+        let metadata = load_font_metadata(session_dir, safe_name).ok();
+        Self {
+            stage,
+            family_name: metadata
+                .as_ref()
+                .map_or_else(|| safe_name.to_string(), |meta| meta.family_name.clone()),
+            face_name: None,
+            weight: metadata.as_ref().map(|meta| meta.weight),
+            missing_weights: Vec::new(),
+            safe_name: Some(safe_name.to_string()),
+            path: None,
+            reason,
+        }
+    }
+
+    /// A weight-matched font `stage` dropped, when its family and weight are
+    /// already at hand.
+    pub fn weighted_font(
+        stage: RunStage,
+        family_name: &str,
+        weight: i32,
+        reason: AppError,
+    ) -> Self {
+        Self {
+            stage,
+            family_name: family_name.to_string(),
+            face_name: None,
+            weight: Some(weight),
+            missing_weights: Vec::new(),
+            safe_name: Some(FontMetadata::generate_safe_name(family_name, weight)),
+            path: None,
+            reason,
+        }
+    }
+
+    /// The requested weights discovery skipped for a family that has no face
+    /// close enough to them.
+    pub fn missing_weights(family_name: &str, missing_weights: Vec<i32>, reason: AppError) -> Self {
+        Self {
+            stage: RunStage::Discovery,
+            family_name: family_name.to_string(),
+            face_name: None,
+            weight: None,
+            missing_weights,
+            safe_name: None,
+            path: None,
+            reason,
+        }
+    }
+}
+
+/// Reads the session's dropped-font report; empty for a session processed
+/// before the report existed.
+pub fn read_dropped_fonts(session_dir: &Path) -> Result<Vec<DroppedFont>> {
+    let path = session_dir.join(DROPPED_FONTS_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+/// Adds `dropped` to the session's report.
+pub fn record_dropped_fonts(session_dir: &Path, dropped: Vec<DroppedFont>) -> Result<()> {
+    if dropped.is_empty() {
+        return Ok(());
+    }
+    let mut report = read_dropped_fonts(session_dir)?;
+    report.extend(dropped);
+    write_dropped_fonts(session_dir, &report)
+}
+
+/// Removes the entries of `stage` and every later stage, before `stage` runs
+/// again.
+pub fn reset_dropped_fonts(session_dir: &Path, stage: RunStage) -> Result<()> {
+    let mut report = read_dropped_fonts(session_dir)?;
+    let len = report.len();
+    report.retain(|dropped| dropped.stage < stage);
+    if report.len() == len {
+        return Ok(());
+    }
+    write_dropped_fonts(session_dir, &report)
+}
+
+fn write_dropped_fonts(session_dir: &Path, report: &[DroppedFont]) -> Result<()> {
+    fs::write(
+        session_dir.join(DROPPED_FONTS_FILE),
+        serde_json::to_string_pretty(report)?,
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCode;
+    use serde_json::json;
+
+    fn dropped(stage: RunStage, family_name: &str) -> DroppedFont {
+        DroppedFont::weighted_font(
+            stage,
+            family_name,
+            400,
+            AppError::new(ErrorCode::Processing, "dropped"),
+        )
+    }
+
+    fn families(session_dir: &Path) -> Vec<(RunStage, String)> {
+        read_dropped_fonts(session_dir)
+            .unwrap()
+            .into_iter()
+            .map(|dropped| (dropped.stage, dropped.family_name))
+            .collect()
+    }
+
+    fn entry(stage: RunStage, family_name: &str) -> (RunStage, String) {
+        (stage, family_name.to_string())
+    }
+
+    #[test]
+    fn records_each_stage_after_the_previous_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(read_dropped_fonts(dir.path()).unwrap().is_empty());
+        record_dropped_fonts(dir.path(), Vec::new()).unwrap();
+        assert!(!dir.path().join(DROPPED_FONTS_FILE).exists());
+
+        record_dropped_fonts(dir.path(), vec![dropped(RunStage::Discovery, "A")]).unwrap();
+        record_dropped_fonts(
+            dir.path(),
+            vec![
+                dropped(RunStage::Rendering, "B"),
+                dropped(RunStage::Rendering, "C"),
+            ],
+        )
+        .unwrap();
+        record_dropped_fonts(dir.path(), vec![dropped(RunStage::Analysis, "D")]).unwrap();
+
+        assert_eq!(
+            families(dir.path()),
+            [
+                entry(RunStage::Discovery, "A"),
+                entry(RunStage::Rendering, "B"),
+                entry(RunStage::Rendering, "C"),
+                entry(RunStage::Analysis, "D"),
+            ]
+        );
+    }
+
+    #[test]
+    fn reset_clears_the_rerun_stage_and_later_ones_only() {
+        let dir = tempfile::tempdir().unwrap();
+        record_dropped_fonts(
+            dir.path(),
+            vec![
+                dropped(RunStage::Discovery, "A"),
+                dropped(RunStage::Rendering, "B"),
+                dropped(RunStage::Analysis, "C"),
+            ],
+        )
+        .unwrap();
+
+        reset_dropped_fonts(dir.path(), RunStage::Clustering).unwrap();
+        assert_eq!(families(dir.path()).len(), 3);
+
+        reset_dropped_fonts(dir.path(), RunStage::Rendering).unwrap();
+        assert_eq!(families(dir.path()), [entry(RunStage::Discovery, "A")]);
+
+        reset_dropped_fonts(dir.path(), RunStage::Discovery).unwrap();
+        assert!(families(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn kept_font_reads_its_family_and_weight_from_the_saved_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let safe_name = FontMetadata::generate_safe_name("Noto Serif", 700);
+        let font_dir = dir.path().join("samples").join(&safe_name);
+        fs::create_dir_all(&font_dir).unwrap();
+        let metadata = json!({
+            "source": "system",
+            "safe_name": safe_name,
+            "font_name": "Noto Serif Bold",
+            "family_name": "Noto Serif",
+            "family_names": {},
+            "preferred_family_names": {},
+            "style_name": "Bold",
+            "style_names": {},
+            "preferred_style_names": {},
+            "publishers": {},
+            "designers": {},
+            "weight": 700,
+            "weights": [],
+            "font_index": 0,
+        });
+        fs::write(font_dir.join("meta.json"), metadata.to_string()).unwrap();
+
+        let reason = AppError::new(ErrorCode::Image, "Sample could not be decoded");
+        let dropped = DroppedFont::kept_font(dir.path(), RunStage::Analysis, &safe_name, reason);
+        assert_eq!(dropped.stage, RunStage::Analysis);
+        assert_eq!(dropped.family_name, "Noto Serif");
+        assert_eq!(dropped.weight, Some(700));
+        assert_eq!(dropped.safe_name.as_deref(), Some(safe_name.as_str()));
+        assert_eq!(dropped.reason.code(), ErrorCode::Image);
+
+        let reason = AppError::new(ErrorCode::Io, "Sample is missing");
+        let dropped = DroppedFont::kept_font(dir.path(), RunStage::Rendering, "400_Gone", reason);
+        assert_eq!(dropped.family_name, "400_Gone");
+        assert_eq!(dropped.weight, None);
+        assert_eq!(dropped.reason.code(), ErrorCode::Io);
+    }
+
+    #[test]
+    fn weighted_and_missing_weight_entries_keep_their_reason() {
+        let reason = AppError::new(ErrorCode::MissingGlyph, "No glyph for 'あ'");
+        let dropped = DroppedFont::weighted_font(RunStage::Rendering, "Noto Sans", 300, reason);
+        assert_eq!(dropped.weight, Some(300));
+        assert_eq!(dropped.safe_name.as_deref(), Some("300_Noto_Sans"));
+        assert_eq!(dropped.reason.code(), ErrorCode::MissingGlyph);
+        assert_eq!(dropped.reason.to_string(), "No glyph for 'あ'");
+
+        let reason = AppError::new(ErrorCode::NoWeightMatch, "No face within the window");
+        let dropped = DroppedFont::missing_weights("Noto Sans", vec![100, 900], reason);
+        assert_eq!(dropped.stage, RunStage::Discovery);
+        assert_eq!(dropped.weight, None);
+        assert_eq!(dropped.missing_weights, [100, 900]);
+        assert_eq!(dropped.safe_name, None);
+        assert_eq!(dropped.reason.code(), ErrorCode::NoWeightMatch);
+    }
+}
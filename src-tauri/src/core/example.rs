@@ -5,7 +5,7 @@
 //! sees a populated graph on first launch.
 
 use crate::core::{
-    read_session_config_from_document, rewrite_document_config, AppState,
+    migrate_session_document, read_session_config_from_document, rewrite_document_config, AppState,
     SESSION_DOCUMENT_EXTENSION,
 };
 use crate::error::Result;
@@ -70,11 +70,12 @@ pub fn extract_example_session(app: &AppHandle) -> Result<Option<String>> {
                 e
             ))
         })?;
-        // Re-stamp the copied example with the running app version so it is never
-        // pruned as "unsupported" on a later launch. This decouples the bundled
+        // Bring the copied example up to the current session schema, then
+        // re-stamp it with the running app version. This decouples the bundled
         // document's baked-in version from the release version, so the example can
         // be authored once and shipped as-is without hand-editing or a throwaway
         // version bump after release.
+        migrate_session_document(&dest_path)?;
         if let Ok(mut session) = read_session_config_from_document(&dest_path) {
             let app_version = env!("CARGO_PKG_VERSION").to_string();
             session.app_version = app_version.clone();
@@ -7,7 +7,7 @@
 //! both to populate a session's corpus and to render single previews.
 
 use crate::config::FontSet;
-use crate::core::AppState;
+use crate::core::{AppState, CancellationToken};
 use crate::error::{AppError, Result};
 use reqwest::blocking::Client;
 use serde::Deserialize;
@@ -261,11 +261,13 @@ impl GoogleFontsDownloader {
     }
 
     /// Downloads every matching font for the active session into `output_dir`,
-    /// returning the paths written. A no-op for system-font sessions.
+    /// returning the paths written. A no-op for system-font sessions; fails
+    /// with a cancelled error once `cancel` is observed.
     pub async fn download_fonts_to_dir(
         &self,
         state: &AppState,
         output_dir: PathBuf,
+        cancel: &CancellationToken,
     ) -> Result<Vec<PathBuf>> {
         let (font_set, text, target_weights) = {
             let guard = state.current_session.lock().unwrap();
@@ -280,8 +282,9 @@ impl GoogleFontsDownloader {
                 rendering.weights.clone(),
             )
         };
+        let cancel = cancel.clone();
         tokio::task::spawn_blocking(move || {
-            download_fonts_impl(&font_set, &text, &output_dir, &target_weights)
+            download_fonts_impl(&font_set, &text, &output_dir, &target_weights, &cancel)
         })
         .await
         .map_err(|e| AppError::Processing(e.to_string()))?
@@ -292,12 +295,16 @@ impl GoogleFontsDownloader {
 ///
 /// Candidates are narrowed by subset coverage and available weights, capped to
 /// the count implied by `font_set`, then every `(font, weight)` is fetched
-/// concurrently. Individual download failures are logged and skipped.
+/// concurrently. Individual download failures are logged and skipped. Each
+/// download waits while `cancel` is paused; once it is cancelled no further
+/// download starts, and the in-flight ones finish before the cancelled error
+/// is returned.
 fn download_fonts_impl(
     font_set: &FontSet,
     target_text: &str,
     output_dir: &Path,
     target_weights: &[i32],
+    cancel: &CancellationToken,
 ) -> Result<Vec<PathBuf>> {
     let all_fonts = load_google_fonts_metadata()?;
 
@@ -360,6 +367,9 @@ fn download_fonts_impl(
         let client = Arc::clone(&client);
 
         for &req_weight in target_weights {
+            if cancel.checkpoint().is_err() {
+                return;
+            }
             let Some(api_weight) = google_font_api_weight(req_weight, &font.variants) else {
                 continue;
             };
@@ -388,6 +398,7 @@ fn download_fonts_impl(
             }
         }
     });
+    cancel.check()?;
 
     let paths = Arc::try_unwrap(downloaded_paths)
         .unwrap()
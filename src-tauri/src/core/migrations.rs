@@ -0,0 +1,878 @@
+//! Step-by-step upgrades of stored sessions to the current on-disk schema.
+//!
+//! Every session records the `schema_version` of its layout in `config.json`.
+//! Sessions written before that field existed are schema 1 when written by
+//! 0.26.1 or later and schema 0 otherwise. [`MIGRATIONS`]
+//! holds one step per schema version; each step rewrites the raw JSON of the
+//! session's `config.json` and every font's `meta.json`/`computed.json`, so it
+//! works even when the old layout no longer deserialises into the current
+//! types. After the last step the session must load with the current types,
+//! and only then is anything written back.
+//!
+//! [`migrate_stored_sessions`] runs at startup over every document in
+//! `Generated` and every processing directory. A session that cannot be
+//! migrated is moved to the `Quarantine` directory instead of being deleted,
+//! and each run that touched anything is appended to a report readable with
+//! [`read_migration_report`]. Sessions from a newer build are left untouched.
+
+use crate::config::{
+    AlgorithmConfig, ComputedData, FontMetadata, ProcessStatus, ProcessingStatus, SessionConfig,
+};
+use crate::core::session::{
+    extract_document_to_dir, has_session_config, is_session_document_path, load_dendrogram,
+    pack_dir_to_document, read_session_config_from_dir, read_session_config_from_document,
+    remove_dir_all_best_effort, rewrite_document_config, save_computed_data, save_font_metadata,
+    write_session_config_atomic, PackMode,
+};
+use crate::core::AppState;
+use crate::error::{AppError, Result};
+use chrono::{DateTime, Utc};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Schema version written by this build.
+pub const CURRENT_SESSION_SCHEMA: u32 = 1;
+/// Directory under the base dir holding sessions that failed to migrate.
+pub(crate) const QUARANTINE_DIR: &str = "Quarantine";
+/// File under the base dir recording past migration runs.
+const MIGRATION_REPORT_FILE: &str = "migration_report.json";
+/// How many migration runs the report keeps.
+const MIGRATION_REPORT_RUN_LIMIT: usize = 50;
+
+/// One upgrade step, from schema `from` to `from + 1`.
+struct Migration {
+    from: u32,
+    description: &'static str,
+    apply: fn(&mut SessionFiles) -> Result<()>,
+}
+
+/// Every step, in schema order.
+const MIGRATIONS: &[Migration] = &[Migration {
+    from: 0,
+    description: "Upgrade pre-0.26.1 layout: fill missing config and font metadata fields, \
+                  drop incomplete clustering results",
+    apply: migrate_legacy_layout,
+}];
+
+/// Raw JSON of a session directory while it is being migrated.
+struct SessionFiles {
+    dir: PathBuf,
+    config: Value,
+    /// `meta.json` and `computed.json` (if present) per font, by `safe_name`.
+    fonts: BTreeMap<String, (Value, Option<Value>)>,
+}
+
+/// What happened to one stored session during a migration run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationEntry {
+    /// Session id, when the config was readable.
+    pub session_id: Option<String>,
+    /// Where the session was found.
+    pub path: PathBuf,
+    /// Schema the session was stored with, when it could be determined.
+    pub from_schema: Option<u32>,
+    #[serde(flatten)]
+    pub outcome: MigrationOutcome,
+}
+
+/// The result of migrating one session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum MigrationOutcome {
+    /// Upgraded in place; `steps` describes each applied migration.
+    Migrated { steps: Vec<String> },
+    /// Could not be migrated and was moved to `quarantine_path` (or left in
+    /// place when even that failed).
+    Quarantined {
+        reason: String,
+        quarantine_path: Option<PathBuf>,
+    },
+    /// Written by a newer build; left untouched.
+    Skipped { reason: String },
+}
+
+/// One startup migration run that found something to do.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationRun {
+    pub ran_at: DateTime<Utc>,
+    pub app_version: String,
+    pub entries: Vec<MigrationEntry>,
+}
+
+/// Result of bringing a single document up to date.
+pub enum DocumentMigration {
+    /// Already at [`CURRENT_SESSION_SCHEMA`].
+    Current,
+    /// Upgraded from `from`, applying `steps`.
+    Migrated { from: u32, steps: Vec<String> },
+    /// Written by a build with a newer schema.
+    Newer(u32),
+}
+
+/// Migrates every stored session to [`CURRENT_SESSION_SCHEMA`], quarantining
+/// those that cannot be migrated, and appends the run to the report when it
+/// did anything.
+///
+/// Stray non-document files in `Generated` (e.g. temp files of an interrupted
+/// write) are removed, as before migrations existed.
+pub fn migrate_stored_sessions() -> Result<MigrationRun> {
+    let mut entries = Vec::new();
+    let quarantine_dir = AppState::get_base_dir()?.join(QUARANTINE_DIR);
+
+    let generated_dir = AppState::get_generated_dir()?;
+    if generated_dir.exists() {
+        for entry in fs::read_dir(&generated_dir)? {
+            let path = entry?.path();
+            if !is_session_document_path(&path) {
+                if path.is_dir() {
+                    fs::remove_dir_all(&path)?;
+                } else {
+                    fs::remove_file(&path)?;
+                }
+                continue;
+            }
+            entries.extend(migrate_stored_document(path, &quarantine_dir));
+        }
+    }
+
+    let processing_root = AppState::get_session_processing_root()?;
+    if processing_root.exists() {
+        for entry in fs::read_dir(&processing_root)? {
+            let path = entry?.path();
+            let is_hidden = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with('.'));
+            // Directories without a config are aborted creations, which
+            // storage reconciliation removes.
+            if is_hidden || !path.is_dir() || !has_session_config(&path) {
+                continue;
+            }
+            entries.extend(migrate_processing_dir(path, &quarantine_dir));
+        }
+    }
+
+    let run = MigrationRun {
+        ran_at: Utc::now(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        entries,
+    };
+    for entry in &run.entries {
+        match &entry.outcome {
+            MigrationOutcome::Migrated { .. } => {
+                println!("🔄 Migrated session {}", entry.path.display())
+            }
+            MigrationOutcome::Quarantined { reason, .. } => eprintln!(
+                "⚠️ Quarantined session {}: {}",
+                entry.path.display(),
+                reason
+            ),
+            MigrationOutcome::Skipped { reason } => {
+                println!("⏭️ Skipped session {}: {}", entry.path.display(), reason)
+            }
+        }
+    }
+    if !run.entries.is_empty() {
+        append_migration_report(&run)?;
+    }
+    Ok(run)
+}
+
+/// Migrates one stored document, quarantining it on failure. `None` when it
+/// was already current.
+fn migrate_stored_document(path: PathBuf, quarantine_dir: &Path) -> Option<MigrationEntry> {
+    let config = read_document_config_value(&path).ok();
+    let from_schema = config.as_ref().map(stored_schema);
+    let outcome = match migrate_session_document(&path) {
+        Ok(DocumentMigration::Current) => return None,
+        Ok(DocumentMigration::Migrated { steps, .. }) => MigrationOutcome::Migrated { steps },
+        Ok(DocumentMigration::Newer(schema)) => MigrationOutcome::Skipped {
+            reason: newer_schema_message(schema),
+        },
+        Err(error) => quarantine(&path, quarantine_dir, error),
+    };
+    Some(MigrationEntry {
+        session_id: config.as_ref().and_then(session_id_of),
+        path,
+        from_schema,
+        outcome,
+    })
+}
+
+/// Migrates one processing directory in place, quarantining it on failure.
+/// `None` when it was already current.
+fn migrate_processing_dir(path: PathBuf, quarantine_dir: &Path) -> Option<MigrationEntry> {
+    let config = read_dir_config_value(&path).ok();
+    let from_schema = config.as_ref().map(stored_schema);
+    let is_stamped = config
+        .as_ref()
+        .is_some_and(|config| config.get("schema_version").is_some());
+    let outcome = match from_schema {
+        Some(CURRENT_SESSION_SCHEMA) if is_stamped => return None,
+        Some(CURRENT_SESSION_SCHEMA) => {
+            match read_session_config_from_dir(&path).and_then(|session| {
+                stamp_schema_version(session, |session| {
+                    write_session_config_atomic(session, &path)
+                })
+            }) {
+                Ok(()) => return None,
+                Err(error) => quarantine(&path, quarantine_dir, error),
+            }
+        }
+        Some(schema) if schema > CURRENT_SESSION_SCHEMA => MigrationOutcome::Skipped {
+            reason: newer_schema_message(schema),
+        },
+        _ => match from_schema
+            .ok_or_else(|| AppError::Processing("Unreadable session config".into()))
+            .and_then(|schema| migrate_session_dir(&path, schema))
+        {
+            Ok(steps) => MigrationOutcome::Migrated { steps },
+            Err(error) => quarantine(&path, quarantine_dir, error),
+        },
+    };
+    Some(MigrationEntry {
+        session_id: config.as_ref().and_then(session_id_of),
+        path,
+        from_schema,
+        outcome,
+    })
+}
+
+/// Brings the document at `path` up to [`CURRENT_SESSION_SCHEMA`], rewriting
+/// it atomically. Errors when the document is unreadable or a migration step
+/// fails; the document is then left as it was.
+pub fn migrate_session_document(path: &Path) -> Result<DocumentMigration> {
+    let config = read_document_config_value(path)?;
+    let schema = stored_schema(&config);
+    if schema == CURRENT_SESSION_SCHEMA {
+        if config.get("schema_version").is_none() {
+            stamp_schema_version(read_session_config_from_document(path)?, |session| {
+                rewrite_document_config(path, session)
+            })?;
+        }
+        return Ok(DocumentMigration::Current);
+    }
+    if schema > CURRENT_SESSION_SCHEMA {
+        return Ok(DocumentMigration::Newer(schema));
+    }
+
+    let cache_root = AppState::get_session_cache_root()?;
+    fs::create_dir_all(&cache_root)?;
+    let staging = tempfile::Builder::new()
+        .prefix(".migrate-")
+        .tempdir_in(&cache_root)
+        .map_err(|e| {
+            AppError::Io(format!(
+                "Failed to create migration staging dir in {}: {}",
+                cache_root.display(),
+                e
+            ))
+        })?;
+    extract_document_to_dir(path, staging.path())?;
+    let steps = migrate_session_dir(staging.path(), schema)?;
+    pack_dir_to_document(staging.path(), path, PackMode::Standard)?;
+    Ok(DocumentMigration::Migrated {
+        from: schema,
+        steps,
+    })
+}
+
+/// Records [`CURRENT_SESSION_SCHEMA`] in a session whose layout is already
+/// current but predates `schema_version`, so it isn't read as schema 0 once
+/// the typed config is written back. Nothing else changes.
+fn stamp_schema_version(
+    mut session: SessionConfig,
+    write: impl FnOnce(&SessionConfig) -> Result<()>,
+) -> Result<()> {
+    session.schema_version = CURRENT_SESSION_SCHEMA;
+    write(&session)
+}
+
+/// Human-readable reason for leaving a newer session alone.
+pub fn newer_schema_message(schema: u32) -> String {
+    format!(
+        "Written by a newer FontCluster (session schema {}, this version reads up to {})",
+        schema, CURRENT_SESSION_SCHEMA
+    )
+}
+
+/// Returns every recorded migration run, oldest first.
+pub fn read_migration_report() -> Result<Vec<MigrationRun>> {
+    let path = AppState::get_base_dir()?.join(MIGRATION_REPORT_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(&path)?)?)
+}
+
+/// Applies every migration from `schema` onwards to the session in `dir`,
+/// then validates the result against the current types and writes it back.
+fn migrate_session_dir(dir: &Path, mut schema: u32) -> Result<Vec<String>> {
+    let mut files = SessionFiles::load(dir)?;
+    let mut steps = Vec::new();
+    while schema < CURRENT_SESSION_SCHEMA {
+        let migration = MIGRATIONS
+            .iter()
+            .find(|migration| migration.from == schema)
+            .ok_or_else(|| {
+                AppError::Processing(format!("No migration from session schema {}", schema))
+            })?;
+        (migration.apply)(&mut files)?;
+        steps.push(migration.description.to_string());
+        schema += 1;
+    }
+
+    let config = files
+        .config
+        .as_object_mut()
+        .ok_or_else(|| AppError::Processing("Session config is not a JSON object".into()))?;
+    config.insert("schema_version".into(), json!(CURRENT_SESSION_SCHEMA));
+    config.insert(
+        "modified_app_version".into(),
+        json!(env!("CARGO_PKG_VERSION")),
+    );
+    files.validate_and_save()?;
+    Ok(steps)
+}
+
+impl SessionFiles {
+    fn load(dir: &Path) -> Result<Self> {
+        let config = read_dir_config_value(dir)?;
+        let mut fonts = BTreeMap::new();
+        let samples_dir = dir.join("samples");
+        if samples_dir.exists() {
+            for entry in fs::read_dir(&samples_dir)? {
+                let font_dir = entry?.path();
+                let Some(safe_name) = font_dir.file_name().and_then(|name| name.to_str()) else {
+                    continue;
+                };
+                let meta_path = font_dir.join("meta.json");
+                if !font_dir.is_dir() || !meta_path.exists() {
+                    continue;
+                }
+                let meta = read_json(&meta_path)?;
+                let computed_path = font_dir.join("computed.json");
+                let computed = if computed_path.exists() {
+                    Some(read_json(&computed_path)?)
+                } else {
+                    None
+                };
+                fonts.insert(safe_name.to_string(), (meta, computed));
+            }
+        }
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            config,
+            fonts,
+        })
+    }
+
+    /// Parses everything with the current types (and, for a clustered
+    /// session, its dendrogram) before writing any of it back.
+    fn validate_and_save(self) -> Result<()> {
+        let invalid = |what: &str, error: serde_json::Error| {
+            AppError::Processing(format!("Migrated {what} is still invalid: {error}"))
+        };
+        let session: SessionConfig =
+            serde_json::from_value(self.config).map_err(|e| invalid("config.json", e))?;
+        let mut fonts = Vec::with_capacity(self.fonts.len());
+        for (safe_name, (meta, computed)) in self.fonts {
+            let meta: FontMetadata = serde_json::from_value(meta)
+                .map_err(|e| invalid(&format!("{safe_name}/meta.json"), e))?;
+            let computed: Option<ComputedData> =
+                computed
+                    .map(serde_json::from_value)
+                    .transpose()
+                    .map_err(|e| invalid(&format!("{safe_name}/computed.json"), e))?;
+            fonts.push((safe_name, meta, computed));
+        }
+        if session.status.process_status == ProcessStatus::Clustered {
+            load_dendrogram(&self.dir)?;
+        }
+
+        for (safe_name, meta, computed) in fonts {
+            save_font_metadata(&self.dir, &meta)?;
+            if let Some(computed) = computed {
+                save_computed_data(&self.dir, &safe_name, &computed)?;
+            }
+        }
+        write_session_config_atomic(&session, &self.dir)
+    }
+}
+
+/// Schema 0 → 1: sessions from before 0.26.1.
+///
+/// Fields added since are filled with the defaults new sessions get. Per-font
+/// clustering results missing the dendrogram layout (`leaf_angle`,
+/// `cluster_angle`, `color_index`) cannot be reconstructed and are dropped;
+/// if that leaves a "clustered" session without complete results or without
+/// `dendrogram.json`, its status falls back to the last stage whose outputs
+/// are all present, so re-running it only redoes what is missing.
+fn migrate_legacy_layout(files: &mut SessionFiles) -> Result<()> {
+    if !files.config.is_object() {
+        return Err(AppError::Processing(
+            "Session config is not a JSON object".into(),
+        ));
+    }
+    let created_at = files
+        .config
+        .get("created_at")
+        .cloned()
+        .unwrap_or_else(|| json!(Utc::now()));
+    fill_missing(
+        &mut files.config,
+        &json!({
+            "title": "",
+            "created_at": created_at,
+            "modified_at": created_at,
+            "discovered_fonts": {},
+            "algorithm": AlgorithmConfig::default(),
+            "status": ProcessingStatus::default(),
+        }),
+    );
+
+    let mut has_incomplete_clustering = false;
+    for (safe_name, (meta, computed)) in &mut files.fonts {
+        fill_missing(
+            meta,
+            &json!({
+                "source": "system",
+                "safe_name": safe_name,
+                "style_name": "",
+                "family_names": {},
+                "preferred_family_names": {},
+                "style_names": {},
+                "preferred_style_names": {},
+                "publishers": {},
+                "designers": {},
+                "weights": [],
+                "font_index": 0,
+            }),
+        );
+        let Some(computed) = computed.as_mut().and_then(Value::as_object_mut) else {
+            has_incomplete_clustering = true;
+            continue;
+        };
+        let clustering_is_complete = computed
+            .get("clustering")
+            .and_then(Value::as_object)
+            .is_some_and(|clustering| {
+                ["k", "leaf_angle", "cluster_angle", "color_index"]
+                    .iter()
+                    .all(|key| clustering.contains_key(*key))
+            });
+        if !clustering_is_complete {
+            computed.remove("clustering");
+            has_incomplete_clustering = true;
+        }
+    }
+
+    let is_clustered = files.config["status"]["process_status"] == json!(ProcessStatus::Clustered);
+    let has_dendrogram = files.dir.join("dendrogram.json").exists();
+    if is_clustered && (has_incomplete_clustering || !has_dendrogram) {
+        let all_fonts_have = |file: &str| {
+            files.fonts.keys().all(|safe_name| {
+                files
+                    .dir
+                    .join("samples")
+                    .join(safe_name)
+                    .join(file)
+                    .exists()
+            })
+        };
+        let status = if files.fonts.is_empty() {
+            ProcessStatus::Empty
+        } else if all_fonts_have("vector.bin") {
+            ProcessStatus::Analyzed
+        } else if all_fonts_have("sample.png") {
+            ProcessStatus::Rendered
+        } else {
+            ProcessStatus::Empty
+        };
+        files.config["status"]["process_status"] = json!(status);
+        files.config["status"]["clusters_amount"] = json!(0);
+        files.config["status"]["clustering_stats"] =
+            json!(ProcessingStatus::default().clustering_stats);
+        for (_, computed) in files.fonts.values_mut() {
+            if let Some(computed) = computed.as_mut().and_then(Value::as_object_mut) {
+                computed.remove("clustering");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Recursively inserts every key of `defaults` that `target` lacks, leaving
+/// existing values alone.
+fn fill_missing(target: &mut Value, defaults: &Value) {
+    let (Some(target), Some(defaults)) = (target.as_object_mut(), defaults.as_object()) else {
+        return;
+    };
+    for (key, default) in defaults {
+        match target.get_mut(key) {
+            Some(existing) => fill_missing(existing, default),
+            None => {
+                target.insert(key.clone(), default.clone());
+            }
+        }
+    }
+}
+
+/// The schema a session was stored with; see the module docs for sessions
+/// that predate `schema_version`. A session without any readable version is
+/// assumed to be as old as possible.
+fn stored_schema(config: &Value) -> u32 {
+    if let Some(schema) = config.get("schema_version").and_then(Value::as_u64) {
+        return u32::try_from(schema).unwrap_or(u32::MAX);
+    }
+    // The layout in use when `schema_version` was introduced dates from 0.26.1.
+    let schema_1 = Version::new(0, 26, 1);
+    let version = ["modified_app_version", "app_version"]
+        .iter()
+        .filter_map(|key| config.get(*key).and_then(Value::as_str))
+        .find_map(|version| Version::parse(version.trim_start_matches('v')).ok());
+    match version {
+        Some(version) if version >= schema_1 => 1,
+        _ => 0,
+    }
+}
+
+fn session_id_of(config: &Value) -> Option<String> {
+    config
+        .get("session_id")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+/// Moves a session that failed to migrate into `quarantine_dir`.
+///
+/// Documents are moved as-is; processing directories (which live on the
+/// cache volume) are packed into a document there and then removed.
+fn quarantine(path: &Path, quarantine_dir: &Path, error: AppError) -> MigrationOutcome {
+    let reason = error.to_string();
+    let quarantine_path = (|| -> Result<PathBuf> {
+        fs::create_dir_all(quarantine_dir)?;
+        let file_name = path
+            .file_stem()
+            .and_then(|name| name.to_str())
+            .unwrap_or("session");
+        let mut destination = quarantine_dir.join(format!(
+            "{file_name}.{}",
+            crate::core::SESSION_DOCUMENT_EXTENSION
+        ));
+        if destination.exists() {
+            destination = quarantine_dir.join(format!(
+                "{file_name}-{}.{}",
+                Utc::now().format("%Y%m%d%H%M%S"),
+                crate::core::SESSION_DOCUMENT_EXTENSION
+            ));
+        }
+        if path.is_dir() {
+            pack_dir_to_document(path, &destination, PackMode::Standard)?;
+            remove_dir_all_best_effort(path);
+        } else if fs::rename(path, &destination).is_err() {
+            fs::copy(path, &destination)?;
+            fs::remove_file(path)?;
+        }
+        Ok(destination)
+    })();
+    match quarantine_path {
+        Ok(quarantine_path) => MigrationOutcome::Quarantined {
+            reason,
+            quarantine_path: Some(quarantine_path),
+        },
+        Err(error) => MigrationOutcome::Quarantined {
+            reason: format!("{reason} (could not be moved to quarantine: {error})"),
+            quarantine_path: None,
+        },
+    }
+}
+
+fn append_migration_report(run: &MigrationRun) -> Result<()> {
+    let mut runs = read_migration_report().unwrap_or_default();
+    runs.push(run.clone());
+    let excess = runs.len().saturating_sub(MIGRATION_REPORT_RUN_LIMIT);
+    runs.drain(..excess);
+    let base_dir = AppState::get_base_dir()?;
+    fs::create_dir_all(&base_dir)?;
+    fs::write(
+        base_dir.join(MIGRATION_REPORT_FILE),
+        serde_json::to_string_pretty(&runs)?,
+    )?;
+    Ok(())
+}
+
+fn read_document_config_value(path: &Path) -> Result<Value> {
+    let file = fs::File::open(path).map_err(|e| {
+        AppError::Io(format!(
+            "Failed to open session document {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| {
+        AppError::Processing(format!(
+            "Invalid session document {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    let entry = archive.by_name("config.json").map_err(|e| {
+        AppError::Processing(format!(
+            "Session document {} has no config: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    Ok(serde_json::from_reader(entry)?)
+}
+
+fn read_dir_config_value(dir: &Path) -> Result<Value> {
+    read_json(&dir.join("config.json"))
+}
+
+fn read_json(path: &Path) -> Result<Value> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| AppError::Io(format!("Failed to read {}: {}", path.display(), e)))?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    fn legacy_files(dir: &Path, fonts: &[(&str, Value)]) -> SessionFiles {
+        SessionFiles {
+            dir: dir.to_path_buf(),
+            config: json!({
+                "app_version": "0.20.0",
+                "session_id": "legacy",
+                "created_at": "2024-01-01T00:00:00Z",
+                "status": { "process_status": ProcessStatus::Clustered },
+            }),
+            fonts: fonts
+                .iter()
+                .map(|(safe_name, computed)| {
+                    let meta = json!({ "font_name": safe_name.to_uppercase() });
+                    (safe_name.to_string(), (meta, Some(computed.clone())))
+                })
+                .collect(),
+        }
+    }
+
+    fn touch(dir: &Path, safe_name: &str, file: &str) {
+        let font_dir = dir.join("samples").join(safe_name);
+        fs::create_dir_all(&font_dir).unwrap();
+        fs::write(font_dir.join(file), b"").unwrap();
+    }
+
+    fn complete_clustering() -> Value {
+        json!({
+            "clustering": { "k": 0, "leaf_angle": 0.5, "cluster_angle": 1.0, "color_index": 2 }
+        })
+    }
+
+    #[test]
+    fn legacy_step_fills_missing_fields_with_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("dendrogram.json"), b"{}").unwrap();
+        let mut files = legacy_files(dir.path(), &[("a", complete_clustering())]);
+        migrate_legacy_layout(&mut files).unwrap();
+
+        let config = &files.config;
+        assert_eq!(config["title"], json!(""));
+        assert_eq!(config["modified_at"], config["created_at"]);
+        assert_eq!(config["discovered_fonts"], json!({}));
+        assert_eq!(config["algorithm"], json!(AlgorithmConfig::default()));
+        // Present fields keep their values; missing nested ones are filled.
+        assert_eq!(
+            config["status"]["process_status"],
+            json!(ProcessStatus::Clustered)
+        );
+        assert_eq!(config["status"]["clusters_amount"], json!(0));
+
+        let (meta, computed) = &files.fonts["a"];
+        assert_eq!(meta["font_name"], json!("A"));
+        assert_eq!(meta["safe_name"], json!("a"));
+        assert_eq!(meta["source"], json!("system"));
+        assert_eq!(meta["font_index"], json!(0));
+        assert_eq!(computed.as_ref().unwrap(), &complete_clustering());
+    }
+
+    #[test]
+    fn legacy_step_drops_incomplete_clustering_and_falls_back_to_analyzed() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("dendrogram.json"), b"{}").unwrap();
+        for safe_name in ["a", "b"] {
+            touch(dir.path(), safe_name, "sample.png");
+            touch(dir.path(), safe_name, "vector.bin");
+        }
+        let mut files = legacy_files(
+            dir.path(),
+            &[
+                ("a", complete_clustering()),
+                ("b", json!({ "clustering": { "k": 1 } })),
+            ],
+        );
+        migrate_legacy_layout(&mut files).unwrap();
+
+        assert_eq!(
+            files.config["status"]["process_status"],
+            json!(ProcessStatus::Analyzed)
+        );
+        // Once the session is no longer clustered no font keeps its result.
+        for (_, computed) in files.fonts.values() {
+            assert!(computed.as_ref().unwrap().get("clustering").is_none());
+        }
+    }
+
+    #[test]
+    fn legacy_step_falls_back_to_the_last_complete_stage() {
+        let dir = tempfile::tempdir().unwrap();
+        touch(dir.path(), "a", "sample.png");
+        touch(dir.path(), "a", "vector.bin");
+        touch(dir.path(), "b", "sample.png");
+        // Complete clustering but no dendrogram.json.
+        let mut files = legacy_files(
+            dir.path(),
+            &[("a", complete_clustering()), ("b", complete_clustering())],
+        );
+        migrate_legacy_layout(&mut files).unwrap();
+        assert_eq!(
+            files.config["status"]["process_status"],
+            json!(ProcessStatus::Rendered)
+        );
+
+        let mut files = legacy_files(dir.path(), &[("c", complete_clustering())]);
+        migrate_legacy_layout(&mut files).unwrap();
+        assert_eq!(
+            files.config["status"]["process_status"],
+            json!(ProcessStatus::Empty)
+        );
+    }
+
+    #[test]
+    fn detects_stored_schema() {
+        assert_eq!(stored_schema(&json!({ "schema_version": 1 })), 1);
+        assert_eq!(stored_schema(&json!({ "schema_version": 7 })), 7);
+        assert_eq!(
+            stored_schema(&json!({ "schema_version": u64::MAX })),
+            u32::MAX
+        );
+        // Unstamped sessions are dated by the build that last wrote them.
+        assert_eq!(
+            stored_schema(&json!({ "modified_app_version": "0.26.1" })),
+            1
+        );
+        assert_eq!(
+            stored_schema(&json!({ "modified_app_version": "v0.30.0" })),
+            1
+        );
+        assert_eq!(
+            stored_schema(&json!({ "modified_app_version": "0.26.0" })),
+            0
+        );
+        assert_eq!(
+            stored_schema(&json!({ "modified_app_version": "dev", "app_version": "0.27.0" })),
+            1
+        );
+        assert_eq!(stored_schema(&json!({ "app_version": "0.9.0" })), 0);
+        assert_eq!(stored_schema(&json!({})), 0);
+    }
+
+    #[test]
+    fn newer_processing_dir_is_skipped_untouched() {
+        let root = tempfile::tempdir().unwrap();
+        let session_dir = root.path().join("newer");
+        fs::create_dir_all(&session_dir).unwrap();
+        let config = r#"{"schema_version":99,"session_id":"newer","unknown_field":true}"#;
+        fs::write(session_dir.join("config.json"), config).unwrap();
+        let quarantine_dir = root.path().join(QUARANTINE_DIR);
+
+        let entry = migrate_processing_dir(session_dir.clone(), &quarantine_dir).unwrap();
+        assert!(matches!(entry.outcome, MigrationOutcome::Skipped { .. }));
+        assert_eq!(entry.from_schema, Some(99));
+        assert_eq!(entry.session_id.as_deref(), Some("newer"));
+        assert_eq!(
+            fs::read_to_string(session_dir.join("config.json")).unwrap(),
+            config
+        );
+        assert!(!quarantine_dir.exists());
+    }
+
+    #[test]
+    fn newer_document_is_skipped_untouched() {
+        let root = tempfile::tempdir().unwrap();
+        let path = root
+            .path()
+            .join(format!("newer.{}", crate::core::SESSION_DOCUMENT_EXTENSION));
+        {
+            let mut writer = zip::ZipWriter::new(fs::File::create(&path).unwrap());
+            writer
+                .start_file("config.json", SimpleFileOptions::default())
+                .unwrap();
+            writer
+                .write_all(br#"{"schema_version":99,"session_id":"newer"}"#)
+                .unwrap();
+            writer.finish().unwrap();
+        }
+        let before = fs::read(&path).unwrap();
+        let quarantine_dir = root.path().join(QUARANTINE_DIR);
+
+        let entry = migrate_stored_document(path.clone(), &quarantine_dir).unwrap();
+        assert!(matches!(entry.outcome, MigrationOutcome::Skipped { .. }));
+        assert_eq!(entry.from_schema, Some(99));
+        assert_eq!(fs::read(&path).unwrap(), before);
+        assert!(!quarantine_dir.exists());
+    }
+
+    #[test]
+    fn unparseable_processing_dir_is_quarantined() {
+        let root = tempfile::tempdir().unwrap();
+        let session_dir = root.path().join("broken");
+        fs::create_dir_all(&session_dir).unwrap();
+        fs::write(session_dir.join("config.json"), b"{ not json").unwrap();
+        let quarantine_dir = root.path().join(QUARANTINE_DIR);
+
+        let entry = migrate_processing_dir(session_dir.clone(), &quarantine_dir).unwrap();
+        let MigrationOutcome::Quarantined {
+            quarantine_path: Some(quarantine_path),
+            ..
+        } = &entry.outcome
+        else {
+            panic!("not quarantined: {:?}", entry.outcome);
+        };
+        assert_eq!(entry.from_schema, None);
+        assert!(!session_dir.exists());
+        assert_eq!(quarantine_path.parent(), Some(quarantine_dir.as_path()));
+        assert!(quarantine_path.is_file());
+    }
+
+    #[test]
+    fn unparseable_document_is_quarantined() {
+        let root = tempfile::tempdir().unwrap();
+        let path = root.path().join(format!(
+            "broken.{}",
+            crate::core::SESSION_DOCUMENT_EXTENSION
+        ));
+        fs::write(&path, b"not a zip archive").unwrap();
+        let quarantine_dir = root.path().join(QUARANTINE_DIR);
+
+        let entry = migrate_stored_document(path.clone(), &quarantine_dir).unwrap();
+        let MigrationOutcome::Quarantined {
+            quarantine_path: Some(quarantine_path),
+            ..
+        } = &entry.outcome
+        else {
+            panic!("not quarantined: {:?}", entry.outcome);
+        };
+        assert!(!path.exists());
+        assert_eq!(fs::read(&quarantine_path).unwrap(), b"not a zip archive");
+    }
+}
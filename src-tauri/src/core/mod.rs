@@ -1,32 +1,64 @@
 //! Domain core: the processing pipeline, session storage and shared state.
 //!
 //! The pipeline stages run in order — [`discoverer`] → [`sample_renderer`] →
-//! [`analyzer`] → [`clusterer`] — operating on the session state owned by
-//! [`session`]. Supporting modules cover event reporting ([`events`]), the
-//! plugin bridge ([`plugin_bridge`]), Google Fonts
-//! downloading ([`google_fonts_downloader`]) and example-session seeding
-//! ([`example`]). Each submodule's contents are re-exported at the crate's
-//! `core` path for convenience.
+//! [`analyzer`] → [`clusterer`] — scheduled by the job [`queue`] and operating
+//! on the session state owned by [`session`], whose stored layout
+//! [`migrations`] keeps current and whose clusterings [`comparison`] compares;
+//! [`sweep`] evaluates grids of clustering parameters on an analysed session.
+//! Supporting modules cover event reporting ([`events`]), the plugin bridge
+//! ([`plugin_bridge`]), Google Fonts downloading ([`google_fonts_downloader`]),
+//! system font lookup ([`system_fonts`]), fonts embedded in portable documents
+//! ([`portable`]), per-job cancellation and pausing ([`cancellation`]),
+//! recovery of interrupted jobs ([`resume`]), worker resource limits
+//! ([`resources`]), per-session run history ([`run_log`]), the report of
+//! excluded fonts ([`dropped_fonts`]), disk usage and session retention
+//! ([`storage`]) and example-session seeding ([`example`]).
+//! Each submodule's contents are re-exported at the crate's `core` path for
+//! convenience.
 
 pub mod analyzer;
+pub mod cancellation;
 pub mod clusterer;
+pub mod comparison;
 pub mod discoverer;
+pub mod dropped_fonts;
 pub mod events;
 pub mod example;
 pub mod google_fonts_downloader;
+pub mod migrations;
 pub mod models;
 mod optimal_leaf_ordering;
 pub mod plugin_bridge;
+pub mod portable;
+pub mod queue;
+pub mod resources;
+pub mod resume;
+pub mod run_log;
 pub mod sample_renderer;
 pub mod session;
+pub mod storage;
+pub mod sweep;
+pub mod system_fonts;
 
 pub use analyzer::*;
+pub use cancellation::*;
 pub use clusterer::*;
+pub use comparison::*;
 pub use discoverer::*;
+pub use dropped_fonts::*;
 pub use events::*;
 pub use example::*;
 pub use google_fonts_downloader::*;
+pub use migrations::*;
 pub use models::*;
 pub use plugin_bridge::*;
+pub use portable::*;
+pub use queue::*;
+pub use resources::*;
+pub use resume::*;
+pub use run_log::*;
 pub use sample_renderer::*;
 pub use session::*;
+pub use storage::*;
+pub use sweep::*;
+pub use system_fonts::*;
@@ -4,24 +4,48 @@
 //! `MugiSus/fontcluster-models`. Every release carries the same three assets:
 //! `model.json`, `model.onnx`, and
 //! `attribute_directions.json`. GitHub computes a SHA-256 digest for every
-//! asset; downloads are streamed to a staging directory, verified against
+//! asset; downloads are assembled in a staging directory, verified against
 //! those digests, validated as one bundle, and atomically renamed into
 //! Application Support only after all files pass.
+//!
+//! Assets are fetched into partial files named by their expected digest, so
+//! an interrupted download resumes with a `Range` request where it stopped,
+//! even in a later run. Failed requests are retried with backoff, and a large
+//! asset can be fetched over several connections at once.
+//!
+//! The releases can also come from another [`ModelSource`]: a
+//! GitHub-compatible API, a static HTTP mirror or a directory listing them in
+//! an `index.json`. Every source declares the same asset digests, so its
+//! downloads are verified exactly like GitHub's.
+//!
+//! Bundles that are not published can be imported from a local directory or
+//! zip archive ([`import_local_model`]). They are installed under the
+//! `local:` namespace, with checksums computed on import, and are never
+//! downloaded; their attribute directions are optional.
+//!
+//! An installed model can be re-hashed against its manifest
+//! ([`verify_model`]) and removed ([`delete_model`]); both take the same
+//! per-model lock as installation.
 
+use crate::core::storage::dir_size;
 use crate::core::{AppState, EventSink};
-use crate::error::{AppError, Result};
+use crate::error::{AppError, ErrorCode, Result};
 use reqwest::blocking::Client;
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use sha2::{Digest, Sha256};
 use std::collections::{BTreeMap, HashMap};
 use std::fs::{self, File, OpenOptions};
-use std::io::{Read, Write};
-use std::path::{Path, PathBuf};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Mutex, OnceLock};
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
-/// GitHub API root for the repository that owns the model releases.
+/// GitHub API root for the repository that owns the model releases, used
+/// unless the [`ModelSource`] names another.
 const MODEL_REPOSITORY_API: &str = "https://api.github.com/repos/MugiSus/fontcluster-models";
 /// GitHub REST API contract used to interpret release asset metadata.
 const GITHUB_API_VERSION: &str = "2026-03-10";
@@ -33,6 +57,30 @@ const MAX_MANIFEST_BYTES: u64 = 64 * 1024;
 const REMOTE_CATALOG_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
 /// The complete and exclusive asset set accepted for one model release.
 const REQUIRED_ASSETS: [&str; 3] = ["model.json", "model.onnx", "attribute_directions.json"];
+/// Prefix of the IDs of models imported with [`import_local_model`].
+pub const LOCAL_MODEL_PREFIX: &str = "local:";
+/// Directory under the model root holding the imported models, by name.
+const LOCAL_MODELS_DIR: &str = "local";
+/// Directory under the model root holding one directory per model being
+/// downloaded. Each holds that model's partially downloaded assets, named
+/// `<sha256>-<offset>.part` after their expected digest and the offset their
+/// bytes start at, and its verified assets awaiting installation, named
+/// `<sha256>.complete`. Keeping them per model lets the model lock cover them,
+/// even when two models share an asset.
+const PARTIAL_DOWNLOADS_DIR: &str = ".partial";
+/// Partial downloads untouched for this long are discarded.
+const PARTIAL_DOWNLOAD_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+/// Requests made for one byte range before its download fails.
+const DOWNLOAD_ATTEMPTS: u32 = 5;
+/// Delay before the first retry of a failed request; doubled for every
+/// further retry.
+const DOWNLOAD_RETRY_DELAY: Duration = Duration::from_secs(1);
+/// Smallest asset split across several connections.
+const PARALLEL_DOWNLOAD_MIN_BYTES: u64 = 32 * 1024 * 1024;
+/// File under the base dir holding the [`ModelSource`].
+const MODEL_SOURCE_FILE: &str = "model_source.json";
+/// Release index at the root of an HTTP mirror or filesystem repository.
+const MIRROR_INDEX_FILE: &str = "index.json";
 /// Attribute names whose directions must all be present in a compatible bundle.
 const EMPHASIS_ATTRIBUTES: [&str; 37] = [
     "angular",
@@ -74,10 +122,49 @@ const EMPHASIS_ATTRIBUTES: [&str; 37] = [
     "wide",
 ];
 
+/// Where published model releases are listed and downloaded from.
+///
+/// Mirrors describe their releases in an `index.json` holding a list of
+/// releases in the shape of GitHub's release API — `tag_name`, `name`,
+/// `draft`, `prerelease` and `assets` with `name`, `browser_download_url`,
+/// `size` and a `sha256:<hex>` `digest` — against which every asset is
+/// verified.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+pub enum ModelSource {
+    /// A GitHub-compatible REST API, such as GitHub Enterprise.
+    Github {
+        /// API root of the model repository (`…/repos/<owner>/<repo>`);
+        /// `None` for the public `MugiSus/fontcluster-models`.
+        #[serde(default, alias = "api_base")]
+        api_base: Option<String>,
+    },
+    /// A static HTTP server with `index.json` at `base_url`. Relative asset
+    /// URLs are resolved against `base_url`.
+    HttpMirror {
+        #[serde(alias = "base_url")]
+        base_url: String,
+    },
+    /// A directory, such as a mounted share, laid out like an HTTP mirror.
+    /// Relative asset URLs are paths inside it.
+    Filesystem { path: PathBuf },
+}
+
+impl Default for ModelSource {
+    fn default() -> Self {
+        ModelSource::Github { api_base: None }
+    }
+}
+
 /// Public metadata stored beside every model and published as `model.json`.
 ///
 /// The manifest is the bundle's source of truth for identity, display metadata,
-/// compatibility, and the checksums of the two payload files.
+/// compatibility, the input and output `model.onnx` expects, and the checksums
+/// of the two payload files.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ModelManifest {
@@ -90,18 +177,121 @@ pub struct ModelManifest {
     /// Optional parameter count supplied by the model publisher.
     #[serde(default)]
     pub parameter_count: Option<u64>,
+    /// Input `model.onnx` expects; manifests without one describe the
+    /// original grayscale models.
+    #[serde(default)]
+    pub input: ModelInputSpec,
+    /// Embedding output `model.onnx` produces.
+    #[serde(default)]
+    pub output: ModelOutputSpec,
     /// Digests that bind the manifest to its inference and attribute payloads.
     checksums: ModelChecksums,
 }
 
+/// The image tensor a model takes: `[batch, channels, size, size]`, with pixel
+/// values scaled to `[0, 1]` and then normalised per channel as
+/// `(value - mean) / std`. Undeclared fields keep the values of the original
+/// 224 px grayscale models, whose graphs normalise their own input.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ModelInputSpec {
+    /// Side of the square input image, in pixels.
+    pub size: u32,
+    pub channels: ChannelLayout,
+    /// Per-channel mean; a single value applies to every channel.
+    pub mean: Vec<f32>,
+    /// Per-channel standard deviation; a single value applies to every
+    /// channel.
+    pub std: Vec<f32>,
+    /// Name of the graph's symbolic batch dimension, fixed when the session is
+    /// built.
+    pub batch_dimension: String,
+    /// Images per inference batch unless the resource profile sets one.
+    pub batch_size: usize,
+}
+
+impl Default for ModelInputSpec {
+    fn default() -> Self {
+        Self {
+            size: 224,
+            channels: ChannelLayout::Grayscale,
+            mean: vec![0.0],
+            std: vec![1.0],
+            batch_dimension: "batch_size".into(),
+            batch_size: 8,
+        }
+    }
+}
+
+impl ModelInputSpec {
+    /// Normalisation `(mean, std)` of channel `channel`.
+    pub fn normalisation(&self, channel: usize) -> (f32, f32) {
+        let value = |values: &[f32]| values.get(channel).or(values.first()).copied();
+        (
+            value(&self.mean).unwrap_or(0.0),
+            value(&self.std).unwrap_or(1.0),
+        )
+    }
+}
+
+/// Channels of a model's input image. Samples are rendered in grayscale, so
+/// every RGB channel receives the same values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelLayout {
+    #[default]
+    Grayscale,
+    Rgb,
+}
+
+impl ChannelLayout {
+    /// Number of input channels.
+    pub fn count(self) -> usize {
+        match self {
+            ChannelLayout::Grayscale => 1,
+            ChannelLayout::Rgb => 3,
+        }
+    }
+}
+
+/// The `[batch, dimensions]` embedding output of a model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ModelOutputSpec {
+    /// Name of the graph output holding the embeddings.
+    pub name: String,
+    /// Embedding dimensionality, shared by the attribute directions.
+    pub dimensions: usize,
+}
+
+impl Default for ModelOutputSpec {
+    fn default() -> Self {
+        Self {
+            name: "embedding".into(),
+            dimensions: 512,
+        }
+    }
+}
+
 /// SHA-256 values declared by `model.json` for the non-manifest assets.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct ModelChecksums {
     /// Lowercase or uppercase hexadecimal digest for `model.onnx`.
     model_sha256: String,
-    /// Lowercase or uppercase hexadecimal digest for `attribute_directions.json`.
-    attribute_directions_sha256: String,
+    /// Lowercase or uppercase hexadecimal digest for `attribute_directions.json`;
+    /// required for published models, absent for a local model imported
+    /// without directions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    attribute_directions_sha256: Option<String>,
+}
+
+impl ModelManifest {
+    /// Declared SHA-256 of `model.onnx`, checked whenever the bundle is
+    /// resolved.
+    pub fn model_sha256(&self) -> &str {
+        &self.checksums.model_sha256
+    }
 }
 
 /// A validated model directory ready for inference and attribute emphasis.
@@ -143,6 +333,31 @@ pub struct ModelCatalogEntry {
     pub availability: ModelAvailability,
 }
 
+/// Result of re-hashing an installed model with [`verify_model`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelVerification {
+    pub model_id: String,
+    /// Whether every declared payload matches its checksum.
+    pub valid: bool,
+    /// One entry per payload the manifest declares a checksum for.
+    pub files: Vec<VerifiedFile>,
+}
+
+/// Checksum comparison of one model payload.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifiedFile {
+    /// File name inside the bundle.
+    pub name: String,
+    /// Lowercase SHA-256 declared by the manifest.
+    pub expected_sha256: String,
+    /// Lowercase SHA-256 of the file on disk; `None` when it is missing or
+    /// unreadable.
+    pub actual_sha256: Option<String>,
+    pub matches: bool,
+}
+
 /// Catalog lookup remains useful offline: installed entries are returned
 /// even when GitHub cannot be reached, with the failure carried as a warning.
 #[derive(Debug, Serialize)]
@@ -159,7 +374,8 @@ pub struct ModelCatalogResponse {
 struct GithubReleaseAsset {
     /// Published asset filename; also the filename used inside the bundle.
     name: String,
-    /// Direct URL used by the blocking downloader.
+    /// Direct URL used by the blocking downloader; a mirror may give one
+    /// relative to its root.
     browser_download_url: String,
     /// GitHub-declared byte length used as a strict download bound.
     size: u64,
@@ -205,6 +421,9 @@ struct AttributeDirection {
 /// Only warning-free responses refresh this record. An expired record remains
 /// available as an offline fallback but does not prevent the next retry.
 struct CachedRemoteCatalog {
+    /// Source the entries were listed from; a cache for another source is
+    /// discarded.
+    source: ModelSource,
     /// Time of the last warning-free GitHub response.
     fetched_at: Instant,
     /// Remote entries only; local availability is recomputed by [`list_models`].
@@ -259,6 +478,16 @@ fn remote_model_catalog() -> (Vec<ModelCatalogEntry>, Option<String>) {
     let mut cached = cache
         .lock()
         .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let repository = match ModelRepository::open() {
+        Ok(repository) => repository,
+        Err(error) => return (Vec::new(), Some(error.to_string())),
+    };
+    if cached
+        .as_ref()
+        .is_some_and(|cached| cached.source != repository.source)
+    {
+        *cached = None;
+    }
     if let Some(cached) = cached
         .as_ref()
         .filter(|cached| cached.fetched_at.elapsed() < REMOTE_CATALOG_CACHE_TTL)
@@ -266,9 +495,10 @@ fn remote_model_catalog() -> (Vec<ModelCatalogEntry>, Option<String>) {
         return (cached.models.clone(), None);
     }
 
-    match fetch_remote_model_catalog() {
+    match fetch_remote_model_catalog(&repository) {
         Ok((models, None)) => {
             *cached = Some(CachedRemoteCatalog {
+                source: repository.source.clone(),
                 fetched_at: Instant::now(),
                 models: models.clone(),
             });
@@ -291,15 +521,17 @@ fn remote_model_catalog() -> (Vec<ModelCatalogEntry>, Option<String>) {
     }
 }
 
-/// Builds catalog entries from all compatible public GitHub releases.
+/// Builds catalog entries from all compatible public releases of the model
+/// source.
 ///
-/// Releases with an invalid ID or asset shape are ignored. A network failure
+/// Releases with an invalid or `local:` ID or asset shape are ignored. A network failure
 /// while reading one manifest retains that release using its release title and
 /// reports a warning; an invalid manifest excludes the release. Failure to list
 /// releases at all is returned as an error for the cache layer to handle.
-fn fetch_remote_model_catalog() -> Result<(Vec<ModelCatalogEntry>, Option<String>)> {
-    let client = github_client()?;
-    let releases = fetch_releases(&client)?;
+fn fetch_remote_model_catalog(
+    repository: &ModelRepository,
+) -> Result<(Vec<ModelCatalogEntry>, Option<String>)> {
+    let releases = repository.releases()?;
     let mut models = Vec::new();
     let mut warnings = Vec::new();
     for release in releases
@@ -307,10 +539,13 @@ fn fetch_remote_model_catalog() -> Result<(Vec<ModelCatalogEntry>, Option<String
         .filter(|release| !release.draft && !release.prerelease)
     {
         let model_id = release.tag_name.clone();
-        if validate_model_id(&model_id).is_err() || required_release_assets(&release).is_err() {
+        if validate_model_id(&model_id).is_err()
+            || is_local_model_id(&model_id)
+            || required_release_assets(&release).is_err()
+        {
             continue;
         }
-        let manifest = match fetch_release_manifest(&client, &release, &model_id) {
+        let manifest = match fetch_release_manifest(repository, &release, &model_id) {
             Ok(manifest) => Some(manifest),
             Err(error @ AppError::Network(_)) => {
                 warnings.push(format!("Could not read metadata for '{model_id}': {error}"));
@@ -355,14 +590,15 @@ fn fetch_remote_model_catalog() -> Result<(Vec<ModelCatalogEntry>, Option<String
 /// Invalid IDs, absent installations, malformed metadata, and digest failures
 /// are returned as errors.
 pub fn resolve_model(model_id: &str) -> Result<ModelBundle> {
-    validate_model_id(model_id)?;
-    let directory = installed_models_root()?.join(model_id);
+    let directory = model_directory(model_id)?;
     if directory.exists() {
         load_model_bundle(&directory, model_id)
     } else {
-        Err(AppError::Processing(format!(
-            "Model '{model_id}' is not installed"
-        )))
+        Err(AppError::new(
+            ErrorCode::ModelNotInstalled,
+            format!("Model '{model_id}' is not installed"),
+        )
+        .with_model_id(model_id))
     }
 }
 
@@ -371,8 +607,9 @@ pub fn resolve_model(model_id: &str) -> Result<ModelBundle> {
 ///
 /// Installation is serialized per model with an advisory file lock. The
 /// release manifest is validated before large assets are downloaded, every
-/// asset is size-bounded and SHA-256 verified while streaming, and the bundle
-/// is assembled under a temporary `.download-*` directory. The completed
+/// asset is size-bounded while it downloads and SHA-256 verified once all of
+/// its parts are joined, and the bundle is assembled under a temporary
+/// `.download-*` directory. The completed
 /// directory replaces the destination by rename; a previous destination is
 /// kept as `.invalid-*` until that rename succeeds so interrupted replacement
 /// can be recovered on the next call.
@@ -381,30 +618,39 @@ pub fn resolve_model(model_id: &str) -> Result<ModelBundle> {
 /// terminal completion or failure event when a download is required. Existing
 /// valid installations produce no download events.
 ///
+/// A `local:` model has no release, so it is only resolved.
+///
+/// Assets of at least `PARALLEL_DOWNLOAD_MIN_BYTES` are fetched over up to
+/// `download_connections` connections when the source accepts range requests.
+/// Verified assets stay under `.partial/<model_id>` until the bundle is in
+/// place, so an install that fails after downloading some of them doesn't
+/// fetch them again.
+///
 /// This function performs blocking HTTP and filesystem work. Async callers
 /// must run its entire lifecycle in a blocking task so the blocking reqwest
 /// client is not dropped inside an async runtime.
-pub fn ensure_model(model_id: &str, events: &impl EventSink) -> Result<ModelBundle> {
-    if let Ok(bundle) = resolve_model(model_id) {
-        let invalid_backup = installed_models_root()?.join(format!(".invalid-{model_id}"));
-        if invalid_backup.exists() {
-            let _ = fs::remove_dir_all(invalid_backup);
+pub fn ensure_model(
+    model_id: &str,
+    download_connections: usize,
+    events: &impl EventSink,
+) -> Result<ModelBundle> {
+    match resolve_model(model_id) {
+        Ok(bundle) if !is_local_model_id(model_id) => {
+            let invalid_backup = installed_models_root()?.join(format!(".invalid-{model_id}"));
+            if invalid_backup.exists() {
+                let _ = fs::remove_dir_all(invalid_backup);
+            }
+            return Ok(bundle);
         }
-        return Ok(bundle);
+        Ok(bundle) => return Ok(bundle),
+        Err(error) if is_local_model_id(model_id) => return Err(error),
+        Err(_) => {}
     }
     validate_model_id(model_id)?;
 
     let models_root = installed_models_root()?;
     fs::create_dir_all(&models_root)?;
-    let locks_root = models_root.join(".locks");
-    fs::create_dir_all(&locks_root)?;
-    let lock = OpenOptions::new()
-        .create(true)
-        .truncate(false)
-        .read(true)
-        .write(true)
-        .open(locks_root.join(format!("{model_id}.lock")))?;
-    fs4::FileExt::lock(&lock)?;
+    let _lock = lock_model(&models_root, model_id)?;
 
     remove_stale_downloads(&models_root, model_id)?;
     reconcile_invalid_backup(&models_root, model_id)?;
@@ -412,15 +658,15 @@ pub fn ensure_model(model_id: &str, events: &impl EventSink) -> Result<ModelBund
         return Ok(bundle);
     }
 
-    let client = github_client()?;
-    let release = fetch_release(&client, model_id)?;
+    let repository = ModelRepository::open()?;
+    let release = repository.release(model_id)?;
     if release.draft || release.prerelease || release.tag_name != model_id {
         return Err(AppError::Processing(format!(
             "Model '{model_id}' has no compatible published release"
         )));
     }
     let assets = required_release_assets(&release)?;
-    fetch_release_manifest(&client, &release, model_id)?;
+    fetch_release_manifest(&repository, &release, model_id)?;
     let total_bytes = assets.iter().map(|asset| asset.size).sum::<u64>();
     events.emit_value(
         "model_download_started",
@@ -431,17 +677,22 @@ pub fn ensure_model(model_id: &str, events: &impl EventSink) -> Result<ModelBund
         let staging = tempfile::Builder::new()
             .prefix(&format!(".download-{model_id}_"))
             .tempdir_in(&models_root)?;
-        let mut completed_bytes = 0_u64;
+        let progress = DownloadProgress::new(model_id, total_bytes, events);
+        let partial_dir = models_root.join(PARTIAL_DOWNLOADS_DIR).join(model_id);
+        let mut verified_assets = Vec::new();
         for asset in assets {
-            download_asset(
-                &client,
+            let verified = download_asset(
+                &repository,
                 asset,
-                &staging.path().join(&asset.name),
-                model_id,
-                total_bytes,
-                &mut completed_bytes,
-                events,
+                &partial_dir,
+                download_connections,
+                &progress,
             )?;
+            let staged = staging.path().join(&asset.name);
+            if fs::hard_link(&verified, &staged).is_err() {
+                fs::copy(&verified, &staged)?;
+            }
+            verified_assets.push(verified);
         }
 
         let bundle = validate_model_bundle_structure(staging.path(), model_id)?;
@@ -463,6 +714,10 @@ pub fn ensure_model(model_id: &str, events: &impl EventSink) -> Result<ModelBund
         if invalid_backup.exists() {
             let _ = fs::remove_dir_all(invalid_backup);
         }
+        for verified in verified_assets {
+            let _ = fs::remove_file(verified);
+        }
+        let _ = fs::remove_dir(&partial_dir);
         Ok(ModelBundle {
             directory: destination,
             manifest: bundle.manifest,
@@ -487,50 +742,595 @@ pub fn ensure_model(model_id: &str, events: &impl EventSink) -> Result<ModelBund
     }
 }
 
-/// Constructs the blocking GitHub client shared by one catalog or install operation.
+/// Installs the model bundle at `source` as `local:<id>`, where `<id>` is the
+/// ID its `model.json` declares, and returns the installed bundle.
 ///
-/// Request-specific read timeouts are assigned by the individual fetch and
-/// download functions; this client supplies the application user agent and a
-/// bounded connection-establishment timeout.
-fn github_client() -> Result<Client> {
-    Client::builder()
-        .user_agent(format!("FontCluster/{}", env!("CARGO_PKG_VERSION")))
-        .connect_timeout(Duration::from_secs(15))
-        .build()
-        .map_err(|error| AppError::Network(error.to_string()))
-}
-
-/// Fetches the first page of release metadata used to populate the catalog.
-///
-/// Transport, HTTP-status, and response-decoding failures are normalized to
-/// [`AppError::Network`] because no release can be trusted from a partial list.
-fn fetch_releases(client: &Client) -> Result<Vec<GithubRelease>> {
-    client
-        .get(format!("{MODEL_REPOSITORY_API}/releases?per_page=100"))
-        .timeout(Duration::from_secs(30))
-        .header("Accept", "application/vnd.github+json")
-        .header("X-GitHub-Api-Version", GITHUB_API_VERSION)
-        .send()
-        .and_then(|response| response.error_for_status())
-        .map_err(|error| AppError::Network(error.to_string()))?
-        .json()
-        .map_err(|error| AppError::Network(error.to_string()))
-}
-
-/// Fetches the exact release whose tag is `model_id` for installation.
+/// `source` is a directory or a zip archive holding `model.json`,
+/// `model.onnx` and optionally `attribute_directions.json`, at its root or
+/// inside one top-level folder. With no release to verify against, the
+/// checksums are computed here and written into the installed manifest,
+/// replacing any the source declared; [`resolve_model`] then verifies the
+/// bundle like a downloaded one. A model without attribute directions can
+/// analyse but not emphasise attributes. The bundle is validated with
+/// [`validate_model_bundle_structure`] in a staging directory, and replaces an
+/// earlier import of the same ID only once it passes.
+pub fn import_local_model(source: &Path) -> Result<ModelBundle> {
+    let models_root = installed_models_root()?;
+    let local_root = models_root.join(LOCAL_MODELS_DIR);
+    fs::create_dir_all(&local_root)?;
+    let staging = tempfile::Builder::new()
+        .prefix(".import-")
+        .tempdir_in(&local_root)?;
+    copy_local_bundle(source, staging.path())?;
+
+    let manifest_path = staging.path().join("model.json");
+    let mut manifest: serde_json::Value = serde_json::from_reader(File::open(&manifest_path)?)?;
+    let declared_id = manifest
+        .get("id")
+        .and_then(serde_json::Value::as_str)
+        .unwrap_or_default();
+    let name = declared_id
+        .strip_prefix(LOCAL_MODEL_PREFIX)
+        .unwrap_or(declared_id)
+        .to_string();
+    let model_id = format!("{LOCAL_MODEL_PREFIX}{name}");
+    validate_model_id(&model_id).map_err(|error| error.with_path(source))?;
+    let directions_path = staging.path().join("attribute_directions.json");
+    let checksums = ModelChecksums {
+        model_sha256: sha256_file(&staging.path().join("model.onnx"))?,
+        attribute_directions_sha256: directions_path
+            .exists()
+            .then(|| sha256_file(&directions_path))
+            .transpose()?,
+    };
+    let object = manifest.as_object_mut().ok_or_else(|| {
+        AppError::new(
+            ErrorCode::ModelInvalid,
+            format!("model.json in {} is not an object", source.display()),
+        )
+        .with_path(source)
+    })?;
+    object.insert("id".into(), json!(model_id));
+    object.insert("checksums".into(), serde_json::to_value(&checksums)?);
+    fs::write(&manifest_path, serde_json::to_vec_pretty(&manifest)?)?;
+    let bundle = validate_model_bundle_structure(staging.path(), &model_id)
+        .map_err(|error| error.context(format!("Cannot import {}", source.display())))?;
+
+    let _lock = lock_model(&models_root, &model_id)?;
+
+    let destination = local_root.join(&name);
+    let replaced = local_root.join(format!(".replaced-{name}"));
+    if replaced.exists() {
+        fs::remove_dir_all(&replaced)?;
+    }
+    if destination.exists() {
+        fs::rename(&destination, &replaced)?;
+    }
+    let staging_path = staging.keep();
+    if let Err(error) = fs::rename(&staging_path, &destination) {
+        if replaced.exists() {
+            let _ = fs::rename(&replaced, &destination);
+        }
+        let _ = fs::remove_dir_all(&staging_path);
+        return Err(error.into());
+    }
+    if replaced.exists() {
+        let _ = fs::remove_dir_all(replaced);
+    }
+    println!("📦 Imported model '{model_id}' from {}", source.display());
+    Ok(ModelBundle {
+        directory: destination,
+        manifest: bundle.manifest,
+    })
+}
+
+/// Re-hashes the payloads of the installed `model_id` against the checksums
+/// its manifest declares. A mismatch or missing file is reported in the
+/// result rather than as an error; only a model that is not installed or has
+/// an unreadable manifest fails.
+pub fn verify_model(model_id: &str) -> Result<ModelVerification> {
+    let directory = installed_model_directory(model_id)?;
+    let _lock = lock_model(&installed_models_root()?, model_id)?;
+    verify_model_directory(model_id, &directory)
+}
+
+/// [`verify_model`] for the bundle in `directory`, once it is locked.
+fn verify_model_directory(model_id: &str, directory: &Path) -> Result<ModelVerification> {
+    let manifest: ModelManifest =
+        serde_json::from_reader(File::open(directory.join("model.json"))?)
+            .map_err(|error| AppError::from(error).with_model_id(model_id))?;
+    let checksums = &manifest.checksums;
+    let files: Vec<VerifiedFile> = std::iter::once(("model.onnx", &checksums.model_sha256))
+        .chain(
+            checksums
+                .attribute_directions_sha256
+                .as_ref()
+                .map(|digest| ("attribute_directions.json", digest)),
+        )
+        .map(|(name, expected)| {
+            let expected_sha256 = expected.to_ascii_lowercase();
+            let actual_sha256 = sha256_file(&directory.join(name)).ok();
+            VerifiedFile {
+                name: name.to_string(),
+                matches: actual_sha256.as_deref() == Some(expected_sha256.as_str()),
+                expected_sha256,
+                actual_sha256,
+            }
+        })
+        .collect();
+    let valid = files.iter().all(|file| file.matches);
+    println!(
+        "🔍 Verified model '{model_id}': {}",
+        if valid { "intact" } else { "corrupted" }
+    );
+    Ok(ModelVerification {
+        model_id: model_id.to_string(),
+        valid,
+        files,
+    })
+}
+
+/// Removes the installed `model_id`, with any replacement backup left by an
+/// interrupted install, and returns the bytes freed. Callers check first
+/// that no running job uses the model.
+pub fn delete_model(model_id: &str) -> Result<u64> {
+    let directory = installed_model_directory(model_id)?;
+    let models_root = installed_models_root()?;
+    let _lock = lock_model(&models_root, model_id)?;
+    remove_model_directory(&models_root, model_id, &directory)
+}
+
+/// [`delete_model`] for the bundle in `directory` under `models_root`, once
+/// it is locked.
+fn remove_model_directory(models_root: &Path, model_id: &str, directory: &Path) -> Result<u64> {
+    let freed_bytes = dir_size(directory);
+    fs::remove_dir_all(directory)?;
+    let invalid_backup = models_root.join(format!(".invalid-{model_id}"));
+    if !is_local_model_id(model_id) && invalid_backup.exists() {
+        let _ = fs::remove_dir_all(invalid_backup);
+    }
+    println!("🗑️ Deleted model '{model_id}' ({freed_bytes} bytes)");
+    Ok(freed_bytes)
+}
+
+/// Installation directory of `model_id`, failing with
+/// [`ErrorCode::ModelNotInstalled`] when it doesn't exist.
+fn installed_model_directory(model_id: &str) -> Result<PathBuf> {
+    let directory = model_directory(model_id)?;
+    if !directory.is_dir() {
+        return Err(AppError::new(
+            ErrorCode::ModelNotInstalled,
+            format!("Model '{model_id}' is not installed"),
+        )
+        .with_model_id(model_id));
+    }
+    Ok(directory)
+}
+
+/// Takes the advisory lock serializing the installation, import,
+/// verification and deletion of `model_id`; it is released when the returned
+/// file is dropped.
+fn lock_model(models_root: &Path, model_id: &str) -> Result<File> {
+    let locks_root = models_root.join(".locks");
+    fs::create_dir_all(&locks_root)?;
+    // A colon is not valid in a Windows file name.
+    let lock_name = match model_id.strip_prefix(LOCAL_MODEL_PREFIX) {
+        Some(name) => format!("local_{name}.lock"),
+        None => format!("{model_id}.lock"),
+    };
+    let lock = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .read(true)
+        .write(true)
+        .open(locks_root.join(lock_name))?;
+    fs4::FileExt::lock(&lock)?;
+    Ok(lock)
+}
+
+/// Copies the bundle files of the directory or zip archive at `source` into
+/// `staging`, failing unless `model.json` and `model.onnx` are among them.
 ///
-/// Compatibility and asset-set checks remain the caller's responsibility.
-fn fetch_release(client: &Client, model_id: &str) -> Result<GithubRelease> {
-    client
-        .get(format!("{MODEL_REPOSITORY_API}/releases/tags/{model_id}"))
-        .timeout(Duration::from_secs(30))
-        .header("Accept", "application/vnd.github+json")
-        .header("X-GitHub-Api-Version", GITHUB_API_VERSION)
-        .send()
-        .and_then(|response| response.error_for_status())
-        .map_err(|error| AppError::Network(error.to_string()))?
-        .json()
-        .map_err(|error| AppError::Network(error.to_string()))
+/// The bundle is read from the root of `source` or, when that holds no
+/// `model.json`, from its single top-level folder. An archive whose bundle
+/// files sit in more than one folder, or appear twice, is rejected rather
+/// than merged.
+fn copy_local_bundle(source: &Path, staging: &Path) -> Result<()> {
+    let invalid = |detail: &str| {
+        AppError::new(
+            ErrorCode::ModelInvalid,
+            format!("{} {detail}", source.display()),
+        )
+        .with_path(source)
+    };
+    if source.is_dir() {
+        let root = local_bundle_root(source)?;
+        for name in REQUIRED_ASSETS {
+            let path = root.join(name);
+            if path.is_file() {
+                fs::copy(&path, staging.join(name))?;
+            }
+        }
+    } else {
+        let zip_error = |error: zip::result::ZipError| {
+            AppError::Io(format!("Failed to read {}: {error}", source.display()))
+        };
+        let mut archive = zip::ZipArchive::new(File::open(source)?).map_err(zip_error)?;
+        let mut bundle_root: Option<PathBuf> = None;
+        let mut entries: BTreeMap<&str, usize> = BTreeMap::new();
+        for index in 0..archive.len() {
+            let entry = archive.by_index(index).map_err(zip_error)?;
+            let Some(path) = entry.enclosed_name() else {
+                continue;
+            };
+            let Some(name) = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| REQUIRED_ASSETS.into_iter().find(|asset| *asset == name))
+            else {
+                continue;
+            };
+            if !entry.is_file() || path.components().count() > 2 {
+                continue;
+            }
+            let parent = path.parent().map(Path::to_path_buf).unwrap_or_default();
+            if *bundle_root.get_or_insert_with(|| parent.clone()) != parent {
+                return Err(invalid("holds model files in more than one folder"));
+            }
+            if entries.insert(name, index).is_some() {
+                return Err(invalid(&format!("holds {name} more than once")));
+            }
+        }
+        for (name, index) in entries {
+            let mut entry = archive.by_index(index).map_err(zip_error)?;
+            std::io::copy(&mut entry, &mut File::create(staging.join(name))?)?;
+        }
+    }
+    for name in ["model.json", "model.onnx"] {
+        if !staging.join(name).is_file() {
+            return Err(invalid(&format!("does not contain {name}")));
+        }
+    }
+    Ok(())
+}
+
+/// The directory of a local bundle at `source`: `source` itself when it holds
+/// `model.json` or is not a single-folder wrapper, otherwise its only
+/// top-level folder. Hidden entries are ignored.
+fn local_bundle_root(source: &Path) -> Result<PathBuf> {
+    if source.join("model.json").is_file() {
+        return Ok(source.to_path_buf());
+    }
+    let mut visible = Vec::new();
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        visible.push(entry.path());
+    }
+    match visible.as_slice() {
+        [folder] if folder.is_dir() => Ok(folder.clone()),
+        _ => Ok(source.to_path_buf()),
+    }
+}
+
+/// Reads the stored model source, or the public GitHub repository when none
+/// has been saved.
+pub fn read_model_source() -> Result<ModelSource> {
+    let path = AppState::get_base_dir()?.join(MODEL_SOURCE_FILE);
+    if !path.exists() {
+        return Ok(ModelSource::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(&path)?)?)
+}
+
+/// Validates and saves `source`; the next catalog request lists its releases.
+/// URLs must be `http` or `https`, and a repository path must be absolute.
+pub fn write_model_source(source: &ModelSource) -> Result<()> {
+    let valid = match source {
+        ModelSource::Github { api_base: None } => true,
+        ModelSource::Github {
+            api_base: Some(url),
+        }
+        | ModelSource::HttpMirror { base_url: url } => {
+            url.starts_with("http://") || url.starts_with("https://")
+        }
+        ModelSource::Filesystem { path } => path.is_absolute(),
+    };
+    if !valid {
+        return Err(AppError::new(
+            ErrorCode::ModelSourceInvalid,
+            format!("Invalid model source: {source:?}"),
+        ));
+    }
+    let base_dir = AppState::get_base_dir()?;
+    fs::create_dir_all(&base_dir)?;
+    fs::write(
+        base_dir.join(MODEL_SOURCE_FILE),
+        serde_json::to_vec_pretty(source)?,
+    )?;
+    Ok(())
+}
+
+/// The configured [`ModelSource`] with the blocking HTTP client shared by one
+/// catalog or install operation.
+struct ModelRepository {
+    source: ModelSource,
+    client: Client,
+}
+
+impl ModelRepository {
+    /// Opens the stored model source.
+    ///
+    /// Request-specific read timeouts are assigned by the individual fetch and
+    /// download functions; the client supplies the application user agent and
+    /// a bounded connection-establishment timeout.
+    fn open() -> Result<Self> {
+        let client = Client::builder()
+            .user_agent(format!("FontCluster/{}", env!("CARGO_PKG_VERSION")))
+            .connect_timeout(Duration::from_secs(15))
+            .build()
+            .map_err(|error| AppError::Network(error.to_string()))?;
+        Ok(Self {
+            source: read_model_source()?,
+            client,
+        })
+    }
+
+    /// Fetches the release metadata used to populate the catalog: the first
+    /// page of a GitHub API's releases, or a mirror's whole index.
+    ///
+    /// Transport, HTTP-status, and response-decoding failures are normalized
+    /// to [`AppError::Network`] because no release can be trusted from a
+    /// partial list. A filesystem source's index that can't be read fails with
+    /// [`ErrorCode::Io`], and one that can't be parsed with
+    /// [`ErrorCode::Serialization`], both carrying its path.
+    fn releases(&self) -> Result<Vec<GithubRelease>> {
+        match &self.source {
+            ModelSource::Github { api_base } => self.github_json(&format!(
+                "{}/releases?per_page=100",
+                api_base.as_deref().unwrap_or(MODEL_REPOSITORY_API)
+            )),
+            ModelSource::HttpMirror { base_url } => self
+                .get(
+                    &mirror_url(base_url, MIRROR_INDEX_FILE),
+                    Duration::from_secs(30),
+                )
+                .and_then(|index| {
+                    serde_json::from_reader(index)
+                        .map_err(|error| AppError::Network(error.to_string()))
+                }),
+            ModelSource::Filesystem { path } => {
+                let index = path.join(MIRROR_INDEX_FILE);
+                let file = File::open(&index).map_err(|error| {
+                    AppError::new(
+                        ErrorCode::Io,
+                        format!("Failed to open {}: {error}", index.display()),
+                    )
+                    .with_path(index.clone())
+                })?;
+                serde_json::from_reader(file).map_err(|error| {
+                    AppError::new(
+                        ErrorCode::Serialization,
+                        format!("Failed to parse {}: {error}", index.display()),
+                    )
+                    .with_path(index.clone())
+                })
+            }
+        }
+    }
+
+    /// Fetches the exact release whose tag is `model_id` for installation.
+    ///
+    /// Compatibility and asset-set checks remain the caller's responsibility.
+    fn release(&self, model_id: &str) -> Result<GithubRelease> {
+        match &self.source {
+            ModelSource::Github { api_base } => self.github_json(&format!(
+                "{}/releases/tags/{model_id}",
+                api_base.as_deref().unwrap_or(MODEL_REPOSITORY_API)
+            )),
+            _ => self
+                .releases()?
+                .into_iter()
+                .find(|release| release.tag_name == model_id)
+                .ok_or_else(|| {
+                    AppError::Processing(format!(
+                        "Model '{model_id}' is not in the model mirror's index"
+                    ))
+                }),
+        }
+    }
+
+    /// Opens the contents of `asset` for reading, with `timeout` for an HTTP
+    /// download. A mirror's relative URLs resolve against its root.
+    fn open_asset(&self, asset: &GithubReleaseAsset, timeout: Duration) -> Result<Box<dyn Read>> {
+        let (reader, _) = self
+            .open_asset_range(asset, 0..asset.size, timeout)
+            .map_err(|failure| failure.error)?;
+        Ok(reader)
+    }
+
+    /// Opens the bytes `range` of `asset`, returning the reader with the
+    /// offset its bytes actually start at: a server that ignores the `Range`
+    /// header sends the whole asset from offset zero. A filesystem
+    /// repository's asset must lie inside it (see [`filesystem_asset_path`]).
+    /// Failed HTTP requests are classified for retrying by [`download_error`].
+    fn open_asset_range(
+        &self,
+        asset: &GithubReleaseAsset,
+        range: Range<u64>,
+        timeout: Duration,
+    ) -> std::result::Result<(Box<dyn Read>, u64), DownloadFailure> {
+        let url = asset.browser_download_url.as_str();
+        let url = match &self.source {
+            ModelSource::Filesystem { path } => {
+                let asset_path = filesystem_asset_path(path, url)?;
+                let mut file = File::open(&asset_path).map_err(|error| {
+                    AppError::new(
+                        ErrorCode::Network,
+                        format!("Failed to open {}: {error}", asset_path.display()),
+                    )
+                })?;
+                file.seek(SeekFrom::Start(range.start))?;
+                return Ok((Box::new(file.take(range.end - range.start)), range.start));
+            }
+            source => http_asset_url(source, url),
+        };
+        let mut request = self.client.get(url).timeout(timeout);
+        if range != (0..asset.size) {
+            request = request.header(
+                "Range",
+                format!("bytes={}-{}", range.start, range.end.saturating_sub(1)),
+            );
+        }
+        let response = request
+            .send()
+            .and_then(|response| response.error_for_status())
+            .map_err(download_error)?;
+        let start = if response.status() == StatusCode::PARTIAL_CONTENT {
+            range.start
+        } else {
+            0
+        };
+        Ok((Box::new(response), start))
+    }
+
+    /// Whether the source serves byte ranges of `asset`, which a parallel
+    /// download needs, probed by requesting its second byte.
+    fn accepts_ranges(&self, asset: &GithubReleaseAsset) -> bool {
+        asset.size > 1
+            && self
+                .open_asset_range(asset, 1..2, Duration::from_secs(30))
+                .is_ok_and(|(_, start)| start == 1)
+    }
+
+    /// Fetches and decodes a GitHub API response.
+    fn github_json<T: serde::de::DeserializeOwned>(&self, url: &str) -> Result<T> {
+        self.client
+            .get(url)
+            .timeout(Duration::from_secs(30))
+            .header("Accept", "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", GITHUB_API_VERSION)
+            .send()
+            .and_then(|response| response.error_for_status())
+            .map_err(|error| AppError::Network(error.to_string()))?
+            .json()
+            .map_err(|error| AppError::Network(error.to_string()))
+    }
+
+    /// Starts a plain HTTP download of `url`.
+    fn get(&self, url: &str, timeout: Duration) -> Result<Box<dyn Read>> {
+        let response = self
+            .client
+            .get(url)
+            .timeout(timeout)
+            .send()
+            .and_then(|response| response.error_for_status())
+            .map_err(|error| AppError::Network(error.to_string()))?;
+        Ok(Box::new(response))
+    }
+}
+
+/// The URL an asset listed with `url` is downloaded from: an HTTP mirror's
+/// relative URLs resolve against its root, and absolute URLs are used as
+/// they are.
+fn http_asset_url(source: &ModelSource, url: &str) -> String {
+    match source {
+        ModelSource::HttpMirror { base_url } if !url.contains("://") => mirror_url(base_url, url),
+        _ => url.to_string(),
+    }
+}
+
+/// The file an asset listed with `url` is read from in the filesystem
+/// repository at `root`. An index may only name files inside the
+/// repository: URLs with a scheme, absolute paths and `..` components are
+/// rejected.
+fn filesystem_asset_path(root: &Path, url: &str) -> Result<PathBuf> {
+    let relative = Path::new(url);
+    let inside = !url.contains("://")
+        && relative
+            .components()
+            .any(|component| matches!(component, Component::Normal(_)))
+        && relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if !inside {
+        return Err(AppError::new(
+            ErrorCode::ModelInvalid,
+            format!(
+                "The model repository at {} lists an asset outside it: {url}",
+                root.display()
+            ),
+        )
+        .with_path(root));
+    }
+    Ok(root.join(relative))
+}
+
+/// A failed request for an asset or a byte range of one, with whether
+/// repeating the request may succeed.
+#[derive(Debug)]
+struct DownloadFailure {
+    error: AppError,
+    /// Set for transient failures: a timeout, a connection or read failure, a
+    /// server error, a rate limit or a response cut short.
+    retryable: bool,
+}
+
+impl DownloadFailure {
+    /// A failure another attempt may get past.
+    fn transient(error: AppError) -> Self {
+        Self {
+            error,
+            retryable: true,
+        }
+    }
+}
+
+/// Any other error fails the download at once.
+impl From<AppError> for DownloadFailure {
+    fn from(error: AppError) -> Self {
+        Self {
+            error,
+            retryable: false,
+        }
+    }
+}
+
+impl From<std::io::Error> for DownloadFailure {
+    fn from(error: std::io::Error) -> Self {
+        AppError::from(error).into()
+    }
+}
+
+/// Converts a failed asset request into an [`ErrorCode::Network`] failure,
+/// retryable when another attempt may succeed — a timeout, a connection or
+/// read failure, a server error or a rate limit — but not for responses such
+/// as a 403, 404 or 410.
+fn download_error(error: reqwest::Error) -> DownloadFailure {
+    let retryable = match error.status() {
+        Some(status) => is_retryable_status(status),
+        None => error.is_timeout() || error.is_connect() || error.is_request() || error.is_body(),
+    };
+    DownloadFailure {
+        error: AppError::new(ErrorCode::Network, error.to_string()),
+        retryable,
+    }
+}
+
+/// Whether an asset request answered with `status` is worth repeating.
+fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Joins a path relative to an HTTP mirror onto its `base_url`.
+fn mirror_url(base_url: &str, path: &str) -> String {
+    format!(
+        "{}/{}",
+        base_url.trim_end_matches('/'),
+        path.trim_start_matches('/')
+    )
 }
 
 /// Downloads and authenticates the small manifest before any large payload.
@@ -541,7 +1341,7 @@ fn fetch_release(client: &Client, model_id: &str) -> Result<GithubRelease> {
 /// manifest must agree with GitHub's release-asset digests. This establishes a
 /// single checksum contract for the later streaming downloads.
 fn fetch_release_manifest(
-    client: &Client,
+    repository: &ModelRepository,
     release: &GithubRelease,
     expected_id: &str,
 ) -> Result<ModelManifest> {
@@ -562,12 +1362,7 @@ fn fetch_release_manifest(
         )));
     }
     let expected_digest = parse_sha256(asset)?;
-    let response = client
-        .get(&asset.browser_download_url)
-        .timeout(Duration::from_secs(30))
-        .send()
-        .and_then(|response| response.error_for_status())
-        .map_err(|error| AppError::Network(error.to_string()))?;
+    let response = repository.open_asset(asset, Duration::from_secs(30))?;
     let mut bytes = Vec::with_capacity(asset.size as usize);
     response
         .take(asset.size + 1)
@@ -590,10 +1385,14 @@ fn fetch_release_manifest(
     let manifest: ModelManifest = serde_json::from_slice(&bytes)?;
     validate_manifest(&manifest, expected_id)?;
     for (asset_name, manifest_digest) in [
-        ("model.onnx", &manifest.checksums.model_sha256),
+        ("model.onnx", manifest.checksums.model_sha256.as_str()),
         (
             "attribute_directions.json",
-            &manifest.checksums.attribute_directions_sha256,
+            manifest
+                .checksums
+                .attribute_directions_sha256
+                .as_deref()
+                .unwrap_or_default(),
         ),
     ] {
         let asset = release
@@ -650,89 +1449,361 @@ fn required_release_assets(release: &GithubRelease) -> Result<Vec<&GithubRelease
         .collect()
 }
 
-#[allow(clippy::too_many_arguments)]
-/// Streams one release asset into the staging directory while authenticating it.
-///
-/// At most the GitHub-declared size plus one byte is read, and a response that
-/// crosses the declared size is rejected before the excess byte is written.
-/// `completed_bytes` is cumulative across bundle assets and is advanced only
-/// for bytes written to disk. Progress is emitted in approximately 1 MiB
-/// increments. A short response, oversized response, write failure, event
-/// failure, or SHA-256 mismatch leaves the error cleanup to the owning temporary
-/// directory in [`ensure_model`].
-fn download_asset(
-    client: &Client,
-    asset: &GithubReleaseAsset,
-    destination: &Path,
-    model_id: &str,
+/// Cumulative download progress of one bundle, shared by every asset and
+/// connection. Progress is emitted in approximately 1 MiB increments.
+struct DownloadProgress<'a, E> {
+    model_id: &'a str,
     total_bytes: u64,
-    completed_bytes: &mut u64,
-    events: &impl EventSink,
-) -> Result<()> {
-    let expected_digest = parse_sha256(asset)?;
-    let response = client
-        .get(&asset.browser_download_url)
-        .timeout(Duration::from_secs(60 * 60))
-        .send()
-        .and_then(|response| response.error_for_status())
-        .map_err(|error| AppError::Network(error.to_string()))?;
-    let mut response = response.take(asset.size.saturating_add(1));
-    let mut file = File::create(destination)?;
-    let mut hasher = Sha256::new();
-    let mut buffer = [0_u8; 64 * 1024];
-    let mut last_reported = *completed_bytes;
-    let mut asset_bytes = 0_u64;
+    completed_bytes: AtomicU64,
+    last_reported: Mutex<u64>,
+    events: &'a E,
+}
 
-    loop {
-        let read = response
-            .read(&mut buffer)
-            .map_err(|error| AppError::Network(error.to_string()))?;
-        if read == 0 {
-            break;
-        }
-        let next_asset_bytes = asset_bytes.checked_add(read as u64).ok_or_else(|| {
-            AppError::Network(format!(
-                "Download of {} exceeded its declared size",
-                asset.name
-            ))
-        })?;
-        if next_asset_bytes > asset.size {
-            return Err(AppError::Network(format!(
-                "Download of {} exceeded its declared size of {} bytes",
-                asset.name, asset.size
-            )));
+impl<'a, E: EventSink> DownloadProgress<'a, E> {
+    fn new(model_id: &'a str, total_bytes: u64, events: &'a E) -> Self {
+        Self {
+            model_id,
+            total_bytes,
+            completed_bytes: AtomicU64::new(0),
+            last_reported: Mutex::new(0),
+            events,
         }
-        file.write_all(&buffer[..read])?;
-        hasher.update(&buffer[..read]);
-        asset_bytes = next_asset_bytes;
-        *completed_bytes += read as u64;
-        if completed_bytes.saturating_sub(last_reported) >= 1024 * 1024 {
-            events.emit_value(
+    }
+
+    /// Counts `bytes` written to disk, or found in a resumed partial file.
+    fn advance(&self, bytes: u64) -> Result<()> {
+        let completed_bytes = self.completed_bytes.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        let mut last_reported = self
+            .last_reported
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if completed_bytes.saturating_sub(*last_reported) >= 1024 * 1024 {
+            self.events.emit_value(
                 "model_download_progress",
                 json!({
-                    "modelId": model_id,
-                    "downloadedBytes": *completed_bytes,
-                    "totalBytes": total_bytes,
+                    "modelId": self.model_id,
+                    "downloadedBytes": completed_bytes,
+                    "totalBytes": self.total_bytes,
                 }),
             )?;
-            last_reported = *completed_bytes;
+            *last_reported = completed_bytes;
         }
+        Ok(())
     }
-    file.sync_all()?;
+}
 
-    if asset_bytes != asset.size {
-        return Err(AppError::Network(format!(
-            "Download of {} ended at {} of {} bytes",
-            asset.name, asset_bytes, asset.size
-        )));
-    }
-    let actual_digest = format!("{:x}", hasher.finalize());
-    if actual_digest != expected_digest {
-        return Err(AppError::Processing(format!(
+/// Downloads one release asset into `partial_dir`, authenticates it and
+/// returns the path of the verified copy, `<sha256>.complete`.
+///
+/// Assets of at least `PARALLEL_DOWNLOAD_MIN_BYTES` are split across up to
+/// `connections` connections when the source accepts range requests (see
+/// [`fetch_asset_parts`]). The verified copy is left for [`ensure_model`] to
+/// remove once the bundle is installed; a later call finding it re-hashes it
+/// instead of downloading the asset again.
+fn download_asset(
+    repository: &ModelRepository,
+    asset: &GithubReleaseAsset,
+    partial_dir: &Path,
+    connections: usize,
+    progress: &DownloadProgress<'_, impl EventSink>,
+) -> Result<PathBuf> {
+    let expected_digest = parse_sha256(asset)?.to_ascii_lowercase();
+    fs::create_dir_all(partial_dir)?;
+    let verified = partial_dir.join(format!("{expected_digest}.complete"));
+    if verified.is_file() {
+        if sha256_file(&verified)? == expected_digest {
+            progress.advance(asset.size)?;
+            return Ok(verified);
+        }
+        fs::remove_file(&verified)?;
+    }
+
+    let connections = if connections > 1
+        && asset.size >= PARALLEL_DOWNLOAD_MIN_BYTES
+        && repository.accepts_ranges(asset)
+    {
+        connections
+    } else {
+        1
+    };
+    fetch_asset_parts(
+        repository,
+        asset,
+        partial_dir,
+        &expected_digest,
+        connections,
+        progress,
+    )
+}
+
+/// Fetches `asset` over `connections` range requests (see [`split_ranges`])
+/// and returns the path of the verified copy, `<expected_digest>.complete`.
+///
+/// Each range goes to a partial file under `partial_dir` named after the
+/// expected digest and the offset of the range, so a later call resumes it
+/// where it stopped. Once every range is complete the parts are joined and
+/// the whole file is checked against `expected_digest`; on a mismatch it is
+/// discarded so the next attempt starts over.
+fn fetch_asset_parts(
+    repository: &ModelRepository,
+    asset: &GithubReleaseAsset,
+    partial_dir: &Path,
+    expected_digest: &str,
+    connections: usize,
+    progress: &DownloadProgress<'_, impl EventSink>,
+) -> Result<PathBuf> {
+    let parts: Vec<(PathBuf, Range<u64>)> = split_ranges(asset.size, connections)
+        .into_iter()
+        .map(|range| {
+            (
+                partial_dir.join(format!("{expected_digest}-{}.part", range.start)),
+                range,
+            )
+        })
+        .collect();
+    remove_other_partial_files(partial_dir, expected_digest, &parts)?;
+
+    let failed = AtomicBool::new(false);
+    std::thread::scope(|scope| {
+        let downloads: Vec<_> = parts
+            .iter()
+            .map(|(part, range)| {
+                let failed = &failed;
+                scope.spawn(move || {
+                    let result =
+                        download_range_with_retry(repository, asset, part, range, progress, failed);
+                    if result.is_err() {
+                        failed.store(true, Ordering::Relaxed);
+                    }
+                    result
+                })
+            })
+            .collect();
+        downloads
+            .into_iter()
+            .map(|download| {
+                download.join().unwrap_or_else(|_| {
+                    Err(AppError::Processing(format!(
+                        "Download of {} panicked",
+                        asset.name
+                    )))
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .collect::<Result<()>>()
+    })?;
+
+    let joined = partial_dir.join(format!("{expected_digest}.joined"));
+    if let [(part, _)] = parts.as_slice() {
+        fs::rename(part, &joined)?;
+    } else {
+        let mut file = File::create(&joined)?;
+        for (part, _) in &parts {
+            std::io::copy(&mut File::open(part)?, &mut file)?;
+        }
+        file.sync_all()?;
+        for (part, _) in &parts {
+            fs::remove_file(part)?;
+        }
+    }
+
+    if sha256_file(&joined)? != expected_digest {
+        fs::remove_file(&joined)?;
+        return Err(AppError::Processing(format!(
             "SHA-256 verification failed for {}",
             asset.name
         )));
     }
+    let verified = partial_dir.join(format!("{expected_digest}.complete"));
+    fs::rename(&joined, &verified)?;
+    Ok(verified)
+}
+
+/// The byte ranges an asset of `size` bytes is downloaded in over
+/// `connections` connections: equal consecutive ranges, the last one
+/// shorter when `size` doesn't divide evenly, and no empty ones.
+fn split_ranges(size: u64, connections: usize) -> Vec<Range<u64>> {
+    let connections = connections.max(1) as u64;
+    let chunk_size = size.div_ceil(connections);
+    (0..connections)
+        .map(|index| index * chunk_size)
+        .take_while(|start| *start < size)
+        .map(|start| start..(start + chunk_size).min(size))
+        .collect()
+}
+
+/// Downloads the bytes `range` of `asset` into `part`, retrying failed
+/// requests with exponential backoff. Every retry resumes from the bytes
+/// already on disk. Only transient failures are retried (see
+/// [`DownloadFailure::retryable`]), and only while no other connection has
+/// `failed`.
+fn download_range_with_retry(
+    repository: &ModelRepository,
+    asset: &GithubReleaseAsset,
+    part: &Path,
+    range: &Range<u64>,
+    progress: &DownloadProgress<'_, impl EventSink>,
+    failed: &AtomicBool,
+) -> Result<()> {
+    let mut attempt = 0;
+    let mut counted = 0;
+    loop {
+        match download_range(
+            repository,
+            asset,
+            part,
+            range,
+            progress,
+            &mut counted,
+            failed,
+        ) {
+            Err(failure)
+                if failure.retryable
+                    && attempt + 1 < DOWNLOAD_ATTEMPTS
+                    && !failed.load(Ordering::Relaxed) =>
+            {
+                let delay = DOWNLOAD_RETRY_DELAY * 2_u32.pow(attempt);
+                eprintln!(
+                    "⚠️ Download of {} failed ({}); retrying in {}s",
+                    asset.name,
+                    failure.error,
+                    delay.as_secs()
+                );
+                std::thread::sleep(delay);
+                attempt += 1;
+            }
+            result => return result.map_err(|failure| failure.error),
+        }
+    }
+}
+
+/// One request for the bytes of `range` missing from `part`.
+///
+/// A part longer than its range, left by a download split differently, is
+/// cut to the range, and one whose range the server ignored is restarted when
+/// the range starts at zero. At most the missing length plus one byte is
+/// read, and a response that crosses the range is rejected before the excess
+/// byte is written. Returns early, leaving the part to be resumed, once
+/// another connection has `failed`.
+///
+/// `counted` is the length of the part already counted in `progress` by
+/// earlier attempts; only bytes beyond it advance the progress.
+fn download_range(
+    repository: &ModelRepository,
+    asset: &GithubReleaseAsset,
+    part: &Path,
+    range: &Range<u64>,
+    progress: &DownloadProgress<'_, impl EventSink>,
+    counted: &mut u64,
+    failed: &AtomicBool,
+) -> std::result::Result<(), DownloadFailure> {
+    let length = range.end - range.start;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .read(true)
+        .write(true)
+        .open(part)?;
+    let mut written = file.metadata()?.len();
+    if written > length {
+        file.set_len(length)?;
+        written = length;
+    }
+    count_progress(progress, counted, written)?;
+    if written == length {
+        return Ok(());
+    }
+
+    let (response, start) = repository.open_asset_range(
+        asset,
+        range.start + written..range.end,
+        Duration::from_secs(60 * 60),
+    )?;
+    if start != range.start + written {
+        if start != 0 || range.start != 0 {
+            return Err(AppError::new(
+                ErrorCode::Network,
+                format!(
+                    "The model source ignored the range request for {}",
+                    asset.name
+                ),
+            )
+            .into());
+        }
+        file.set_len(0)?;
+        written = 0;
+    }
+    file.seek(SeekFrom::Start(written))?;
+    let mut response = response.take(length - written + 1);
+    let mut buffer = [0_u8; 64 * 1024];
+    while !failed.load(Ordering::Relaxed) {
+        let read = response.read(&mut buffer).map_err(|error| {
+            DownloadFailure::transient(AppError::new(ErrorCode::Network, error.to_string()))
+        })?;
+        if read == 0 {
+            break;
+        }
+        if written + read as u64 > length {
+            return Err(AppError::Processing(format!(
+                "Download of {} exceeded its declared size of {} bytes",
+                asset.name, asset.size
+            ))
+            .into());
+        }
+        file.write_all(&buffer[..read])?;
+        written += read as u64;
+        count_progress(progress, counted, written)?;
+    }
+    file.sync_all()?;
+
+    if written != length && !failed.load(Ordering::Relaxed) {
+        return Err(DownloadFailure::transient(AppError::new(
+            ErrorCode::Network,
+            format!(
+                "Download of {} ended at {} of {} bytes",
+                asset.name,
+                range.start + written,
+                range.end
+            ),
+        )));
+    }
+    Ok(())
+}
+
+/// Advances `progress` by the bytes of a part of length `written` beyond the
+/// `counted` high-water mark.
+fn count_progress(
+    progress: &DownloadProgress<'_, impl EventSink>,
+    counted: &mut u64,
+    written: u64,
+) -> Result<()> {
+    if written > *counted {
+        progress.advance(written - *counted)?;
+        *counted = written;
+    }
+    Ok(())
+}
+
+/// Removes the partial files of the asset with `digest` that belong to a
+/// different split of it than `parts`. The part starting at zero is in every
+/// split; [`download_range`] cuts it to its range.
+fn remove_other_partial_files(
+    partial_dir: &Path,
+    digest: &str,
+    parts: &[(PathBuf, Range<u64>)],
+) -> Result<()> {
+    let prefix = format!("{digest}-");
+    for entry in fs::read_dir(partial_dir)? {
+        let path = entry?.path();
+        let is_asset_part = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with(&prefix));
+        if is_asset_part && !parts.iter().any(|(part, _)| *part == path) {
+            fs::remove_file(path)?;
+        }
+    }
     Ok(())
 }
 
@@ -748,7 +1819,7 @@ fn parse_sha256(asset: &GithubReleaseAsset) -> Result<&str> {
         .filter(|digest| digest.len() == 64 && digest.bytes().all(|byte| byte.is_ascii_hexdigit()))
         .ok_or_else(|| {
             AppError::Processing(format!(
-                "GitHub did not provide a valid SHA-256 digest for {}",
+                "The model source did not provide a valid SHA-256 digest for {}",
                 asset.name
             ))
         })?;
@@ -757,47 +1828,53 @@ fn parse_sha256(asset: &GithubReleaseAsset) -> Result<&str> {
 
 /// Loads an installed bundle and fully verifies its payload checksums.
 ///
-/// Structural and semantic validation runs before both payloads are streamed
+/// Structural and semantic validation runs before the payloads are streamed
 /// through SHA-256. This is the trust boundary used before model inference or
 /// attribute emphasis consumes files from Application Support.
 fn load_model_bundle(directory: &Path, expected_id: &str) -> Result<ModelBundle> {
     let bundle = validate_model_bundle_structure(directory, expected_id)?;
-    for (path, expected_digest) in [
-        (
-            bundle.directory.join("model.onnx"),
-            &bundle.manifest.checksums.model_sha256,
-        ),
-        (
-            bundle.directory.join("attribute_directions.json"),
-            &bundle.manifest.checksums.attribute_directions_sha256,
-        ),
-    ] {
-        let mut source = File::open(&path)?;
-        let mut hasher = Sha256::new();
-        let mut buffer = [0_u8; 64 * 1024];
-        loop {
-            let read = source.read(&mut buffer)?;
-            if read == 0 {
-                break;
-            }
-            hasher.update(&buffer[..read]);
-        }
-        if format!("{:x}", hasher.finalize()) != expected_digest.to_ascii_lowercase() {
-            return Err(AppError::Processing(format!(
-                "SHA-256 verification failed for {}",
-                path.display()
-            )));
+    let checksums = &bundle.manifest.checksums;
+    let directions_digest = checksums
+        .attribute_directions_sha256
+        .as_ref()
+        .map(|digest| (bundle.directory.join("attribute_directions.json"), digest));
+    for (path, expected_digest) in
+        std::iter::once((bundle.directory.join("model.onnx"), &checksums.model_sha256))
+            .chain(directions_digest)
+    {
+        if sha256_file(&path)? != expected_digest.to_ascii_lowercase() {
+            return Err(AppError::new(
+                ErrorCode::ModelInvalid,
+                format!("SHA-256 verification failed for {}", path.display()),
+            )
+            .with_model_id(expected_id)
+            .with_path(path));
         }
     }
     Ok(bundle)
 }
 
+/// Lowercase hexadecimal SHA-256 of the file at `path`, streamed.
+fn sha256_file(path: &Path) -> Result<String> {
+    let mut source = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0_u8; 64 * 1024];
+    loop {
+        let read = source.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
 /// Validates bundle shape and attribute semantics without re-hashing payloads.
 ///
 /// This is used immediately after download because [`download_asset`] already
 /// authenticated every byte in the same staging directory. It checks manifest
-/// compatibility, nonempty payloads, the complete 37-name attribute set, 512
-/// dimensions, finite values, and approximately unit-length directions.
+/// compatibility, nonempty payloads and, when the manifest declares them, the
+/// attribute directions (see [`validate_attribute_directions`]).
 fn validate_model_bundle_structure(directory: &Path, expected_id: &str) -> Result<ModelBundle> {
     let manifest: ModelManifest =
         serde_json::from_reader(File::open(directory.join("model.json"))?)?;
@@ -805,30 +1882,58 @@ fn validate_model_bundle_structure(directory: &Path, expected_id: &str) -> Resul
 
     let model_path = directory.join("model.onnx");
     if fs::metadata(&model_path).map_or(true, |metadata| metadata.len() == 0) {
-        return Err(AppError::Processing(format!(
-            "{} is missing or empty",
-            model_path.display()
-        )));
+        return Err(AppError::new(
+            ErrorCode::ModelInvalid,
+            format!("{} is missing or empty", model_path.display()),
+        )
+        .with_model_id(expected_id)
+        .with_path(model_path));
+    }
+    if manifest.checksums.attribute_directions_sha256.is_some() {
+        validate_attribute_directions(directory, &manifest, expected_id)?;
     }
+
+    Ok(ModelBundle {
+        directory: directory.to_path_buf(),
+        manifest,
+    })
+}
+
+/// Checks the bundle's `attribute_directions.json`: the complete 37-name set
+/// in the manifest's output dimensionality, with finite, approximately
+/// unit-length directions.
+fn validate_attribute_directions(
+    directory: &Path,
+    manifest: &ModelManifest,
+    expected_id: &str,
+) -> Result<()> {
     let directions_path = directory.join("attribute_directions.json");
     if fs::metadata(&directions_path).map_or(true, |metadata| metadata.len() == 0) {
-        return Err(AppError::Processing(format!(
-            "{} is missing or empty",
-            directions_path.display()
-        )));
+        return Err(AppError::new(
+            ErrorCode::ModelInvalid,
+            format!("{} is missing or empty", directions_path.display()),
+        )
+        .with_model_id(expected_id)
+        .with_path(directions_path));
     }
 
     let directions: AttributeDirections = serde_json::from_reader(File::open(&directions_path)?)?;
-    if directions.dim != 512
+    if directions.dim != manifest.output.dimensions
         || directions.attributes.len() != EMPHASIS_ATTRIBUTES.len()
         || EMPHASIS_ATTRIBUTES
             .iter()
             .any(|name| !directions.attributes.contains_key(*name))
     {
-        return Err(AppError::Processing(format!(
-            "{} must contain all 37 512-dimensional attribute directions",
-            directions_path.display()
-        )));
+        return Err(AppError::new(
+            ErrorCode::ModelInvalid,
+            format!(
+                "{} must contain all 37 {}-dimensional attribute directions",
+                directions_path.display(),
+                manifest.output.dimensions
+            ),
+        )
+        .with_model_id(expected_id)
+        .with_path(directions_path));
     }
     for (name, entry) in directions.attributes {
         let norm_squared = entry
@@ -841,23 +1946,24 @@ fn validate_model_bundle_structure(directory: &Path, expected_id: &str) -> Resul
             || !norm_squared.is_finite()
             || (norm_squared - 1.0).abs() > 0.01
         {
-            return Err(AppError::Processing(format!(
-                "Attribute direction '{name}' is invalid"
-            )));
+            return Err(AppError::new(
+                ErrorCode::ModelInvalid,
+                format!("Attribute direction '{name}' is invalid"),
+            )
+            .with_model_id(expected_id)
+            .with_path(&directions_path));
         }
     }
-
-    Ok(ModelBundle {
-        directory: directory.to_path_buf(),
-        manifest,
-    })
+    Ok(())
 }
 
 /// Enforces the application-facing invariants of `model.json`.
 ///
 /// A compatible manifest has the current API version, exactly the expected ID,
-/// a nonempty display name, a positive parameter count when supplied, and two
-/// syntactically valid SHA-256 values. This check does not read payload files.
+/// a nonempty display name, a positive parameter count when supplied, a usable
+/// input and output declaration (see [`validate_model_io`]), and syntactically
+/// valid SHA-256 values, which must include the attribute directions unless
+/// the model is a `local:` import. This check does not read payload files.
 fn validate_manifest(manifest: &ModelManifest, expected_id: &str) -> Result<()> {
     validate_model_id(&manifest.id)?;
     if manifest.model_api_version != MODEL_API_VERSION
@@ -865,37 +1971,91 @@ fn validate_manifest(manifest: &ModelManifest, expected_id: &str) -> Result<()>
         || manifest.name.trim().is_empty()
         || manifest.parameter_count.is_some_and(|count| count == 0)
     {
-        return Err(AppError::Processing(format!(
-            "Model '{}' is incompatible with FontCluster model API v{}",
-            manifest.id, MODEL_API_VERSION
-        )));
+        return Err(AppError::new(
+            ErrorCode::ModelInvalid,
+            format!(
+                "Model '{}' is incompatible with FontCluster model API v{}",
+                manifest.id, MODEL_API_VERSION
+            ),
+        )
+        .with_model_id(expected_id));
     }
-    for digest in [
-        &manifest.checksums.model_sha256,
-        &manifest.checksums.attribute_directions_sha256,
-    ] {
-        if digest.len() != 64 || !digest.bytes().all(|byte| byte.is_ascii_hexdigit()) {
-            return Err(AppError::Processing(format!(
-                "Model '{}' has an invalid SHA-256 manifest",
+    let directions_digest = &manifest.checksums.attribute_directions_sha256;
+    if directions_digest.is_none() && !is_local_model_id(&manifest.id) {
+        return Err(AppError::new(
+            ErrorCode::ModelInvalid,
+            format!(
+                "Model '{}' does not declare its attribute directions",
                 manifest.id
-            )));
+            ),
+        )
+        .with_model_id(expected_id));
+    }
+    for digest in std::iter::once(&manifest.checksums.model_sha256).chain(directions_digest) {
+        if digest.len() != 64 || !digest.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(AppError::new(
+                ErrorCode::ModelInvalid,
+                format!("Model '{}' has an invalid SHA-256 manifest", manifest.id),
+            )
+            .with_model_id(expected_id));
         }
     }
-    Ok(())
+    validate_model_io(manifest).map_err(|error| error.with_model_id(expected_id))
+}
+
+/// Checks that a manifest's input and output declaration can drive analysis:
+/// positive sizes, one normalisation value or one per channel, finite means,
+/// finite nonzero standard deviations and nonempty names.
+fn validate_model_io(manifest: &ModelManifest) -> Result<()> {
+    let input = &manifest.input;
+    let channels = input.channels.count();
+    let problem = if input.size == 0 || input.batch_size == 0 {
+        Some("a zero input size or batch size".to_string())
+    } else if input.batch_dimension.trim().is_empty() || manifest.output.name.trim().is_empty() {
+        Some("an empty batch dimension or output name".to_string())
+    } else if manifest.output.dimensions == 0 {
+        Some("zero output dimensions".to_string())
+    } else if [&input.mean, &input.std]
+        .iter()
+        .any(|values| values.len() != 1 && values.len() != channels)
+    {
+        Some(format!(
+            "normalisation values for other than {channels} channel(s)"
+        ))
+    } else if input.mean.iter().any(|mean| !mean.is_finite())
+        || input.std.iter().any(|std| !std.is_finite() || *std == 0.0)
+    {
+        Some("a non-finite mean or a zero or non-finite standard deviation".to_string())
+    } else {
+        None
+    };
+    match problem {
+        Some(problem) => Err(AppError::new(
+            ErrorCode::ModelInvalid,
+            format!("Model '{}' declares {problem}", manifest.id),
+        )),
+        None => Ok(()),
+    }
 }
 
 /// Validates the identifier grammar used by release tags and filesystem paths.
 ///
 /// Restricting IDs to lowercase ASCII letters, digits, and internal hyphens
 /// ensures the ID remains one path segment and keeps staging-prefix matching
-/// unambiguous. Empty IDs and leading or trailing hyphens are rejected.
+/// unambiguous. Empty IDs and leading or trailing hyphens are rejected. An
+/// imported model's ID is such a name behind [`LOCAL_MODEL_PREFIX`]; the bare
+/// name `local` is reserved for the directory that holds them.
 fn validate_model_id(model_id: &str) -> Result<()> {
-    let valid = !model_id.is_empty()
-        && !model_id.starts_with('-')
-        && !model_id.ends_with('-')
-        && model_id
+    let name = model_id
+        .strip_prefix(LOCAL_MODEL_PREFIX)
+        .unwrap_or(model_id);
+    let valid = !name.is_empty()
+        && !name.starts_with('-')
+        && !name.ends_with('-')
+        && name
             .bytes()
-            .all(|byte| byte.is_ascii_lowercase() || byte.is_ascii_digit() || byte == b'-');
+            .all(|byte| byte.is_ascii_lowercase() || byte.is_ascii_digit() || byte == b'-')
+        && model_id != LOCAL_MODELS_DIR;
     if valid {
         Ok(())
     } else {
@@ -906,10 +2066,55 @@ fn validate_model_id(model_id: &str) -> Result<()> {
 }
 
 /// Returns the persistent model root under FontCluster's Application Support directory.
-fn installed_models_root() -> Result<PathBuf> {
+pub(crate) fn installed_models_root() -> Result<PathBuf> {
     Ok(AppState::get_base_dir()?.join("Models"))
 }
 
+/// True for the ID of a model imported with [`import_local_model`].
+pub fn is_local_model_id(model_id: &str) -> bool {
+    model_id.starts_with(LOCAL_MODEL_PREFIX)
+}
+
+/// Installation directory of `model_id`: `Models/<id>` for a published model,
+/// `Models/local/<name>` for an imported `local:<name>` one.
+pub(crate) fn model_directory(model_id: &str) -> Result<PathBuf> {
+    validate_model_id(model_id)?;
+    let models_root = installed_models_root()?;
+    Ok(match model_id.strip_prefix(LOCAL_MODEL_PREFIX) {
+        Some(name) => models_root.join(LOCAL_MODELS_DIR).join(name),
+        None => models_root.join(model_id),
+    })
+}
+
+/// Every installed model directory with the ID it is installed under,
+/// imported models included. Staging, backup and lock directories, whose
+/// names are not valid IDs, are skipped.
+pub(crate) fn installed_model_directories() -> Vec<(String, PathBuf)> {
+    let Ok(models_root) = installed_models_root() else {
+        return Vec::new();
+    };
+    let mut directories = Vec::new();
+    for (root, prefix) in [
+        (models_root.clone(), ""),
+        (models_root.join(LOCAL_MODELS_DIR), LOCAL_MODEL_PREFIX),
+    ] {
+        let Ok(entries) = fs::read_dir(root) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let directory = entry.path();
+            let Some(name) = directory.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            let id = format!("{prefix}{name}");
+            if directory.is_dir() && validate_model_id(&id).is_ok() {
+                directories.push((id, directory));
+            }
+        }
+    }
+    directories
+}
+
 /// Discovers locally installed bundles for catalog presentation.
 ///
 /// Discovery is intentionally tolerant and shallow: unreadable directories,
@@ -917,33 +2122,22 @@ fn installed_models_root() -> Result<PathBuf> {
 /// SHA-256 and attribute semantics are deferred to [`resolve_model`]. This
 /// prevents catalog opening from hashing a model that may be hundreds of MB.
 fn discover_local_models() -> Vec<ModelManifest> {
-    let Ok(models_root) = installed_models_root() else {
-        return Vec::new();
-    };
-    let Ok(entries) = fs::read_dir(models_root) else {
-        return Vec::new();
-    };
     let mut manifests = Vec::new();
-    for entry in entries.flatten() {
-        let directory = entry.path();
-        let Some(id) = directory.file_name().and_then(|name| name.to_str()) else {
-            continue;
-        };
-        if validate_model_id(id).is_err() {
-            continue;
-        }
+    for (id, directory) in installed_model_directories() {
         let Ok(file) = File::open(directory.join("model.json")) else {
             continue;
         };
         let Ok(manifest) = serde_json::from_reader::<_, ModelManifest>(file) else {
             continue;
         };
-        if validate_manifest(&manifest, id).is_err()
-            || ["model.onnx", "attribute_directions.json"]
-                .iter()
-                .any(|name| {
-                    fs::metadata(directory.join(name)).map_or(true, |metadata| metadata.len() == 0)
-                })
+        let mut payloads = vec!["model.onnx"];
+        if manifest.checksums.attribute_directions_sha256.is_some() {
+            payloads.push("attribute_directions.json");
+        }
+        if validate_manifest(&manifest, &id).is_err()
+            || payloads.iter().any(|name| {
+                fs::metadata(directory.join(name)).map_or(true, |metadata| metadata.len() == 0)
+            })
         {
             continue;
         }
@@ -971,11 +2165,37 @@ fn reconcile_invalid_backup(models_root: &Path, model_id: &str) -> Result<()> {
     Ok(())
 }
 
-/// Removes abandoned staging directories for one model while its lock is held.
+/// Removes abandoned staging directories for one model while its lock is held,
+/// and partial downloads of any model untouched for
+/// `PARTIAL_DOWNLOAD_MAX_AGE`. Recent partial files are kept for resuming, and
+/// the per-model directories are left for their own model to remove.
 ///
 /// The underscore delimiter cannot occur in a valid model ID, so a prefix for
 /// `foo` cannot accidentally match a staging directory belonging to `foo-bar`.
 fn remove_stale_downloads(models_root: &Path, model_id: &str) -> Result<()> {
+    let partial_root = models_root.join(PARTIAL_DOWNLOADS_DIR);
+    let model_dirs = fs::read_dir(&partial_root)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir());
+    // Files directly under `.partial` were left by releases that shared it
+    // between models.
+    for dir in std::iter::once(partial_root.clone()).chain(model_dirs) {
+        for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+            let is_stale = entry
+                .metadata()
+                .ok()
+                .filter(|metadata| metadata.is_file())
+                .and_then(|metadata| metadata.modified().ok())
+                .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+                .is_some_and(|age| age > PARTIAL_DOWNLOAD_MAX_AGE);
+            if is_stale {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
     let prefix = format!(".download-{model_id}_");
     for entry in fs::read_dir(models_root)? {
         let path = entry?.path();
@@ -989,3 +2209,736 @@ fn remove_stale_downloads(models_root: &Path, model_id: &str) -> Result<()> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIGEST: &str = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    /// A v1 manifest as published before models declared their input and
+    /// output.
+    fn legacy_manifest() -> serde_json::Value {
+        json!({
+            "modelApiVersion": MODEL_API_VERSION,
+            "id": "fontcluster-base",
+            "name": "FontCluster Base",
+            "checksums": {
+                "modelSha256": DIGEST,
+                "attributeDirectionsSha256": DIGEST,
+            },
+        })
+    }
+
+    fn manifest_with_input(input: serde_json::Value) -> ModelManifest {
+        let mut manifest = legacy_manifest();
+        manifest["input"] = input;
+        serde_json::from_value(manifest).unwrap()
+    }
+
+    #[test]
+    fn legacy_manifest_describes_the_grayscale_models() {
+        let manifest: ModelManifest = serde_json::from_value(legacy_manifest()).unwrap();
+        assert_eq!(manifest.input, ModelInputSpec::default());
+        assert_eq!(manifest.input.size, 224);
+        assert_eq!(manifest.input.channels, ChannelLayout::Grayscale);
+        assert_eq!(manifest.input.normalisation(0), (0.0, 1.0));
+        assert_eq!(manifest.output.name, "embedding");
+        assert_eq!(manifest.output.dimensions, 512);
+        assert!(validate_manifest(&manifest, "fontcluster-base").is_ok());
+    }
+
+    #[test]
+    fn partial_input_keeps_the_other_defaults() {
+        let manifest = manifest_with_input(json!({ "size": 384, "channels": "rgb" }));
+        assert_eq!(manifest.input.size, 384);
+        assert_eq!(manifest.input.channels, ChannelLayout::Rgb);
+        assert_eq!(manifest.input.batch_size, 8);
+        // A single normalisation value applies to every channel.
+        assert_eq!(manifest.input.normalisation(2), (0.0, 1.0));
+        assert!(validate_manifest(&manifest, "fontcluster-base").is_ok());
+    }
+
+    #[test]
+    fn per_channel_normalisation_is_accepted() {
+        let manifest = manifest_with_input(json!({
+            "channels": "rgb",
+            "mean": [0.485, 0.456, 0.406],
+            "std": [0.229, 0.224, 0.225],
+        }));
+        assert!(validate_model_io(&manifest).is_ok());
+        assert_eq!(manifest.input.normalisation(1), (0.456, 0.224));
+    }
+
+    #[test]
+    fn normalisation_for_the_wrong_channel_count_is_rejected() {
+        for input in [
+            json!({ "channels": "rgb", "mean": [0.5, 0.5] }),
+            json!({ "channels": "rgb", "std": [0.2, 0.2, 0.2, 0.2] }),
+            json!({ "mean": [0.5, 0.5, 0.5] }),
+        ] {
+            let manifest = manifest_with_input(input.clone());
+            let error = validate_manifest(&manifest, "fontcluster-base").unwrap_err();
+            assert_eq!(error.code(), ErrorCode::ModelInvalid, "{input}");
+        }
+    }
+
+    #[test]
+    fn zero_standard_deviation_is_rejected() {
+        for input in [
+            json!({ "std": [0.0] }),
+            json!({ "channels": "rgb", "std": [0.2, 0.0, 0.2] }),
+        ] {
+            let manifest = manifest_with_input(input.clone());
+            let error = validate_model_io(&manifest).unwrap_err();
+            assert_eq!(error.code(), ErrorCode::ModelInvalid, "{input}");
+        }
+    }
+
+    #[test]
+    fn zero_sizes_are_rejected() {
+        for input in [json!({ "size": 0 }), json!({ "batchSize": 0 })] {
+            assert!(validate_model_io(&manifest_with_input(input)).is_err());
+        }
+        let mut manifest: ModelManifest = serde_json::from_value(legacy_manifest()).unwrap();
+        manifest.output.dimensions = 0;
+        assert!(validate_model_io(&manifest).is_err());
+    }
+
+    /// Writes an installed bundle whose manifest declares the checksums of
+    /// the given payloads.
+    fn write_installed_model(directory: &Path, model: &[u8], directions: &[u8]) {
+        fs::create_dir_all(directory).unwrap();
+        let mut manifest = legacy_manifest();
+        manifest["checksums"] = json!({
+            "modelSha256": format!("{:x}", Sha256::digest(model)),
+            "attributeDirectionsSha256": format!("{:X}", Sha256::digest(directions)),
+        });
+        fs::write(directory.join("model.json"), manifest.to_string()).unwrap();
+        fs::write(directory.join("model.onnx"), model).unwrap();
+        fs::write(directory.join("attribute_directions.json"), directions).unwrap();
+    }
+
+    fn mismatches(verification: &ModelVerification) -> Vec<&str> {
+        verification
+            .files
+            .iter()
+            .filter(|file| !file.matches)
+            .map(|file| file.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn intact_model_verifies() {
+        let dir = tempfile::tempdir().unwrap();
+        write_installed_model(dir.path(), b"weights", b"{}");
+
+        let verification = verify_model_directory("fontcluster-base", dir.path()).unwrap();
+        assert!(verification.valid);
+        assert_eq!(verification.files.len(), 2);
+        for file in &verification.files {
+            assert_eq!(
+                file.actual_sha256.as_deref(),
+                Some(file.expected_sha256.as_str())
+            );
+        }
+    }
+
+    #[test]
+    fn corrupted_or_missing_payloads_fail_verification() {
+        let dir = tempfile::tempdir().unwrap();
+        write_installed_model(dir.path(), b"weights", b"{}");
+        fs::write(dir.path().join("model.onnx"), b"truncated").unwrap();
+
+        let verification = verify_model_directory("fontcluster-base", dir.path()).unwrap();
+        assert!(!verification.valid);
+        assert_eq!(mismatches(&verification), ["model.onnx"]);
+
+        write_installed_model(dir.path(), b"weights", b"{}");
+        fs::remove_file(dir.path().join("attribute_directions.json")).unwrap();
+
+        let verification = verify_model_directory("fontcluster-base", dir.path()).unwrap();
+        assert!(!verification.valid);
+        assert_eq!(mismatches(&verification), ["attribute_directions.json"]);
+        assert_eq!(verification.files[1].actual_sha256, None);
+
+        fs::remove_file(dir.path().join("model.onnx")).unwrap();
+        let verification = verify_model_directory("fontcluster-base", dir.path()).unwrap();
+        assert_eq!(
+            mismatches(&verification),
+            ["model.onnx", "attribute_directions.json"]
+        );
+    }
+
+    #[test]
+    fn unreadable_manifest_fails_verification() {
+        let dir = tempfile::tempdir().unwrap();
+        write_installed_model(dir.path(), b"weights", b"{}");
+        fs::write(dir.path().join("model.json"), b"{").unwrap();
+        assert!(verify_model_directory("fontcluster-base", dir.path()).is_err());
+    }
+
+    #[test]
+    fn deleting_a_model_reports_its_size_and_drops_its_backup() {
+        let models_root = tempfile::tempdir().unwrap();
+        let directory = models_root.path().join("fontcluster-base");
+        write_installed_model(&directory, b"weights", b"{}");
+        let backup = models_root.path().join(".invalid-fontcluster-base");
+        write_installed_model(&backup, b"old", b"{}");
+        let size = dir_size(&directory);
+
+        let freed_bytes =
+            remove_model_directory(models_root.path(), "fontcluster-base", &directory).unwrap();
+        assert_eq!(freed_bytes, size);
+        assert!(!directory.exists());
+        assert!(!backup.exists());
+    }
+
+    fn write_zip(path: &Path, entries: &[&str]) {
+        let mut writer = zip::ZipWriter::new(File::create(path).unwrap());
+        for name in entries {
+            writer
+                .start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            std::io::Write::write_all(&mut writer, name.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    fn write_bundle(directory: &Path) {
+        fs::create_dir_all(directory).unwrap();
+        for name in REQUIRED_ASSETS {
+            fs::write(directory.join(name), name).unwrap();
+        }
+    }
+
+    fn staged(staging: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(staging)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn copies_a_directory_bundle_from_its_root_or_single_folder() {
+        for folder in ["", "bundle"] {
+            let source = tempfile::tempdir().unwrap();
+            write_bundle(&source.path().join(folder));
+            fs::write(source.path().join(".DS_Store"), "").unwrap();
+            let staging = tempfile::tempdir().unwrap();
+            copy_local_bundle(source.path(), staging.path()).unwrap();
+            assert_eq!(
+                staged(staging.path()),
+                ["attribute_directions.json", "model.json", "model.onnx"]
+            );
+            assert_eq!(
+                fs::read_to_string(staging.path().join("model.onnx")).unwrap(),
+                "model.onnx"
+            );
+        }
+    }
+
+    #[test]
+    fn directory_with_several_folders_is_missing_its_manifest() {
+        let source = tempfile::tempdir().unwrap();
+        write_bundle(&source.path().join("first"));
+        write_bundle(&source.path().join("second"));
+        let staging = tempfile::tempdir().unwrap();
+        let error = copy_local_bundle(source.path(), staging.path()).unwrap_err();
+        assert_eq!(error.code(), ErrorCode::ModelInvalid);
+    }
+
+    #[test]
+    fn copies_a_zip_bundle_from_its_root_or_single_folder() {
+        let directory = tempfile::tempdir().unwrap();
+        for entries in [
+            ["model.json", "model.onnx", "README.md"],
+            [
+                "bundle/model.json",
+                "bundle/model.onnx",
+                "bundle/deep/model.json",
+            ],
+        ] {
+            let archive = directory.path().join("bundle.zip");
+            write_zip(&archive, &entries);
+            let staging = tempfile::tempdir().unwrap();
+            copy_local_bundle(&archive, staging.path()).unwrap();
+            assert_eq!(staged(staging.path()), ["model.json", "model.onnx"]);
+            assert_eq!(
+                fs::read_to_string(staging.path().join("model.json")).unwrap(),
+                entries[0]
+            );
+        }
+    }
+
+    #[test]
+    fn zip_with_several_bundle_roots_or_duplicates_is_rejected() {
+        let directory = tempfile::tempdir().unwrap();
+        for entries in [
+            &["first/model.json", "first/model.onnx", "second/model.onnx"][..],
+            &["model.json", "model.onnx", "bundle/model.json"][..],
+            &[
+                "bundle/model.json",
+                "bundle/model.onnx",
+                "bundle//model.onnx",
+            ][..],
+        ] {
+            let archive = directory.path().join("bundle.zip");
+            write_zip(&archive, entries);
+            let staging = tempfile::tempdir().unwrap();
+            let error = copy_local_bundle(&archive, staging.path()).unwrap_err();
+            assert_eq!(error.code(), ErrorCode::ModelInvalid, "{entries:?}");
+        }
+    }
+
+    #[test]
+    fn mirror_urls_join_with_one_slash() {
+        for base_url in ["https://models.example", "https://models.example/"] {
+            for path in ["index.json", "/index.json"] {
+                assert_eq!(
+                    mirror_url(base_url, path),
+                    "https://models.example/index.json"
+                );
+            }
+        }
+        assert_eq!(
+            mirror_url("http://host/mirror/", "base/model.onnx"),
+            "http://host/mirror/base/model.onnx"
+        );
+    }
+
+    #[test]
+    fn relative_asset_urls_resolve_against_the_mirror_only() {
+        let mirror = ModelSource::HttpMirror {
+            base_url: "https://models.example/fontcluster".into(),
+        };
+        assert_eq!(
+            http_asset_url(&mirror, "base/model.onnx"),
+            "https://models.example/fontcluster/base/model.onnx"
+        );
+        assert_eq!(
+            http_asset_url(&mirror, "https://cdn.example/model.onnx"),
+            "https://cdn.example/model.onnx"
+        );
+        let github = ModelSource::default();
+        assert_eq!(
+            http_asset_url(&github, "https://github.com/a/b/model.onnx"),
+            "https://github.com/a/b/model.onnx"
+        );
+    }
+
+    #[test]
+    fn filesystem_assets_stay_inside_the_repository() {
+        let root = Path::new("/srv/models");
+        assert_eq!(
+            filesystem_asset_path(root, "base/model.onnx").unwrap(),
+            root.join("base/model.onnx")
+        );
+        assert_eq!(
+            filesystem_asset_path(root, "./model.json").unwrap(),
+            root.join("model.json")
+        );
+        for url in [
+            "",
+            ".",
+            "file:///etc/passwd",
+            "https://models.example/model.onnx",
+            "/etc/passwd",
+            "../model.onnx",
+            "base/../../model.onnx",
+        ] {
+            let error = filesystem_asset_path(root, url).unwrap_err();
+            assert_eq!(error.code(), ErrorCode::ModelInvalid, "{url}");
+        }
+    }
+
+    #[test]
+    fn model_source_is_tagged_by_kind_and_defaults_to_github() {
+        assert_eq!(
+            serde_json::to_value(ModelSource::default()).unwrap(),
+            json!({ "kind": "github", "apiBase": null })
+        );
+        let mirror = ModelSource::HttpMirror {
+            base_url: "https://models.example".into(),
+        };
+        assert_eq!(
+            serde_json::to_value(&mirror).unwrap(),
+            json!({ "kind": "http_mirror", "baseUrl": "https://models.example" })
+        );
+        // Sources saved with snake_case fields are still read.
+        let sources: Vec<ModelSource> = serde_json::from_value(json!([
+            { "kind": "github" },
+            { "kind": "http_mirror", "baseUrl": "https://models.example" },
+            { "kind": "http_mirror", "base_url": "https://models.example" },
+            { "kind": "filesystem", "path": "/srv/models" },
+        ]))
+        .unwrap();
+        assert_eq!(
+            sources,
+            [
+                ModelSource::default(),
+                mirror.clone(),
+                mirror,
+                ModelSource::Filesystem {
+                    path: "/srv/models".into(),
+                },
+            ]
+        );
+        assert!(serde_json::from_value::<ModelSource>(json!({ "kind": "ftp" })).is_err());
+    }
+
+    #[test]
+    fn unusable_model_sources_are_not_saved() {
+        for source in [
+            ModelSource::Github {
+                api_base: Some("ftp://models.example".into()),
+            },
+            ModelSource::HttpMirror {
+                base_url: "models.example".into(),
+            },
+            ModelSource::Filesystem {
+                path: "relative/models".into(),
+            },
+        ] {
+            let error = write_model_source(&source).unwrap_err();
+            assert_eq!(error.code(), ErrorCode::ModelSourceInvalid, "{source:?}");
+        }
+    }
+
+    #[derive(Clone)]
+    struct NoEvents;
+
+    impl EventSink for NoEvents {
+        fn emit_value(&self, _event: &str, _payload: serde_json::Value) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Asset bytes that differ at every offset a test splits them at.
+    fn asset_bytes(size: usize) -> Vec<u8> {
+        (0..size).map(|index| (index % 251) as u8).collect()
+    }
+
+    fn asset_for(url: &str, bytes: &[u8]) -> GithubReleaseAsset {
+        GithubReleaseAsset {
+            name: "model.onnx".into(),
+            browser_download_url: url.into(),
+            size: bytes.len() as u64,
+            digest: Some(format!("sha256:{:x}", Sha256::digest(bytes))),
+        }
+    }
+
+    fn repository(source: ModelSource) -> ModelRepository {
+        ModelRepository {
+            source,
+            client: Client::builder().no_proxy().build().unwrap(),
+        }
+    }
+
+    /// A filesystem repository holding `bytes` as `model.onnx`.
+    fn filesystem_repository(bytes: &[u8]) -> (tempfile::TempDir, ModelRepository) {
+        let root = tempfile::tempdir().unwrap();
+        fs::write(root.path().join("model.onnx"), bytes).unwrap();
+        let repository = repository(ModelSource::Filesystem {
+            path: root.path().to_path_buf(),
+        });
+        (root, repository)
+    }
+
+    /// Serves `body` over HTTP with a 200 response to every request,
+    /// ignoring `Range` headers, and returns the server's base URL.
+    fn serve_ignoring_ranges(body: Vec<u8>) -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else {
+                    continue;
+                };
+                let _ = stream.read(&mut [0_u8; 4096]);
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                );
+                let _ = stream.write_all(&body);
+            }
+        });
+        format!("http://{address}")
+    }
+
+    fn download_part(
+        repository: &ModelRepository,
+        asset: &GithubReleaseAsset,
+        part: &Path,
+        range: Range<u64>,
+    ) -> std::result::Result<(), DownloadFailure> {
+        let progress = DownloadProgress::new("test", asset.size, &NoEvents);
+        download_range(
+            repository,
+            asset,
+            part,
+            &range,
+            &progress,
+            &mut 0,
+            &AtomicBool::new(false),
+        )
+    }
+
+    #[test]
+    fn split_ranges_cover_the_asset_without_empty_parts() {
+        assert_eq!(split_ranges(100, 1), [0..100]);
+        assert_eq!(split_ranges(100, 4), [0..25, 25..50, 50..75, 75..100]);
+        assert_eq!(split_ranges(10, 3), [0..4, 4..8, 8..10]);
+        // More connections than a small asset has room for leaves some idle.
+        assert_eq!(split_ranges(5, 4), [0..2, 2..4, 4..5]);
+        assert_eq!(split_ranges(3, 16), [0..1, 1..2, 2..3]);
+        assert_eq!(split_ranges(100, 0), [0..100]);
+    }
+
+    #[test]
+    fn downloads_parts_of_a_filesystem_asset() {
+        let bytes = asset_bytes(1000);
+        let (_root, repository) = filesystem_repository(&bytes);
+        let asset = asset_for("model.onnx", &bytes);
+        let partial_dir = tempfile::tempdir().unwrap();
+        for range in split_ranges(1000, 3) {
+            let part = partial_dir.path().join(format!("{}.part", range.start));
+            download_part(&repository, &asset, &part, range.clone()).unwrap();
+            assert_eq!(
+                fs::read(&part).unwrap(),
+                bytes[range.start as usize..range.end as usize]
+            );
+        }
+    }
+
+    #[test]
+    fn oversized_first_part_is_cut_to_a_new_split() {
+        let bytes = asset_bytes(1000);
+        let (_root, repository) = filesystem_repository(&bytes);
+        let asset = asset_for("model.onnx", &bytes);
+        let partial_dir = tempfile::tempdir().unwrap();
+        // Left by a single-connection download before the split changed.
+        let part = partial_dir.path().join("0.part");
+        fs::write(&part, &bytes[..800]).unwrap();
+        download_part(&repository, &asset, &part, 0..500).unwrap();
+        assert_eq!(fs::read(&part).unwrap(), bytes[..500]);
+    }
+
+    #[test]
+    fn parts_of_a_stale_split_are_removed() {
+        let partial_dir = tempfile::tempdir().unwrap();
+        let digest = "ab".repeat(32);
+        let other_digest = "cd".repeat(32);
+        let parts: Vec<(PathBuf, Range<u64>)> = split_ranges(1000, 2)
+            .into_iter()
+            .map(|range| {
+                let part = partial_dir
+                    .path()
+                    .join(format!("{digest}-{}.part", range.start));
+                (part, range)
+            })
+            .collect();
+        let names = [
+            format!("{digest}-0.part"),
+            format!("{digest}-334.part"),
+            format!("{digest}-500.part"),
+            format!("{digest}-667.part"),
+            format!("{digest}.complete"),
+            format!("{other_digest}-334.part"),
+        ];
+        for name in &names {
+            fs::write(partial_dir.path().join(name), "").unwrap();
+        }
+        remove_other_partial_files(partial_dir.path(), &digest, &parts).unwrap();
+        for (name, kept) in names.iter().zip([true, false, true, false, true, true]) {
+            assert_eq!(partial_dir.path().join(name).exists(), kept, "{name}");
+        }
+    }
+
+    #[test]
+    fn part_restarts_when_the_server_ignores_the_range() {
+        let bytes = asset_bytes(1000);
+        let base_url = serve_ignoring_ranges(bytes.clone());
+        let repository = repository(ModelSource::HttpMirror { base_url });
+        let asset = asset_for("model.onnx", &bytes);
+        let partial_dir = tempfile::tempdir().unwrap();
+        let part = partial_dir.path().join("0.part");
+        fs::write(&part, [0xff; 300]).unwrap();
+        download_part(&repository, &asset, &part, 0..1000).unwrap();
+        assert_eq!(fs::read(&part).unwrap(), bytes);
+
+        // A later part cannot restart from zero, and retrying can't help.
+        let part = partial_dir.path().join("500.part");
+        let failure = download_part(&repository, &asset, &part, 500..1000).unwrap_err();
+        assert!(!failure.retryable);
+        assert_eq!(failure.error.code(), ErrorCode::Network);
+    }
+
+    #[test]
+    fn unreadable_local_index_is_not_a_network_error() {
+        let bytes = asset_bytes(10);
+        let (root, repository) = filesystem_repository(&bytes);
+        let index = root.path().join(MIRROR_INDEX_FILE);
+        let error = repository.releases().unwrap_err();
+        assert_eq!(error.code(), ErrorCode::Io);
+        assert_eq!(error.details().path.as_deref(), Some(index.as_path()));
+
+        fs::write(&index, b"{ not json").unwrap();
+        let error = repository.releases().unwrap_err();
+        assert_eq!(error.code(), ErrorCode::Serialization);
+    }
+
+    #[test]
+    fn part_cut_short_is_retried() {
+        let bytes = asset_bytes(1000);
+        let (_root, repository) = filesystem_repository(&bytes[..600]);
+        let asset = asset_for("model.onnx", &bytes);
+        let partial_dir = tempfile::tempdir().unwrap();
+        let part = partial_dir.path().join("0.part");
+        let failure = download_part(&repository, &asset, &part, 0..1000).unwrap_err();
+        assert!(failure.retryable);
+        assert_eq!(failure.error.code(), ErrorCode::Network);
+        // The bytes received so far are kept for the next attempt.
+        assert_eq!(fs::read(&part).unwrap(), bytes[..600]);
+    }
+
+    #[test]
+    fn parts_are_joined_in_order_and_verified() {
+        let bytes = asset_bytes(1000);
+        let (_root, repository) = filesystem_repository(&bytes);
+        let asset = asset_for("model.onnx", &bytes);
+        let digest = parse_sha256(&asset).unwrap().to_string();
+        let partial_dir = tempfile::tempdir().unwrap();
+        // A part an earlier attempt stopped in the middle of.
+        fs::write(
+            partial_dir.path().join(format!("{digest}-250.part")),
+            &bytes[250..300],
+        )
+        .unwrap();
+
+        let progress = DownloadProgress::new("test", asset.size, &NoEvents);
+        let verified = fetch_asset_parts(
+            &repository,
+            &asset,
+            partial_dir.path(),
+            &digest,
+            4,
+            &progress,
+        )
+        .unwrap();
+        assert_eq!(
+            verified,
+            partial_dir.path().join(format!("{digest}.complete"))
+        );
+        assert_eq!(fs::read(&verified).unwrap(), bytes);
+        assert_eq!(fs::read_dir(partial_dir.path()).unwrap().count(), 1);
+        assert_eq!(progress.completed_bytes.load(Ordering::Relaxed), 1000);
+    }
+
+    #[test]
+    fn joined_parts_failing_verification_are_discarded() {
+        let bytes = asset_bytes(1000);
+        let (_root, repository) = filesystem_repository(&bytes);
+        let mut asset = asset_for("model.onnx", b"other bytes");
+        asset.size = bytes.len() as u64;
+        let digest = parse_sha256(&asset).unwrap().to_string();
+        let partial_dir = tempfile::tempdir().unwrap();
+        let progress = DownloadProgress::new("test", asset.size, &NoEvents);
+        assert!(fetch_asset_parts(
+            &repository,
+            &asset,
+            partial_dir.path(),
+            &digest,
+            3,
+            &progress
+        )
+        .is_err());
+        assert_eq!(fs::read_dir(partial_dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn stale_partial_files_are_removed_in_every_model_directory() {
+        let models_root = tempfile::tempdir().unwrap();
+        let partial_root = models_root.path().join(PARTIAL_DOWNLOADS_DIR);
+        let model_dir = partial_root.join("fontcluster-base");
+        fs::create_dir_all(&model_dir).unwrap();
+        let stale = SystemTime::now() - PARTIAL_DOWNLOAD_MAX_AGE - Duration::from_secs(60);
+        let write = |path: PathBuf, modified: SystemTime| {
+            fs::write(&path, b"part").unwrap();
+            File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(modified)
+                .unwrap();
+            path
+        };
+        let legacy = write(partial_root.join("legacy.part"), stale);
+        let old = write(model_dir.join("old.part"), stale);
+        let recent = write(model_dir.join("recent.part"), SystemTime::now());
+
+        remove_stale_downloads(models_root.path(), "other-model").unwrap();
+        assert!(!legacy.exists());
+        assert!(!old.exists());
+        assert!(recent.exists());
+    }
+
+    #[test]
+    fn verified_asset_is_kept_and_reused_until_installed() {
+        let bytes = asset_bytes(1000);
+        let (root, repository) = filesystem_repository(&bytes);
+        let asset = asset_for("model.onnx", &bytes);
+        let partial_dir = tempfile::tempdir().unwrap();
+        let progress = DownloadProgress::new("test", asset.size, &NoEvents);
+        let verified =
+            download_asset(&repository, &asset, partial_dir.path(), 4, &progress).unwrap();
+        assert_eq!(fs::read(&verified).unwrap(), bytes);
+        let left: Vec<PathBuf> = fs::read_dir(partial_dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(left, [verified.clone()]);
+
+        // The verified copy is used without reading the source again.
+        fs::remove_file(root.path().join("model.onnx")).unwrap();
+        let progress = DownloadProgress::new("test", asset.size, &NoEvents);
+        let reused = download_asset(&repository, &asset, partial_dir.path(), 4, &progress).unwrap();
+        assert_eq!(reused, verified);
+        assert_eq!(progress.completed_bytes.load(Ordering::Relaxed), 1000);
+    }
+
+    #[test]
+    fn corrupted_asset_is_discarded() {
+        let bytes = asset_bytes(1000);
+        let (_root, repository) = filesystem_repository(&bytes);
+        let mut asset = asset_for("model.onnx", b"other bytes");
+        asset.size = bytes.len() as u64;
+        let partial_dir = tempfile::tempdir().unwrap();
+        let progress = DownloadProgress::new("test", asset.size, &NoEvents);
+        assert!(download_asset(&repository, &asset, partial_dir.path(), 1, &progress).is_err());
+        assert_eq!(fs::read_dir(partial_dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn only_transient_statuses_are_retried() {
+        for status in [
+            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::BAD_GATEWAY,
+            StatusCode::SERVICE_UNAVAILABLE,
+            StatusCode::TOO_MANY_REQUESTS,
+        ] {
+            assert!(is_retryable_status(status), "{status}");
+        }
+        for status in [
+            StatusCode::FORBIDDEN,
+            StatusCode::NOT_FOUND,
+            StatusCode::GONE,
+            StatusCode::UNAUTHORIZED,
+        ] {
+            assert!(!is_retryable_status(status), "{status}");
+        }
+    }
+}
@@ -24,6 +24,8 @@
 //! cell, which drops the largest side table of the forward pass.
 
 use crate::config::DendrogramMerge;
+use crate::core::CancellationToken;
+use crate::error::Result;
 use rayon::prelude::*;
 
 /// Endpoint-table cells (`|left leaves| × |right leaves|`) below which a merge
@@ -36,19 +38,24 @@ const PARALLEL_CELL_THRESHOLD: usize = 4096;
 /// `i = 0..leaf_count`, then `j = i + 1..leaf_count`. The caller builds both
 /// inputs together; debug assertions and release-mode early returns protect
 /// against a future contract regression.
+///
+/// `cancel` is checked before every merge and every table row of the forward
+/// pass. A cancelled run returns before the backtrack, leaving `merges`
+/// untouched.
 pub(super) fn optimize_leaf_order(
     merges: &mut [DendrogramMerge],
     condensed_distances: &[f32],
     leaf_count: usize,
-) {
+    cancel: &CancellationToken,
+) -> Result<()> {
     if leaf_count < 2 {
-        return;
+        return Ok(());
     }
     if merges.len() != leaf_count - 1
         || condensed_distances.len() != leaf_count * (leaf_count - 1) / 2
     {
         debug_assert!(false, "invalid dendrogram or condensed distance matrix");
-        return;
+        return Ok(());
     }
 
     let node_count = leaf_count + merges.len();
@@ -59,7 +66,7 @@ pub(super) fn optimize_leaf_order(
         .any(|(index, merge)| merge.left >= leaf_count + index || merge.right >= leaf_count + index)
     {
         debug_assert!(false, "dendrogram children are not topologically ordered");
-        return;
+        return Ok(());
     }
 
     // Relabel leaves by the tree's current left-first traversal. Every
@@ -68,14 +75,14 @@ pub(super) fn optimize_leaf_order(
     let leaf_order = ordered_leaves(merges, leaf_count);
     if leaf_order.len() != leaf_count {
         debug_assert!(false, "dendrogram does not contain every leaf exactly once");
-        return;
+        return Ok(());
     }
 
     let mut original_to_sorted = vec![usize::MAX; leaf_count];
     for (sorted, original) in leaf_order.iter().copied().enumerate() {
         if original >= leaf_count || original_to_sorted[original] != usize::MAX {
             debug_assert!(false, "dendrogram contains an invalid or duplicate leaf");
-            return;
+            return Ok(());
         }
         original_to_sorted[original] = sorted;
     }
@@ -101,7 +108,7 @@ pub(super) fn optimize_leaf_order(
         let node = leaf_count + merge_index;
         if left >= node || right >= node || ranges[left][1] != ranges[right][0] {
             debug_assert!(false, "dendrogram children are not topologically ordered");
-            return;
+            return Ok(());
         }
         ranges[node] = [ranges[left][0], ranges[right][1]];
     }
@@ -132,6 +139,7 @@ pub(super) fn optimize_leaf_order(
     let mut cost = vec![0.0f64; leaf_count * leaf_count];
 
     for [left, right] in sorted_children.iter().copied() {
+        cancel.check()?;
         let [left_start, boundary] = ranges[left];
         let right_end = ranges[right][1];
         let right_len = right_end - boundary;
@@ -148,7 +156,11 @@ pub(super) fn optimize_leaf_order(
             // inner endpoint `k`, the cheapest left path plus bridge,
             // `min over m of cost[u, m] + d(m, k)`. Phase 2: every right outer
             // endpoint `w` completes as `min over k of phase1[k] + cost[w, k]`.
+            // A cancelled run skips the remaining rows; the table is discarded.
             let fill_row = |u: usize, row_u: &mut [f64], head_costs: &mut Vec<f64>| {
+                if cancel.is_cancelled() {
+                    return;
+                }
                 let [m0, m1] = partner_range(left, u, leaf_count, &sorted_children, &ranges);
 
                 head_costs.clear();
@@ -201,6 +213,7 @@ pub(super) fn optimize_leaf_order(
         }
     }
 
+    cancel.check()?;
     let [root_left, root_right] = sorted_children[merges.len() - 1];
     let mut best_root = (usize::MAX, usize::MAX);
     let mut best_cost = f64::INFINITY;
@@ -283,6 +296,7 @@ pub(super) fn optimize_leaf_order(
         pending.push((second_child, best_inner.1, last_leaf));
         pending.push((first_child, first_leaf, best_inner.0));
     }
+    Ok(())
 }
 
 /// Returns the final left-first leaf order encoded by an oriented dendrogram.
@@ -457,7 +471,13 @@ mod tests {
             .map(|order| cyclic_cost(order, &distances, points.len()))
             .fold(f64::INFINITY, f64::min);
 
-        optimize_leaf_order(&mut merges, &distances, points.len());
+        optimize_leaf_order(
+            &mut merges,
+            &distances,
+            points.len(),
+            &CancellationToken::new(),
+        )
+        .unwrap();
 
         let optimized_order = leaf_order(&merges, points.len());
         let optimized_cost = cyclic_cost(&optimized_order, &distances, points.len());
@@ -489,21 +509,48 @@ mod tests {
     #[test]
     fn handles_degenerate_inputs_deterministically() {
         let mut single = Vec::new();
-        optimize_leaf_order(&mut single, &[], 1);
+        optimize_leaf_order(&mut single, &[], 1, &CancellationToken::new()).unwrap();
         assert!(single.is_empty());
 
         let mut pair = vec![merge(0, 1, 0.5, 0)];
-        optimize_leaf_order(&mut pair, &[0.5], 2);
+        optimize_leaf_order(&mut pair, &[0.5], 2, &CancellationToken::new()).unwrap();
         assert_eq!(leaf_order(&pair, 2), [0, 1]);
 
         let identical_distances = vec![0.0; 8 * 7 / 2];
         let mut first = alternating_subtrees();
         let mut second = first.clone();
-        optimize_leaf_order(&mut first, &identical_distances, 8);
-        optimize_leaf_order(&mut second, &identical_distances, 8);
+        optimize_leaf_order(
+            &mut first,
+            &identical_distances,
+            8,
+            &CancellationToken::new(),
+        )
+        .unwrap();
+        optimize_leaf_order(
+            &mut second,
+            &identical_distances,
+            8,
+            &CancellationToken::new(),
+        )
+        .unwrap();
         assert_eq!(leaf_order(&first, 8), leaf_order(&second, 8));
     }
 
+    #[test]
+    fn cancelled_run_leaves_the_merges_untouched() {
+        let points = [0.0, 0.1, 10.0, 10.1, 0.2, 0.3, 10.2, 10.3];
+        let distances = condensed_distances(&points);
+        let mut merges = alternating_subtrees();
+        let original_order = leaf_order(&merges, points.len());
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        let result = optimize_leaf_order(&mut merges, &distances, points.len(), &cancel);
+
+        assert!(result.is_err_and(|error| error.is_cancelled()));
+        assert_eq!(leaf_order(&merges, points.len()), original_order);
+    }
+
     #[test]
     fn matches_exhaustive_search_for_balanced_and_unbalanced_trees() {
         let points = [
@@ -552,7 +599,13 @@ mod tests {
                 .iter()
                 .map(|order| cyclic_cost(order, &distances, points.len()))
                 .fold(f64::INFINITY, f64::min);
-            optimize_leaf_order(&mut tree, &distances, points.len());
+            optimize_leaf_order(
+                &mut tree,
+                &distances,
+                points.len(),
+                &CancellationToken::new(),
+            )
+            .unwrap();
             let optimized_cost =
                 cyclic_cost(&leaf_order(&tree, points.len()), &distances, points.len());
             assert!((optimized_cost - exhaustive_minimum).abs() < 1e-6);
@@ -608,7 +661,7 @@ mod tests {
                 .collect();
 
             let start = std::time::Instant::now();
-            optimize_leaf_order(&mut merges, &condensed, n);
+            optimize_leaf_order(&mut merges, &condensed, n, &CancellationToken::new()).unwrap();
             let elapsed = start.elapsed();
 
             let order = leaf_order(&merges, n);
@@ -657,7 +710,13 @@ mod tests {
             .map(|order| cyclic_cost(order, &distances, points.len()))
             .fold(f64::INFINITY, f64::min);
 
-        optimize_leaf_order(&mut tree, &distances, points.len());
+        optimize_leaf_order(
+            &mut tree,
+            &distances,
+            points.len(),
+            &CancellationToken::new(),
+        )
+        .unwrap();
 
         let optimized_cycle =
             cyclic_cost(&leaf_order(&tree, points.len()), &distances, points.len());
@@ -0,0 +1,155 @@
+//! Fonts embedded in portable session documents.
+//!
+//! A session only records the names of the system fonts it clustered, so on
+//! another machine their previews can't be rendered. A portable export
+//! therefore packs a subset of each system font (see
+//! [`crate::rendering::subset`]) next to its sample as
+//! `samples/<safe_name>/font_subset.bin`, covering the glyphs of the
+//! session's sample text, and preview rendering falls back to that file when
+//! the font isn't installed locally. Faces whose `OS/2 fsType` forbids
+//! embedding, and CFF-flavoured faces (which can't be subset), are skipped and
+//! listed in the [`EmbeddedFontReport`]. Google Fonts are never embedded; they
+//! are downloaded on any machine.
+
+use crate::config::{FontSource, RenderConfig};
+use crate::core::session::{load_font_metadata, read_session_config_from_dir};
+use crate::core::SystemFontResolver;
+use crate::error::{AppError, Result};
+use crate::rendering::{
+    embedding_rights, has_glyf_outlines, subset_font, EmbeddingRights, FontRenderer,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use swash::FontRef;
+
+/// File name of an embedded font subset inside a font's sample directory.
+pub const EMBEDDED_FONT_FILE: &str = "font_subset.bin";
+
+/// Which of a session's system fonts a portable export embedded.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EmbeddedFontReport {
+    /// Safe names of the fonts embedded in the document.
+    pub embedded: Vec<String>,
+    /// Safe names of the fonts whose licence forbids embedding.
+    pub restricted: Vec<String>,
+    /// Safe names of the fonts with CFF (or other non-`glyf`) outlines, which
+    /// can't be subset and are too large to embed whole.
+    pub unsupported: Vec<String>,
+    /// Safe names of the fonts that are no longer installed (or failed to
+    /// subset); a subset they already carried from an earlier portable export
+    /// is kept.
+    pub unavailable: Vec<String>,
+}
+
+/// Embedded font subsets to add to a portable document, keyed by their entry
+/// name in the archive.
+#[derive(Debug, Default)]
+pub struct EmbeddedFonts {
+    pub files: BTreeMap<String, Vec<u8>>,
+    pub report: EmbeddedFontReport,
+}
+
+/// What became of one system font during [`collect_embedded_fonts`].
+enum EmbedOutcome {
+    Embedded(Vec<u8>),
+    Restricted,
+    Unsupported,
+}
+
+/// Resolves and subsets every system font of the session in `dir`.
+pub fn collect_embedded_fonts(dir: &Path) -> Result<EmbeddedFonts> {
+    let session = read_session_config_from_dir(dir)?;
+    let rendering = session.algorithm.rendering;
+    let renderer = FontRenderer::new(Arc::new(RenderConfig {
+        text: rendering.text,
+        font_size: rendering.font_size,
+        direction: rendering.direction,
+        features: rendering.features,
+        language: rendering.language,
+        canvas: rendering.canvas,
+        output_dir: dir.to_path_buf(),
+    }))?;
+    let resolver = SystemFontResolver::new();
+
+    let mut embedded_fonts = EmbeddedFonts::default();
+    let samples_dir = dir.join("samples");
+    if !samples_dir.exists() {
+        return Ok(embedded_fonts);
+    }
+    let mut safe_names: Vec<String> = fs::read_dir(&samples_dir)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    safe_names.sort();
+
+    for safe_name in safe_names {
+        let Ok(meta) = load_font_metadata(dir, &safe_name) else {
+            continue;
+        };
+        if meta.source != FontSource::System {
+            continue;
+        }
+        let subset = resolver.resolve(&meta).and_then(|(path, font_index)| {
+            let font_data = fs::read(&path).map_err(|e| {
+                AppError::Io(format!(
+                    "Failed to read font file {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+            let font = FontRef::from_index(&font_data, font_index as usize).ok_or_else(|| {
+                AppError::Font(format!(
+                    "Failed to parse font face {} in {}",
+                    font_index,
+                    path.display()
+                ))
+            })?;
+            match embedding_rights(font) {
+                EmbeddingRights::Restricted => Ok(EmbedOutcome::Restricted),
+                EmbeddingRights::Allowed { .. } if !has_glyf_outlines(font) => {
+                    Ok(EmbedOutcome::Unsupported)
+                }
+                EmbeddingRights::Allowed { subsettable } => {
+                    subset_font(font, renderer.glyph_ids(font), subsettable)
+                        .map(EmbedOutcome::Embedded)
+                }
+            }
+        });
+        match subset {
+            Ok(EmbedOutcome::Embedded(bytes)) => {
+                embedded_fonts
+                    .files
+                    .insert(format!("samples/{safe_name}/{EMBEDDED_FONT_FILE}"), bytes);
+                embedded_fonts.report.embedded.push(safe_name);
+            }
+            Ok(EmbedOutcome::Restricted) => {
+                println!("🔒 Not embedding {}: restricted by its licence", safe_name);
+                embedded_fonts.report.restricted.push(safe_name);
+            }
+            Ok(EmbedOutcome::Unsupported) => {
+                println!(
+                    "📦 Not embedding {}: CFF outlines can't be subset",
+                    safe_name
+                );
+                embedded_fonts.report.unsupported.push(safe_name);
+            }
+            Err(error) => {
+                eprintln!("⚠️ Could not embed {}: {}", safe_name, error);
+                embedded_fonts.report.unavailable.push(safe_name);
+            }
+        }
+    }
+
+    println!(
+        "📦 Embedded {} font subsets ({} restricted, {} unsupported, {} unavailable)",
+        embedded_fonts.report.embedded.len(),
+        embedded_fonts.report.restricted.len(),
+        embedded_fonts.report.unsupported.len(),
+        embedded_fonts.report.unavailable.len()
+    );
+    Ok(embedded_fonts)
+}
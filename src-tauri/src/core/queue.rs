@@ -0,0 +1,433 @@
+//! The persistent job queue.
+//!
+//! Pipeline runs and clustering sweeps are submitted to the [`JobQueue`] held
+//! in [`AppState`] rather than started directly. It starts the highest
+//! priority job (oldest first among equals) whenever fewer than
+//! [`JobQueue::max_concurrent`] are running, holding back any job whose
+//! session already has one in flight. The waiting jobs and the concurrency
+//! limit are saved to `job_queue.json` after every change and restored on the
+//! next launch; jobs that were running when the app quit are not, since their
+//! sessions are recovered as interrupted sessions instead (see
+//! [`super::resume`]). Dispatching itself lives with the worker plumbing in
+//! [`crate::commands::queue`].
+
+use crate::commands::jobs::{RunJobsRequest, RunMode, RunSweepRequest};
+use crate::core::AppState;
+use crate::error::{AppError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::sync::mpsc::Sender;
+use uuid::Uuid;
+
+/// File under the base dir holding the waiting jobs and concurrency limit.
+const JOB_QUEUE_FILE: &str = "job_queue.json";
+/// How many jobs run at once unless configured otherwise.
+pub const DEFAULT_MAX_CONCURRENT_JOBS: usize = 4;
+/// Upper bound on the configurable concurrency limit.
+pub const MAX_CONCURRENT_JOBS_LIMIT: usize = 16;
+
+/// Work a queued job performs once started.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum QueuedJobKind {
+    /// A processing pipeline run.
+    Pipeline { request: RunJobsRequest },
+    /// A clustering sweep.
+    Sweep { request: RunSweepRequest },
+}
+
+/// One submitted job.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedJob {
+    pub job_id: String,
+    /// Higher runs first; jobs of equal priority run in queue order.
+    pub priority: i32,
+    pub submitted_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub kind: QueuedJobKind,
+}
+
+impl QueuedJob {
+    /// Session that must not have another job in flight while this one runs:
+    /// the session a run modifies in place or duplicates, or the one a sweep
+    /// reads. `None` for a fresh run, which creates its own session.
+    pub fn protected_session_id(&self) -> Option<&str> {
+        match &self.kind {
+            QueuedJobKind::Pipeline { request } => match request.run_mode {
+                RunMode::DuplicateChanged => request.source_session_id.as_deref(),
+                RunMode::InPlaceChanged => request.session_id.as_deref(),
+                RunMode::Fresh => None,
+            },
+            QueuedJobKind::Sweep { request } => Some(&request.session_id),
+        }
+    }
+
+    /// Model the request asks the analysis stage to use. `None` for sweeps,
+    /// which only re-cluster stored vectors, and for runs that keep their
+    /// session's analysis settings.
+    pub fn requested_model_id(&self) -> Option<&str> {
+        match &self.kind {
+            QueuedJobKind::Pipeline { request } => request
+                .algorithm
+                .analysis
+                .as_ref()
+                .map(|analysis| analysis.model_id.as_str()),
+            QueuedJobKind::Sweep { .. } => None,
+        }
+    }
+}
+
+/// Waiting and running jobs plus the concurrency limit.
+#[derive(Debug)]
+pub struct JobQueue {
+    /// How many jobs may run at once.
+    pub max_concurrent: usize,
+    /// Jobs waiting to start, in queue order.
+    pub queued: Vec<QueuedJob>,
+    /// Jobs started from the queue that have not finished yet.
+    pub running: Vec<QueuedJob>,
+    /// Where to deliver each job's result, for jobs submitted by a caller
+    /// still awaiting it. Restored jobs have none.
+    waiters: HashMap<String, Sender<Result<String>>>,
+}
+
+/// What the webview sees of the queue; sent with `job_queue_changed`.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobQueueSnapshot {
+    pub max_concurrent: usize,
+    /// Waiting jobs, in queue order.
+    pub queued: Vec<QueuedJob>,
+    pub running: Vec<QueuedJob>,
+}
+
+/// The part of the queue that survives a restart.
+#[derive(Serialize, Deserialize)]
+struct StoredJobQueue {
+    max_concurrent: usize,
+    queued: Vec<QueuedJob>,
+}
+
+impl Default for JobQueue {
+    fn default() -> Self {
+        Self {
+            max_concurrent: DEFAULT_MAX_CONCURRENT_JOBS,
+            queued: Vec::new(),
+            running: Vec::new(),
+            waiters: HashMap::new(),
+        }
+    }
+}
+
+impl JobQueue {
+    /// Reads the saved queue, or an empty one when none has been saved.
+    pub fn load() -> Result<Self> {
+        let path = AppState::get_base_dir()?.join(JOB_QUEUE_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let stored: StoredJobQueue = serde_json::from_str(&fs::read_to_string(&path)?)?;
+        Ok(Self {
+            max_concurrent: stored.max_concurrent.clamp(1, MAX_CONCURRENT_JOBS_LIMIT),
+            queued: stored.queued,
+            ..Self::default()
+        })
+    }
+
+    /// Saves the waiting jobs and the concurrency limit.
+    pub fn save(&self) -> Result<()> {
+        let base_dir = AppState::get_base_dir()?;
+        fs::create_dir_all(&base_dir)?;
+        let stored = StoredJobQueue {
+            max_concurrent: self.max_concurrent,
+            queued: self.queued.clone(),
+        };
+        fs::write(
+            base_dir.join(JOB_QUEUE_FILE),
+            serde_json::to_vec_pretty(&stored)?,
+        )?;
+        Ok(())
+    }
+
+    /// A copy of the queue state without the result waiters.
+    pub fn snapshot(&self) -> JobQueueSnapshot {
+        JobQueueSnapshot {
+            max_concurrent: self.max_concurrent,
+            queued: self.queued.clone(),
+            running: self.running.clone(),
+        }
+    }
+
+    /// Appends a job and returns its id. With `waiter`, the job's result is
+    /// sent there once it finishes.
+    pub fn submit(
+        &mut self,
+        kind: QueuedJobKind,
+        priority: i32,
+        waiter: Option<Sender<Result<String>>>,
+    ) -> String {
+        let job_id = Uuid::now_v7().to_string();
+        self.queued.push(QueuedJob {
+            job_id: job_id.clone(),
+            priority,
+            submitted_at: Utc::now(),
+            kind,
+        });
+        if let Some(waiter) = waiter {
+            self.waiters.insert(job_id.clone(), waiter);
+        }
+        job_id
+    }
+
+    /// Moves the next job allowed to start from `queued` to `running` and
+    /// returns it; `None` when the limit is reached or every waiting job's
+    /// session is busy. `busy_session_ids` are sessions with a job running
+    /// outside the queue's knowledge (e.g. under its real session id).
+    pub fn start_next(&mut self, busy_session_ids: &HashSet<String>) -> Option<QueuedJob> {
+        if self.running.len() >= self.max_concurrent {
+            return None;
+        }
+        let index = self.next_startable(busy_session_ids)?;
+        let job = self.queued.remove(index);
+        self.running.push(job.clone());
+        Some(job)
+    }
+
+    /// Index in `queued` of the highest-priority, oldest job whose session is
+    /// free.
+    fn next_startable(&self, busy_session_ids: &HashSet<String>) -> Option<usize> {
+        let mut busy: HashSet<&str> = busy_session_ids.iter().map(String::as_str).collect();
+        busy.extend(
+            self.running
+                .iter()
+                .filter_map(QueuedJob::protected_session_id),
+        );
+        self.queued
+            .iter()
+            .enumerate()
+            .filter(|(_, job)| {
+                job.protected_session_id()
+                    .is_none_or(|session_id| !busy.contains(session_id))
+            })
+            // `max_by_key` keeps the last maximum, so the index is reversed to
+            // prefer the oldest job among equal priorities.
+            .max_by_key(|(index, job)| (job.priority, std::cmp::Reverse(*index)))
+            .map(|(index, _)| index)
+    }
+
+    /// Sessions the waiting and running jobs target (see
+    /// [`QueuedJob::protected_session_id`]), which must outlive those jobs.
+    pub fn protected_session_ids(&self) -> impl Iterator<Item = &str> {
+        self.queued
+            .iter()
+            .chain(&self.running)
+            .filter_map(QueuedJob::protected_session_id)
+    }
+
+    /// Whether a waiting or running job asks for `model_id` (see
+    /// [`QueuedJob::requested_model_id`]).
+    pub fn requests_model(&self, model_id: &str) -> bool {
+        self.queued
+            .iter()
+            .chain(&self.running)
+            .any(|job| job.requested_model_id() == Some(model_id))
+    }
+
+    /// Removes a finished job from `running` and returns whoever awaits its
+    /// result.
+    pub fn finish(&mut self, job_id: &str) -> Option<Sender<Result<String>>> {
+        self.running.retain(|job| job.job_id != job_id);
+        self.waiters.remove(job_id)
+    }
+
+    /// Removes the waiting jobs matching `predicate`, answering their
+    /// waiters with `"Cancelled"`. Returns the removed jobs.
+    pub fn cancel_queued(&mut self, predicate: impl Fn(&QueuedJob) -> bool) -> Vec<QueuedJob> {
+        let (cancelled, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut self.queued)
+            .into_iter()
+            .partition(predicate);
+        self.queued = kept;
+        for job in &cancelled {
+            if let Some(waiter) = self.waiters.remove(&job.job_id) {
+                let _ = waiter.send(Ok("Cancelled".into()));
+            }
+        }
+        cancelled
+    }
+
+    /// Sets a waiting job's priority.
+    pub fn set_priority(&mut self, job_id: &str, priority: i32) -> Result<()> {
+        let job = self
+            .queued
+            .iter_mut()
+            .find(|job| job.job_id == job_id)
+            .ok_or_else(|| {
+                AppError::Processing(format!("Job {} is not waiting in the queue", job_id))
+            })?;
+        job.priority = priority;
+        Ok(())
+    }
+
+    /// Puts the waiting jobs in the order of `job_ids`, which must list each
+    /// of them exactly once.
+    pub fn reorder(&mut self, job_ids: &[String]) -> Result<()> {
+        let is_permutation = job_ids.len() == self.queued.len()
+            && job_ids.iter().collect::<HashSet<_>>().len() == job_ids.len()
+            && self.queued.iter().all(|job| job_ids.contains(&job.job_id));
+        if !is_permutation {
+            return Err(AppError::Processing(
+                "The new order must list every queued job exactly once".into(),
+            ));
+        }
+        self.queued.sort_by_key(|job| {
+            job_ids
+                .iter()
+                .position(|job_id| *job_id == job.job_id)
+                .unwrap_or(usize::MAX)
+        });
+        Ok(())
+    }
+
+    /// Sets how many jobs may run at once. Lowering it lets running jobs
+    /// finish; it only holds back new starts.
+    pub fn set_max_concurrent(&mut self, max_concurrent: usize) -> Result<()> {
+        if !(1..=MAX_CONCURRENT_JOBS_LIMIT).contains(&max_concurrent) {
+            return Err(AppError::Processing(format!(
+                "The job concurrency limit must be between 1 and {MAX_CONCURRENT_JOBS_LIMIT}"
+            )));
+        }
+        self.max_concurrent = max_concurrent;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::jobs::AlgorithmConfigPatch;
+    use crate::config::AnalysisConfig;
+
+    fn in_place(session_id: &str) -> QueuedJobKind {
+        QueuedJobKind::Pipeline {
+            request: RunJobsRequest {
+                algorithm: AlgorithmConfigPatch {
+                    rendering: None,
+                    analysis: None,
+                    clustering: None,
+                },
+                session_id: Some(session_id.into()),
+                source_session_id: None,
+                override_status: None,
+                run_mode: RunMode::InPlaceChanged,
+                resume: false,
+                resource_profile: None,
+            },
+        }
+    }
+
+    #[test]
+    fn starts_highest_priority_then_oldest() {
+        let mut queue = JobQueue::default();
+        let low = queue.submit(in_place("a"), 0, None);
+        let high = queue.submit(in_place("b"), 5, None);
+        let low_later = queue.submit(in_place("c"), 0, None);
+
+        let busy = HashSet::new();
+        assert_eq!(queue.start_next(&busy).unwrap().job_id, high);
+        assert_eq!(queue.start_next(&busy).unwrap().job_id, low);
+        assert_eq!(queue.start_next(&busy).unwrap().job_id, low_later);
+        assert!(queue.start_next(&busy).is_none());
+    }
+
+    #[test]
+    fn holds_back_jobs_on_busy_sessions_and_past_the_limit() {
+        let mut queue = JobQueue::default();
+        queue.set_max_concurrent(2).unwrap();
+        let first = queue.submit(in_place("a"), 0, None);
+        let same_session = queue.submit(in_place("a"), 9, None);
+        let other = queue.submit(in_place("b"), 0, None);
+        let third = queue.submit(in_place("c"), 0, None);
+
+        let busy = HashSet::from(["a".to_string()]);
+        assert_eq!(queue.start_next(&busy).unwrap().job_id, other);
+        assert_eq!(queue.start_next(&busy).unwrap().job_id, third);
+        assert!(queue.start_next(&HashSet::new()).is_none());
+
+        queue.finish(&other);
+        queue.finish(&third);
+        let started = queue.start_next(&HashSet::new()).unwrap().job_id;
+        assert_eq!(started, same_session);
+        // `first` shares session `a` with the job now running.
+        assert!(queue.start_next(&HashSet::new()).is_none());
+        queue.finish(&started);
+        assert_eq!(queue.start_next(&HashSet::new()).unwrap().job_id, first);
+    }
+
+    #[test]
+    fn protects_the_sessions_of_waiting_and_running_jobs() {
+        let mut queue = JobQueue::default();
+        queue.submit(in_place("running"), 1, None);
+        queue.submit(in_place("waiting"), 0, None);
+        queue.submit(
+            QueuedJobKind::Sweep {
+                request: RunSweepRequest {
+                    session_id: "swept".into(),
+                    grid: Default::default(),
+                },
+            },
+            0,
+            None,
+        );
+        queue.set_max_concurrent(1).unwrap();
+        queue.start_next(&HashSet::new()).unwrap();
+
+        let protected: HashSet<&str> = queue.protected_session_ids().collect();
+        assert_eq!(protected, HashSet::from(["running", "waiting", "swept"]));
+    }
+
+    #[test]
+    fn finds_the_models_waiting_and_running_jobs_request() {
+        let fresh = |model_id: &str| QueuedJobKind::Pipeline {
+            request: RunJobsRequest {
+                algorithm: AlgorithmConfigPatch {
+                    rendering: None,
+                    analysis: Some(AnalysisConfig {
+                        model_id: model_id.into(),
+                    }),
+                    clustering: None,
+                },
+                session_id: None,
+                source_session_id: None,
+                override_status: None,
+                run_mode: RunMode::Fresh,
+                resume: false,
+                resource_profile: None,
+            },
+        };
+        let mut queue = JobQueue::default();
+        queue.submit(fresh("running"), 1, None);
+        queue.submit(fresh("waiting"), 0, None);
+        queue.submit(in_place("a"), 0, None);
+        queue.start_next(&HashSet::new()).unwrap();
+
+        assert!(queue.requests_model("running"));
+        assert!(queue.requests_model("waiting"));
+        assert!(!queue.requests_model("unused"));
+
+        let finished = queue.running[0].job_id.clone();
+        queue.finish(&finished);
+        assert!(!queue.requests_model("running"));
+    }
+
+    #[test]
+    fn reorder_requires_every_queued_job() {
+        let mut queue = JobQueue::default();
+        let a = queue.submit(in_place("a"), 0, None);
+        let b = queue.submit(in_place("b"), 0, None);
+
+        assert!(queue.reorder(std::slice::from_ref(&b)).is_err());
+        queue.reorder(&[b.clone(), a.clone()]).unwrap();
+        assert_eq!(queue.start_next(&HashSet::new()).unwrap().job_id, b);
+    }
+}
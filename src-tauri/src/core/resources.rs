@@ -0,0 +1,451 @@
+//! Resource limits for job workers.
+//!
+//! A [`ResourceProfile`] bounds what one worker process may take from the
+//! machine: the size of rayon's global pool (rendering, preprocessing, leaf
+//! ordering), ONNX Runtime's intra-op threads and batch size, the connections
+//! a model download opens, the process priority and a soft ceiling on
+//! resident memory. The profile is stored in the settings (see
+//! [`read_resource_profile`]) and can be overridden per run through
+//! [`RunJobsRequest::resource_profile`](crate::commands::jobs::RunJobsRequest).
+//!
+//! Workers apply it once at startup ([`ResourceProfile::apply_to_process`]).
+//! The memory ceiling is enforced by analysis, which halves its batch size
+//! when the worker's resident memory exceeds it (see [`BatchSizeGovernor`])
+//! and fails with an [`ErrorCode::ResourceLimit`] error once a single image no
+//! longer fits, and by clustering and sweeps, which check that their pairwise
+//! distance matrices fit before allocating them (see [`ensure_allocation_fits`]).
+
+use crate::core::AppState;
+use crate::error::{AppError, ErrorCode, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// File under the base dir holding the [`ResourceProfile`].
+const RESOURCE_PROFILE_FILE: &str = "resource_profile.json";
+/// Most connections one model asset may be downloaded over.
+pub const MAX_DOWNLOAD_CONNECTIONS: usize = 16;
+/// Fraction of the memory ceiling resident memory must grow by after a batch
+/// size reduction before [`BatchSizeGovernor`] reduces it again.
+const REDUCTION_GROWTH_DIVISOR: u64 = 32;
+/// Niceness a low-priority worker runs at on Unix.
+#[cfg(unix)]
+const LOW_PRIORITY_NICENESS: libc::c_int = 10;
+
+/// What a job worker may use; the default leaves every limit off.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResourceProfile {
+    /// Size of the worker's rayon thread pool; `None` uses every core.
+    #[serde(default)]
+    pub max_threads: Option<usize>,
+    /// ONNX Runtime intra-op threads; `None` follows `max_threads`.
+    #[serde(default)]
+    pub onnx_threads: Option<usize>,
+    /// Runs the worker below normal scheduling priority.
+    #[serde(default)]
+    pub low_priority: bool,
+    /// Soft ceiling on the worker's resident memory, in MiB; `None` sets no
+    /// ceiling.
+    #[serde(default)]
+    pub memory_ceiling_mb: Option<u64>,
+    /// Images per ONNX inference batch; `None` uses the batch size the
+    /// model's manifest declares.
+    #[serde(default)]
+    pub batch_size: Option<usize>,
+    /// Connections a large model asset is downloaded over, at most
+    /// [`MAX_DOWNLOAD_CONNECTIONS`]; `None` uses one.
+    #[serde(default)]
+    pub download_connections: Option<usize>,
+}
+
+impl ResourceProfile {
+    /// Threads ONNX inference may use.
+    pub fn effective_onnx_threads(&self) -> usize {
+        self.onnx_threads
+            .or(self.max_threads)
+            .unwrap_or_else(available_parallelism)
+    }
+
+    /// Connections a model download may open; a hand-edited profile's count
+    /// is capped at [`MAX_DOWNLOAD_CONNECTIONS`].
+    pub fn effective_download_connections(&self) -> usize {
+        self.download_connections
+            .unwrap_or(1)
+            .min(MAX_DOWNLOAD_CONNECTIONS)
+    }
+
+    /// The memory ceiling in bytes, if one is set.
+    pub fn memory_ceiling_bytes(&self) -> Option<u64> {
+        self.memory_ceiling_mb
+            .map(|mb| mb.saturating_mul(1024 * 1024))
+    }
+
+    /// Applies the thread limit and priority to the current worker process.
+    /// Must run before anything uses rayon's global pool.
+    pub fn apply_to_process(&self) -> Result<()> {
+        if let Some(threads) = self.max_threads {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build_global()
+                .map_err(|error| {
+                    AppError::Processing(format!("Failed to limit the worker threads: {error}"))
+                })?;
+        }
+        if self.low_priority {
+            lower_process_priority()?;
+        }
+        println!(
+            "🧮 Worker resources: {} threads, {} ONNX threads, {} priority, memory ceiling {}",
+            rayon::current_num_threads(),
+            self.effective_onnx_threads(),
+            if self.low_priority { "low" } else { "normal" },
+            self.memory_ceiling_mb
+                .map_or_else(|| "none".to_string(), |mb| format!("{mb} MiB"))
+        );
+        Ok(())
+    }
+}
+
+/// Reads the stored profile, or the default when none has been saved.
+pub fn read_resource_profile() -> Result<ResourceProfile> {
+    let path = AppState::get_base_dir()?.join(RESOURCE_PROFILE_FILE);
+    if !path.exists() {
+        return Ok(ResourceProfile::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(&path)?)?)
+}
+
+/// Validates and saves `profile`.
+pub fn write_resource_profile(profile: &ResourceProfile) -> Result<()> {
+    validate_resource_profile(profile)?;
+    let base_dir = AppState::get_base_dir()?;
+    fs::create_dir_all(&base_dir)?;
+    fs::write(
+        base_dir.join(RESOURCE_PROFILE_FILE),
+        serde_json::to_vec_pretty(profile)?,
+    )?;
+    Ok(())
+}
+
+/// Rejects thread counts, connection counts, memory ceilings and batch sizes
+/// of zero, which could never run a job, and more than
+/// [`MAX_DOWNLOAD_CONNECTIONS`] download connections, which would only burden
+/// the model source.
+pub fn validate_resource_profile(profile: &ResourceProfile) -> Result<()> {
+    if profile.max_threads == Some(0) || profile.onnx_threads == Some(0) {
+        return Err(AppError::Processing(
+            "A resource profile must allow at least one thread".into(),
+        ));
+    }
+    if profile.download_connections == Some(0) {
+        return Err(AppError::Processing(
+            "A resource profile must allow at least one download connection".into(),
+        ));
+    }
+    if profile
+        .download_connections
+        .is_some_and(|connections| connections > MAX_DOWNLOAD_CONNECTIONS)
+    {
+        return Err(AppError::Processing(format!(
+            "A resource profile may allow at most {MAX_DOWNLOAD_CONNECTIONS} download connections"
+        )));
+    }
+    if profile.batch_size == Some(0) {
+        return Err(AppError::Processing(
+            "A resource profile's batch size must be at least one image".into(),
+        ));
+    }
+    if profile.memory_ceiling_mb == Some(0) {
+        return Err(AppError::Processing(
+            "A resource profile's memory ceiling must be at least 1 MiB".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// The [`ErrorCode::ResourceLimit`] error reporting that `what` no longer fits
+/// under the memory ceiling of `profile`.
+pub fn memory_limit_error(profile: &ResourceProfile, what: &str) -> AppError {
+    AppError::new(
+        ErrorCode::ResourceLimit,
+        format!(
+            "{what} needs more memory than the {} MiB ceiling of the resource profile allows",
+            profile.memory_ceiling_mb.unwrap_or_default()
+        ),
+    )
+}
+
+/// Fails with an [`ErrorCode::ResourceLimit`] error, naming `what`, when
+/// allocating `bytes` more would take the worker over the memory ceiling of
+/// `profile`. Where resident memory can't be measured only `bytes` itself is
+/// compared with the ceiling.
+pub fn ensure_allocation_fits(profile: &ResourceProfile, bytes: u64, what: &str) -> Result<()> {
+    if allocation_fits(
+        profile.memory_ceiling_bytes(),
+        resident_memory_bytes(),
+        bytes,
+    ) {
+        Ok(())
+    } else {
+        Err(memory_limit_error(profile, what))
+    }
+}
+
+fn allocation_fits(ceiling: Option<u64>, resident: Option<u64>, bytes: u64) -> bool {
+    ceiling.is_none_or(|ceiling| resident.unwrap_or(0).saturating_add(bytes) <= ceiling)
+}
+
+/// Decides when analysis halves its batch size to get back under the memory
+/// ceiling.
+///
+/// Resident memory seldom shrinks once the allocator holds on to freed pages,
+/// so a worker that went over the ceiling usually stays over it after a
+/// reduction too; reacting to that alone would halve the batch after every
+/// batch down to a single image. A further reduction therefore waits until
+/// resident memory has grown past the level measured at the previous one by
+/// more than 1/[`REDUCTION_GROWTH_DIVISOR`] of the ceiling.
+#[derive(Debug, Clone)]
+pub struct BatchSizeGovernor {
+    ceiling: Option<u64>,
+    /// Resident memory measured at the last reduction.
+    reduced_at: Option<u64>,
+}
+
+impl BatchSizeGovernor {
+    pub fn new(profile: &ResourceProfile) -> Self {
+        Self {
+            ceiling: profile.memory_ceiling_bytes(),
+            reduced_at: None,
+        }
+    }
+
+    /// Takes the resident memory measured after a batch (`None` where it
+    /// can't be measured) and returns whether the batch size should shrink.
+    pub fn should_reduce(&mut self, resident: Option<u64>) -> bool {
+        let (Some(ceiling), Some(resident)) = (self.ceiling, resident) else {
+            return false;
+        };
+        if resident <= ceiling {
+            return false;
+        }
+        let margin = ceiling / REDUCTION_GROWTH_DIVISOR;
+        if self
+            .reduced_at
+            .is_some_and(|reduced_at| resident <= reduced_at.saturating_add(margin))
+        {
+            return false;
+        }
+        self.reduced_at = Some(resident);
+        true
+    }
+}
+
+/// Threads the machine offers.
+fn available_parallelism() -> usize {
+    std::thread::available_parallelism().map_or(1, |threads| threads.get())
+}
+
+/// Lowers the scheduling priority of the current process. On Linux the
+/// niceness applies to the calling thread, and is inherited by every thread
+/// started after it, which is why workers apply their profile first.
+#[cfg(unix)]
+fn lower_process_priority() -> Result<()> {
+    // SAFETY: setpriority only reads its integer arguments.
+    let result = unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, LOW_PRIORITY_NICENESS) };
+    if result != 0 {
+        return Err(AppError::Processing(format!(
+            "Failed to lower the worker priority: {}",
+            std::io::Error::last_os_error()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn lower_process_priority() -> Result<()> {
+    use windows_sys::Win32::System::Threading::{
+        GetCurrentProcess, SetPriorityClass, BELOW_NORMAL_PRIORITY_CLASS,
+    };
+    // SAFETY: the pseudo handle of the current process is always valid.
+    if unsafe { SetPriorityClass(GetCurrentProcess(), BELOW_NORMAL_PRIORITY_CLASS) } == 0 {
+        return Err(AppError::Processing(format!(
+            "Failed to lower the worker priority: {}",
+            std::io::Error::last_os_error()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(not(any(unix, windows)))]
+fn lower_process_priority() -> Result<()> {
+    Ok(())
+}
+
+/// Resident memory of the current process in bytes, or `None` where it cannot
+/// be measured.
+#[cfg(target_os = "linux")]
+pub fn resident_memory_bytes() -> Option<u64> {
+    let statm = fs::read_to_string("/proc/self/statm").ok()?;
+    let resident_pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+    // SAFETY: sysconf only reads its integer argument.
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    Some(resident_pages * u64::try_from(page_size).ok()?)
+}
+
+#[cfg(target_os = "macos")]
+pub fn resident_memory_bytes() -> Option<u64> {
+    let mut info = std::mem::MaybeUninit::<libc::proc_taskinfo>::zeroed();
+    let size = std::mem::size_of::<libc::proc_taskinfo>() as libc::c_int;
+    // SAFETY: `info` is a writable buffer of exactly `size` bytes.
+    let written = unsafe {
+        libc::proc_pidinfo(
+            std::process::id() as libc::c_int,
+            libc::PROC_PIDTASKINFO,
+            0,
+            info.as_mut_ptr().cast(),
+            size,
+        )
+    };
+    if written != size {
+        return None;
+    }
+    // SAFETY: proc_pidinfo filled the whole struct.
+    Some(unsafe { info.assume_init() }.pti_resident_size)
+}
+
+#[cfg(windows)]
+pub fn resident_memory_bytes() -> Option<u64> {
+    use windows_sys::Win32::System::ProcessStatus::{
+        GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS,
+    };
+    use windows_sys::Win32::System::Threading::GetCurrentProcess;
+    let mut counters = std::mem::MaybeUninit::<PROCESS_MEMORY_COUNTERS>::zeroed();
+    let size = std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
+    // SAFETY: `counters` is a writable buffer of exactly `size` bytes.
+    if unsafe { GetProcessMemoryInfo(GetCurrentProcess(), counters.as_mut_ptr(), size) } == 0 {
+        return None;
+    }
+    // SAFETY: GetProcessMemoryInfo filled the struct.
+    Some(unsafe { counters.assume_init() }.WorkingSetSize as u64)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+pub fn resident_memory_bytes() -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIB: u64 = 1024 * 1024;
+
+    fn ceiling(mb: u64) -> ResourceProfile {
+        ResourceProfile {
+            memory_ceiling_mb: Some(mb),
+            ..ResourceProfile::default()
+        }
+    }
+
+    #[test]
+    fn default_profile_is_valid() {
+        assert!(validate_resource_profile(&ResourceProfile::default()).is_ok());
+    }
+
+    #[test]
+    fn rejects_zero_limits() {
+        let invalid = [
+            ResourceProfile {
+                max_threads: Some(0),
+                ..ResourceProfile::default()
+            },
+            ResourceProfile {
+                onnx_threads: Some(0),
+                ..ResourceProfile::default()
+            },
+            ResourceProfile {
+                download_connections: Some(0),
+                ..ResourceProfile::default()
+            },
+            ResourceProfile {
+                batch_size: Some(0),
+                ..ResourceProfile::default()
+            },
+            ceiling(0),
+            ResourceProfile {
+                download_connections: Some(MAX_DOWNLOAD_CONNECTIONS + 1),
+                ..ResourceProfile::default()
+            },
+        ];
+        for profile in invalid {
+            assert!(
+                validate_resource_profile(&profile).is_err(),
+                "{profile:?} was accepted"
+            );
+        }
+    }
+
+    #[test]
+    fn accepts_the_most_download_connections() {
+        let profile = ResourceProfile {
+            download_connections: Some(MAX_DOWNLOAD_CONNECTIONS),
+            ..ResourceProfile::default()
+        };
+        assert!(validate_resource_profile(&profile).is_ok());
+    }
+
+    #[test]
+    fn accepts_positive_limits() {
+        let profile = ResourceProfile {
+            max_threads: Some(1),
+            onnx_threads: Some(2),
+            low_priority: true,
+            memory_ceiling_mb: Some(512),
+            batch_size: Some(1),
+            download_connections: Some(4),
+        };
+        assert!(validate_resource_profile(&profile).is_ok());
+        assert_eq!(profile.effective_onnx_threads(), 2);
+        assert_eq!(profile.effective_download_connections(), 4);
+        assert_eq!(profile.memory_ceiling_bytes(), Some(512 * MIB));
+    }
+
+    #[test]
+    fn governor_never_reduces_without_a_ceiling_or_a_measurement() {
+        let mut governor = BatchSizeGovernor::new(&ResourceProfile::default());
+        assert!(!governor.should_reduce(Some(u64::MAX)));
+        let mut governor = BatchSizeGovernor::new(&ceiling(100));
+        assert!(!governor.should_reduce(None));
+        assert!(!governor.should_reduce(Some(100 * MIB)));
+    }
+
+    #[test]
+    fn governor_reduces_again_only_after_further_growth() {
+        let mut governor = BatchSizeGovernor::new(&ceiling(320));
+        assert!(governor.should_reduce(Some(330 * MIB)));
+        // Still over the ceiling, but no longer growing: keep the batch size.
+        assert!(!governor.should_reduce(Some(330 * MIB)));
+        assert!(!governor.should_reduce(Some(325 * MIB)));
+        // Growth within the margin (1/32 of the ceiling, 10 MiB) is noise.
+        assert!(!governor.should_reduce(Some(340 * MIB)));
+        assert!(governor.should_reduce(Some(341 * MIB)));
+        assert!(!governor.should_reduce(Some(345 * MIB)));
+    }
+
+    #[test]
+    fn allocation_fits_under_the_ceiling() {
+        assert!(allocation_fits(None, Some(u64::MAX), u64::MAX));
+        assert!(allocation_fits(Some(100), Some(60), 40));
+        assert!(!allocation_fits(Some(100), Some(60), 41));
+        // Unmeasurable resident memory only compares the allocation itself.
+        assert!(allocation_fits(Some(100), None, 100));
+        assert!(!allocation_fits(Some(100), None, 101));
+        assert!(!allocation_fits(Some(100), Some(u64::MAX), 1));
+    }
+
+    #[test]
+    fn allocation_over_the_ceiling_is_a_resource_limit_error() {
+        let error = ensure_allocation_fits(&ceiling(1), 2 * MIB, "Clustering").unwrap_err();
+        assert_eq!(error.code(), ErrorCode::ResourceLimit);
+        assert!(ensure_allocation_fits(&ResourceProfile::default(), u64::MAX, "x").is_ok());
+    }
+}
@@ -0,0 +1,316 @@
+//! Crash recovery for interrupted processing jobs.
+//!
+//! A job works in the session's `Processing/<id>` directory and only packs it
+//! into a document once clustering completes, so a working directory whose
+//! status is short of `Clustered` belongs to a job that was killed, cancelled
+//! or failed. [`find_interrupted_sessions`] lists those for the UI to offer a
+//! resume, which reruns the pipeline in place from the persisted
+//! `process_status`.
+//!
+//! Rendering and analysis are per-font, so each font also gets a completion
+//! marker file in its sample directory once a stage's outputs for it are
+//! fully written. A resumed stage skips the marked fonts instead of starting
+//! over. Markers only exist while their stage is incomplete: a fresh attempt
+//! clears them first and a completed stage removes them before the status
+//! advances, so they never outlive the attempt that wrote them.
+//! A cancelled job discards the outputs of the fonts it left unmarked (see
+//! [`discard_partial_outputs`]).
+
+use crate::config::{ProcessStatus, SessionConfig};
+use crate::core::session::read_session_config_from_dir;
+use crate::core::AppState;
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// A per-font pipeline stage whose progress is marked font by font.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontStage {
+    /// `sample.png`, the stroke-contrast metric and `computed.json` written.
+    Rendered,
+    /// `vector.bin` written.
+    Analyzed,
+}
+
+impl FontStage {
+    /// Name of the marker file inside the font's sample directory.
+    fn marker_file(self) -> &'static str {
+        match self {
+            FontStage::Rendered => ".rendered",
+            FontStage::Analyzed => ".analyzed",
+        }
+    }
+
+    /// The stage's main per-font output, which later stages and session
+    /// migrations take as evidence that the stage ran for the font.
+    fn output_file(self) -> &'static str {
+        match self {
+            FontStage::Rendered => "sample.png",
+            FontStage::Analyzed => "vector.bin",
+        }
+    }
+}
+
+/// Marks `stage` complete for font `safe_name`. Call only once every output of
+/// the stage for that font is on disk.
+pub fn mark_font_complete(session_dir: &Path, safe_name: &str, stage: FontStage) -> Result<()> {
+    let font_dir = session_dir.join("samples").join(safe_name);
+    fs::write(font_dir.join(stage.marker_file()), [])?;
+    Ok(())
+}
+
+/// True if an interrupted attempt already completed `stage` for `safe_name`.
+pub fn is_font_complete(session_dir: &Path, safe_name: &str, stage: FontStage) -> bool {
+    session_dir
+        .join("samples")
+        .join(safe_name)
+        .join(stage.marker_file())
+        .exists()
+}
+
+/// Removes every `stage` marker in the session, when the stage starts afresh
+/// or once it has completed.
+pub fn clear_font_markers(session_dir: &Path, stage: FontStage) -> Result<()> {
+    let samples_dir = session_dir.join("samples");
+    if !samples_dir.exists() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(samples_dir)? {
+        let marker = entry?.path().join(stage.marker_file());
+        if marker.exists() {
+            fs::remove_file(marker)?;
+        }
+    }
+    Ok(())
+}
+
+/// Deletes what a stopped job left half-written in the session at `dir`: the
+/// output of the stage its status was in for every font that stage had not
+/// marked complete. Marked fonts keep their outputs for a resume; a
+/// clustering run clears its outputs before writing any, so it leaves nothing
+/// to discard.
+pub fn discard_partial_outputs(dir: &Path) -> Result<()> {
+    let stage = match read_session_config_from_dir(dir)?.status.process_status {
+        ProcessStatus::Empty => FontStage::Rendered,
+        ProcessStatus::Rendered => FontStage::Analyzed,
+        ProcessStatus::Analyzed | ProcessStatus::Clustered => return Ok(()),
+    };
+    let Ok(entries) = fs::read_dir(dir.join("samples")) else {
+        return Ok(());
+    };
+    for entry in entries {
+        let font_dir = entry?.path();
+        let output = font_dir.join(stage.output_file());
+        if output.exists() && !font_dir.join(stage.marker_file()).exists() {
+            fs::remove_file(output)?;
+        }
+    }
+    Ok(())
+}
+
+/// How many fonts in the session carry a `stage` marker.
+pub fn count_font_markers(session_dir: &Path, stage: FontStage) -> usize {
+    let Ok(entries) = fs::read_dir(session_dir.join("samples")) else {
+        return 0;
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().join(stage.marker_file()).exists())
+        .count()
+}
+
+/// A processing session whose job stopped before clustering completed.
+#[derive(Debug, Clone, Serialize)]
+pub struct InterruptedSession {
+    pub session_id: String,
+    pub title: String,
+    /// Stage the resumed pipeline restarts from.
+    pub process_status: ProcessStatus,
+    /// Last time the job persisted its progress.
+    pub modified_at: DateTime<Utc>,
+    /// Font-weight pairs discovery kept; `0` when interrupted before discovery
+    /// finished.
+    pub discovered_fonts: usize,
+    /// Fonts an interrupted rendering pass already finished.
+    pub rendered_fonts: usize,
+    /// Fonts an interrupted analysis pass already finished.
+    pub analyzed_fonts: usize,
+    /// True when a packed document from an earlier completed run exists, so
+    /// discarding the interruption restores that result rather than deleting
+    /// the session.
+    pub has_document: bool,
+}
+
+/// Lists the processing sessions left short of `Clustered`, most recently
+/// modified first, skipping `running_session_ids`.
+pub fn find_interrupted_sessions(
+    running_session_ids: &HashSet<String>,
+) -> Result<Vec<InterruptedSession>> {
+    let processing_root = AppState::get_session_processing_root()?;
+    if !processing_root.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut sessions = Vec::new();
+    for entry in fs::read_dir(&processing_root)? {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let Ok(session) = read_session_config_from_dir(&path) else {
+            continue;
+        };
+        if session.status.process_status == ProcessStatus::Clustered
+            || running_session_ids.contains(&session.session_id)
+        {
+            continue;
+        }
+        sessions.push(interrupted_session(&path, session)?);
+    }
+    sessions.sort_by(|a, b| b.modified_at.cmp(&a.modified_at));
+    Ok(sessions)
+}
+
+/// Summarises one interrupted session stored at `dir`.
+fn interrupted_session(dir: &Path, session: SessionConfig) -> Result<InterruptedSession> {
+    Ok(InterruptedSession {
+        has_document: AppState::get_session_document_path(&session.session_id)?.exists(),
+        discovered_fonts: session.discovered_fonts.values().map(Vec::len).sum(),
+        rendered_fonts: count_font_markers(dir, FontStage::Rendered),
+        analyzed_fonts: count_font_markers(dir, FontStage::Analyzed),
+        session_id: session.session_id,
+        title: session.title,
+        process_status: session.status.process_status,
+        modified_at: session.modified_at,
+    })
+}
+
+/// Drops the working directory of an interrupted session. A session with a
+/// document from an earlier run falls back to that result; one without is
+/// deleted entirely.
+pub fn discard_interrupted_session(id: &str) -> Result<()> {
+    if AppState::get_session_document_path(id)?.exists() {
+        fs::remove_dir_all(AppState::get_session_processing_dir(id)?)?;
+    } else {
+        AppState::delete_session_files(id)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AlgorithmConfig, ProcessingStatus};
+    use serde_json::json;
+
+    fn write_font_files(dir: &Path, safe_name: &str, files: &[&str]) {
+        let font_dir = dir.join("samples").join(safe_name);
+        fs::create_dir_all(&font_dir).unwrap();
+        for file in files {
+            fs::write(font_dir.join(file), b"").unwrap();
+        }
+    }
+
+    fn has_file(dir: &Path, safe_name: &str, file: &str) -> bool {
+        dir.join("samples").join(safe_name).join(file).exists()
+    }
+
+    fn write_config(dir: &Path, process_status: ProcessStatus) {
+        let status = ProcessingStatus {
+            process_status,
+            ..ProcessingStatus::default()
+        };
+        let config = json!({
+            "app_version": env!("CARGO_PKG_VERSION"),
+            "modified_app_version": env!("CARGO_PKG_VERSION"),
+            "session_id": "interrupted",
+            "created_at": Utc::now(),
+            "modified_at": Utc::now(),
+            "discovered_fonts": {},
+            "algorithm": AlgorithmConfig::default(),
+            "status": status,
+        });
+        fs::write(dir.join("config.json"), config.to_string()).unwrap();
+    }
+
+    #[test]
+    fn marks_counts_and_clears_markers_per_stage() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        for safe_name in ["a", "b", "c"] {
+            write_font_files(dir, safe_name, &[]);
+        }
+        mark_font_complete(dir, "a", FontStage::Rendered).unwrap();
+        mark_font_complete(dir, "b", FontStage::Rendered).unwrap();
+        mark_font_complete(dir, "a", FontStage::Analyzed).unwrap();
+
+        assert!(is_font_complete(dir, "a", FontStage::Rendered));
+        assert!(!is_font_complete(dir, "c", FontStage::Rendered));
+        assert_eq!(count_font_markers(dir, FontStage::Rendered), 2);
+        assert_eq!(count_font_markers(dir, FontStage::Analyzed), 1);
+
+        clear_font_markers(dir, FontStage::Rendered).unwrap();
+        assert_eq!(count_font_markers(dir, FontStage::Rendered), 0);
+        // Other stages' markers are left alone.
+        assert!(is_font_complete(dir, "a", FontStage::Analyzed));
+    }
+
+    #[test]
+    fn markers_of_a_session_without_samples_count_as_none() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(count_font_markers(dir.path(), FontStage::Rendered), 0);
+        clear_font_markers(dir.path(), FontStage::Rendered).unwrap();
+    }
+
+    #[test]
+    fn discards_unmarked_outputs_of_the_current_stage() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        // Rendering finished; analysis was stopped after marking `a`.
+        write_config(dir, ProcessStatus::Rendered);
+        write_font_files(dir, "a", &["sample.png", "vector.bin", ".analyzed"]);
+        write_font_files(dir, "b", &["sample.png", "vector.bin"]);
+        write_font_files(dir, "c", &["sample.png"]);
+
+        discard_partial_outputs(dir).unwrap();
+
+        assert!(has_file(dir, "a", "vector.bin"));
+        assert!(!has_file(dir, "b", "vector.bin"));
+        // Outputs of the completed rendering stage are kept.
+        for safe_name in ["a", "b", "c"] {
+            assert!(has_file(dir, safe_name, "sample.png"));
+        }
+    }
+
+    #[test]
+    fn discards_unmarked_samples_of_an_interrupted_rendering() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        write_config(dir, ProcessStatus::Empty);
+        write_font_files(dir, "a", &["sample.png", ".rendered"]);
+        write_font_files(dir, "b", &["sample.png"]);
+
+        discard_partial_outputs(dir).unwrap();
+
+        assert!(has_file(dir, "a", "sample.png"));
+        assert!(!has_file(dir, "b", "sample.png"));
+    }
+
+    #[test]
+    fn analyzed_and_clustered_sessions_discard_nothing() {
+        for status in [ProcessStatus::Analyzed, ProcessStatus::Clustered] {
+            let dir = tempfile::tempdir().unwrap();
+            let dir = dir.path();
+            write_config(dir, status);
+            write_font_files(dir, "a", &["sample.png", "vector.bin"]);
+
+            discard_partial_outputs(dir).unwrap();
+
+            assert!(has_file(dir, "a", "sample.png"));
+            assert!(has_file(dir, "a", "vector.bin"));
+        }
+    }
+}
@@ -0,0 +1,337 @@
+//! Per-session run history, written as JSON lines inside the session.
+//!
+//! Every pipeline run appends [`RunLogEntry`] lines to `run_log.jsonl` in the
+//! session directory: what started the run, the model it used, when each
+//! stage started and how long it took, every font a stage dropped with the
+//! error that dropped it, and how the run ended. The file is packed into the
+//! session document with everything else, so the history travels with the
+//! session; a duplicated session inherits its source's history, and each
+//! run's `run_started` entry names the session it ran on.
+//!
+//! Only the latest [`MAX_LOGGED_RUNS`] runs are kept: starting a run drops
+//! the entries of older ones, so re-running a session does not grow its log
+//! and document without bound.
+//!
+//! Writing is best-effort: a log that cannot be appended to is reported on
+//! stderr but never fails the run it describes.
+
+use crate::commands::jobs::RunMode;
+use crate::config::ProcessStatus;
+use crate::core::resources::ResourceProfile;
+use crate::error::{AppError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Name of the JSON-lines run log inside a session directory/document.
+pub const RUN_LOG_FILE: &str = "run_log.jsonl";
+/// Runs a session's log keeps, the one being started included.
+pub const MAX_LOGGED_RUNS: usize = 20;
+
+/// A pipeline stage as recorded in the run log, in pipeline order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStage {
+    Discovery,
+    Rendering,
+    Analysis,
+    Clustering,
+}
+
+/// One line of the run log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunLogEntry {
+    /// Identifies the run, shared by every entry it wrote.
+    pub run_id: String,
+    pub at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub event: RunLogEvent,
+}
+
+/// What a run-log entry records.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum RunLogEvent {
+    /// The worker started processing the session.
+    RunStarted {
+        session_id: String,
+        app_version: String,
+        run_mode: RunMode,
+        /// Whether the interrupted stage kept the fonts it already finished.
+        resume: bool,
+        /// Stage the run started from.
+        resume_status: ProcessStatus,
+        /// Size of the thread pool rendering and preprocessing run on.
+        rayon_threads: usize,
+        /// Threads the machine offers.
+        available_parallelism: usize,
+        /// Intra-op threads ONNX inference ran with; `0` for runs logged
+        /// before this was recorded, which used every available thread.
+        #[serde(default)]
+        onnx_threads: usize,
+        /// Resource limits the worker ran under.
+        #[serde(default)]
+        resource_profile: ResourceProfile,
+    },
+    /// The model bundle analysis and attribute emphasis ran with.
+    ModelSelected {
+        model_id: String,
+        /// Declared SHA-256 of `model.onnx`, verified when it was installed.
+        model_sha256: String,
+    },
+    StageStarted {
+        stage: RunStage,
+    },
+    StageCompleted {
+        stage: RunStage,
+        duration_ms: u64,
+    },
+    /// A stage dropped a font instead of failing.
+    FontFailed {
+        stage: RunStage,
+        safe_name: String,
+        /// The error that dropped the font.
+        reason: AppError,
+    },
+    /// A font's sample overflowed the normalised canvas and was kept with
+    /// its ink clipped.
+    SampleClipped {
+        safe_name: String,
+    },
+    /// The run ended normally; `result` is `"Success"` or `"Cancelled"`.
+    RunFinished {
+        result: String,
+        duration_ms: u64,
+    },
+    RunFailed {
+        error: AppError,
+        duration_ms: u64,
+    },
+}
+
+/// Appends the entries of one run to a session's run log.
+#[derive(Debug, Clone)]
+pub struct RunLog {
+    run_id: String,
+    path: PathBuf,
+}
+
+impl RunLog {
+    /// Starts a new run writing to the log in `session_dir`, first dropping
+    /// all but the latest `MAX_LOGGED_RUNS - 1` runs already logged.
+    pub fn start(session_dir: &Path) -> Self {
+        let log = Self {
+            run_id: Uuid::new_v4().to_string(),
+            path: session_dir.join(RUN_LOG_FILE),
+        };
+        if let Err(error) = trim_run_log(&log.path, MAX_LOGGED_RUNS - 1) {
+            eprintln!("Failed to trim the run log: {}", error);
+        }
+        log
+    }
+
+    /// Appends `event`, logging rather than returning any failure.
+    pub fn append(&self, event: RunLogEvent) {
+        if let Err(error) = self.try_append(event) {
+            eprintln!("Failed to write the run log: {}", error);
+        }
+    }
+
+    fn try_append(&self, event: RunLogEvent) -> Result<()> {
+        let entry = RunLogEntry {
+            run_id: self.run_id.clone(),
+            at: Utc::now(),
+            event,
+        };
+        let mut line = serde_json::to_string(&entry)?;
+        line.push('\n');
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(line.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// The run a log line belongs to, read without parsing the rest of it.
+#[derive(Deserialize)]
+struct LoggedRun {
+    run_id: String,
+}
+
+/// Rewrites the log at `path` with only the entries of its `keep` most
+/// recently started runs. Unreadable lines are dropped with them, so a line
+/// cut short by a crash cannot swallow the next appended entry. The file is
+/// left alone when there is nothing to drop.
+fn trim_run_log(path: &Path, keep: usize) -> Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+    let content = fs::read_to_string(path)?;
+    let lines: Vec<(&str, Option<String>)> = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let run_id = serde_json::from_str::<LoggedRun>(line)
+                .ok()
+                .map(|run| run.run_id);
+            (line, run_id)
+        })
+        .collect();
+    let mut run_ids: Vec<&str> = Vec::new();
+    for run_id in lines.iter().filter_map(|(_, run_id)| run_id.as_deref()) {
+        if !run_ids.contains(&run_id) {
+            run_ids.push(run_id);
+        }
+    }
+    let dropped_runs = run_ids.len().saturating_sub(keep);
+    let has_unreadable_lines = lines.iter().any(|(_, run_id)| run_id.is_none());
+    if dropped_runs == 0 && !has_unreadable_lines && content.ends_with('\n') {
+        return Ok(());
+    }
+    let kept_runs: HashSet<&str> = run_ids[dropped_runs..].iter().copied().collect();
+    let mut kept = String::new();
+    for (line, run_id) in &lines {
+        if run_id
+            .as_deref()
+            .is_some_and(|run_id| kept_runs.contains(run_id))
+        {
+            kept.push_str(line);
+            kept.push('\n');
+        }
+    }
+    fs::write(path, kept)?;
+    Ok(())
+}
+
+/// Reads every entry of the run log in `session_dir`, oldest first. A session
+/// processed before run logs existed has none. Lines that do not parse, such
+/// as one cut short by a crash, are skipped.
+pub fn read_run_log(session_dir: &Path) -> Result<Vec<RunLogEntry>> {
+    let path = session_dir.join(RUN_LOG_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(fs::read_to_string(path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(error) => {
+                eprintln!("Skipping unreadable run log line: {}", error);
+                None
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_ids(entries: &[RunLogEntry]) -> Vec<&str> {
+        let mut run_ids: Vec<&str> = Vec::new();
+        for entry in entries {
+            if !run_ids.contains(&entry.run_id.as_str()) {
+                run_ids.push(&entry.run_id);
+            }
+        }
+        run_ids
+    }
+
+    #[test]
+    fn appended_events_are_read_back_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(read_run_log(dir.path()).unwrap().is_empty());
+
+        let log = RunLog::start(dir.path());
+        log.append(RunLogEvent::StageStarted {
+            stage: RunStage::Rendering,
+        });
+        log.append(RunLogEvent::StageCompleted {
+            stage: RunStage::Rendering,
+            duration_ms: 12,
+        });
+        log.append(RunLogEvent::RunFinished {
+            result: "Success".to_string(),
+            duration_ms: 15,
+        });
+
+        let entries = read_run_log(dir.path()).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert!(entries.iter().all(|entry| entry.run_id == log.run_id));
+        assert!(entries.windows(2).all(|pair| pair[0].at <= pair[1].at));
+        assert!(matches!(
+            entries[0].event,
+            RunLogEvent::StageStarted {
+                stage: RunStage::Rendering
+            }
+        ));
+        assert!(matches!(
+            entries[1].event,
+            RunLogEvent::StageCompleted {
+                stage: RunStage::Rendering,
+                duration_ms: 12
+            }
+        ));
+        assert!(matches!(
+            &entries[2].event,
+            RunLogEvent::RunFinished { result, duration_ms: 15 } if result == "Success"
+        ));
+    }
+
+    #[test]
+    fn truncated_or_corrupt_lines_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = RunLog::start(dir.path());
+        log.append(RunLogEvent::StageStarted {
+            stage: RunStage::Analysis,
+        });
+        let path = dir.path().join(RUN_LOG_FILE);
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"not json\n{\"run_id\":\"cut").unwrap();
+        drop(file);
+
+        let entries = read_run_log(dir.path()).unwrap();
+        assert_eq!(entries.len(), 1);
+
+        // The next run drops the broken lines before appending.
+        let next = RunLog::start(dir.path());
+        next.append(RunLogEvent::StageStarted {
+            stage: RunStage::Clustering,
+        });
+        let entries = read_run_log(dir.path()).unwrap();
+        assert_eq!(
+            run_ids(&entries),
+            [log.run_id.as_str(), next.run_id.as_str()]
+        );
+    }
+
+    #[test]
+    fn keeps_only_the_latest_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let runs: Vec<RunLog> = (0..MAX_LOGGED_RUNS + 3)
+            .map(|_| {
+                let log = RunLog::start(dir.path());
+                log.append(RunLogEvent::StageStarted {
+                    stage: RunStage::Discovery,
+                });
+                log.append(RunLogEvent::StageCompleted {
+                    stage: RunStage::Discovery,
+                    duration_ms: 1,
+                });
+                log
+            })
+            .collect();
+
+        let entries = read_run_log(dir.path()).unwrap();
+        let expected: Vec<&str> = runs[3..].iter().map(|log| log.run_id.as_str()).collect();
+        assert_eq!(run_ids(&entries), expected);
+        assert_eq!(entries.len(), MAX_LOGGED_RUNS * 2);
+    }
+}
@@ -2,19 +2,31 @@
 //!
 //! For every `(family, weight)` pair the discovery stage kept, this opens the
 //! corresponding [`FontRenderSource`] and renders `sample.png` into the
-//! session directory. Rendering runs in parallel with [`rayon`]; a font that
-//! fails to render is dropped (its directory removed and the progress
-//! denominator decreased) rather than failing the whole stage.
+//! session directory, recording the sample's estimated stroke contrast in the
+//! font's metadata. Rendering runs in parallel with [`rayon`]; a font that
+//! fails to render is dropped (its directory removed, the progress
+//! denominator decreased and the error recorded in the [run log] and the
+//! [dropped-font report]) rather than failing the whole stage. Fonts an
+//! interrupted run already rendered (see [`crate::core::resume`]) are skipped.
+//!
+//! [run log]: crate::core::run_log
+//! [dropped-font report]: crate::core::dropped_fonts
 
 use crate::commands::progress::progress_events;
 use crate::config::{ComputedData, ProgressStage, RenderConfig};
-use crate::core::session::{load_computed_data, save_computed_data};
-use crate::core::{AppState, EventSink, FontRenderSource};
+use crate::core::dropped_fonts::{record_dropped_fonts, reset_dropped_fonts, DroppedFont};
+use crate::core::resume::{clear_font_markers, is_font_complete, mark_font_complete, FontStage};
+use crate::core::session::{
+    load_computed_data, load_font_metadata, save_computed_data, save_font_metadata,
+};
+use crate::core::{
+    AppState, CancellationToken, EventSink, FontRenderSource, RunLogEvent, RunStage,
+};
 use crate::error::{AppError, Result};
 use crate::rendering::FontRenderer;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 /// Stateless façade for the rendering stage.
 pub struct SampleRenderer {}
@@ -27,36 +39,48 @@ impl SampleRenderer {
     /// Renders a sample image for every discovered font in the active session.
     ///
     /// `render_sources` maps each font's `safe_name` to where its face can be
-    /// reopened (produced by the discovery stage). Advances the session status
-    /// to `Rendered` on success; returns early if cancelled.
+    /// reopened (produced by the discovery stage). Fonts already marked
+    /// rendered count as done without being rendered again. Advances the
+    /// session status to `Rendered` on success; each font waits while `cancel`
+    /// is paused, and once it is cancelled no further font starts and a
+    /// cancelled error is returned.
     pub async fn render_all(
         &self,
         events: &impl EventSink,
         state: &AppState,
         render_sources: HashMap<String, FontRenderSource>,
+        cancel: &CancellationToken,
     ) -> Result<()> {
-        let (discovered_fonts, session_id, text, font_size) = {
+        let (discovered_fonts, session_id, rendering) = {
             let guard = state.current_session.lock().unwrap();
             let s = guard.as_ref().unwrap();
-            let rendering = &s.algorithm.rendering;
             (
                 s.discovered_fonts.clone(),
                 s.session_id.clone(),
-                rendering.text.clone(),
-                rendering.font_size,
+                s.algorithm.rendering.clone(),
             )
         };
         let session_dir = AppState::get_session_processing_dir(&session_id)?;
+        reset_dropped_fonts(&session_dir, RunStage::Rendering)?;
 
         let mut tasks = Vec::new();
+        let mut resumed_count = 0;
         for (weight, families) in discovered_fonts {
             for family in families {
-                tasks.push((family, weight));
+                let safe_name = crate::config::FontMetadata::generate_safe_name(&family, weight);
+                if is_font_complete(&session_dir, &safe_name, FontStage::Rendered) {
+                    resumed_count += 1;
+                } else {
+                    tasks.push((family, weight));
+                }
             }
         }
 
         println!("📋 Total sample rendering tasks: {}", tasks.len());
-        if tasks.is_empty() {
+        if resumed_count > 0 {
+            println!("⏩ Resuming: {} samples already rendered", resumed_count);
+        }
+        if tasks.is_empty() && resumed_count == 0 {
             println!("⚠️ No fonts discovered for weights. Skipping rendering.");
         }
 
@@ -65,26 +89,42 @@ impl SampleRenderer {
             events,
             state,
             ProgressStage::Rendering,
-            tasks.len() as i32,
+            (tasks.len() + resumed_count) as i32,
         );
+        if resumed_count > 0 {
+            progress_events::increase_numerator(
+                events,
+                state,
+                ProgressStage::Rendering,
+                resumed_count as i32,
+            );
+        }
 
         let render_config = Arc::new(RenderConfig {
-            text,
-            font_size,
+            text: rendering.text,
+            font_size: rendering.font_size,
+            direction: rendering.direction,
+            features: rendering.features,
+            language: rendering.language,
+            canvas: rendering.canvas,
             output_dir: session_dir,
         });
+        // Built once up front so an invalid feature list or language tag fails
+        // the stage instead of dropping every font individually.
+        let renderer = FontRenderer::new(Arc::clone(&render_config))?;
 
         let events = events.clone();
         let state_clone = state.clone();
-        let render_config = Arc::clone(&render_config);
+        let cancel_blocking = cancel.clone();
 
-        tokio::task::spawn_blocking(move || -> Result<()> {
+        let dropped_fonts = tokio::task::spawn_blocking(move || -> Result<Vec<DroppedFont>> {
             use rayon::prelude::*;
             let completed_since_progress = AtomicUsize::new(0);
+            let dropped_fonts = Mutex::new(Vec::new());
             tasks
                 .into_par_iter()
                 .for_each(|(family_name, target_weight)| {
-                    if state_clone.is_cancelled.load(Ordering::Relaxed) {
+                    if cancel_blocking.checkpoint().is_err() {
                         return;
                     }
 
@@ -101,12 +141,23 @@ impl SampleRenderer {
                             ))
                         })?;
 
-                        let renderer = FontRenderer::new(Arc::clone(&render_config));
-                        renderer.render_sample(
+                        let stats = renderer.render_sample(
                             &render_source.path,
                             render_source.font_index,
                             &safe_name,
                         )?;
+                        let mut meta = load_font_metadata(&render_config.output_dir, &safe_name)?;
+                        if stats.clipped {
+                            eprintln!(
+                                "⚠️ Sample of {} is clipped by the normalised canvas",
+                                family_name
+                            );
+                            state_clone.log_run_event(RunLogEvent::SampleClipped {
+                                safe_name: safe_name.clone(),
+                            });
+                        }
+                        meta.metrics.stroke_contrast = stats.stroke_contrast;
+                        save_font_metadata(&render_config.output_dir, &meta)?;
                         let mut computed =
                             load_computed_data(&render_config.output_dir, &safe_name).unwrap_or(
                                 ComputedData {
@@ -116,6 +167,11 @@ impl SampleRenderer {
                             );
                         computed.rendered_text = Some(render_config.text.clone());
                         save_computed_data(&render_config.output_dir, &safe_name, &computed)?;
+                        mark_font_complete(
+                            &render_config.output_dir,
+                            &safe_name,
+                            FontStage::Rendered,
+                        )?;
                         Ok(())
                     })();
 
@@ -132,6 +188,20 @@ impl SampleRenderer {
                         }
                         Err(e) => {
                             eprintln!("❌ Failed to process {}: {}", family_name, e);
+                            state_clone.log_run_event(RunLogEvent::FontFailed {
+                                stage: RunStage::Rendering,
+                                safe_name: safe_name.clone(),
+                                reason: e.clone(),
+                            });
+                            dropped_fonts
+                                .lock()
+                                .unwrap()
+                                .push(DroppedFont::weighted_font(
+                                    RunStage::Rendering,
+                                    &family_name,
+                                    target_weight,
+                                    e.with_font_name(&family_name),
+                                ));
                             let font_dir =
                                 render_config.output_dir.join("samples").join(&safe_name);
                             if font_dir.exists() {
@@ -155,15 +225,15 @@ impl SampleRenderer {
                     remainder as i32,
                 );
             }
-            Ok(())
+            Ok(dropped_fonts.into_inner().unwrap())
         })
         .await
         .map_err(|e| AppError::Processing(e.to_string()))??;
+        record_dropped_fonts(&state.get_session_dir()?, dropped_fonts)?;
 
-        if state.is_cancelled.load(Ordering::Relaxed) {
-            return Ok(());
-        }
+        cancel.check()?;
 
+        clear_font_markers(&state.get_session_dir()?, FontStage::Rendered)?;
         state.update_status(|s| s.process_status = crate::config::ProcessStatus::Rendered)?;
         Ok(())
     }
@@ -25,24 +25,31 @@ use crate::config::{
 };
 use crate::error::Result;
 use chrono::{DateTime, Utc};
-use semver::Version;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
-use std::process::Child;
-use std::sync::atomic::AtomicBool;
+use std::process::{Child, ChildStdin};
 use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 use walkdir::WalkDir;
 use zip::write::SimpleFileOptions;
 
+use super::cancellation::{CancellationToken, PAUSE_COMMAND, RESUME_COMMAND};
+use super::migrations::{
+    migrate_session_document, newer_schema_message, DocumentMigration, CURRENT_SESSION_SCHEMA,
+};
 use super::plugin_bridge::PluginConnection;
+use super::portable::{
+    collect_embedded_fonts, EmbeddedFontReport, EmbeddedFonts, EMBEDDED_FONT_FILE,
+};
+use super::queue::JobQueue;
+use super::run_log::{RunLog, RunLogEvent};
+use super::sweep::remove_session_sweeps;
 
 /// File extension of a packed session document.
 pub const SESSION_DOCUMENT_EXTENSION: &str = "fontclusterdoc";
-/// Sessions written by an app version older than this are pruned on startup.
-const MIN_SUPPORTED_SESSION_VERSION: &str = "0.26.1";
 /// Name of the JSON config file inside a session directory/document.
 const SESSION_CONFIG_FILE: &str = "config.json";
 /// Name of the JSON file recording the full clustering dendrogram.
@@ -56,11 +63,67 @@ fn session_view_lock() -> &'static Mutex<()> {
     SESSION_VIEW_LOCK.get_or_init(|| Mutex::new(()))
 }
 
-/// A spawned worker process for one running job, plus its cancellation flag.
+/// The error for a session with neither a working directory nor a document.
+fn session_not_found(id: &str) -> crate::error::AppError {
+    crate::error::AppError::new(
+        crate::error::ErrorCode::SessionNotFound,
+        format!("Session {} not found", id),
+    )
+    .with_session_id(id)
+}
+
+/// A spawned worker process for one running job, plus its cancellation state.
 #[derive(Clone)]
 pub struct RunningJob {
     pub child: Arc<Mutex<Child>>,
-    pub is_cancelled: Arc<AtomicBool>,
+    /// The worker's stdin, which controls its job (see
+    /// [`CancellationToken::controlled_by_stdin`]); closing it cancels the job.
+    pub stdin: Arc<Mutex<Option<ChildStdin>>>,
+    /// Set once the app asked the job to stop, so a worker killed after
+    /// [`cancel`](Self::cancel)'s grace period still counts as cancelled.
+    pub cancellation: CancellationToken,
+}
+
+impl RunningJob {
+    /// Asks the worker to suspend its job at the next safe checkpoint.
+    pub fn pause(&self) -> Result<()> {
+        self.send_command(PAUSE_COMMAND)
+    }
+
+    /// Asks the worker to continue a paused job.
+    pub fn resume(&self) -> Result<()> {
+        self.send_command(RESUME_COMMAND)
+    }
+
+    /// Writes one control line to the worker; fails once it was cancelled.
+    fn send_command(&self, command: &str) -> Result<()> {
+        let mut stdin = self.stdin.lock().unwrap();
+        let stdin = stdin.as_mut().ok_or_else(|| {
+            crate::error::AppError::Processing("The job is being cancelled".into())
+        })?;
+        writeln!(stdin, "{command}")?;
+        stdin.flush()?;
+        Ok(())
+    }
+
+    /// Asks the worker to stop by closing its stdin, then kills it if it has
+    /// not exited within `grace`, e.g. because it is stuck in native code.
+    pub fn cancel(&self, grace: Duration) {
+        self.cancellation.cancel();
+        self.stdin.lock().unwrap().take();
+        let child = self.child.clone();
+        std::thread::spawn(move || {
+            let deadline = Instant::now() + grace;
+            while Instant::now() < deadline {
+                if !matches!(child.lock().unwrap().try_wait(), Ok(None)) {
+                    return;
+                }
+                std::thread::sleep(Duration::from_millis(100));
+            }
+            println!("🛑 Job worker ignored cancellation; killing it");
+            let _ = child.lock().unwrap().kill();
+        });
+    }
 }
 
 /// Shared, cloneable handle to all mutable runtime state.
@@ -70,11 +133,13 @@ pub struct RunningJob {
 pub struct AppState {
     /// The session currently loaded in memory, if any.
     pub current_session: Arc<Mutex<Option<SessionConfig>>>,
-    /// Running job workers keyed by session id (or run id before the session
+    /// Running job workers keyed by session id (or job id before the session
     /// id is known).
     pub current_job_children: Arc<Mutex<HashMap<String, RunningJob>>>,
-    /// Cooperative cancellation flag for the in-process pipeline.
-    pub is_cancelled: Arc<AtomicBool>,
+    /// Waiting and queue-started jobs; see [`super::queue`].
+    pub job_queue: Arc<Mutex<JobQueue>>,
+    /// Run log of the in-process pipeline's current run, if one started.
+    pub run_log: Arc<Mutex<Option<RunLog>>>,
     /// Font most recently pushed to the plugin bridge.
     pub plugin_bridge_font: Arc<Mutex<Option<FontMetadata>>>,
     /// Timestamp of the last [`plugin_bridge_font`](Self::plugin_bridge_font)
@@ -93,7 +158,8 @@ impl AppState {
         Self {
             current_session: Arc::new(Mutex::new(None)),
             current_job_children: Arc::new(Mutex::new(HashMap::new())),
-            is_cancelled: Arc::new(AtomicBool::new(false)),
+            job_queue: Arc::new(Mutex::new(JobQueue::default())),
+            run_log: Arc::new(Mutex::new(None)),
             plugin_bridge_font: Arc::new(Mutex::new(None)),
             plugin_bridge_modified_date: Arc::new(Mutex::new(None)),
             plugin_bridge_preview_text: Arc::new(Mutex::new(None)),
@@ -101,6 +167,23 @@ impl AppState {
         }
     }
 
+    /// Sessions a job is running on or waiting for: the keys of
+    /// [`current_job_children`](Self::current_job_children) and the sessions
+    /// the queue's waiting and running jobs target. Garbage collection and
+    /// deletion leave them alone.
+    pub fn busy_session_ids(&self) -> HashSet<String> {
+        let mut busy: HashSet<String> = self
+            .current_job_children
+            .lock()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect();
+        let queue = self.job_queue.lock().unwrap();
+        busy.extend(queue.protected_session_ids().map(str::to_string));
+        busy
+    }
+
     /// `<data-dir>/FontCluster` — root of all persistent data.
     pub fn get_base_dir() -> Result<PathBuf> {
         dirs::data_dir()
@@ -115,7 +198,7 @@ impl AppState {
 
     /// Path of the packed document for session `id`.
     pub fn get_session_document_path(id: &str) -> Result<PathBuf> {
-        Ok(Self::get_generated_dir()?.join(format!("{id}.{SESSION_DOCUMENT_EXTENSION}")))
+        Ok(session_document_path_in(&Self::get_generated_dir()?, id))
     }
 
     /// `<cache-dir>/FontCluster/Session` — root of the unpacked working/view
@@ -214,17 +297,16 @@ impl AppState {
             return Ok(current);
         }
 
-        Err(crate::error::AppError::Processing(format!(
-            "Session {} not found",
-            id
-        )))
+        Err(session_not_found(id))
     }
 
     /// Startup cleanup of the cache directories.
     ///
     /// Drops hidden/leftover entries and any processing directory without a
     /// valid config, and clears the entire `Current` view root (views are
-    /// always re-extracted on demand).
+    /// always re-extracted on demand). Processing directories with a config
+    /// are kept even when their job died, so they can be resumed (see
+    /// [`super::resume`]).
     pub fn reconcile_session_storage() -> Result<()> {
         let processing_root = Self::get_session_processing_root()?;
         if processing_root.exists() {
@@ -253,64 +335,78 @@ impl AppState {
         Ok(())
     }
 
-    /// Removes sessions older than [`MIN_SUPPORTED_SESSION_VERSION`].
+    /// Deletes session `id`'s document, its working/view directories and the
+    /// clustering sweeps run on it. The single deletion path shared by the
+    /// delete command and storage garbage collection.
     ///
-    /// Scans both stored documents and processing directories, deleting any
-    /// whose recorded app version is too old or whose config cannot be read,
-    /// along with stray non-session files under `Generated`.
-    pub fn prune_unsupported_sessions() -> Result<()> {
-        let min_version = Version::parse(MIN_SUPPORTED_SESSION_VERSION).map_err(|e| {
-            crate::error::AppError::Processing(format!(
-                "Invalid minimum supported session version: {}",
-                e
-            ))
-        })?;
-
-        let generated_dir = Self::get_generated_dir()?;
-        if generated_dir.exists() {
-            for entry in fs::read_dir(&generated_dir)? {
-                let path = entry?.path();
-                if !is_session_document_path(&path) {
-                    if path.is_dir() {
-                        fs::remove_dir_all(&path)?;
-                    } else {
-                        fs::remove_file(&path)?;
-                    }
-                    continue;
-                }
-
-                let remove = match read_session_config_from_document(&path) {
-                    Ok(session) => parse_session_version(&session)
-                        .map(|version| version < min_version)
-                        .unwrap_or(true),
-                    Err(_) => true,
-                };
-                if remove {
-                    fs::remove_file(&path)?;
-                }
-            }
+    /// Returns `true` if a document or processing directory was removed (i.e.
+    /// the session actually existed).
+    pub fn delete_session_files(id: &str) -> Result<bool> {
+        let mut deleted = false;
+        let document_path = Self::get_session_document_path(id)?;
+        if document_path.exists() {
+            fs::remove_file(&document_path)?;
+            deleted = true;
+        }
+        let processing_dir = Self::get_session_processing_dir(id)?;
+        if processing_dir.exists() {
+            fs::remove_dir_all(&processing_dir)?;
+            deleted = true;
+        }
+        let current_dir = Self::get_session_current_dir(id)?;
+        if current_dir.exists() {
+            fs::remove_dir_all(&current_dir)?;
         }
+        remove_session_sweeps(id)?;
+        Ok(deleted)
+    }
 
-        let processing_root = Self::get_session_processing_root()?;
-        if processing_root.exists() {
-            for entry in fs::read_dir(&processing_root)? {
-                let path = entry?.path();
-                if !path.is_dir() {
-                    continue;
-                }
-                let remove = match read_session_config_from_dir(&path) {
-                    Ok(session) => parse_session_version(&session)
-                        .map(|version| version < min_version)
-                        .unwrap_or(true),
-                    Err(_) => true,
-                };
-                if remove {
-                    fs::remove_dir_all(&path)?;
-                }
-            }
+    /// Copies an external `.fontclusterdoc` into `Generated` and returns the
+    /// imported session's config.
+    ///
+    /// A document from an older release is migrated to the current schema
+    /// (see [`super::migrations`]) on the way in; one that cannot be migrated,
+    /// or that was written by a newer release, is rejected with an
+    /// explanation. When the id is already taken (e.g. re-importing a shared
+    /// copy of one's own session) or is not a UUID, the copy gets a fresh id
+    /// rather than overwriting anything. `modified_at` is set to the import
+    /// time so the session shows up at the top of the history and is not
+    /// immediately pruned as old.
+    pub fn import_session_document(source: &Path) -> Result<SessionConfig> {
+        import_session_document_into(
+            source,
+            &Self::get_generated_dir()?,
+            &Self::get_session_processing_root()?,
+        )
+    }
+
+    /// Writes session `id` as a `.fontclusterdoc` at `destination`.
+    ///
+    /// Copies the packed document when there is one; a session that only
+    /// exists as a processing directory (e.g. interrupted before it was
+    /// finalised) is packed on the fly. A `portable` export is always repacked
+    /// with the session's system fonts embedded (see [`super::portable`]) and
+    /// reports which fonts made it in. Callers must make sure no job is still
+    /// writing to the session directory.
+    pub fn export_session_document(
+        id: &str,
+        destination: &Path,
+        portable: bool,
+    ) -> Result<Option<EmbeddedFontReport>> {
+        if portable {
+            let dir = Self::resolve_session_dir(id)?;
+            let embedded_fonts = collect_embedded_fonts(&dir)?;
+            pack_dir_to_document(&dir, destination, PackMode::Portable(&embedded_fonts))?;
+            return Ok(Some(embedded_fonts.report));
         }
 
-        Ok(())
+        export_stored_session(
+            id,
+            &Self::get_session_document_path(id)?,
+            &Self::get_session_processing_dir(id)?,
+            destination,
+        )?;
+        Ok(None)
     }
 
     /// Creates a brand-new session, writes its initial config, and makes it the
@@ -320,8 +416,14 @@ impl AppState {
         let session = SessionConfig {
             app_version: env!("CARGO_PKG_VERSION").to_string(),
             modified_app_version: env!("CARGO_PKG_VERSION").to_string(),
+            schema_version: CURRENT_SESSION_SCHEMA,
             session_id: id.clone(),
             title: String::new(),
+            tags: Vec::new(),
+            notes: String::new(),
+            pinned: false,
+            favorite: false,
+            folder: String::new(),
             created_at: chrono::Utc::now(),
             modified_at: chrono::Utc::now(),
             discovered_fonts: HashMap::new(),
@@ -371,6 +473,10 @@ impl AppState {
             session.session_id = id.clone();
             session.created_at = now;
             session.modified_at = now;
+            // The copy keeps its source's title, tags, notes and folder, but
+            // pinning and favouriting mark one particular run.
+            session.pinned = false;
+            session.favorite = false;
             session.app_version = env!("CARGO_PKG_VERSION").to_string();
             session.modified_app_version = env!("CARGO_PKG_VERSION").to_string();
             write_session_config_atomic(&session, &processing_dir)?;
@@ -413,10 +519,7 @@ impl AppState {
 
         if !has_session_config(&processing_dir) {
             if !document_path.exists() {
-                return Err(crate::error::AppError::Processing(format!(
-                    "Session {} not found",
-                    id
-                )));
+                return Err(session_not_found(id));
             }
             if processing_dir.exists() {
                 remove_dir_all_best_effort(&processing_dir);
@@ -452,11 +555,18 @@ impl AppState {
             )));
         }
         let document_path = Self::get_session_document_path(id)?;
-        pack_dir_to_document(&processing_dir, &document_path)?;
+        pack_dir_to_document(&processing_dir, &document_path, PackMode::Standard)?;
         remove_dir_all_best_effort(&processing_dir);
         Ok(())
     }
 
+    /// Appends `event` to the current run's log; a no-op outside a run.
+    pub fn log_run_event(&self, event: RunLogEvent) {
+        if let Some(run_log) = self.run_log.lock().unwrap().as_ref() {
+            run_log.append(event);
+        }
+    }
+
     /// Mutates the active session's [`ProcessingStatus`] and persists it.
     pub fn update_status<F>(&self, f: F) -> Result<()>
     where
@@ -562,57 +672,65 @@ impl AppState {
         Ok(())
     }
 
-    /// Sets the user-given title of session `id` in every place it is stored:
-    /// the processing directory, the extracted `Current` view, the packed
-    /// document, and the in-memory active session.
-    ///
-    /// Deliberately does not bump `modified_at`: a rename is a metadata edit,
-    /// and bumping would reorder the history list and re-trigger unread
-    /// markers keyed on the modification time.
+    /// Sets the user-given title of session `id`; see
+    /// [`Self::update_session_metadata`].
     pub fn set_session_title(&self, id: &str, title: &str) -> Result<()> {
+        self.update_session_metadata(id, |session| session.title = title.to_string())
+    }
+
+    /// Applies a user metadata edit (title, tags, notes, flags, folder) to
+    /// session `id` in every place it is stored: the processing directory, the
+    /// extracted `Current` view, the packed document, and the in-memory active
+    /// session. Returns the edited config.
+    ///
+    /// Deliberately does not bump `modified_at`: a metadata edit would
+    /// otherwise reorder the history list and re-trigger unread markers keyed
+    /// on the modification time.
+    pub fn update_session_metadata(
+        &self,
+        id: &str,
+        edit: impl Fn(&mut SessionConfig),
+    ) -> Result<SessionConfig> {
         let _guard = session_view_lock()
             .lock()
             .map_err(|_| crate::error::AppError::Processing("Session view lock poisoned".into()))?;
 
-        let mut found = false;
+        let mut edited = None;
 
         let processing = Self::get_session_processing_dir(id)?;
         if has_session_config(&processing) {
             let mut session = read_session_config_from_dir(&processing)?;
-            session.title = title.to_string();
+            edit(&mut session);
             write_session_config_atomic(&session, &processing)?;
-            found = true;
+            edited = Some(session);
         }
 
         let current = Self::get_session_current_dir(id)?;
         if has_session_config(&current) {
             let mut session = read_session_config_from_dir(&current)?;
-            session.title = title.to_string();
+            edit(&mut session);
             write_session_config_atomic(&session, &current)?;
         }
 
         let document_path = Self::get_session_document_path(id)?;
         if document_path.exists() {
             let mut session = read_session_config_from_document(&document_path)?;
-            session.title = title.to_string();
+            edit(&mut session);
             rewrite_document_config(&document_path, &session)?;
-            found = true;
+            if edited.is_none() {
+                edited = Some(session);
+            }
         }
 
-        if !found {
-            return Err(crate::error::AppError::Processing(format!(
-                "Session {} not found",
-                id
-            )));
-        }
+        let edited = edited.ok_or_else(|| session_not_found(id))?;
 
         let mut guard = self.current_session.lock().unwrap();
         if let Some(session) = guard.as_mut() {
             if session.session_id == id {
-                session.title = title.to_string();
+                edit(session);
             }
         }
-        Ok(())
+        Ok(edited)
     }
 
     /// Atomically writes the session config into its processing directory.
@@ -629,7 +747,7 @@ impl AppState {
 }
 
 /// True if `dir` looks like a session directory (contains a config file).
-fn has_session_config(dir: &Path) -> bool {
+pub(crate) fn has_session_config(dir: &Path) -> bool {
     dir.join(SESSION_CONFIG_FILE).exists()
 }
 
@@ -684,7 +802,7 @@ fn copy_session_dir(source: &Path, destination: &Path) -> Result<()> {
 /// directory is renamed to a hidden `.removing-*` sibling and retried; any
 /// remaining leftovers are cleaned up by [`reconcile_session_storage`] on the
 /// next startup. Problems are logged, not returned.
-fn remove_dir_all_best_effort(path: &Path) {
+pub(crate) fn remove_dir_all_best_effort(path: &Path) {
     let Ok(metadata) = fs::symlink_metadata(path) else {
         return;
     };
@@ -747,13 +865,6 @@ pub fn is_session_document_path(path: &Path) -> bool {
         .is_some_and(|extension| extension == SESSION_DOCUMENT_EXTENSION)
 }
 
-/// Parses a session's version, preferring the last-modified version and
-/// falling back to the creating version (tolerating a leading `v`).
-fn parse_session_version(session: &SessionConfig) -> std::result::Result<Version, semver::Error> {
-    Version::parse(session.modified_app_version.trim_start_matches('v'))
-        .or_else(|_| Version::parse(session.app_version.trim_start_matches('v')))
-}
-
 /// Reads `config.json` out of a packed session document without unpacking it.
 pub fn read_session_config_from_document(path: &Path) -> Result<SessionConfig> {
     let file = fs::File::open(path).map_err(|e| {
@@ -805,7 +916,7 @@ pub fn read_session_config_from_dir(dir: &Path) -> Result<SessionConfig> {
 
 /// Writes `config.json` atomically via a temp file + rename, so a crash mid
 /// write can never leave a truncated config behind.
-fn write_session_config_atomic(session: &SessionConfig, dir: &Path) -> Result<()> {
+pub(crate) fn write_session_config_atomic(session: &SessionConfig, dir: &Path) -> Result<()> {
     fs::create_dir_all(dir).map_err(|e| {
         crate::error::AppError::Io(format!(
             "Failed to create session dir {}: {}",
@@ -838,6 +949,135 @@ fn write_session_config_atomic(session: &SessionConfig, dir: &Path) -> Result<()
     Ok(())
 }
 
+/// [`AppState::import_session_document`] into `generated_dir`, treating ids
+/// with a directory under `processing_root` as taken too.
+fn import_session_document_into(
+    source: &Path,
+    generated_dir: &Path,
+    processing_root: &Path,
+) -> Result<SessionConfig> {
+    fs::create_dir_all(generated_dir).map_err(|e| {
+        crate::error::AppError::Io(format!(
+            "Failed to create Generated dir {}: {}",
+            generated_dir.display(),
+            e
+        ))
+    })?;
+    let staging = tempfile::Builder::new()
+        .prefix(".import-")
+        .tempfile_in(generated_dir)
+        .map_err(|e| {
+            crate::error::AppError::Io(format!(
+                "Failed to create temporary session document in {}: {}",
+                generated_dir.display(),
+                e
+            ))
+        })?
+        .into_temp_path();
+    fs::copy(source, &staging).map_err(|e| {
+        crate::error::AppError::Io(format!(
+            "Failed to copy session document {}: {}",
+            source.display(),
+            e
+        ))
+    })?;
+    match migrate_session_document(&staging) {
+        Ok(DocumentMigration::Current | DocumentMigration::Migrated { .. }) => {}
+        Ok(DocumentMigration::Newer(schema)) => {
+            return Err(crate::error::AppError::Processing(format!(
+                "Session document {} cannot be opened: {}",
+                source.display(),
+                newer_schema_message(schema)
+            )));
+        }
+        Err(error) => {
+            return Err(crate::error::AppError::Processing(format!(
+                "Session document {} is from an older FontCluster and could not be \
+                 upgraded: {}",
+                source.display(),
+                error
+            )));
+        }
+    }
+
+    let mut session = read_session_config_from_document(&staging)?;
+    let id_is_taken = Uuid::parse_str(&session.session_id).is_err()
+        || session_document_path_in(generated_dir, &session.session_id).exists()
+        || processing_root.join(&session.session_id).exists();
+    if id_is_taken {
+        session.session_id = Uuid::now_v7().to_string();
+    }
+    session.modified_at = Utc::now();
+    rewrite_document_config(&staging, &session)?;
+    let document_path = session_document_path_in(generated_dir, &session.session_id);
+    staging.persist_noclobber(&document_path).map_err(|e| {
+        crate::error::AppError::Io(format!(
+            "Failed to store imported session document {}: {}",
+            document_path.display(),
+            e
+        ))
+    })?;
+
+    println!(
+        "📥 Imported session {} from {}",
+        session.session_id,
+        source.display()
+    );
+    Ok(session)
+}
+
+/// Path of the packed document for session `id` in `generated_dir`.
+fn session_document_path_in(generated_dir: &Path, id: &str) -> PathBuf {
+    generated_dir.join(format!("{id}.{SESSION_DOCUMENT_EXTENSION}"))
+}
+
+/// The non-portable [`AppState::export_session_document`] of session `id`,
+/// stored as the document at `document_path` or, while it has one, the
+/// processing directory `processing_dir`.
+fn export_stored_session(
+    id: &str,
+    document_path: &Path,
+    processing_dir: &Path,
+    destination: &Path,
+) -> Result<()> {
+    if has_session_config(processing_dir) {
+        return pack_dir_to_document(processing_dir, destination, PackMode::Standard);
+    }
+    if !document_path.exists() {
+        return Err(crate::error::AppError::Processing(format!(
+            "Session {} does not exist",
+            id
+        )));
+    }
+
+    let parent = destination
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let temporary = tempfile::NamedTempFile::new_in(parent).map_err(|e| {
+        crate::error::AppError::Io(format!(
+            "Failed to create temporary session document in {}: {}",
+            parent.display(),
+            e
+        ))
+    })?;
+    fs::copy(document_path, temporary.path()).map_err(|e| {
+        crate::error::AppError::Io(format!(
+            "Failed to copy session document {}: {}",
+            document_path.display(),
+            e
+        ))
+    })?;
+    temporary.persist(destination).map_err(|e| {
+        crate::error::AppError::Io(format!(
+            "Failed to write session document {}: {}",
+            destination.display(),
+            e
+        ))
+    })?;
+    Ok(())
+}
+
 /// Replaces `config.json` inside a packed session document, copying every
 /// other entry verbatim (no re-compression). Written to a temp file first and
 /// atomically persisted, like [`pack_dir_to_document`].
@@ -919,7 +1159,7 @@ pub(crate) fn rewrite_document_config(document_path: &Path, session: &SessionCon
 }
 
 /// Unpacks every entry of a session document zip into `dir`.
-fn extract_document_to_dir(document_path: &Path, dir: &Path) -> Result<()> {
+pub(crate) fn extract_document_to_dir(document_path: &Path, dir: &Path) -> Result<()> {
     let file = fs::File::open(document_path).map_err(|e| {
         crate::error::AppError::Io(format!(
             "Failed to open session document {}: {}",
@@ -967,12 +1207,23 @@ fn extract_document_to_dir(document_path: &Path, dir: &Path) -> Result<()> {
     Ok(())
 }
 
+/// What [`pack_dir_to_document`] puts in a document besides `dir` itself.
+#[derive(Clone, Copy)]
+pub(crate) enum PackMode<'a> {
+    /// Just the session directory.
+    Standard,
+    /// The session directory plus these font subsets, which replace any the
+    /// directory already holds for the same fonts. Subsets of fonts now found
+    /// to be restricted are left out.
+    Portable(&'a EmbeddedFonts),
+}
+
 /// Packs `dir` into a session document at `document_path`.
 ///
 /// Entries are added in sorted order and stored uncompressed (the samples are
 /// already-compressed PNGs), and the archive is written to a temp file first
 /// then atomically persisted into place.
-fn pack_dir_to_document(dir: &Path, document_path: &Path) -> Result<()> {
+pub(crate) fn pack_dir_to_document(dir: &Path, document_path: &Path, mode: PackMode) -> Result<()> {
     let parent = document_path
         .parent()
         .ok_or_else(|| crate::error::AppError::Io("Document path has no parent".into()))?;
@@ -1016,6 +1267,20 @@ fn pack_dir_to_document(dir: &Path, document_path: &Path) -> Result<()> {
                 .map(|component| component.as_os_str().to_string_lossy().into_owned())
                 .collect::<Vec<_>>()
                 .join("/");
+            if let PackMode::Portable(embedded_fonts) = mode {
+                let report = &embedded_fonts.report;
+                let is_replaced = embedded_fonts.files.contains_key(&entry_name)
+                    || report
+                        .restricted
+                        .iter()
+                        .chain(&report.unsupported)
+                        .any(|safe_name| {
+                            entry_name == format!("samples/{safe_name}/{EMBEDDED_FONT_FILE}")
+                        });
+                if is_replaced {
+                    continue;
+                }
+            }
             writer.start_file(&entry_name, options).map_err(|e| {
                 crate::error::AppError::Processing(format!(
                     "Failed to start zip entry {}: {}",
@@ -1031,6 +1296,17 @@ fn pack_dir_to_document(dir: &Path, document_path: &Path) -> Result<()> {
             })?;
             std::io::copy(&mut file, &mut writer)?;
         }
+        if let PackMode::Portable(embedded_fonts) = mode {
+            for (entry_name, bytes) in &embedded_fonts.files {
+                writer.start_file(entry_name, options).map_err(|e| {
+                    crate::error::AppError::Processing(format!(
+                        "Failed to start zip entry {}: {}",
+                        entry_name, e
+                    ))
+                })?;
+                writer.write_all(bytes)?;
+            }
+        }
         writer.finish().map_err(|e| {
             crate::error::AppError::Processing(format!(
                 "Failed to finish session document {}: {}",
@@ -1199,3 +1475,168 @@ pub fn load_sample_vectors(session_dir: &Path) -> Result<(Vec<Vec<f32>>, Vec<Str
 
     Ok((vectors, ids))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "samples/400_Noto_Serif/sample.png";
+
+    /// Writes a session with one sample into `dir` and returns its config.
+    fn write_session(dir: &Path, session_id: &str) -> SessionConfig {
+        let session = SessionConfig {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            modified_app_version: env!("CARGO_PKG_VERSION").to_string(),
+            schema_version: CURRENT_SESSION_SCHEMA,
+            session_id: session_id.to_string(),
+            title: "Shared serifs".to_string(),
+            tags: vec!["serif".to_string()],
+            notes: String::new(),
+            pinned: false,
+            favorite: true,
+            folder: String::new(),
+            created_at: Utc::now(),
+            modified_at: Utc::now(),
+            discovered_fonts: HashMap::new(),
+            algorithm: AlgorithmConfig::default(),
+            status: ProcessingStatus::default(),
+        };
+        fs::create_dir_all(dir.join(SAMPLE).parent().unwrap()).unwrap();
+        fs::write(dir.join(SAMPLE), b"png bytes").unwrap();
+        fs::write(
+            dir.join(SESSION_CONFIG_FILE),
+            serde_json::to_string_pretty(&session).unwrap(),
+        )
+        .unwrap();
+        session
+    }
+
+    /// Packs a session written by [`write_session`] into `path`.
+    fn write_document(path: &Path, session_id: &str) {
+        let dir = tempfile::tempdir().unwrap();
+        write_session(dir.path(), session_id);
+        pack_dir_to_document(dir.path(), path, PackMode::Standard).unwrap();
+    }
+
+    fn sample_of(document_path: &Path) -> Vec<u8> {
+        let dir = tempfile::tempdir().unwrap();
+        extract_document_to_dir(document_path, dir.path()).unwrap();
+        fs::read(dir.path().join(SAMPLE)).unwrap()
+    }
+
+    #[test]
+    fn exported_session_imports_unchanged() {
+        let app = tempfile::tempdir().unwrap();
+        let generated_dir = app.path().join("Generated");
+        let processing_root = app.path().join("Processing");
+        let session_id = Uuid::now_v7().to_string();
+        let processing_dir = processing_root.join(&session_id);
+        let original = write_session(&processing_dir, &session_id);
+        let document_path = session_document_path_in(&generated_dir, &session_id);
+        let exported = app.path().join("shared.fontclusterdoc");
+
+        export_stored_session(&session_id, &document_path, &processing_dir, &exported).unwrap();
+
+        let other = tempfile::tempdir().unwrap();
+        let other_generated_dir = other.path().join("Generated");
+        let imported = import_session_document_into(
+            &exported,
+            &other_generated_dir,
+            &other.path().join("Processing"),
+        )
+        .unwrap();
+        assert_eq!(imported.session_id, session_id);
+        assert_eq!(imported.title, original.title);
+        assert_eq!(imported.tags, original.tags);
+        assert_eq!(imported.favorite, original.favorite);
+        assert!(imported.modified_at >= original.modified_at);
+
+        let imported_path = session_document_path_in(&other_generated_dir, &session_id);
+        assert_eq!(sample_of(&imported_path), b"png bytes");
+
+        // A packed session is exported as a copy of its document.
+        let re_exported = other.path().join("again.fontclusterdoc");
+        export_stored_session(
+            &session_id,
+            &imported_path,
+            &other.path().join("Processing").join(&session_id),
+            &re_exported,
+        )
+        .unwrap();
+        assert_eq!(
+            fs::read(&re_exported).unwrap(),
+            fs::read(&imported_path).unwrap()
+        );
+    }
+
+    #[test]
+    fn exporting_a_missing_session_fails() {
+        let app = tempfile::tempdir().unwrap();
+        let result = export_stored_session(
+            "missing",
+            &app.path().join("missing.fontclusterdoc"),
+            &app.path().join("missing"),
+            &app.path().join("out.fontclusterdoc"),
+        );
+        assert!(result.is_err());
+        assert!(!app.path().join("out.fontclusterdoc").exists());
+    }
+
+    #[test]
+    fn import_of_a_taken_id_gets_a_fresh_one() {
+        let app = tempfile::tempdir().unwrap();
+        let generated_dir = app.path().join("Generated");
+        let processing_root = app.path().join("Processing");
+        let session_id = Uuid::now_v7().to_string();
+        let shared = app.path().join("shared.fontclusterdoc");
+        write_document(&shared, &session_id);
+
+        let first =
+            import_session_document_into(&shared, &generated_dir, &processing_root).unwrap();
+        assert_eq!(first.session_id, session_id);
+        let second =
+            import_session_document_into(&shared, &generated_dir, &processing_root).unwrap();
+        assert_ne!(second.session_id, session_id);
+        assert!(Uuid::parse_str(&second.session_id).is_ok());
+
+        let first_path = session_document_path_in(&generated_dir, &session_id);
+        let second_path = session_document_path_in(&generated_dir, &second.session_id);
+        assert_eq!(
+            read_session_config_from_document(&first_path)
+                .unwrap()
+                .session_id,
+            session_id
+        );
+        assert_eq!(
+            read_session_config_from_document(&second_path)
+                .unwrap()
+                .session_id,
+            second.session_id
+        );
+        assert_eq!(sample_of(&second_path), b"png bytes");
+    }
+
+    #[test]
+    fn import_restamps_ids_in_processing_or_not_uuids() {
+        let app = tempfile::tempdir().unwrap();
+        let generated_dir = app.path().join("Generated");
+        let processing_root = app.path().join("Processing");
+
+        let processing_id = Uuid::now_v7().to_string();
+        fs::create_dir_all(processing_root.join(&processing_id)).unwrap();
+        let shared = app.path().join("processing.fontclusterdoc");
+        write_document(&shared, &processing_id);
+        let imported =
+            import_session_document_into(&shared, &generated_dir, &processing_root).unwrap();
+        assert_ne!(imported.session_id, processing_id);
+        assert!(session_document_path_in(&generated_dir, &imported.session_id).exists());
+        assert!(!session_document_path_in(&generated_dir, &processing_id).exists());
+
+        let shared = app.path().join("named.fontclusterdoc");
+        write_document(&shared, "../escape");
+        let imported =
+            import_session_document_into(&shared, &generated_dir, &processing_root).unwrap();
+        assert!(Uuid::parse_str(&imported.session_id).is_ok());
+        assert!(session_document_path_in(&generated_dir, &imported.session_id).exists());
+    }
+}
@@ -0,0 +1,283 @@
+//! Disk usage reporting and the session retention policy.
+//!
+//! Everything FontCluster keeps on disk lives under a handful of roots: the
+//! packed documents in `Generated`, the `Session/Processing` and
+//! `Session/Current` cache trees, the installed models, the preview cache and
+//! the sweep and quarantine directories. [`storage_report`] breaks their size
+//! down per session and per model. The [`StoragePolicy`] bounds how many
+//! unpinned sessions are kept and for how long; it is applied by the storage
+//! commands through [`AppState::delete_session_files`].
+
+use crate::core::migrations::QUARANTINE_DIR;
+use crate::core::models::{installed_model_directories, installed_models_root};
+use crate::core::session::{
+    is_session_document_path, read_session_config_from_dir, read_session_config_from_document,
+};
+use crate::core::sweep::SWEEPS_DIR;
+use crate::core::AppState;
+use crate::error::{AppError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// File under the base dir holding the [`StoragePolicy`].
+const STORAGE_POLICY_FILE: &str = "storage_policy.json";
+/// How many unpinned sessions the default policy keeps.
+const DEFAULT_KEEP_LATEST: usize = 20;
+
+/// Which sessions garbage collection keeps. Pinned and favourite sessions,
+/// the active session and sessions with a running job are always kept and
+/// don't count towards [`Self::keep_latest`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoragePolicy {
+    /// How many of the most recently modified sessions to keep; `None` keeps
+    /// every session regardless of count.
+    #[serde(default = "default_keep_latest")]
+    pub keep_latest: Option<usize>,
+    /// Sessions not modified for more than this many days are deleted; `None`
+    /// never deletes by age.
+    #[serde(default)]
+    pub max_age_days: Option<u32>,
+}
+
+impl Default for StoragePolicy {
+    fn default() -> Self {
+        Self {
+            keep_latest: default_keep_latest(),
+            max_age_days: None,
+        }
+    }
+}
+
+/// Serde fallback for [`StoragePolicy::keep_latest`]: the history cap that
+/// applied before the policy was configurable.
+fn default_keep_latest() -> Option<usize> {
+    Some(DEFAULT_KEEP_LATEST)
+}
+
+/// Reads the stored policy, or the default when none has been saved.
+pub fn read_storage_policy() -> Result<StoragePolicy> {
+    let path = AppState::get_base_dir()?.join(STORAGE_POLICY_FILE);
+    if !path.exists() {
+        return Ok(StoragePolicy::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(&path)?)?)
+}
+
+/// Validates and saves `policy`. A `keep_latest` of `0` is rejected, since it
+/// would delete every unpinned session including the one just created.
+pub fn write_storage_policy(policy: &StoragePolicy) -> Result<()> {
+    if policy.keep_latest == Some(0) {
+        return Err(AppError::Processing(
+            "The storage policy must keep at least one session".into(),
+        ));
+    }
+    let base_dir = AppState::get_base_dir()?;
+    fs::create_dir_all(&base_dir)?;
+    fs::write(
+        base_dir.join(STORAGE_POLICY_FILE),
+        serde_json::to_vec_pretty(policy)?,
+    )?;
+    Ok(())
+}
+
+/// Bytes one session occupies in each of its storage locations.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SessionStorage {
+    pub session_id: String,
+    /// Title from the session's config; empty when untitled or unreadable.
+    pub title: String,
+    pub modified_at: Option<DateTime<Utc>>,
+    pub pinned: bool,
+    pub favorite: bool,
+    /// Packed document in `Generated`.
+    pub document_bytes: u64,
+    /// Working directory in `Session/Processing`.
+    pub processing_bytes: u64,
+    /// Extracted read-only view in `Session/Current`.
+    pub current_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// Bytes an installed model bundle occupies. Serialised in camelCase like the
+/// other model-management results.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelStorage {
+    pub model_id: String,
+    pub bytes: u64,
+}
+
+/// Disk usage of the installed models.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelStorageSummary {
+    /// Per-model usage, largest first.
+    pub models: Vec<ModelStorage>,
+    /// Whole model root, including interrupted downloads.
+    pub models_bytes: u64,
+}
+
+/// Disk usage of everything FontCluster stores.
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageReport {
+    /// Per-session usage, largest first.
+    pub sessions: Vec<SessionStorage>,
+    /// Per-model usage, largest first.
+    pub models: Vec<ModelStorage>,
+    /// Whole model root, including interrupted downloads.
+    pub models_bytes: u64,
+    pub preview_cache_bytes: u64,
+    pub sweeps_bytes: u64,
+    pub quarantine_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// Measures every storage root; `preview_cache_dir` is the preview cache,
+/// which lives in the app cache dir Tauri resolves.
+pub fn storage_report(preview_cache_dir: &Path) -> Result<StorageReport> {
+    let mut sessions: BTreeMap<String, SessionStorage> = BTreeMap::new();
+
+    let generated_dir = AppState::get_generated_dir()?;
+    if generated_dir.exists() {
+        for entry in fs::read_dir(&generated_dir)? {
+            let path = entry?.path();
+            if !is_session_document_path(&path) {
+                continue;
+            }
+            let Some(id) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            let storage = session_entry(&mut sessions, id);
+            storage.document_bytes = dir_size(&path);
+            if let Ok(session) = read_session_config_from_document(&path) {
+                storage.title = session.title;
+                storage.modified_at = Some(session.modified_at);
+                storage.pinned = session.pinned;
+                storage.favorite = session.favorite;
+            }
+        }
+    }
+
+    for (root, is_processing) in [
+        (AppState::get_session_processing_root()?, true),
+        (AppState::get_session_current_root()?, false),
+    ] {
+        if !root.exists() {
+            continue;
+        }
+        for entry in fs::read_dir(&root)? {
+            let path = entry?.path();
+            let Some(id) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            if id.starts_with('.') || !path.is_dir() {
+                continue;
+            }
+            let bytes = dir_size(&path);
+            let storage = session_entry(&mut sessions, id);
+            if is_processing {
+                storage.processing_bytes = bytes;
+                // A live working copy is newer than any packed document.
+                if let Ok(session) = read_session_config_from_dir(&path) {
+                    storage.title = session.title;
+                    storage.modified_at = Some(session.modified_at);
+                    storage.pinned = session.pinned;
+                    storage.favorite = session.favorite;
+                }
+            } else {
+                storage.current_bytes = bytes;
+            }
+        }
+    }
+
+    let mut sessions: Vec<SessionStorage> = sessions
+        .into_values()
+        .map(|mut storage| {
+            storage.total_bytes =
+                storage.document_bytes + storage.processing_bytes + storage.current_bytes;
+            storage
+        })
+        .collect();
+    sessions.sort_by(|a, b| b.total_bytes.cmp(&a.total_bytes));
+
+    let ModelStorageSummary {
+        models,
+        models_bytes,
+    } = model_storage_summary()?;
+
+    let base_dir = AppState::get_base_dir()?;
+    let preview_cache_bytes = dir_size(preview_cache_dir);
+    let sweeps_bytes = dir_size(&base_dir.join(SWEEPS_DIR));
+    let quarantine_bytes = dir_size(&base_dir.join(QUARANTINE_DIR));
+    let total_bytes = sessions
+        .iter()
+        .map(|storage| storage.total_bytes)
+        .sum::<u64>()
+        + models_bytes
+        + preview_cache_bytes
+        + sweeps_bytes
+        + quarantine_bytes;
+
+    Ok(StorageReport {
+        sessions,
+        models,
+        models_bytes,
+        preview_cache_bytes,
+        sweeps_bytes,
+        quarantine_bytes,
+        total_bytes,
+    })
+}
+
+/// Measures every installed model and the model root.
+pub fn model_storage_summary() -> Result<ModelStorageSummary> {
+    let mut models: Vec<ModelStorage> = installed_model_directories()
+        .into_iter()
+        .map(|(model_id, directory)| ModelStorage {
+            model_id,
+            bytes: dir_size(&directory),
+        })
+        .collect();
+    models.sort_by(|a, b| b.bytes.cmp(&a.bytes));
+    Ok(ModelStorageSummary {
+        models,
+        models_bytes: dir_size(&installed_models_root()?),
+    })
+}
+
+/// Bytes session `id` occupies across its document and cache directories.
+pub fn session_storage_bytes(id: &str) -> Result<u64> {
+    Ok(dir_size(&AppState::get_session_document_path(id)?)
+        + dir_size(&AppState::get_session_processing_dir(id)?)
+        + dir_size(&AppState::get_session_current_dir(id)?))
+}
+
+/// The usage record of session `id`, created empty on first use.
+fn session_entry<'a>(
+    sessions: &'a mut BTreeMap<String, SessionStorage>,
+    id: &str,
+) -> &'a mut SessionStorage {
+    sessions
+        .entry(id.to_string())
+        .or_insert_with(|| SessionStorage {
+            session_id: id.to_string(),
+            ..Default::default()
+        })
+}
+
+/// Total size of the files under `path` (or of `path` itself when it is a
+/// file); `0` when it doesn't exist. Unreadable entries are skipped and
+/// symlinks are not followed.
+pub fn dir_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
@@ -0,0 +1,487 @@
+//! Clustering parameter sweeps.
+//!
+//! A sweep evaluates a grid of [`ClusteringConfig`] variants against one
+//! analysed session without creating a session per variant. The session's
+//! vectors are read once; the features are built once per distinct
+//! `preprocessing_dimensions`, the linkage once per distinct `method`, and
+//! each variant only re-cuts that linkage. Every variant's labels and quality
+//! metrics are written to one summary under `<base>/Sweeps`, from which the
+//! chosen variant is promoted to a real session by an ordinary duplicating
+//! run with its clustering config.
+
+use crate::config::{ClusteringConfig, ClusteringMethod, ProcessStatus};
+use crate::core::clusterer::{
+    cluster_features, cut_dendrogram, ensure_distance_matrix_fits, kodama_method, leaf_labels,
+    load_vector_matrix, unit_diameter_distances,
+};
+use crate::core::session::read_session_config_from_dir;
+use crate::core::{AppState, CancellationToken, EventSink, ResourceProfile};
+use crate::error::{AppError, Result};
+use chrono::{DateTime, Utc};
+use kodama::{linkage, Dendrogram};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Directory under the base dir holding sweep summaries.
+pub(crate) const SWEEPS_DIR: &str = "Sweeps";
+/// Upper bound on the variants one sweep may expand to.
+pub const MAX_SWEEP_VARIANTS: usize = 64;
+
+/// Values to sweep per clustering parameter. The variants are the cartesian
+/// product of every non-empty axis; an empty axis keeps the session's value.
+/// Every other clustering setting (emphasis, metric weights, PCA switch) is
+/// taken from the session.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClusteringSweepGrid {
+    #[serde(default)]
+    pub methods: Vec<ClusteringMethod>,
+    #[serde(default)]
+    pub distance_thresholds: Vec<f32>,
+    #[serde(default)]
+    pub preprocessing_dimensions: Vec<usize>,
+    #[serde(default)]
+    pub target_cluster_counts: Vec<usize>,
+}
+
+impl ClusteringSweepGrid {
+    /// Expands the grid over `base`, varying dimensions slowest and target
+    /// counts fastest.
+    pub fn variants(&self, base: &ClusteringConfig) -> Vec<ClusteringConfig> {
+        fn axis<T: Copy>(values: &[T], base: T) -> Vec<T> {
+            if values.is_empty() {
+                vec![base]
+            } else {
+                values.to_vec()
+            }
+        }
+
+        let mut variants = Vec::new();
+        for dimensions in axis(
+            &self.preprocessing_dimensions,
+            base.preprocessing_dimensions,
+        ) {
+            for method in axis(&self.methods, base.method) {
+                for threshold in axis(&self.distance_thresholds, base.distance_threshold) {
+                    for target in axis(&self.target_cluster_counts, base.target_cluster_count) {
+                        variants.push(ClusteringConfig {
+                            method,
+                            preprocessing_dimensions: dimensions,
+                            distance_threshold: threshold,
+                            target_cluster_count: target,
+                            ..base.clone()
+                        });
+                    }
+                }
+            }
+        }
+        variants
+    }
+
+    /// [`variants`](Self::variants), failing when there are more than
+    /// [`MAX_SWEEP_VARIANTS`].
+    pub fn checked_variants(&self, base: &ClusteringConfig) -> Result<Vec<ClusteringConfig>> {
+        let variants = self.variants(base);
+        if variants.len() > MAX_SWEEP_VARIANTS {
+            return Err(AppError::Processing(format!(
+                "The sweep has {} variants; at most {} are allowed",
+                variants.len(),
+                MAX_SWEEP_VARIANTS
+            )));
+        }
+        Ok(variants)
+    }
+}
+
+/// Quality metrics of one sweep variant.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepMetrics {
+    pub clusters_amount: usize,
+    /// Clusters with a single font.
+    pub singletons: usize,
+    /// Size of the largest cluster.
+    pub largest_cluster: usize,
+    /// Height of the last applied merge, in the unit-diameter feature space.
+    pub cut_height: f32,
+    /// Mean silhouette coefficient over all fonts (singletons count as `0`);
+    /// `None` when there is only one cluster or every font is its own.
+    pub silhouette: Option<f32>,
+}
+
+/// One evaluated variant of a sweep.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepVariant {
+    /// The session's clustering config with this variant's parameters.
+    pub config: ClusteringConfig,
+    /// Cluster label per font safe name.
+    pub labels: BTreeMap<String, i32>,
+    pub metrics: SweepMetrics,
+}
+
+/// Summary artifact of a finished sweep.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusteringSweep {
+    pub sweep_id: String,
+    pub session_id: String,
+    pub created_at: DateTime<Utc>,
+    pub app_version: String,
+    pub grid: ClusteringSweepGrid,
+    pub variants: Vec<SweepVariant>,
+    /// Index of the variant with the highest silhouette, if any is defined.
+    pub best_variant: Option<usize>,
+}
+
+/// Evaluates every variant of `grid` on session `session_id` and writes the
+/// summary. `model_directory` must hold the session's model when its
+/// clustering config uses attribute directions.
+///
+/// Emits `sweep_progress` after each variant. Returns an error when the
+/// session hasn't been analysed or the grid expands to more than
+/// [`MAX_SWEEP_VARIANTS`] variants, a resource-limit error when the distance
+/// matrix would exceed the memory ceiling of `resource_profile`, and a
+/// cancelled error, without writing a summary, once `cancel` is observed
+/// between linkages and variants, which also wait while it is paused.
+pub fn run_clustering_sweep(
+    events: &impl EventSink,
+    session_id: &str,
+    grid: &ClusteringSweepGrid,
+    model_directory: Option<&Path>,
+    resource_profile: &ResourceProfile,
+    cancel: &CancellationToken,
+) -> Result<ClusteringSweep> {
+    let session_dir = AppState::resolve_session_dir(session_id)?;
+    let session = read_session_config_from_dir(&session_dir)?;
+    if session.status.process_status < ProcessStatus::Analyzed {
+        return Err(AppError::Processing(format!(
+            "Session {} has not been analysed",
+            session_id
+        )));
+    }
+    let configs = grid.checked_variants(&session.algorithm.clustering)?;
+
+    let (data, ids) = load_vector_matrix(&session_dir)?;
+    if data.is_empty() {
+        return Err(AppError::Processing(
+            "No analyzed font vectors are available for clustering".into(),
+        ));
+    }
+    let n = ids.len();
+    ensure_distance_matrix_fits(resource_profile, n)?;
+    let sweep_id = Uuid::now_v7().to_string();
+    println!(
+        "🧪 Sweeping {} clustering variants over {} fonts",
+        configs.len(),
+        n
+    );
+
+    let mut variants: Vec<Option<SweepVariant>> = vec![None; configs.len()];
+    let mut completed = 0;
+    let mut feature_groups: Vec<usize> = Vec::new();
+    for config in &configs {
+        if !feature_groups.contains(&config.preprocessing_dimensions) {
+            feature_groups.push(config.preprocessing_dimensions);
+        }
+    }
+    for dimensions in feature_groups {
+        cancel.checkpoint()?;
+        let group: Vec<usize> = (0..configs.len())
+            .filter(|&index| configs[index].preprocessing_dimensions == dimensions)
+            .collect();
+        let points = cluster_features(
+            data.clone(),
+            &ids,
+            &session_dir,
+            &configs[group[0]],
+            model_directory,
+        )?;
+        let (_, distances) = unit_diameter_distances(points);
+
+        let mut methods: Vec<ClusteringMethod> = Vec::new();
+        for &index in &group {
+            if !methods.contains(&configs[index].method) {
+                methods.push(configs[index].method);
+            }
+        }
+        for method in methods {
+            cancel.checkpoint()?;
+            let dendrogram = method_linkage(&distances, n, method);
+            for &index in group
+                .iter()
+                .filter(|&&index| configs[index].method == method)
+            {
+                cancel.checkpoint()?;
+                let config = &configs[index];
+                let cut = cut_dendrogram(dendrogram.steps(), n, config);
+                let labels = leaf_labels(&cut.clusters, n);
+                let metrics = SweepMetrics {
+                    clusters_amount: cut.clusters.len(),
+                    singletons: cut
+                        .clusters
+                        .iter()
+                        .filter(|(_, members)| members.len() == 1)
+                        .count(),
+                    largest_cluster: cut
+                        .clusters
+                        .iter()
+                        .map(|(_, members)| members.len())
+                        .max()
+                        .unwrap_or(0),
+                    cut_height: cut.cut_height,
+                    silhouette: silhouette(&distances, &labels, cut.clusters.len()),
+                };
+                variants[index] = Some(SweepVariant {
+                    config: config.clone(),
+                    labels: ids.iter().cloned().zip(labels).collect(),
+                    metrics,
+                });
+
+                completed += 1;
+                events.emit_value(
+                    "sweep_progress",
+                    json!({
+                        "sessionId": session_id,
+                        "sweepId": sweep_id,
+                        "completed": completed,
+                        "total": configs.len(),
+                    }),
+                )?;
+            }
+        }
+    }
+
+    let variants: Vec<SweepVariant> = variants.into_iter().flatten().collect();
+    let best_variant = variants
+        .iter()
+        .enumerate()
+        .filter_map(|(index, variant)| variant.metrics.silhouette.map(|score| (index, score)))
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(index, _)| index);
+    let sweep = ClusteringSweep {
+        sweep_id,
+        session_id: session_id.to_string(),
+        created_at: Utc::now(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        grid: grid.clone(),
+        variants,
+        best_variant,
+    };
+
+    let path = sweep_path(&sweep.sweep_id)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, serde_json::to_vec_pretty(&sweep)?)?;
+    println!("✅ Sweep {} written to {}", sweep.sweep_id, path.display());
+    Ok(sweep)
+}
+
+/// Reads the summary of sweep `sweep_id`.
+pub fn read_clustering_sweep(sweep_id: &str) -> Result<ClusteringSweep> {
+    let path = sweep_path(sweep_id)?;
+    let contents = fs::read_to_string(&path)
+        .map_err(|e| AppError::Io(format!("Failed to read sweep {}: {}", path.display(), e)))?;
+    Ok(serde_json::from_str(&contents)?)
+}
+
+/// Lists the sweeps run on session `session_id`, newest first.
+pub fn list_clustering_sweeps(session_id: &str) -> Result<Vec<ClusteringSweep>> {
+    list_sweeps_in(&AppState::get_base_dir()?.join(SWEEPS_DIR), session_id)
+}
+
+/// [`list_clustering_sweeps`] over the summaries in `sweeps_dir`; files that
+/// are not readable sweep summaries are skipped.
+fn list_sweeps_in(sweeps_dir: &Path, session_id: &str) -> Result<Vec<ClusteringSweep>> {
+    if !sweeps_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut sweeps: Vec<ClusteringSweep> = fs::read_dir(sweeps_dir)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|entry| {
+            let sweep: ClusteringSweep =
+                serde_json::from_str(&fs::read_to_string(entry.path()).ok()?).ok()?;
+            (sweep.session_id == session_id).then_some(sweep)
+        })
+        .collect();
+    sweeps.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(sweeps)
+}
+
+/// Deletes the summaries of every sweep run on session `session_id`.
+pub fn remove_session_sweeps(session_id: &str) -> Result<()> {
+    for sweep in list_clustering_sweeps(session_id)? {
+        fs::remove_file(sweep_path(&sweep.sweep_id)?)?;
+    }
+    Ok(())
+}
+
+/// Path of the summary of sweep `sweep_id`. Ids are UUIDs, so anything else
+/// is rejected rather than joined into a path.
+fn sweep_path(sweep_id: &str) -> Result<PathBuf> {
+    let sweep_id = Uuid::parse_str(sweep_id)
+        .map_err(|_| AppError::Processing(format!("Invalid sweep id {}", sweep_id)))?;
+    Ok(AppState::get_base_dir()?
+        .join(SWEEPS_DIR)
+        .join(format!("{}.json", sweep_id.hyphenated())))
+}
+
+/// Links the condensed `distances` of `n` points with `method`; `kodama`
+/// consumes its input as workspace, so it gets a copy.
+fn method_linkage(distances: &[f32], n: usize, method: ClusteringMethod) -> Dendrogram<f32> {
+    let mut workspace = distances.to_vec();
+    linkage(&mut workspace, n, kodama_method(method))
+}
+
+/// Index of the distance between points `i` and `j` (`i != j`) in a condensed
+/// distance matrix over `n` points.
+fn condensed_index(n: usize, i: usize, j: usize) -> usize {
+    let (i, j) = if i < j { (i, j) } else { (j, i) };
+    n * i - i * (i + 1) / 2 + (j - i - 1)
+}
+
+/// Mean silhouette coefficient of `labels` (each in `0..cluster_count`) over
+/// the condensed `distances`; `None` unless `2 <= cluster_count < n`.
+fn silhouette(distances: &[f32], labels: &[i32], cluster_count: usize) -> Option<f32> {
+    let n = labels.len();
+    if cluster_count < 2 || cluster_count >= n {
+        return None;
+    }
+    let mut sizes = vec![0usize; cluster_count];
+    for &label in labels {
+        sizes[label as usize] += 1;
+    }
+
+    let mut total = 0.0f64;
+    let mut sums = vec![0.0f64; cluster_count];
+    for (i, &label) in labels.iter().enumerate() {
+        let own = label as usize;
+        if sizes[own] == 1 {
+            continue;
+        }
+        sums.fill(0.0);
+        for j in (0..n).filter(|&j| j != i) {
+            sums[labels[j] as usize] += f64::from(distances[condensed_index(n, i, j)]);
+        }
+        let cohesion = sums[own] / (sizes[own] - 1) as f64;
+        let separation = (0..cluster_count)
+            .filter(|&label| label != own)
+            .map(|label| sums[label] / sizes[label] as f64)
+            .fold(f64::INFINITY, f64::min);
+        let spread = cohesion.max(separation);
+        if spread > 0.0 {
+            total += (separation - cohesion) / spread;
+        }
+    }
+    Some((total / n as f64) as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid_expands_non_empty_axes_over_the_base() {
+        let base = ClusteringConfig::default();
+        let grid = ClusteringSweepGrid {
+            methods: vec![ClusteringMethod::Average, ClusteringMethod::Ward],
+            distance_thresholds: vec![0.2, 0.4, 0.6],
+            ..Default::default()
+        };
+
+        let variants = grid.variants(&base);
+        assert_eq!(variants.len(), 6);
+        assert!(variants
+            .iter()
+            .all(|variant| variant.preprocessing_dimensions == base.preprocessing_dimensions));
+        assert_eq!(variants[3].method, ClusteringMethod::Ward);
+        assert_eq!(variants[3].distance_threshold, 0.2);
+    }
+
+    #[test]
+    fn grid_is_capped_at_the_variant_limit() {
+        let base = ClusteringConfig::default();
+        let grid = |thresholds: usize, dimensions: usize| ClusteringSweepGrid {
+            distance_thresholds: (1..=thresholds).map(|step| step as f32 / 100.0).collect(),
+            preprocessing_dimensions: (1..=dimensions).collect(),
+            ..Default::default()
+        };
+
+        let variants = grid(MAX_SWEEP_VARIANTS / 2, 2)
+            .checked_variants(&base)
+            .unwrap();
+        assert_eq!(variants.len(), MAX_SWEEP_VARIANTS);
+        assert!(grid(MAX_SWEEP_VARIANTS + 1, 1)
+            .checked_variants(&base)
+            .is_err());
+        assert!(grid(MAX_SWEEP_VARIANTS / 2 + 1, 2)
+            .checked_variants(&base)
+            .is_err());
+    }
+
+    fn sweep(session_id: &str, created_at: DateTime<Utc>) -> ClusteringSweep {
+        ClusteringSweep {
+            sweep_id: Uuid::now_v7().to_string(),
+            session_id: session_id.to_string(),
+            created_at,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            grid: ClusteringSweepGrid::default(),
+            variants: Vec::new(),
+            best_variant: None,
+        }
+    }
+
+    fn write_sweep(sweeps_dir: &Path, sweep: &ClusteringSweep) {
+        fs::write(
+            sweeps_dir.join(format!("{}.json", sweep.sweep_id)),
+            serde_json::to_vec_pretty(sweep).unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn listing_skips_malformed_and_foreign_summaries() {
+        let dir = tempfile::tempdir().unwrap();
+        let sweeps_dir = dir.path();
+        assert!(list_sweeps_in(&sweeps_dir.join("missing"), "a")
+            .unwrap()
+            .is_empty());
+
+        let older = sweep("a", Utc::now() - chrono::Duration::hours(1));
+        let newer = sweep("a", Utc::now());
+        write_sweep(sweeps_dir, &older);
+        write_sweep(sweeps_dir, &newer);
+        write_sweep(sweeps_dir, &sweep("b", Utc::now()));
+        fs::write(sweeps_dir.join("truncated.json"), b"{\"sweep_id\": \"").unwrap();
+        fs::write(sweeps_dir.join("other.json"), b"{\"session_id\": \"a\"}").unwrap();
+        fs::write(sweeps_dir.join("notes.txt"), b"not a sweep").unwrap();
+        fs::create_dir(sweeps_dir.join("folder.json")).unwrap();
+
+        let listed: Vec<String> = list_sweeps_in(sweeps_dir, "a")
+            .unwrap()
+            .into_iter()
+            .map(|sweep| sweep.sweep_id)
+            .collect();
+        assert_eq!(listed, [newer.sweep_id, older.sweep_id]);
+    }
+
+    #[test]
+    fn silhouette_rewards_separated_clusters() {
+        // Two tight pairs far apart on a line: 0, 0.1 and 0.9, 1.0.
+        let positions = [0.0f32, 0.1, 0.9, 1.0];
+        let mut distances = Vec::new();
+        for i in 0..positions.len() {
+            for j in (i + 1)..positions.len() {
+                distances.push((positions[i] - positions[j]).abs());
+            }
+        }
+
+        let separated = silhouette(&distances, &[0, 0, 1, 1], 2).unwrap();
+        let mixed = silhouette(&distances, &[0, 1, 0, 1], 2).unwrap();
+        assert!(separated > 0.8);
+        assert!(mixed < 0.0);
+        assert_eq!(silhouette(&distances, &[0, 0, 0, 0], 1), None);
+    }
+}
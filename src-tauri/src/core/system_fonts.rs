@@ -0,0 +1,95 @@
+//! Lookup of installed system fonts by a session's [`FontMetadata`].
+//!
+//! Sessions only record a font's names and weight, not where the file lives,
+//! so anything that has to reopen a system font later (previews, portable
+//! exports) resolves it again through a [`SystemFontResolver`].
+
+use crate::config::FontMetadata;
+use crate::error::{AppError, Result};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// One indexed system font face: where to find it plus the fields used to
+/// match it against a [`FontMetadata`].
+struct SystemFontFace {
+    path: PathBuf,
+    font_index: u32,
+    weight: i32,
+    families: Vec<String>,
+}
+
+/// In-memory index of installed system fonts for fast resolution.
+///
+/// `by_postscript_name` gives an exact lookup; `faces` backs the fuzzy
+/// family-and-weight fallback when the PostScript name is unknown.
+pub struct SystemFontResolver {
+    faces: Vec<SystemFontFace>,
+    by_postscript_name: HashMap<String, (PathBuf, u32)>,
+}
+
+impl SystemFontResolver {
+    /// Builds the index by scanning the system font database once.
+    pub fn new() -> Self {
+        let mut db = fontdb::Database::new();
+        db.load_system_fonts();
+
+        let mut faces = Vec::new();
+        let mut by_postscript_name = HashMap::new();
+        for face in db.faces() {
+            let path = match &face.source {
+                fontdb::Source::File(path) => path,
+                fontdb::Source::SharedFile(path, _) => path,
+                fontdb::Source::Binary(_) => continue,
+            };
+            let path = path.to_path_buf();
+            by_postscript_name.insert(face.post_script_name.clone(), (path.clone(), face.index));
+            faces.push(SystemFontFace {
+                path,
+                font_index: face.index,
+                weight: face.weight.0 as i32,
+                families: face
+                    .families
+                    .iter()
+                    .map(|(family, _)| family.clone())
+                    .collect(),
+            });
+        }
+
+        Self {
+            faces,
+            by_postscript_name,
+        }
+    }
+
+    /// Resolves a font to a `(path, face index)`, matching on PostScript name
+    /// first and falling back to a family-name + nearby-weight search.
+    pub fn resolve(&self, font: &FontMetadata) -> Result<(PathBuf, u32)> {
+        if let Some((path, font_index)) = font
+            .postscript_name
+            .as_ref()
+            .and_then(|postscript_name| self.by_postscript_name.get(postscript_name))
+        {
+            return Ok((path.clone(), *font_index));
+        }
+
+        let face = self
+            .faces
+            .iter()
+            .find(|face| {
+                (face.weight - font.weight).abs() <= 50
+                    && face.families.iter().any(|family| {
+                        family == &font.family_name
+                            || font.family_names.values().any(|name| name == family)
+                            || font
+                                .preferred_family_names
+                                .values()
+                                .any(|name| name == family)
+                    })
+            })
+            .ok_or_else(|| {
+                AppError::Processing(format!("Failed to resolve system font {}", font.font_name))
+            })?;
+
+        Ok((face.path.clone(), face.font_index))
+    }
+}
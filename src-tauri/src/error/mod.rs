@@ -1,13 +1,19 @@
 //! Application-wide error type.
 //!
 //! Every fallible operation in the backend returns [`Result<T>`], which is
-//! [`std::result::Result`] specialised to [`AppError`]. The variants are
-//! coarse on purpose: each one carries a human-readable message rather than a
-//! structured payload, because errors ultimately cross the Tauri boundary as
-//! plain strings (see the [`Serialize`] implementation).
+//! [`std::result::Result`] specialised to [`AppError`]. Most variants are
+//! coarse: each one carries a human-readable message. Where the UI or a plugin
+//! needs to tell failures apart, an error carries a specific [`ErrorCode`],
+//! structured [`ErrorDetails`] and the error it adds context to (see
+//! [`AppError::Coded`]). Errors cross the Tauri boundary, and the worker
+//! process boundary, as a `{code, message, details}` object (see the
+//! [`Serialize`] implementation).
 
-use serde::Serialize;
+use crate::core::RunStage;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize};
 use std::io;
+use std::path::PathBuf;
 
 /// Convenience alias for results returned throughout the backend.
 pub type Result<T> = std::result::Result<T, AppError>;
@@ -17,7 +23,7 @@ pub type Result<T> = std::result::Result<T, AppError>;
 /// Each variant wraps a pre-formatted message. The [`From`] implementations
 /// below convert common third-party errors into the matching variant so that
 /// call sites can rely on the `?` operator.
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum AppError {
     #[error("IO error: {0}")]
     Io(String),
@@ -42,16 +48,240 @@ pub enum AppError {
 
     #[error("Network error: {0}")]
     Network(String),
+
+    /// An error with a specific code and details, built with [`AppError::new`]
+    /// or by adding details or [context](AppError::context) to another error.
+    /// Its message is followed by that of the error it wraps, if any.
+    #[error(
+        "{message}{}",
+        .source.as_ref().map(|source| format!(": {source}")).unwrap_or_default()
+    )]
+    Coded {
+        code: ErrorCode,
+        message: String,
+        details: Box<ErrorDetails>,
+        #[source]
+        source: Option<Box<AppError>>,
+    },
+}
+
+/// Stable, machine-readable kind of an [`AppError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    Io,
+    Font,
+    MissingGlyph,
+    Image,
+    Processing,
+    Serialization,
+    Tauri,
+    Network,
+    /// The requested model is not installed.
+    ModelNotInstalled,
+    /// A model manifest, release or installed bundle failed validation.
+    ModelInvalid,
+    /// A model source was rejected before being saved.
+    ModelSourceInvalid,
+    /// No font has a glyph for every character of the sample text at one of
+    /// the requested weights.
+    NoFontsMatched,
+    /// A font family has no face close enough to a requested weight.
+    NoWeightMatch,
+    /// The session does not exist.
+    SessionNotFound,
+    /// The session has a processing job running.
+    SessionBusy,
+    /// A worker process exited without reporting an error.
+    WorkerFailed,
+    /// The job was cancelled; see [`crate::core::cancellation`].
+    Cancelled,
+    /// A job needed more than its resource profile allows; see
+    /// [`crate::core::resources`].
+    ResourceLimit,
+}
+
+/// Structured context attached to an [`AppError`]; every field is optional.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ErrorDetails {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// Pipeline stage the error happened in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stage: Option<RunStage>,
+}
+
+impl AppError {
+    /// Creates an error with a specific `code`.
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        AppError::Coded {
+            code,
+            message: message.into(),
+            details: Box::default(),
+            source: None,
+        }
+    }
+
+    /// The error's machine-readable kind.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            AppError::Io(_) => ErrorCode::Io,
+            AppError::Font(_) => ErrorCode::Font,
+            AppError::MissingGlyph(_) => ErrorCode::MissingGlyph,
+            AppError::Image(_) => ErrorCode::Image,
+            AppError::Processing(_) => ErrorCode::Processing,
+            AppError::Serialization(_) => ErrorCode::Serialization,
+            AppError::Tauri(_) => ErrorCode::Tauri,
+            AppError::Network(_) => ErrorCode::Network,
+            AppError::Coded { code, .. } => *code,
+        }
+    }
+
+    /// True if the error only reports that its job was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.code() == ErrorCode::Cancelled
+    }
+
+    /// The error's structured details; empty unless some were attached.
+    pub fn details(&self) -> ErrorDetails {
+        match self {
+            AppError::Coded { details, .. } => (**details).clone(),
+            _ => ErrorDetails::default(),
+        }
+    }
+
+    /// Wraps the error in a higher-level `message`. The result keeps the
+    /// error's code and details, and the error becomes its source.
+    pub fn context(self, message: impl Into<String>) -> Self {
+        AppError::Coded {
+            code: self.code(),
+            message: message.into(),
+            details: Box::new(self.details()),
+            source: Some(Box::new(self)),
+        }
+    }
+
+    /// Attaches the file the error concerns.
+    pub fn with_path(self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        self.with_details(|details| details.path = Some(path))
+    }
+
+    /// Attaches the model the error concerns.
+    pub fn with_model_id(self, model_id: impl Into<String>) -> Self {
+        let model_id = model_id.into();
+        self.with_details(|details| details.model_id = Some(model_id))
+    }
+
+    /// Attaches the font the error concerns.
+    pub fn with_font_name(self, font_name: impl Into<String>) -> Self {
+        let font_name = font_name.into();
+        self.with_details(|details| details.font_name = Some(font_name))
+    }
+
+    /// Attaches the session the error concerns.
+    pub fn with_session_id(self, session_id: impl Into<String>) -> Self {
+        let session_id = session_id.into();
+        self.with_details(|details| details.session_id = Some(session_id))
+    }
+
+    /// Attaches the pipeline stage the error happened in, unless one is
+    /// already set.
+    pub fn with_stage(self, stage: RunStage) -> Self {
+        self.with_details(|details| {
+            details.stage.get_or_insert(stage);
+        })
+    }
+
+    /// Applies `edit` to the error's details, first turning a plain variant
+    /// into [`AppError::Coded`] with the same code and message.
+    fn with_details(self, edit: impl FnOnce(&mut ErrorDetails)) -> Self {
+        match self {
+            AppError::Coded {
+                code,
+                message,
+                mut details,
+                source,
+            } => {
+                edit(&mut *details);
+                AppError::Coded {
+                    code,
+                    message,
+                    details,
+                    source,
+                }
+            }
+            error => {
+                let mut details = ErrorDetails::default();
+                edit(&mut details);
+                AppError::Coded {
+                    code: error.code(),
+                    message: error.to_string(),
+                    details: Box::new(details),
+                    source: None,
+                }
+            }
+        }
+    }
 }
 
-/// Serialises an error as its display string so it can be returned to the
-/// frontend through a Tauri command result.
+/// [`AppError::context`] for results.
+pub trait ResultExt<T> {
+    /// Wraps an error in a higher-level `message`; see [`AppError::context`].
+    fn context(self, message: impl Into<String>) -> Result<T>;
+}
+
+impl<T> ResultExt<T> for Result<T> {
+    fn context(self, message: impl Into<String>) -> Result<T> {
+        self.map_err(|error| error.context(message))
+    }
+}
+
+/// Serialises an error as `{code, message, details}` so the frontend and
+/// plugins can branch on the code; `message` is the full display string.
 impl Serialize for AppError {
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        let mut error = serializer.serialize_struct("AppError", 3)?;
+        error.serialize_field("code", &self.code())?;
+        error.serialize_field("message", &self.to_string())?;
+        error.serialize_field("details", &self.details())?;
+        error.end()
+    }
+}
+
+/// The serialised form of an [`AppError`].
+#[derive(Deserialize)]
+struct SerializedError {
+    code: ErrorCode,
+    message: String,
+    #[serde(default)]
+    details: ErrorDetails,
+}
+
+/// Restores an error serialised by another process, such as a job worker.
+/// Its code, message and details survive; the source chain is already part
+/// of the message.
+impl<'de> Deserialize<'de> for AppError {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let error = SerializedError::deserialize(deserializer)?;
+        Ok(AppError::Coded {
+            code: error.code,
+            message: error.message,
+            details: Box::new(error.details),
+            source: None,
+        })
     }
 }
 
@@ -78,3 +308,52 @@ impl From<image::ImageError> for AppError {
         AppError::Image(e.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_code_message_and_details() {
+        let error = AppError::new(ErrorCode::ModelNotInstalled, "Model is not installed")
+            .with_model_id("fontcluster-v2");
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["code"], "model_not_installed");
+        assert_eq!(json["message"], "Model is not installed");
+        assert_eq!(json["details"]["model_id"], "fontcluster-v2");
+        assert!(json["details"].get("path").is_none());
+    }
+
+    #[test]
+    fn context_keeps_code_and_chains_message() {
+        let error = AppError::Network("connection refused".into())
+            .with_stage(RunStage::Discovery)
+            .context("Failed to download fonts");
+        assert_eq!(error.code(), ErrorCode::Network);
+        assert_eq!(error.details().stage, Some(RunStage::Discovery));
+        assert_eq!(
+            error.to_string(),
+            "Failed to download fonts: Network error: connection refused"
+        );
+    }
+
+    #[test]
+    fn with_stage_keeps_the_innermost_stage() {
+        let error = AppError::Processing("bad tensor".into())
+            .with_stage(RunStage::Analysis)
+            .with_stage(RunStage::Clustering);
+        assert_eq!(error.details().stage, Some(RunStage::Analysis));
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let error = AppError::new(ErrorCode::SessionBusy, "Session is processing")
+            .with_session_id("abc")
+            .context("Cannot export");
+        let restored: AppError =
+            serde_json::from_str(&serde_json::to_string(&error).unwrap()).unwrap();
+        assert_eq!(restored.code(), ErrorCode::SessionBusy);
+        assert_eq!(restored.to_string(), error.to_string());
+        assert_eq!(restored.details(), error.details());
+    }
+}
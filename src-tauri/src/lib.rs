@@ -19,10 +19,12 @@ pub mod rendering;
 
 use crate::commands::font::FontPreviewCacheState;
 use crate::core::AppState;
+use std::path::PathBuf;
 use std::sync::Arc;
 #[cfg(target_os = "macos")]
 use tauri::menu::{AboutMetadata, Menu, MenuItem, PredefinedMenuItem, Submenu};
-use tauri::{AppHandle, Emitter};
+use tauri::webview::PageLoadEvent;
+use tauri::{AppHandle, Emitter, Manager, Webview};
 
 /// Builds the native macOS application menu.
 ///
@@ -108,16 +110,67 @@ fn handle_menu(app: &AppHandle, event: tauri::menu::MenuEvent) {
     }
 }
 
+/// Imports every `.fontclusterdoc` among `paths` (files the app was asked to
+/// open) and tells the webview about each imported session.
+fn import_opened_documents(app: &AppHandle, paths: impl IntoIterator<Item = PathBuf>) {
+    for path in paths {
+        if !crate::core::is_session_document_path(&path) || !path.is_file() {
+            continue;
+        }
+        match AppState::import_session_document(&path) {
+            Ok(session) => {
+                let _ = app.emit("session_imported", session.session_id);
+            }
+            Err(error) => eprintln!("❌ Failed to import {}: {}", path.display(), error),
+        }
+    }
+}
+
+/// Handles runtime events after setup; on macOS and iOS, documents opened via
+/// Finder / "Open With" arrive here rather than as command-line arguments.
+fn handle_run_event(app: &AppHandle, event: tauri::RunEvent) {
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    if let tauri::RunEvent::Opened { urls } = event {
+        import_opened_documents(app, urls.iter().filter_map(|url| url.to_file_path().ok()));
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+    let _ = (app, event);
+}
+
 /// Builds and runs the Tauri application.
 ///
-/// Performs startup housekeeping (pruning unsupported sessions, reconciling the
-/// session cache), starts the plugin bridge server, registers shared state and
-/// command handlers, then hands control to the Tauri runtime.
+/// Emits `interrupted_sessions_found` with the sessions whose job stopped
+/// before clustering completed, so the webview can offer to resume or discard
+/// them. Runs on every page load, so a reloaded webview is offered them again;
+/// sessions with a running or queued job are left out.
+fn report_interrupted_sessions(webview: &Webview) {
+    let busy_session_ids = webview.state::<AppState>().busy_session_ids();
+    match crate::core::find_interrupted_sessions(&busy_session_ids) {
+        Ok(sessions) if !sessions.is_empty() => {
+            println!(
+                "⏸️ {} interrupted session(s) can be resumed",
+                sessions.len()
+            );
+            if let Err(error) = webview.emit("interrupted_sessions_found", &sessions) {
+                eprintln!("Failed to report interrupted sessions: {}", error);
+            }
+        }
+        Ok(_) => {}
+        Err(error) => eprintln!("Failed to scan for interrupted sessions: {}", error),
+    }
+}
+
+/// Performs startup housekeeping (migrating stored sessions to the current
+/// schema, reconciling the session cache), starts the plugin bridge server,
+/// registers shared state and command handlers, restores the job queue,
+/// imports any session documents the app was opened with, offers the webview
+/// the interrupted sessions once it has loaded, then hands control to the
+/// Tauri runtime.
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let app_state = AppState::new();
-    if let Err(error) = AppState::prune_unsupported_sessions() {
-        eprintln!("Failed to prune unsupported sessions: {}", error);
+    if let Err(error) = crate::core::migrate_stored_sessions() {
+        eprintln!("Failed to migrate stored sessions: {}", error);
     }
     if let Err(error) = AppState::reconcile_session_storage() {
         eprintln!("Failed to reconcile session storage: {}", error);
@@ -136,25 +189,65 @@ pub fn run() {
             // would get an in-window menu bar strip, so they skip it.
             #[cfg(target_os = "macos")]
             app.set_menu(create_menu(app.handle())?)?;
-            #[cfg(not(target_os = "macos"))]
-            let _ = app;
+            crate::commands::restore_job_queue(app.handle());
+            // Windows and Linux pass "Open With" documents as arguments.
+            import_opened_documents(app.handle(), std::env::args_os().skip(1).map(PathBuf::from));
             Ok(())
         })
+        .on_page_load(|webview, payload| {
+            if payload.event() == PageLoadEvent::Finished {
+                report_interrupted_sessions(webview);
+            }
+        })
         .on_menu_event(handle_menu)
         .invoke_handler(tauri::generate_handler![
             crate::commands::load_session,
             crate::commands::get_session_history,
             crate::commands::get_running_session_ids,
+            crate::commands::get_interrupted_sessions,
+            crate::commands::discard_interrupted_session,
+            crate::commands::get_run_log,
+            crate::commands::get_dropped_fonts,
             crate::commands::get_latest_session_id,
             crate::commands::delete_session,
             crate::commands::update_session_title,
+            crate::commands::update_session_metadata,
+            crate::commands::search_sessions,
+            crate::commands::import_session_document,
+            crate::commands::export_session_document,
+            crate::commands::get_migration_report,
+            crate::commands::compare_sessions,
+            crate::commands::get_clustering_sweep,
+            crate::commands::list_session_sweeps,
+            crate::commands::get_storage_report,
+            crate::commands::get_storage_policy,
+            crate::commands::set_storage_policy,
+            crate::commands::get_resource_profile,
+            crate::commands::set_resource_profile,
+            crate::commands::run_storage_gc,
             crate::commands::run_jobs,
+            crate::commands::resume_session,
+            crate::commands::run_sweep,
             crate::commands::stop_jobs,
+            crate::commands::pause_jobs,
+            crate::commands::resume_jobs,
+            crate::commands::get_job_queue,
+            crate::commands::set_job_priority,
+            crate::commands::reorder_job_queue,
+            crate::commands::remove_queued_job,
+            crate::commands::set_job_concurrency,
             crate::commands::list_models,
+            crate::commands::import_local_model,
+            crate::commands::get_model_source,
+            crate::commands::set_model_source,
+            crate::commands::verify_model,
+            crate::commands::delete_model,
+            crate::commands::get_model_storage,
             crate::commands::render_font_preview,
             crate::commands::send_font_to_plugin,
             crate::commands::get_connected_plugins,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(handle_run_event);
 }
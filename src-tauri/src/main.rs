@@ -3,10 +3,11 @@
 
 //! Executable entry point.
 //!
-//! The same binary serves two roles. When launched with the worker flag (see
-//! [`fontcluster_lib::commands::is_worker_run_jobs_arg`]) it runs the headless
-//! job pipeline and exits; otherwise it launches the full Tauri app via
-//! [`fontcluster_lib::run`].
+//! The same binary serves two roles. When launched with a worker flag (see
+//! [`fontcluster_lib::commands::is_worker_run_jobs_arg`] and
+//! [`fontcluster_lib::commands::is_worker_run_sweep_arg`]) it runs the
+//! headless job pipeline or clustering sweep and exits; otherwise it launches
+//! the full Tauri app via [`fontcluster_lib::run`].
 
 use mimalloc::MiMalloc;
 
@@ -24,6 +25,19 @@ fn main() {
                 std::process::exit(2);
             };
             if let Err(error) = fontcluster_lib::commands::run_jobs_worker(&request_json) {
+                fontcluster_lib::commands::report_worker_error(&error);
+                eprintln!("{error}");
+                std::process::exit(1);
+            }
+            return;
+        }
+        if fontcluster_lib::commands::is_worker_run_sweep_arg(&arg) {
+            let Some(request_json) = args.next() else {
+                eprintln!("Missing worker request payload");
+                std::process::exit(2);
+            };
+            if let Err(error) = fontcluster_lib::commands::run_sweep_worker(&request_json) {
+                fontcluster_lib::commands::report_worker_error(&error);
                 eprintln!("{error}");
                 std::process::exit(1);
             }
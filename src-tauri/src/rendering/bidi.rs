@@ -0,0 +1,314 @@
+//! Minimal bidirectional run resolution for single-line sample text.
+//!
+//! Sample strings are one short paragraph without explicit embedding controls,
+//! so this implements the subset of the Unicode Bidirectional Algorithm
+//! (UAX #9) that matters for them: the paragraph level from the first strong
+//! character (P2–P3), the weak-type rules for numbers (W1–W7), neutral
+//! resolution (N1–N2), implicit levels (I1–I2), and reordering of the resolved
+//! level runs (L2). Explicit embeddings/isolates are treated as neutrals.
+//!
+//! [`visual_runs`] returns byte ranges in visual (left-to-right) order. Each
+//! run still has to be shaped in its own direction; swash does not reverse
+//! right-to-left clusters itself, so the renderer does that per run.
+
+use std::ops::Range;
+use swash::text::{BidiClass, Codepoint};
+
+/// A maximal span of text sharing one resolved embedding level.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BidiRun {
+    /// Byte range of the run within the source text.
+    pub range: Range<usize>,
+    /// Resolved embedding level; odd levels are right-to-left.
+    pub level: u8,
+}
+
+impl BidiRun {
+    pub fn is_rtl(&self) -> bool {
+        self.level % 2 == 1
+    }
+}
+
+/// Bidi classes collapsed to what the simplified resolution distinguishes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Class {
+    L,
+    R,
+    /// Arabic letter; resolved to [`Class::R`] by W3 once W2 has used it.
+    Al,
+    En,
+    An,
+    Es,
+    Et,
+    Cs,
+    Neutral,
+}
+
+impl Class {
+    fn of(ch: char) -> Self {
+        match ch.bidi_class() {
+            BidiClass::L => Self::L,
+            BidiClass::R => Self::R,
+            BidiClass::AL => Self::Al,
+            BidiClass::EN => Self::En,
+            BidiClass::AN => Self::An,
+            BidiClass::ES => Self::Es,
+            BidiClass::ET => Self::Et,
+            BidiClass::CS => Self::Cs,
+            // NSM is resolved against its predecessor by the caller (W1).
+            _ => Self::Neutral,
+        }
+    }
+}
+
+/// Returns whether `text` reads right-to-left by the first-strong-character
+/// rule (P2–P3), or `None` when it has no strong character at all.
+pub(crate) fn first_strong_is_rtl(text: &str) -> Option<bool> {
+    text.chars().find_map(|ch| match ch.bidi_class() {
+        BidiClass::L => Some(false),
+        BidiClass::R | BidiClass::AL => Some(true),
+        _ => None,
+    })
+}
+
+/// Resolves `text` into level runs in visual order.
+///
+/// `paragraph_rtl` fixes the paragraph direction; `None` derives it from the
+/// first strong character and falls back to left-to-right.
+pub(crate) fn visual_runs(text: &str, paragraph_rtl: Option<bool>) -> Vec<BidiRun> {
+    let paragraph_rtl = paragraph_rtl
+        .or_else(|| first_strong_is_rtl(text))
+        .unwrap_or(false);
+    let paragraph_level = u8::from(paragraph_rtl);
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    if chars.is_empty() {
+        return Vec::new();
+    }
+
+    // W1: a non-spacing mark takes the class of the character before it.
+    let mut classes = Vec::with_capacity(chars.len());
+    for &(_, ch) in &chars {
+        let class = if ch.bidi_class() == BidiClass::NSM {
+            classes.last().copied().unwrap_or(Class::Neutral)
+        } else {
+            Class::of(ch)
+        };
+        classes.push(class);
+    }
+
+    // W2: a European number whose preceding strong character is an Arabic
+    // letter is an Arabic number. W3: Arabic letters then count as R.
+    let mut last_strong = if paragraph_rtl { Class::R } else { Class::L };
+    for class in &mut classes {
+        match *class {
+            Class::L | Class::R | Class::Al => last_strong = *class,
+            Class::En if last_strong == Class::Al => *class = Class::An,
+            _ => {}
+        }
+    }
+    for class in &mut classes {
+        if *class == Class::Al {
+            *class = Class::R;
+        }
+    }
+
+    // W4: a single separator between two numbers of the same kind joins them.
+    for index in 1..classes.len().saturating_sub(1) {
+        let (before, after) = (classes[index - 1], classes[index + 1]);
+        classes[index] = match classes[index] {
+            Class::Es | Class::Cs if before == Class::En && after == Class::En => Class::En,
+            Class::Cs if before == Class::An && after == Class::An => Class::An,
+            class => class,
+        };
+    }
+
+    // W5: a sequence of terminators adjacent to a European number joins it.
+    let mut index = 0;
+    while index < classes.len() {
+        if classes[index] != Class::Et {
+            index += 1;
+            continue;
+        }
+        let start = index;
+        while index < classes.len() && classes[index] == Class::Et {
+            index += 1;
+        }
+        let touches_number = (start > 0 && classes[start - 1] == Class::En)
+            || classes.get(index) == Some(&Class::En);
+        if touches_number {
+            classes[start..index].fill(Class::En);
+        }
+    }
+
+    // W6/W7: leftover separators become neutral, and a European number whose
+    // preceding strong context is left-to-right is treated as L.
+    let mut last_strong = if paragraph_rtl { Class::R } else { Class::L };
+    for class in &mut classes {
+        match *class {
+            Class::L | Class::R => last_strong = *class,
+            Class::En if last_strong == Class::L => *class = Class::L,
+            Class::Es | Class::Et | Class::Cs => *class = Class::Neutral,
+            _ => {}
+        }
+    }
+
+    // N1/N2: neutrals between two runs of the same direction take it (numbers
+    // count as R); any other neutral takes the paragraph direction.
+    let strong_direction = |class: Class| match class {
+        Class::L => Some(Class::L),
+        Class::R | Class::En | Class::An => Some(Class::R),
+        _ => None,
+    };
+    let sos = if paragraph_rtl { Class::R } else { Class::L };
+    let mut index = 0;
+    while index < classes.len() {
+        if classes[index] != Class::Neutral {
+            index += 1;
+            continue;
+        }
+        let start = index;
+        while index < classes.len() && classes[index] == Class::Neutral {
+            index += 1;
+        }
+        let before = start
+            .checked_sub(1)
+            .and_then(|previous| strong_direction(classes[previous]))
+            .unwrap_or(sos);
+        let after = classes
+            .get(index)
+            .and_then(|&class| strong_direction(class))
+            .unwrap_or(sos);
+        let resolved = if before == after { before } else { sos };
+        classes[start..index].fill(resolved);
+    }
+
+    // I1/I2: implicit levels relative to the paragraph level.
+    let levels: Vec<u8> = classes
+        .iter()
+        .map(|class| match (paragraph_rtl, class) {
+            (false, Class::R) => paragraph_level + 1,
+            (false, Class::En | Class::An) => paragraph_level + 2,
+            (true, Class::L | Class::En | Class::An) => paragraph_level + 1,
+            _ => paragraph_level,
+        })
+        .collect();
+
+    let mut runs: Vec<BidiRun> = Vec::new();
+    for (position, &(offset, ch)) in chars.iter().enumerate() {
+        let end = offset + ch.len_utf8();
+        match runs.last_mut() {
+            Some(run) if run.level == levels[position] => run.range.end = end,
+            _ => runs.push(BidiRun {
+                range: offset..end,
+                level: levels[position],
+            }),
+        }
+    }
+
+    // L2: from the highest level down to the lowest odd level, reverse every
+    // maximal sequence of runs at that level or above.
+    let highest = runs.iter().map(|run| run.level).max().unwrap_or(0);
+    let lowest_odd = runs
+        .iter()
+        .map(|run| run.level)
+        .filter(|level| level % 2 == 1)
+        .min()
+        .unwrap_or(highest + 1);
+    for level in (lowest_odd..=highest).rev() {
+        let mut index = 0;
+        while index < runs.len() {
+            if runs[index].level < level {
+                index += 1;
+                continue;
+            }
+            let start = index;
+            while index < runs.len() && runs[index].level >= level {
+                index += 1;
+            }
+            runs[start..index].reverse();
+        }
+    }
+
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_texts<'a>(text: &'a str, runs: &[BidiRun]) -> Vec<(&'a str, bool)> {
+        runs.iter()
+            .map(|run| (&text[run.range.clone()], run.is_rtl()))
+            .collect()
+    }
+
+    #[test]
+    fn latin_text_is_one_left_to_right_run() {
+        let text = "Hamburgefonstiv 123";
+        assert_eq!(
+            run_texts(text, &visual_runs(text, None)),
+            vec![(text, false)]
+        );
+    }
+
+    #[test]
+    fn hebrew_text_is_one_right_to_left_run() {
+        let text = "שלום עולם";
+        assert_eq!(first_strong_is_rtl(text), Some(true));
+        assert_eq!(
+            run_texts(text, &visual_runs(text, None)),
+            vec![(text, true)]
+        );
+    }
+
+    /// Digits inside right-to-left text keep their own left-to-right order and
+    /// are placed visually before (left of) the Arabic that precedes them.
+    #[test]
+    fn numbers_in_rtl_paragraph_are_reordered_as_their_own_run() {
+        let text = "عدد 123";
+        let runs = visual_runs(text, None);
+        assert_eq!(run_texts(text, &runs), vec![("123", false), ("عدد ", true)]);
+        assert_eq!(runs[0].level, 2);
+    }
+
+    /// After Arabic letters digits are Arabic numbers (W2), so a trailing
+    /// percent sign is not absorbed into the number (W5) and stays in the
+    /// right-to-left run.
+    #[test]
+    fn digits_after_arabic_letters_are_arabic_numbers() {
+        let text = "عدد 50%";
+        assert_eq!(
+            run_texts(text, &visual_runs(text, None)),
+            vec![("%", true), ("50", false), ("عدد ", true)]
+        );
+    }
+
+    /// After Hebrew letters the same digits stay European numbers and keep
+    /// their terminator.
+    #[test]
+    fn digits_after_hebrew_letters_stay_european_numbers() {
+        let text = "מספר 50%";
+        assert_eq!(
+            run_texts(text, &visual_runs(text, None)),
+            vec![("50%", false), ("מספר ", true)]
+        );
+    }
+
+    #[test]
+    fn rtl_word_in_ltr_paragraph_keeps_logical_position() {
+        let text = "abc אבג def";
+        assert_eq!(
+            run_texts(text, &visual_runs(text, None)),
+            vec![("abc ", false), ("אבג", true), (" def", false)]
+        );
+    }
+
+    #[test]
+    fn forced_direction_overrides_first_strong_character() {
+        let text = "abc";
+        let runs = visual_runs(text, Some(true));
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].level, 2);
+        assert!(!runs[0].is_rtl());
+    }
+}
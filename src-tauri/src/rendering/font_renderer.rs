@@ -1,14 +1,20 @@
 //! Rasterises a string of glyphs from a font face into a grayscale PNG.
 //!
 //! Shaping and scaling are done with [`swash`]: the text is shaped to glyph
-//! positions, each glyph is rendered (preferring colour outline/bitmap sources
-//! before plain outlines), and the glyph coverage is composited into a tightly
-//! cropped LA8 (luminance + alpha) image. The samples this produces are the
-//! input to the analysis stage.
+//! positions, either as a horizontal line (with bidi run reordering, see
+//! [`super::bidi`]) or as a vertical column, each glyph is rendered
+//! (preferring colour outline/bitmap sources before plain outlines), and the
+//! glyph coverage is composited into an LA8 (luminance + alpha) image. That
+//! image is either tightly cropped to the ink or, in
+//! [`SampleCanvas::Normalized`] mode, a fixed square on which every face is
+//! scaled to the same cap height and set on the same baseline. The samples
+//! this produces are the input to the analysis stage.
 
-use crate::config::RenderConfig;
+use super::bidi;
+use crate::config::{RenderConfig, SampleCanvas, TextDirection};
 use crate::error::{AppError, Result};
 use image::ImageEncoder;
+use std::collections::BTreeSet;
 use std::fs::{self, File};
 use std::io::BufWriter;
 use std::panic::{self, AssertUnwindSafe};
@@ -17,10 +23,19 @@ use std::sync::Arc;
 use swash::scale::image::{Content, Image};
 use swash::scale::{Render, ScaleContext, Source, StrikeWith};
 use swash::shape::{Direction, ShapeContext};
-use swash::text::Script;
+use swash::text::{Language, Script};
 use swash::zeno::{Format, Vector};
 use swash::{FontRef, GlyphId};
 
+/// Fraction of the normalised canvas covered by the cap height.
+const NORMALIZED_CAP_HEIGHT: f32 = 0.5;
+/// Distance of the baseline from the top of the normalised canvas, as a
+/// fraction of the canvas side. Leaves room for descenders below.
+const NORMALIZED_BASELINE: f32 = 0.7;
+/// Cap height assumed, relative to the ascender, for faces whose OS/2 table
+/// predates `sCapHeight` (version < 2).
+const FALLBACK_CAP_HEIGHT_RATIO: f32 = 0.7;
+
 /// A rendered glyph image positioned in the output's pixel coordinate space.
 struct RenderedGlyph {
     image: Image,
@@ -28,18 +43,59 @@ struct RenderedGlyph {
     y: i32,
 }
 
+/// Measurements taken from a rendered sample.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SampleStats {
+    /// See [`estimate_stroke_contrast`]; `None` when the sample has too
+    /// little ink to measure.
+    pub stroke_contrast: Option<f32>,
+    /// Whether ink fell outside the fixed canvas of
+    /// [`SampleCanvas::Normalized`], e.g. because the text is wider than the
+    /// canvas. Always `false` for cropped samples.
+    pub clipped: bool,
+}
+
 /// Renders sample images for a single [`RenderConfig`] (text + size + output).
 pub struct FontRenderer {
     config: Arc<RenderConfig>,
+    features: Vec<([u8; 4], u16)>,
+    language: Option<Language>,
 }
 
 impl FontRenderer {
-    pub fn new(config: Arc<RenderConfig>) -> Self {
-        Self { config }
+    /// Creates a renderer, validating the configured OpenType feature
+    /// settings and language tag up front.
+    pub fn new(config: Arc<RenderConfig>) -> Result<Self> {
+        let features = config
+            .features
+            .iter()
+            .map(|spec| parse_feature_setting(spec))
+            .collect::<Result<Vec<_>>>()?;
+        let language = config
+            .language
+            .as_deref()
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(|tag| {
+                Language::parse(tag).ok_or_else(|| {
+                    AppError::Processing(format!("Invalid BCP-47 language tag '{tag}'"))
+                })
+            })
+            .transpose()?;
+        Ok(Self {
+            config,
+            features,
+            language,
+        })
     }
 
     /// Renders the configured text into `samples/<safe_name>/sample.png`.
-    pub fn render_sample(&self, font_path: &Path, font_index: u32, safe_name: &str) -> Result<()> {
+    pub fn render_sample(
+        &self,
+        font_path: &Path,
+        font_index: u32,
+        safe_name: &str,
+    ) -> Result<SampleStats> {
         let path = self
             .config
             .output_dir
@@ -55,7 +111,12 @@ impl FontRenderer {
     /// into an [`AppError`], because malformed fonts can make the underlying
     /// shaping/scaling code panic and a single bad font must not abort a whole
     /// parallel render pass.
-    pub fn render_to_path(&self, font_path: &Path, font_index: u32, path: &Path) -> Result<()> {
+    pub fn render_to_path(
+        &self,
+        font_path: &Path,
+        font_index: u32,
+        path: &Path,
+    ) -> Result<SampleStats> {
         match panic::catch_unwind(AssertUnwindSafe(|| {
             self.render_to_path_inner(font_path, font_index, path)
         })) {
@@ -73,14 +134,153 @@ impl FontRenderer {
         }
     }
 
+    /// Glyphs the configured text maps to in `font`: everything the shaper
+    /// emits for the configured direction, features and language, plus each
+    /// character's nominal `cmap` glyph. This is the set a font subset must
+    /// keep to reproduce the sample.
+    pub fn glyph_ids(&self, font: FontRef) -> BTreeSet<GlyphId> {
+        let (glyphs, _) = self.layout(&mut ShapeContext::new(), font, self.config.font_size);
+        let charmap = font.charmap();
+        glyphs
+            .into_iter()
+            .map(|(glyph_id, _, _)| glyph_id)
+            .chain(self.config.text.chars().map(|ch| charmap.map(ch)))
+            .collect()
+    }
+
+    /// Shapes the text in the configured direction; see
+    /// [`layout_horizontal`](Self::layout_horizontal) and
+    /// [`layout_vertical`](Self::layout_vertical).
+    fn layout(
+        &self,
+        shape_context: &mut ShapeContext,
+        font: FontRef,
+        size: f32,
+    ) -> (Vec<(GlyphId, f32, f32)>, f32) {
+        match self.config.direction {
+            TextDirection::TopToBottom => self.layout_vertical(shape_context, font, size),
+            TextDirection::Auto => self.layout_horizontal(shape_context, font, size, None),
+            TextDirection::LeftToRight => {
+                self.layout_horizontal(shape_context, font, size, Some(false))
+            }
+            TextDirection::RightToLeft => {
+                self.layout_horizontal(shape_context, font, size, Some(true))
+            }
+        }
+    }
+
+    /// Shapes the text as a horizontal line at `size` pixels per em and
+    /// returns glyph positions (y-up, origin at the start of the line) plus
+    /// the total advance.
+    ///
+    /// The text is split into bidi level runs (see [`bidi::visual_runs`]);
+    /// `paragraph_rtl` forces the paragraph direction, `None` detects it.
+    /// Each run is shaped with its own script and direction, and right-to-left
+    /// runs have their clusters reversed because swash emits them in logical
+    /// order.
+    fn layout_horizontal(
+        &self,
+        shape_context: &mut ShapeContext,
+        font: FontRef,
+        size: f32,
+        paragraph_rtl: Option<bool>,
+    ) -> (Vec<(GlyphId, f32, f32)>, f32) {
+        let text = &self.config.text;
+        let mut glyphs = Vec::new();
+        let mut pen_x = 0.0;
+        for run in bidi::visual_runs(text, paragraph_rtl) {
+            let run_text = &text[run.range.clone()];
+            let direction = if run.is_rtl() {
+                Direction::RightToLeft
+            } else {
+                Direction::LeftToRight
+            };
+            let mut shaper = shape_context
+                .builder(font)
+                .size(size)
+                .script(dominant_script(run_text))
+                .language(self.language)
+                .direction(direction)
+                .features(&self.features)
+                .build();
+            shaper.add_str(run_text);
+
+            let mut clusters = Vec::<Vec<(GlyphId, f32, f32, f32)>>::new();
+            shaper.shape_with(|cluster| {
+                clusters.push(
+                    cluster
+                        .glyphs
+                        .iter()
+                        .map(|glyph| (glyph.id, glyph.x, glyph.y, glyph.advance))
+                        .collect(),
+                );
+            });
+            if run.is_rtl() {
+                clusters.reverse();
+            }
+            for (glyph_id, x, y, advance) in clusters.into_iter().flatten() {
+                glyphs.push((glyph_id, pen_x + x, y));
+                pen_x += advance;
+            }
+        }
+        (glyphs, pen_x)
+    }
+
+    /// Shapes the text as a single top-to-bottom column at `size` pixels per
+    /// em and returns glyph positions (y-up, pen moving downwards, column
+    /// centred on x = 0) plus the total advance.
+    ///
+    /// Vertical alternates are requested through the `vert`/`vrt2` features,
+    /// and each glyph is centred horizontally on the column with its vertical
+    /// origin (`VORG`, or `vmtx` top side bearing) on the pen position,
+    /// advancing by the `vmtx` advance height. Faces without vertical metrics
+    /// fall back to the values swash synthesises from ascent/descent.
+    fn layout_vertical(
+        &self,
+        shape_context: &mut ShapeContext,
+        font: FontRef,
+        size: f32,
+    ) -> (Vec<(GlyphId, f32, f32)>, f32) {
+        let text = &self.config.text;
+        let glyph_metrics = font.glyph_metrics(&[]).scale(size);
+        let mut shaper = shape_context
+            .builder(font)
+            .size(size)
+            .script(dominant_script(text))
+            .language(self.language)
+            .direction(Direction::LeftToRight)
+            .features([("vert", 1), ("vrt2", 1)])
+            .features(&self.features)
+            .build();
+        shaper.add_str(text);
+
+        let mut glyphs = Vec::new();
+        let mut pen_y = 0.0;
+        shaper.shape_with(|cluster| {
+            for glyph in cluster.glyphs {
+                let x = -glyph_metrics.advance_width(glyph.id) / 2.0;
+                let y = pen_y - glyph_metrics.vertical_origin(glyph.id);
+                glyphs.push((glyph.id, x, y));
+                pen_y -= glyph_metrics.advance_height(glyph.id);
+            }
+        });
+        (glyphs, -pen_y)
+    }
+
     /// The actual rendering, run under the panic guard of
     /// [`render_to_path`](Self::render_to_path).
     ///
-    /// Validates that the face covers every character, shapes the text, renders
-    /// each glyph, composites them into a tightly-cropped LA8 buffer, and
-    /// writes it as a PNG. Returns an error if the face is missing a glyph or
-    /// produces no visible pixels.
-    fn render_to_path_inner(&self, font_path: &Path, font_index: u32, path: &Path) -> Result<()> {
+    /// Validates that the face covers every character, shapes the text in the
+    /// configured direction, renders each glyph, composites them into an LA8
+    /// buffer (tightly cropped, or the fixed canvas of
+    /// [`SampleCanvas::Normalized`]), and writes it as a PNG. Returns an error
+    /// if the face is missing a glyph or produces no visible pixels.
+    fn render_to_path_inner(
+        &self,
+        font_path: &Path,
+        font_index: u32,
+        path: &Path,
+    ) -> Result<SampleStats> {
         let font_data = std::fs::read(font_path).map_err(|e| {
             AppError::Io(format!(
                 "Failed to read font file {}: {}",
@@ -103,34 +303,34 @@ impl FontRenderer {
             }
         }
 
-        let script = swash::text::analyze(self.config.text.chars())
-            .map(|(properties, _)| properties.script())
-            .find(|script| !matches!(script, Script::Common | Script::Inherited | Script::Unknown))
-            .unwrap_or(Script::Latin);
-        let mut shape_context = ShapeContext::new();
-        let mut shaper = shape_context
-            .builder(font)
-            .size(self.config.font_size)
-            .script(script)
-            .direction(Direction::LeftToRight)
-            .build();
-        shaper.add_str(&self.config.text);
+        let (size, canvas_side) = match self.config.canvas {
+            SampleCanvas::Cropped => (self.config.font_size, None),
+            SampleCanvas::Normalized => {
+                let side = self.config.font_size.round().max(1.0);
+                (normalized_font_size(font, side), Some(side))
+            }
+        };
 
-        let mut glyphs = Vec::<(GlyphId, f32, f32)>::new();
-        let mut pen_x = 0.0;
-        shaper.shape_with(|cluster| {
-            for glyph in cluster.glyphs {
-                glyphs.push((glyph.id, pen_x + glyph.x, glyph.y));
-                pen_x += glyph.advance;
+        let vertical = self.config.direction == TextDirection::TopToBottom;
+        let (mut glyphs, extent) = self.layout(&mut ShapeContext::new(), font, size);
+
+        // On the normalised canvas the line is centred along its advance and
+        // pinned to a fixed baseline (or column centre for vertical text), so
+        // the layout origin moves into canvas space before rasterising.
+        if let Some(side) = canvas_side {
+            let (offset_x, offset_y) = if vertical {
+                (side / 2.0, -(side - extent) / 2.0)
+            } else {
+                ((side - extent) / 2.0, -side * NORMALIZED_BASELINE)
+            };
+            for (_, x, y) in &mut glyphs {
+                *x += offset_x;
+                *y += offset_y;
             }
-        });
+        }
 
         let mut scale_context = ScaleContext::new();
-        let mut scaler = scale_context
-            .builder(font)
-            .size(self.config.font_size)
-            .hint(true)
-            .build();
+        let mut scaler = scale_context.builder(font).size(size).hint(true).build();
         let sources = [
             Source::ColorOutline(0),
             Source::ColorBitmap(StrikeWith::BestFit),
@@ -175,17 +375,22 @@ impl FontRenderer {
             ));
         }
 
-        // Composite all glyphs into a tightly-cropped LA8 buffer: luminance is
-        // a constant 255 and the per-pixel coverage is accumulated into the
-        // alpha channel (saturating), so overlapping glyphs don't wrap around.
+        if let Some(side) = canvas_side {
+            (min_x, min_y, max_x, max_y) = (0, 0, side as i32, side as i32);
+        }
+
+        // Composite all glyphs into the LA8 buffer: luminance is a constant
+        // 255 and the per-pixel coverage is accumulated into the alpha channel
+        // (saturating), so overlapping glyphs don't wrap around. Pixels that
+        // fall outside the buffer (only possible on the fixed canvas) are
+        // clipped, which is reported when any of them carries ink.
         let width = (max_x - min_x).max(1) as u32;
         let height = (max_y - min_y).max(1) as u32;
         let mut la8_pixels = vec![0u8; (width * height * 2) as usize];
+        let mut clipped = false;
 
         for rendered_glyph in rendered {
             let image = rendered_glyph.image;
-            let dst_x = (rendered_glyph.x - min_x) as usize;
-            let dst_y = (rendered_glyph.y - min_y) as usize;
             let glyph_width = image.placement.width as usize;
             let glyph_height = image.placement.height as usize;
             let (source_stride, alpha_offset, pixel_stride) = match image.content {
@@ -194,19 +399,26 @@ impl FontRenderer {
             };
             // A corrupt face can return a pixel buffer shorter than its
             // placement advertises; skip it rather than index out of bounds.
-            if dst_x + glyph_width > width as usize
-                || dst_y + glyph_height > height as usize
-                || image.data.len() < source_stride * glyph_height
-            {
-                eprintln!("❌ Skipping glyph whose placement exceeds its canvas or pixel buffer");
+            if image.data.len() < source_stride * glyph_height {
+                eprintln!("❌ Skipping glyph whose pixel buffer is smaller than its placement");
                 continue;
             }
             for row in 0..glyph_height {
+                let target_y = rendered_glyph.y - min_y + row as i32;
                 let source_row = row * source_stride;
-                let target_row = (dst_y + row) * width as usize;
                 for col in 0..glyph_width {
                     let alpha = image.data[source_row + alpha_offset + col * pixel_stride];
-                    let target_index = (target_row + dst_x + col) * 2;
+                    let target_x = rendered_glyph.x - min_x + col as i32;
+                    if target_y < 0
+                        || target_y >= height as i32
+                        || target_x < 0
+                        || target_x >= width as i32
+                    {
+                        clipped |= alpha > 0;
+                        continue;
+                    }
+                    let target_row = target_y as usize * width as usize;
+                    let target_index = (target_row + target_x as usize) * 2;
                     la8_pixels[target_index] = 255;
                     la8_pixels[target_index + 1] =
                         la8_pixels[target_index + 1].saturating_add(alpha);
@@ -231,6 +443,221 @@ impl FontRenderer {
         );
         encoder.write_image(&la8_pixels, width, height, image::ExtendedColorType::La8)?;
 
-        Ok(())
+        Ok(SampleStats {
+            stroke_contrast: estimate_stroke_contrast(&la8_pixels, width as usize),
+            clipped,
+        })
+    }
+}
+
+/// Returns the first specific script in `text`, ignoring `Common`/`Inherited`
+/// characters such as spaces, digits and punctuation. Defaults to Latin.
+fn dominant_script(text: &str) -> Script {
+    swash::text::analyze(text.chars())
+        .map(|(properties, _)| properties.script())
+        .find(|script| !matches!(script, Script::Common | Script::Inherited | Script::Unknown))
+        .unwrap_or(Script::Latin)
+}
+
+/// Parses one OpenType feature setting.
+///
+/// Accepts `tag` or `+tag` (enable), `-tag` (disable) and `tag=N` (select
+/// alternate `N`, e.g. `salt=2`), where `tag` is a four-character feature tag
+/// such as `ss01`, `smcp` or `onum`.
+pub fn parse_feature_setting(spec: &str) -> Result<([u8; 4], u16)> {
+    let invalid = || AppError::Processing(format!("Invalid OpenType feature setting '{spec}'"));
+    let spec = spec.trim();
+    let (tag, value) = if let Some(tag) = spec.strip_prefix('-') {
+        (tag, 0)
+    } else if let Some((tag, value)) = spec.split_once('=') {
+        (tag, value.trim().parse::<u16>().map_err(|_| invalid())?)
+    } else {
+        (spec.strip_prefix('+').unwrap_or(spec), 1)
+    };
+    let tag: [u8; 4] = tag.trim().as_bytes().try_into().map_err(|_| invalid())?;
+    if !tag.iter().all(|byte| (0x20..=0x7e).contains(byte)) {
+        return Err(invalid());
+    }
+    Ok((tag, value))
+}
+
+/// Returns the pixels-per-em that puts the face's cap height at
+/// [`NORMALIZED_CAP_HEIGHT`] of a `side`-pixel canvas.
+///
+/// Uses the OS/2 `sCapHeight`, falling back to a fraction of the ascender for
+/// faces that don't declare one.
+fn normalized_font_size(font: FontRef, side: f32) -> f32 {
+    let metrics = font.metrics(&[]);
+    let units_per_em = f32::from(metrics.units_per_em.max(1));
+    let cap_height = if metrics.cap_height > 0.0 {
+        metrics.cap_height
+    } else {
+        metrics.ascent * FALLBACK_CAP_HEIGHT_RATIO
+    };
+    if cap_height <= 0.0 {
+        return side * NORMALIZED_CAP_HEIGHT;
+    }
+    side * NORMALIZED_CAP_HEIGHT * units_per_em / cap_height
+}
+
+/// Estimates the stroke contrast of a rendered LA8 sample.
+///
+/// Ink runs along rows measure the thickness of vertical strokes and runs
+/// along columns that of horizontal ones (both also cross bowls diagonally,
+/// which the median largely ignores). The result is the larger median run
+/// length over the smaller, so it reads as thick-to-thin regardless of stress.
+fn estimate_stroke_contrast(la8_pixels: &[u8], width: usize) -> Option<f32> {
+    if width == 0 {
+        return None;
+    }
+    let height = la8_pixels.len() / 2 / width;
+    let is_ink = |x: usize, y: usize| la8_pixels[(y * width + x) * 2 + 1] >= 128;
+    let run_lengths = |outer: usize, inner: usize, ink: &dyn Fn(usize, usize) -> bool| {
+        let mut runs = Vec::new();
+        for line in 0..outer {
+            let mut run = 0u32;
+            for position in 0..inner {
+                if ink(line, position) {
+                    run += 1;
+                } else if run > 0 {
+                    runs.push(run);
+                    run = 0;
+                }
+            }
+            if run > 0 {
+                runs.push(run);
+            }
+        }
+        runs
+    };
+    let median = |mut runs: Vec<u32>| {
+        if runs.is_empty() {
+            return None;
+        }
+        let middle = runs.len() / 2;
+        Some(*runs.select_nth_unstable(middle).1 as f32)
+    };
+
+    let horizontal = median(run_lengths(height, width, &|y, x| is_ink(x, y)))?;
+    let vertical = median(run_lengths(width, height, &|x, y| is_ink(x, y)))?;
+    Some(horizontal.max(vertical) / horizontal.min(vertical))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal TrueType face; see `tests/fixtures/make_fixture_font.py`.
+    const FIXTURE_FONT: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/FixtureSans.ttf"
+    );
+
+    fn renderer(text: &str, canvas: SampleCanvas, output_dir: &Path) -> FontRenderer {
+        FontRenderer::new(Arc::new(RenderConfig {
+            text: text.to_string(),
+            font_size: 64.0,
+            direction: TextDirection::LeftToRight,
+            features: Vec::new(),
+            language: None,
+            canvas,
+            output_dir: output_dir.to_path_buf(),
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn normalized_canvas_reports_text_wider_than_the_canvas() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("sample.png");
+
+        let fits = renderer("I", SampleCanvas::Normalized, dir.path())
+            .render_to_path(Path::new(FIXTURE_FONT), 0, &output)
+            .unwrap();
+        assert!(!fits.clipped);
+        let png = image::open(&output).unwrap();
+        assert_eq!((png.width(), png.height()), (64, 64));
+
+        let overflows = renderer("HOHOHO", SampleCanvas::Normalized, dir.path())
+            .render_to_path(Path::new(FIXTURE_FONT), 0, &output)
+            .unwrap();
+        assert!(overflows.clipped);
+        let png = image::open(&output).unwrap();
+        assert_eq!((png.width(), png.height()), (64, 64));
+    }
+
+    #[test]
+    fn cropped_canvas_never_clips() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("sample.png");
+        let stats = renderer("HOHOHO", SampleCanvas::Cropped, dir.path())
+            .render_to_path(Path::new(FIXTURE_FONT), 0, &output)
+            .unwrap();
+        assert!(!stats.clipped);
+        assert!(image::open(&output).unwrap().width() > 64);
+    }
+
+    /// LA8 pixels of a `size`-square ring whose left/right sides are `side`
+    /// pixels wide and whose top/bottom bars are `bar` pixels thick.
+    fn ring(size: usize, side: usize, bar: usize) -> Vec<u8> {
+        let mut pixels = Vec::with_capacity(size * size * 2);
+        for y in 0..size {
+            for x in 0..size {
+                let ink = x < side || x >= size - side || y < bar || y >= size - bar;
+                pixels.extend_from_slice(&[0, if ink { 255 } else { 0 }]);
+            }
+        }
+        pixels
+    }
+
+    #[test]
+    fn stroke_contrast_is_one_for_monoline_strokes() {
+        assert_eq!(estimate_stroke_contrast(&ring(20, 2, 2), 20), Some(1.0));
+    }
+
+    #[test]
+    fn stroke_contrast_is_thick_over_thin() {
+        assert_eq!(estimate_stroke_contrast(&ring(20, 6, 2), 20), Some(3.0));
+        // Stress direction does not matter: thick bars over thin sides.
+        assert_eq!(estimate_stroke_contrast(&ring(20, 2, 6), 20), Some(3.0));
+    }
+
+    #[test]
+    fn stroke_contrast_needs_ink() {
+        assert_eq!(estimate_stroke_contrast(&[], 0), None);
+        assert_eq!(estimate_stroke_contrast(&[0; 20 * 20 * 2], 20), None);
+        // Faint anti-aliasing below half coverage is not ink.
+        let faint: Vec<u8> = ring(20, 2, 2)
+            .chunks(2)
+            .flat_map(|pixel| [0, pixel[1] / 3])
+            .collect();
+        assert_eq!(estimate_stroke_contrast(&faint, 20), None);
+    }
+
+    #[test]
+    fn feature_setting_accepts_enable_disable_and_alternates() {
+        assert_eq!(parse_feature_setting("smcp").unwrap(), (*b"smcp", 1));
+        assert_eq!(parse_feature_setting("+onum").unwrap(), (*b"onum", 1));
+        assert_eq!(parse_feature_setting("-liga").unwrap(), (*b"liga", 0));
+        assert_eq!(parse_feature_setting("salt=2").unwrap(), (*b"salt", 2));
+        assert_eq!(parse_feature_setting(" ss01 = 3 ").unwrap(), (*b"ss01", 3));
+    }
+
+    #[test]
+    fn feature_setting_rejects_malformed_tags_and_values() {
+        for spec in [
+            "",
+            "lig",
+            "ligature",
+            "salt=x",
+            "salt=-1",
+            "smc\u{e9}",
+            "sm\tp",
+        ] {
+            assert!(
+                parse_feature_setting(spec).is_err(),
+                "{spec:?} was accepted"
+            );
+        }
     }
 }
@@ -1,5 +1,10 @@
-//! Font rasterisation. See [`font_renderer`] for the renderer itself.
+//! Font rasterisation. See [`font_renderer`] for the renderer itself,
+//! [`bidi`] for how mixed-direction sample text is ordered, and [`subset`]
+//! for the font subsets embedded in portable session documents.
 
+mod bidi;
 pub mod font_renderer;
+pub mod subset;
 
 pub use font_renderer::*;
+pub use subset::*;
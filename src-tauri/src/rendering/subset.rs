@@ -0,0 +1,449 @@
+//! Font subsetting for fonts embedded in portable session documents.
+//!
+//! Subsets keep glyph ids unchanged: the outlines of unused glyphs are emptied
+//! from `glyf` and `loca` is rebuilt, while every other table (`cmap`,
+//! `GSUB`/`GPOS`, metrics) is copied as-is, so a subset shapes exactly like the
+//! original face for the glyphs it keeps. That is far smaller than the full
+//! font for the short sample strings sessions use without having to renumber
+//! glyphs through the layout tables. Faces whose vendor forbids subsetting
+//! are embedded whole instead. CFF-flavoured (and other non-`glyf`) faces
+//! can't be subset here and would be embedded at full size, so they are not
+//! embedded at all; check [`has_glyf_outlines`]. A face from a collection is
+//! always written out as a standalone font.
+//!
+//! Callers must check [`embedding_rights`] first; nothing here refuses a face
+//! on licensing grounds.
+
+use crate::error::{AppError, Result};
+use std::collections::BTreeSet;
+use swash::{tag_from_bytes, FontRef, GlyphId};
+
+/// `OS/2 fsType`: Restricted License embedding.
+const FS_TYPE_RESTRICTED: u16 = 0x0002;
+/// `OS/2 fsType`: Preview & Print embedding.
+const FS_TYPE_PREVIEW_AND_PRINT: u16 = 0x0004;
+/// `OS/2 fsType`: Editable embedding.
+const FS_TYPE_EDITABLE: u16 = 0x0008;
+/// `OS/2 fsType`: the face must not be subsetted before embedding.
+const FS_TYPE_NO_SUBSETTING: u16 = 0x0100;
+/// `OS/2 fsType`: only the face's bitmaps may be embedded.
+const FS_TYPE_BITMAP_ONLY: u16 = 0x0200;
+
+/// Tables left out of every embedded face: a digital signature no longer
+/// matches once the font has been rewritten.
+const DROPPED_TABLES: [&[u8; 4]; 1] = [b"DSIG"];
+
+/// `head.checkSumAdjustment` is this minus the checksum of the whole font.
+const CHECKSUM_MAGIC: u32 = 0xB1B0_AFBA;
+
+/// A table's tag and its bytes within the font file.
+type TableRecord<'a> = ([u8; 4], &'a [u8]);
+
+/// What a face's `OS/2 fsType` allows a portable document to do with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbeddingRights {
+    /// The face may be embedded. `subsettable` is `false` when the vendor
+    /// requires the whole face to be embedded.
+    Allowed { subsettable: bool },
+    /// Restricted License or bitmap-only embedding: the face must not be
+    /// embedded at all (the renderer needs outlines).
+    Restricted,
+}
+
+/// Reads the embedding permissions of `font` from `OS/2 fsType`.
+///
+/// A face without an `OS/2` table declares no restriction and is treated as
+/// installable. Before `OS/2` version 3 several usage bits may be set, in
+/// which case the least restrictive one applies, so Restricted License only
+/// wins when neither Preview & Print nor Editable is also set.
+pub fn embedding_rights(font: FontRef) -> EmbeddingRights {
+    let Some(fs_type) = font
+        .table(tag_from_bytes(b"OS/2"))
+        .and_then(|os2| read_u16(os2, 8))
+    else {
+        return EmbeddingRights::Allowed { subsettable: true };
+    };
+    let restricted = fs_type & FS_TYPE_RESTRICTED != 0
+        && fs_type & (FS_TYPE_PREVIEW_AND_PRINT | FS_TYPE_EDITABLE) == 0;
+    if restricted || fs_type & FS_TYPE_BITMAP_ONLY != 0 {
+        EmbeddingRights::Restricted
+    } else {
+        EmbeddingRights::Allowed {
+            subsettable: fs_type & FS_TYPE_NO_SUBSETTING == 0,
+        }
+    }
+}
+
+/// Whether `font` has TrueType (`glyf`) outlines, the only kind
+/// [`subset_font`] can subset.
+pub fn has_glyf_outlines(font: FontRef) -> bool {
+    font.table(tag_from_bytes(b"glyf")).is_some()
+}
+
+/// Writes `font` as a standalone font file keeping only the outlines of
+/// `glyph_ids` (plus `.notdef` and the components of kept composite glyphs).
+///
+/// With `subsettable` false every table is copied unchanged. A subsettable
+/// face without `glyf` outlines is an error.
+pub fn subset_font(
+    font: FontRef,
+    glyph_ids: impl IntoIterator<Item = GlyphId>,
+    subsettable: bool,
+) -> Result<Vec<u8>> {
+    let (sfnt_version, records) = read_table_directory(font)?;
+    let mut tables: Vec<([u8; 4], Vec<u8>)> = records
+        .into_iter()
+        .filter(|(tag, _)| !DROPPED_TABLES.contains(&tag))
+        .map(|(tag, data)| (tag, data.to_vec()))
+        .collect();
+
+    if subsettable {
+        subset_glyf(&mut tables, glyph_ids)?;
+    }
+    Ok(write_font(sfnt_version, tables))
+}
+
+/// Replaces `glyf`/`loca` with versions that keep only the reachable glyphs,
+/// switching `loca` to the long format.
+fn subset_glyf(
+    tables: &mut [([u8; 4], Vec<u8>)],
+    glyph_ids: impl IntoIterator<Item = GlyphId>,
+) -> Result<()> {
+    let table = |tag: &[u8; 4]| {
+        tables
+            .iter()
+            .position(|(table_tag, _)| table_tag == tag)
+            .ok_or_else(|| {
+                AppError::Font(format!(
+                    "Font has no {} table",
+                    String::from_utf8_lossy(tag)
+                ))
+            })
+    };
+    let (head, maxp, loca, glyf) = (
+        table(b"head")?,
+        table(b"maxp")?,
+        table(b"loca")?,
+        table(b"glyf")?,
+    );
+
+    let malformed = |what: &str| AppError::Font(format!("Malformed {what} table"));
+    let long_offsets = read_u16(&tables[head].1, 50).ok_or_else(|| malformed("head"))? != 0;
+    let num_glyphs = read_u16(&tables[maxp].1, 4).ok_or_else(|| malformed("maxp"))? as usize;
+    let offsets = (0..=num_glyphs)
+        .map(|index| {
+            if long_offsets {
+                read_u32(&tables[loca].1, index * 4).map(|offset| offset as usize)
+            } else {
+                read_u16(&tables[loca].1, index * 2).map(|offset| offset as usize * 2)
+            }
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| malformed("loca"))?;
+    let glyf_data = &tables[glyf].1;
+    let glyph_data = |gid: usize| {
+        let (start, end) = (offsets[gid], offsets[gid + 1]);
+        glyf_data.get(start..end.max(start)).unwrap_or(&[])
+    };
+
+    // Composite glyphs are drawn from other glyphs, which must be kept too.
+    let mut kept = BTreeSet::new();
+    let mut pending: Vec<usize> = std::iter::once(0)
+        .chain(glyph_ids.into_iter().map(usize::from))
+        .filter(|&gid| gid < num_glyphs)
+        .collect();
+    while let Some(gid) = pending.pop() {
+        if kept.insert(gid) {
+            pending.extend(
+                composite_components(glyph_data(gid))
+                    .into_iter()
+                    .filter(|&component| component < num_glyphs),
+            );
+        }
+    }
+
+    let mut new_glyf = Vec::new();
+    let mut new_loca = Vec::with_capacity((num_glyphs + 1) * 4);
+    for gid in 0..num_glyphs {
+        new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+        if kept.contains(&gid) {
+            new_glyf.extend_from_slice(glyph_data(gid));
+            new_glyf.resize(new_glyf.len().next_multiple_of(4), 0);
+        }
+    }
+    new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+
+    tables[head].1[50..52].copy_from_slice(&1u16.to_be_bytes());
+    tables[loca].1 = new_loca;
+    tables[glyf].1 = new_glyf;
+    Ok(())
+}
+
+/// Glyph ids referenced by a composite glyph; empty for a simple glyph.
+fn composite_components(glyph: &[u8]) -> Vec<usize> {
+    const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+    const WE_HAVE_A_SCALE: u16 = 0x0008;
+    const MORE_COMPONENTS: u16 = 0x0020;
+    const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+    const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+
+    let is_composite = read_u16(glyph, 0).is_some_and(|contours| (contours as i16) < 0);
+    if !is_composite {
+        return Vec::new();
+    }
+    let mut components = Vec::new();
+    let mut offset = 10;
+    while let (Some(flags), Some(gid)) = (read_u16(glyph, offset), read_u16(glyph, offset + 2)) {
+        components.push(gid as usize);
+        offset += 4;
+        offset += if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+            4
+        } else {
+            2
+        };
+        offset += if flags & WE_HAVE_A_SCALE != 0 {
+            2
+        } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            4
+        } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+            8
+        } else {
+            0
+        };
+        if flags & MORE_COMPONENTS == 0 {
+            break;
+        }
+    }
+    components
+}
+
+/// Reads the face's sfnt version and `(tag, data)` for every table record.
+fn read_table_directory<'a>(font: FontRef<'a>) -> Result<(u32, Vec<TableRecord<'a>>)> {
+    let malformed = || AppError::Font("Malformed font table directory".into());
+    let data = font.data;
+    let directory = font.offset as usize;
+    let sfnt_version = read_u32(data, directory).ok_or_else(malformed)?;
+    let num_tables = read_u16(data, directory + 4).ok_or_else(malformed)? as usize;
+    let mut tables = Vec::with_capacity(num_tables);
+    for index in 0..num_tables {
+        let record = directory + 12 + index * 16;
+        let tag: [u8; 4] = data
+            .get(record..record + 4)
+            .and_then(|tag| tag.try_into().ok())
+            .ok_or_else(malformed)?;
+        let offset = read_u32(data, record + 8).ok_or_else(malformed)? as usize;
+        let length = read_u32(data, record + 12).ok_or_else(malformed)? as usize;
+        let table = data
+            .get(offset..offset.saturating_add(length))
+            .ok_or_else(malformed)?;
+        tables.push((tag, table));
+    }
+    Ok((sfnt_version, tables))
+}
+
+/// Serialises tables into a single-face font file with a valid directory,
+/// table checksums and `head.checkSumAdjustment`.
+fn write_font(sfnt_version: u32, mut tables: Vec<([u8; 4], Vec<u8>)>) -> Vec<u8> {
+    tables.sort_by_key(|(tag, _)| *tag);
+    for (tag, data) in &mut tables {
+        if tag == b"head" && data.len() >= 12 {
+            data[8..12].fill(0);
+        }
+    }
+
+    let num_tables = tables.len() as u16;
+    let entry_selector = num_tables.max(1).ilog2() as u16;
+    let search_range = (1u16 << entry_selector) * 16;
+    let mut font = Vec::new();
+    font.extend_from_slice(&sfnt_version.to_be_bytes());
+    font.extend_from_slice(&num_tables.to_be_bytes());
+    font.extend_from_slice(&search_range.to_be_bytes());
+    font.extend_from_slice(&entry_selector.to_be_bytes());
+    font.extend_from_slice(&(num_tables * 16 - search_range).to_be_bytes());
+
+    let mut offset = 12 + tables.len() * 16;
+    let mut head_offset = None;
+    for (tag, data) in &tables {
+        if tag == b"head" {
+            head_offset = Some(offset);
+        }
+        font.extend_from_slice(tag);
+        font.extend_from_slice(&checksum(data).to_be_bytes());
+        font.extend_from_slice(&(offset as u32).to_be_bytes());
+        font.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        offset += data.len().next_multiple_of(4);
+    }
+    for (_, data) in &tables {
+        font.extend_from_slice(data);
+        font.resize(font.len().next_multiple_of(4), 0);
+    }
+
+    if let Some(head_offset) = head_offset.filter(|&head| font.len() >= head + 12) {
+        let adjustment = CHECKSUM_MAGIC.wrapping_sub(checksum(&font));
+        font[head_offset + 8..head_offset + 12].copy_from_slice(&adjustment.to_be_bytes());
+    }
+    font
+}
+
+/// OpenType table checksum: the wrapping sum of big-endian `u32`s, with the
+/// last one zero-padded.
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0u8; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2)
+        .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swash::scale::ScaleContext;
+
+    /// Minimal TrueType face; see `tests/fixtures/make_fixture_font.py`.
+    /// Glyph 6 (`Ĥ`) is a composite of glyphs 2 (`H`) and 5 (`^`).
+    const FIXTURE_FONT: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/FixtureSans.ttf"
+    );
+    const NUM_GLYPHS: usize = 8;
+
+    fn fixture() -> Vec<u8> {
+        std::fs::read(FIXTURE_FONT).unwrap()
+    }
+
+    fn table<'a>(font: FontRef<'a>, tag: &[u8; 4]) -> &'a [u8] {
+        read_table_directory(font)
+            .unwrap()
+            .1
+            .into_iter()
+            .find(|(table_tag, _)| table_tag == tag)
+            .unwrap()
+            .1
+    }
+
+    /// Byte length of every glyph's outline, read from a long `loca`.
+    fn glyph_lengths(font: FontRef) -> Vec<u32> {
+        let loca = table(font, b"loca");
+        (0..NUM_GLYPHS)
+            .map(|gid| read_u32(loca, (gid + 1) * 4).unwrap() - read_u32(loca, gid * 4).unwrap())
+            .collect()
+    }
+
+    /// Offset of `tag`'s record in the table directory.
+    fn table_record(data: &[u8], tag: &[u8; 4]) -> usize {
+        (0..read_u16(data, 4).unwrap() as usize)
+            .map(|index| 12 + index * 16)
+            .find(|&record| &data[record..record + 4] == tag)
+            .unwrap()
+    }
+
+    fn with_fs_type(fs_type: u16) -> Vec<u8> {
+        let mut data = fixture();
+        let os2 = read_u32(&data, table_record(&data, b"OS/2") + 8).unwrap() as usize;
+        data[os2 + 8..os2 + 10].copy_from_slice(&fs_type.to_be_bytes());
+        data
+    }
+
+    #[test]
+    fn subset_keeps_composite_components_and_empties_other_glyphs() {
+        let data = fixture();
+        let font = FontRef::from_index(&data, 0).unwrap();
+        let subset = subset_font(font, [6], true).unwrap();
+        let reparsed = FontRef::from_index(&subset, 0).unwrap();
+
+        let kept: Vec<bool> = glyph_lengths(reparsed)
+            .into_iter()
+            .map(|length| length > 0)
+            .collect();
+        // .notdef, H and ^ (components of Ĥ), and Ĥ itself; space has no outline.
+        assert_eq!(kept, [true, false, true, false, false, true, true, false]);
+        assert!(subset.len() < data.len());
+
+        // Glyph ids are unchanged, so the cmap still maps Ĥ to the composite,
+        // which swash draws from its kept components.
+        assert_eq!(reparsed.charmap().map('Ĥ'), 6);
+        let mut context = ScaleContext::new();
+        let outline = context.builder(reparsed).build().scale_outline(6).unwrap();
+        assert_eq!(outline.bounds().max.y, 850.0);
+        assert_eq!(outline.bounds().min.x, 100.0);
+    }
+
+    #[test]
+    fn subset_has_valid_checksums() {
+        let data = fixture();
+        let subset = subset_font(FontRef::from_index(&data, 0).unwrap(), [2, 3], true).unwrap();
+        assert_eq!(checksum(&subset), CHECKSUM_MAGIC);
+
+        let reparsed = FontRef::from_index(&subset, 0).unwrap();
+        let num_tables = read_u16(&subset, 4).unwrap() as usize;
+        for index in 0..num_tables {
+            let record = 12 + index * 16;
+            let tag: [u8; 4] = subset[record..record + 4].try_into().unwrap();
+            let mut table_data = table(reparsed, &tag).to_vec();
+            if &tag == b"head" {
+                table_data[8..12].fill(0);
+            }
+            assert_eq!(
+                read_u32(&subset, record + 4).unwrap(),
+                checksum(&table_data),
+                "{} checksum",
+                String::from_utf8_lossy(&tag)
+            );
+        }
+    }
+
+    #[test]
+    fn unsubsettable_face_is_copied_whole() {
+        let data = fixture();
+        let font = FontRef::from_index(&data, 0).unwrap();
+        let copy = subset_font(font, [2], false).unwrap();
+        let copy_font = FontRef::from_index(&copy, 0).unwrap();
+        assert_eq!(table(copy_font, b"glyf"), table(font, b"glyf"));
+        assert_eq!(checksum(&copy), CHECKSUM_MAGIC);
+    }
+
+    #[test]
+    fn face_without_glyf_outlines_is_not_subset() {
+        let mut data = fixture();
+        // Hide `glyf` behind a tag that keeps the directory sorted.
+        let record = table_record(&data, b"glyf");
+        data[record..record + 4].copy_from_slice(b"glyx");
+        let font = FontRef::from_index(&data, 0).unwrap();
+        assert!(!has_glyf_outlines(font));
+        assert!(subset_font(font, [2], true).is_err());
+    }
+
+    #[test]
+    fn embedding_rights_follow_fs_type() {
+        let rights = |fs_type: u16| {
+            let data = with_fs_type(fs_type);
+            embedding_rights(FontRef::from_index(&data, 0).unwrap())
+        };
+        let allowed = EmbeddingRights::Allowed { subsettable: true };
+        assert_eq!(rights(0), allowed);
+        assert_eq!(rights(FS_TYPE_RESTRICTED), EmbeddingRights::Restricted);
+        assert_eq!(rights(FS_TYPE_PREVIEW_AND_PRINT), allowed);
+        assert_eq!(rights(FS_TYPE_EDITABLE), allowed);
+        // Before OS/2 v3 the least restrictive of several usage bits applies.
+        assert_eq!(
+            rights(FS_TYPE_RESTRICTED | FS_TYPE_PREVIEW_AND_PRINT),
+            allowed
+        );
+        assert_eq!(
+            rights(FS_TYPE_PREVIEW_AND_PRINT | FS_TYPE_NO_SUBSETTING),
+            EmbeddingRights::Allowed { subsettable: false }
+        );
+        assert_eq!(rights(FS_TYPE_BITMAP_ONLY), EmbeddingRights::Restricted);
+    }
+}
@@ -0,0 +1,183 @@
+#!/usr/bin/env python3
+"""Writes FixtureSans.ttf, the minimal TrueType face used by the unit tests.
+
+The face has unitsPerEm 1000, cap height 700 and these glyphs:
+
+    0 .notdef     box
+    1 space       empty
+    2 H           three rectangles
+    3 I           one rectangle
+    4 O           square ring (outer and inner contour)
+    5 circumflex  triangle, mapped from `^`
+    6 Hcircumflex composite of H and circumflex, mapped from U+0124
+    7 unused      rectangle that no character maps to
+
+Only the standard library is needed: `python3 make_fixture_font.py`.
+"""
+
+import struct
+from pathlib import Path
+
+UNITS_PER_EM = 1000
+ASCENDER = 800
+DESCENDER = -200
+CAP_HEIGHT = 700
+X_HEIGHT = 500
+
+
+def rect(x0, y0, x1, y1):
+    """A clockwise rectangle contour."""
+    return [(x0, y0), (x0, y1), (x1, y1), (x1, y0)]
+
+
+def simple_glyph(contours):
+    points = [point for contour in contours for point in contour]
+    xs = [x for x, _ in points]
+    ys = [y for _, y in points]
+    data = struct.pack(">h4h", len(contours), min(xs), min(ys), max(xs), max(ys))
+    end = -1
+    for contour in contours:
+        end += len(contour)
+        data += struct.pack(">H", end)
+    data += struct.pack(">H", 0)  # no instructions
+    data += bytes([0x01] * len(points))  # on-curve, 16-bit deltas
+    previous = 0
+    for x in xs:
+        data += struct.pack(">h", x - previous)
+        previous = x
+    previous = 0
+    for y in ys:
+        data += struct.pack(">h", y - previous)
+        previous = y
+    return data, (min(xs), min(ys), max(xs), max(ys))
+
+
+def composite_glyph(components, bbox):
+    args_are_words, args_are_xy, more, use_my_metrics = 0x0001, 0x0002, 0x0020, 0x0200
+    data = struct.pack(">h4h", -1, *bbox)
+    for index, (gid, dx, dy) in enumerate(components):
+        flags = args_are_words | args_are_xy
+        if index == 0:
+            flags |= use_my_metrics
+        if index < len(components) - 1:
+            flags |= more
+        data += struct.pack(">HHhh", flags, gid, dx, dy)
+    return data
+
+
+def checksum(data):
+    data = data + b"\0" * (-len(data) % 4)
+    return sum(struct.unpack(f">{len(data) // 4}I", data)) & 0xFFFFFFFF
+
+
+def build():
+    h, _ = simple_glyph([rect(100, 0, 200, 700), rect(500, 0, 600, 700), rect(200, 300, 500, 400)])
+    glyphs = [
+        simple_glyph([rect(50, 0, 450, 700), rect(100, 50, 400, 650)[::-1]]),
+        (b"", (0, 0, 0, 0)),
+        (h, (100, 0, 600, 700)),
+        simple_glyph([rect(100, 0, 200, 700)]),
+        simple_glyph([rect(50, 0, 650, 700), rect(150, 100, 550, 600)[::-1]]),
+        simple_glyph([[(150, 750), (250, 850), (350, 750)]]),
+        (composite_glyph([(2, 0, 0), (5, 100, 0)], (100, 0, 600, 850)), (100, 0, 600, 850)),
+        simple_glyph([rect(100, 0, 300, 500)]),
+    ]
+    advances = [500, 300, 700, 300, 700, 500, 700, 400]
+    num_glyphs = len(glyphs)
+
+    glyf = b""
+    loca = b""
+    for data, _ in glyphs:
+        loca += struct.pack(">I", len(glyf))
+        glyf += data + b"\0" * (-len(data) % 4)
+    loca += struct.pack(">I", len(glyf))
+
+    boxes = [box for data, box in glyphs if data]
+    x_min = min(box[0] for box in boxes)
+    y_min = min(box[1] for box in boxes)
+    x_max = max(box[2] for box in boxes)
+    y_max = max(box[3] for box in boxes)
+
+    head = struct.pack(
+        ">IIIIHHqqhhhhHhhhh",
+        0x00010000, 0x00010000, 0, 0x5F0F3CF5, 0x0003, UNITS_PER_EM, 0, 0,
+        x_min, y_min, x_max, y_max, 0, 8, 2, 1, 0,
+    )
+    hhea = struct.pack(
+        ">Ihhh" "H" "hhh" "hhh" "4h" "hH",
+        0x00010000, ASCENDER, DESCENDER, 0, max(advances), 0, 0, x_max, 1, 0, 0,
+        0, 0, 0, 0, 0, num_glyphs,
+    )
+    maxp = struct.pack(
+        ">IHHHHHHHHHHHHHH",
+        0x00010000, num_glyphs, 12, 3, 12, 3, 2, 0, 0, 0, 0, 0, 0, 2, 1,
+    )
+    os2 = struct.pack(
+        ">HhHHH" "10h" "h" "10s" "4I" "4s" "HHH" "hhh" "HH" "2I" "hhHHH",
+        4, 500, 400, 5, 0,
+        650, 600, 0, 75, 650, 600, 0, 350, 50, 300,
+        0, b"\0" * 10, 1, 0, 0, 0, b"NONE", 0x0040, 0x20, 0x124,
+        ASCENDER, DESCENDER, 0, ASCENDER, -DESCENDER, 1, 0,
+        X_HEIGHT, CAP_HEIGHT, 0, 0x20, 2,
+    )
+    hmtx = b"".join(
+        struct.pack(">Hh", advance, box[0]) for advance, (_, box) in zip(advances, glyphs)
+    )
+
+    segments = [(0x20, 0x20, 1), (0x48, 0x49, 2), (0x4F, 0x4F, 4), (0x5E, 0x5E, 5), (0x124, 0x124, 6)]
+    seg_count = len(segments) + 1
+    ends = [end for _, end, _ in segments] + [0xFFFF]
+    starts = [start for start, _, _ in segments] + [0xFFFF]
+    deltas = [(gid - start) & 0xFFFF for start, _, gid in segments] + [1]
+    search_range = 2 * (1 << (seg_count.bit_length() - 1))
+    subtable = struct.pack(
+        ">HHHHHH",
+        4, 16 + 8 * seg_count, 0, seg_count * 2, search_range,
+        (seg_count.bit_length() - 1),
+    )
+    subtable += struct.pack(">H", seg_count * 2 - search_range)
+    subtable += struct.pack(f">{seg_count}H", *ends) + struct.pack(">H", 0)
+    subtable += struct.pack(f">{seg_count}H", *starts)
+    subtable += struct.pack(f">{seg_count}H", *deltas)
+    subtable += struct.pack(f">{seg_count}H", *([0] * seg_count))
+    cmap = struct.pack(">HHHHI", 0, 1, 3, 1, 12) + subtable
+
+    names = [(1, "Fixture Sans"), (2, "Regular"), (4, "Fixture Sans Regular"), (6, "FixtureSans-Regular")]
+    strings = b""
+    records = b""
+    for name_id, text in names:
+        encoded = text.encode("utf-16-be")
+        records += struct.pack(">HHHHHH", 3, 1, 0x409, name_id, len(encoded), len(strings))
+        strings += encoded
+    name = struct.pack(">HHH", 0, len(names), 6 + 12 * len(names)) + records + strings
+
+    post = struct.pack(">IihhIIIII", 0x00030000, 0, -100, 50, 0, 0, 0, 0, 0)
+
+    tables = sorted({
+        b"OS/2": os2, b"cmap": cmap, b"glyf": glyf, b"head": head, b"hhea": hhea,
+        b"hmtx": hmtx, b"loca": loca, b"maxp": maxp, b"name": name, b"post": post,
+    }.items())
+    num_tables = len(tables)
+    entry_selector = num_tables.bit_length() - 1
+    search_range = (1 << entry_selector) * 16
+    font = struct.pack(
+        ">IHHHH", 0x00010000, num_tables, search_range, entry_selector,
+        num_tables * 16 - search_range,
+    )
+    offset = 12 + 16 * num_tables
+    body = b""
+    head_offset = None
+    for tag, data in tables:
+        if tag == b"head":
+            head_offset = offset
+        font += struct.pack(">4sIII", tag, checksum(data), offset, len(data))
+        padded = data + b"\0" * (-len(data) % 4)
+        body += padded
+        offset += len(padded)
+    font += body
+    adjustment = (0xB1B0AFBA - checksum(font)) & 0xFFFFFFFF
+    return font[: head_offset + 8] + struct.pack(">I", adjustment) + font[head_offset + 12 :]
+
+
+if __name__ == "__main__":
+    Path(__file__).with_name("FixtureSans.ttf").write_bytes(build())
@@ -3,6 +3,7 @@ import { createStore } from 'solid-js/store';
 import { listen } from '@tauri-apps/api/event';
 import { platform } from '@tauri-apps/plugin-os';
 import { ClipboardListener } from './components/clipboard-listener';
+import { InterruptedSessionsPrompt } from './components/interrupted-sessions-prompt';
 import { useAppEvents } from './hooks/use-app-events';
 import { Toaster } from './components/ui/sonner';
 import { ChatPanel } from './components/chat';
@@ -42,6 +43,7 @@ function App() {
     <>
       <Toaster position='bottom-center' />
       <ClipboardListener />
+      <InterruptedSessionsPrompt />
       <div class='flex h-full min-h-0'>
         <Show when={isInterfaceVisible() && panelState.control}>
           <ControlPanel
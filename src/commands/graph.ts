@@ -1,7 +1,7 @@
 import { batch } from 'solid-js';
 import { GRAPH_MODE_CAPABILITIES } from '@/lib/graph-modes';
 import { setAppState, type GraphMode } from '@/store';
-import { type FontWeight } from '@/types/font';
+import { type FontWeight, type MetricFilter } from '@/types/font';
 
 export const setActiveGraphWeights = (weights: FontWeight[]) =>
   setAppState('ui', 'activeGraphWeights', weights);
@@ -18,3 +18,6 @@ export const setGraphMode = (mode: GraphMode) =>
 
 export const setVisibleGraphClusters = (clusterIds: number[]) =>
   setAppState('ui', 'visibleGraphClusters', clusterIds);
+
+export const setMetricFilter = (filter: MetricFilter | null) =>
+  setAppState('ui', 'metricFilter', filter);
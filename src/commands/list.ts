@@ -1,4 +1,8 @@
 import { setAppState } from '@/store';
+import { type FontMetricKey } from '@/types/font';
 
 export const setListPreviewText = (text: string) =>
   setAppState('ui', 'listPreviewText', text);
+
+export const setListSortMetric = (key: FontMetricKey | null) =>
+  setAppState('ui', 'listSortMetric', key);
@@ -6,9 +6,24 @@ import { appState, setAppState } from '@/store';
 import { type FontItemRecord } from '@/types/font';
 import {
   type AlgorithmConfig,
+  type ClusteringSweep,
+  type ClusteringSweepGrid,
   type DendrogramData,
+  type DroppedFont,
+  type EmbeddedFontReport,
+  type InterruptedSession,
+  type JobQueueSnapshot,
+  type GcReport,
+  type MigrationRun,
   type ProcessStatus,
+  type ResourceProfile,
+  type RunLogEntry,
+  type SessionComparison,
   type SessionConfig,
+  type SessionMetadataPatch,
+  type SessionSearchQuery,
+  type StoragePolicy,
+  type StorageReport,
 } from '@/types/session';
 
 export type ProcessingRunMode =
@@ -21,6 +36,10 @@ export interface RunProcessingOptions {
   sourceSessionId?: string;
   overrideStatus?: ProcessStatus;
   runMode?: ProcessingRunMode;
+  /** Queue priority; higher starts first. Defaults to 0. */
+  priority?: number;
+  /** Resource limits for this run only; defaults to the stored profile. */
+  resourceProfile?: ResourceProfile;
 }
 
 interface SessionPayload {
@@ -130,9 +149,101 @@ export const updateSessionTitle = async (
   }
 };
 
+/**
+ * Edits a session's tags, notes, flags, folder or title and mirrors the
+ * result into the active session's store slice when it is the one loaded.
+ */
+export const updateSessionMetadata = async (
+  sessionId: string,
+  patch: SessionMetadataPatch,
+) => {
+  const session = await invoke<SessionConfig>('update_session_metadata', {
+    sessionId,
+    patch,
+  });
+  if (appState.session.session_id === sessionId) {
+    batch(() => {
+      setAppState('session', 'title', session.title);
+      setAppState('session', 'tags', session.tags);
+      setAppState('session', 'notes', session.notes);
+      setAppState('session', 'pinned', session.pinned);
+      setAppState('session', 'favorite', session.favorite);
+      setAppState('session', 'folder', session.folder);
+    });
+  }
+  return session;
+};
+
+/** Searches the stored sessions, newest first. */
+export const searchSessions = async (query: SessionSearchQuery) =>
+  await invoke<SessionConfig[]>('search_sessions', { query });
+
+/**
+ * Copies a `.fontclusterdoc` at `path` into the session store and returns the
+ * imported session's config. The backend re-stamps the id when it is already
+ * taken, migrates documents from older releases and rejects ones written by a
+ * newer release.
+ */
+export const importSessionDocument = async (path: string) =>
+  await invoke<SessionConfig>('import_session_document', { path });
+
+/**
+ * Writes a session as a `.fontclusterdoc` at `path`. A `portable` export embeds
+ * subsets of the session's system fonts and resolves to a report of which
+ * fonts were embedded; a plain export resolves to `null`.
+ */
+export const exportSessionDocument = async (
+  sessionId: string,
+  path: string,
+  portable = false,
+) =>
+  await invoke<EmbeddedFontReport | null>('export_session_document', {
+    sessionId,
+    path,
+    portable,
+  });
+
+/** Reads the session migration history, oldest run first. */
+export const getMigrationReport = async () =>
+  await invoke<MigrationRun[]>('get_migration_report');
+
+/**
+ * Compares the clusterings of two sessions: partition agreement scores, the
+ * cluster contingency table and the fonts that moved most.
+ */
+export const compareSessions = async (sessionA: string, sessionB: string) =>
+  await invoke<SessionComparison>('compare_sessions', { sessionA, sessionB });
+
+/** Measures the disk usage of sessions, models, previews and sweeps. */
+export const getStorageReport = async () =>
+  await invoke<StorageReport>('get_storage_report');
+
+/** Reads the session retention policy. */
+export const getStoragePolicy = async () =>
+  await invoke<StoragePolicy>('get_storage_policy');
+
+/** Saves the session retention policy; rejects a `keep_latest` of 0. */
+export const setStoragePolicy = async (policy: StoragePolicy) =>
+  await invoke('set_storage_policy', { policy });
+
+/** Reads the worker resource profile. */
+export const getResourceProfile = async () =>
+  await invoke<ResourceProfile>('get_resource_profile');
+
+/** Saves the worker resource profile; rejects zero threads or memory. */
+export const setResourceProfile = async (profile: ResourceProfile) =>
+  await invoke('set_resource_profile', { profile });
+
+/**
+ * Deletes the sessions the retention policy no longer keeps. A dry run only
+ * reports which sessions would be deleted.
+ */
+export const runStorageGc = async (dryRun = false) =>
+  await invoke<GcReport>('run_storage_gc', { dryRun });
+
 /**
  * Submits an algorithm draft and explicit session-ownership mode to the
- * backend pipeline.
+ * backend job queue, resolving once the run has finished.
  *
  * The backend owns config invalidation and any required model installation;
  * this command only forwards the request and refreshes the active session after
@@ -148,6 +259,8 @@ export const runProcessingJobs = async (
     sourceSessionId,
     overrideStatus,
     runMode = 'in_place_changed',
+    priority,
+    resourceProfile,
   } = options;
   selectionHistory.reset();
 
@@ -157,6 +270,8 @@ export const runProcessingJobs = async (
     sourceSessionId,
     overrideStatus,
     runMode,
+    priority,
+    resourceProfile,
   });
   console.log('Complete pipeline result:', result);
   if (
@@ -168,6 +283,107 @@ export const runProcessingJobs = async (
   }
 };
 
+/**
+ * Evaluates a grid of clustering variants on an analysed session without
+ * creating sessions, resolving to the stored sweep summary.
+ */
+export const runClusteringSweep = async (
+  sessionId: string,
+  grid: ClusteringSweepGrid,
+  priority?: number,
+) =>
+  await invoke<ClusteringSweep>('run_sweep', { sessionId, grid, priority });
+
+/** Lists the sweeps run on a session, newest first. */
+export const listSessionSweeps = async (sessionId: string) =>
+  await invoke<ClusteringSweep[]>('list_session_sweeps', { sessionId });
+
+/**
+ * Promotes one sweep variant to a real session: a duplicating run of the swept
+ * session with the variant's clustering config, which reuses its analysed
+ * vectors and only re-clusters.
+ */
+export const promoteSweepVariant = async (
+  sweep: ClusteringSweep,
+  variantIndex: number,
+) => {
+  const variant = sweep.variants[variantIndex];
+  if (!variant) throw new Error(`Sweep has no variant ${variantIndex}`);
+  await runProcessingJobs(
+    { clustering: variant.config },
+    { sourceSessionId: sweep.session_id, runMode: 'duplicate_changed' },
+  );
+};
+
+/**
+ * Lists the sessions whose job was killed, cancelled or failed before
+ * clustering completed.
+ */
+export const getInterruptedSessions = async () =>
+  await invoke<InterruptedSession[]>('get_interrupted_sessions');
+
+/**
+ * Resumes an interrupted session from its persisted status, reusing the fonts
+ * it already rendered or analysed, and refreshes it when it is the active one.
+ */
+export const resumeSession = async (sessionId: string) => {
+  selectionHistory.reset();
+  const result = await invoke<string>('resume_session', { sessionId });
+  console.log('Resumed pipeline result:', result);
+  if (sessionId === appState.session.session_id) {
+    await refreshSession();
+  }
+};
+
+/**
+ * Drops an interrupted session's partial work; a session that completed an
+ * earlier run falls back to that result, any other is deleted.
+ */
+export const discardInterruptedSession = async (sessionId: string) =>
+  await invoke('discard_interrupted_session', { sessionId });
+
+/**
+ * Reads a session's run log, oldest first: the stage timings, dropped fonts
+ * and model of every run that processed it.
+ */
+export const getRunLog = async (sessionId: string) =>
+  await invoke<RunLogEntry[]>('get_run_log', { sessionId });
+
+/** Lists the fonts a session's pipeline excluded and why. */
+export const getDroppedFonts = async (sessionId: string) =>
+  await invoke<DroppedFont[]>('get_dropped_fonts', { sessionId });
+
+/** Reads the waiting and running jobs and the concurrency limit. */
+export const getJobQueue = async () =>
+  await invoke<JobQueueSnapshot>('get_job_queue');
+
+/** Changes a waiting job's priority; higher starts first. */
+export const setJobPriority = async (jobId: string, priority: number) =>
+  await invoke('set_job_priority', { jobId, priority });
+
+/** Reorders the waiting jobs; `jobIds` must list each exactly once. */
+export const reorderJobQueue = async (jobIds: string[]) =>
+  await invoke('reorder_job_queue', { jobIds });
+
+/** Drops a waiting job; resolves `false` if it already started. */
+export const removeQueuedJob = async (jobId: string) =>
+  await invoke<boolean>('remove_queued_job', { jobId });
+
+/** Sets how many jobs may run at once (1–16). */
+export const setJobConcurrency = async (maxConcurrent: number) =>
+  await invoke('set_job_concurrency', { maxConcurrent });
+
+/**
+ * Suspends the running job for `sessionId` (or every running job) at its next
+ * safe checkpoint; the worker then emits `job_paused`.
+ */
+export const pauseJobs = async (sessionId?: string) =>
+  await invoke('pause_jobs', { sessionId });
+
+/** Continues paused jobs; the worker then emits `job_resumed`. */
+export const resumeJobs = async (sessionId?: string) =>
+  await invoke('resume_jobs', { sessionId });
+
 export const stopJobs = async (sessionId?: string) => {
   try {
     await invoke('stop_jobs', { sessionId });
@@ -34,6 +34,7 @@ import {
 import { appState } from '@/store';
 import { runProcessingJobs, type ProcessingRunMode } from '@/commands/session';
 import { useI18n } from '@/i18n';
+import { getErrorMessage } from '@/lib/utils';
 import {
   DEFAULT_CLUSTERING_CONFIG,
   DEFAULT_RENDERING_CONFIG,
@@ -353,7 +354,7 @@ export function ControlContent() {
       });
     } catch (error) {
       console.error('Failed to process fonts:', error);
-      toast.error(t.jobs.toasts.failed({ error: String(error) }));
+      toast.error(t.jobs.toasts.failed({ error: getErrorMessage(error) }));
     }
   };
 
@@ -21,6 +21,7 @@ import {
 } from '@/components/ui/select';
 import { useI18n } from '@/i18n';
 import { listModels } from '@/lib/models';
+import { getErrorMessage } from '@/lib/utils';
 import type { ModelCatalogEntry } from '@/types/model';
 import { SelectProperty } from './select-property';
 
@@ -107,7 +108,7 @@ export function ModelProperty(props: ModelPropertyProps) {
    */
   const catalogWarning = createMemo(() => {
     const error = catalog.error;
-    if (error) return error instanceof Error ? error.message : String(error);
+    if (error) return getErrorMessage(error);
     return catalog()?.warning ?? null;
   });
 
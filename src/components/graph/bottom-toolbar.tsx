@@ -64,7 +64,7 @@ export function GraphBottomToolbar(props: GraphBottomToolbarProps) {
   const { t } = useI18n();
   // The filter dot lights up whenever the graph shows less than everything:
   // a search query, a weight subset (any session weight not currently active),
-  // or a cluster narrowed down. Checking "some weight is excluded" avoids the
+  // a cluster narrowed down, or a metric range. Checking "some weight is excluded" avoids the
   // false positive the old length compare hit while a session was still loading.
   const isFilterActive = createMemo(() => {
     const activeWeights = new Set(appState.ui.activeGraphWeights);
@@ -72,7 +72,8 @@ export function GraphBottomToolbar(props: GraphBottomToolbarProps) {
     return (
       appState.ui.searchQuery.length > 0 ||
       sessionWeights.some((weight) => !activeWeights.has(weight)) ||
-      appState.ui.visibleGraphClusters.length > 0
+      appState.ui.visibleGraphClusters.length > 0 ||
+      appState.ui.metricFilter !== null
     );
   });
   const graphModeLabel = () => {
@@ -10,6 +10,10 @@ import { useFilteredFontMetadataKeys } from '@/hooks/use-filtered-font-metadata-
 import { useDismiss } from '@/hooks/use-dismiss';
 import { WeightSelector } from '@/components/weight-selector';
 import { ClusterSelector } from '@/components/cluster-selector';
+import {
+  METRIC_OPTIONS_SELECTOR,
+  MetricFilterSelector,
+} from '@/components/metric-filter-selector';
 
 /** How many of the largest clusters get a visibility toggle, for now. */
 const MAX_CLUSTER_TOGGLES = 9;
@@ -48,7 +52,7 @@ export function GraphFilterDock(props: GraphFilterDockProps) {
   const dismissRef = useDismiss({
     enabled: () => props.isOpen,
     onDismiss: () => props.onClose(),
-    ignoreSelector: '[data-filter-toggle]',
+    ignoreSelector: `[data-filter-toggle], ${METRIC_OPTIONS_SELECTOR}`,
   });
 
   const updateQuery = (value: string) => {
@@ -132,6 +136,9 @@ export function GraphFilterDock(props: GraphFilterDockProps) {
             onChange={setVisibleGraphClusters}
           />
         </Show>
+
+        <div class='mx-0.5 h-5 w-px bg-border/60' />
+        <MetricFilterSelector />
       </div>
     </div>
   );
@@ -1,5 +1,11 @@
 import { createSignal, Show } from 'solid-js';
-import { HistoryIcon, PauseIcon, PlayIcon, Trash2Icon } from 'lucide-solid';
+import {
+  HistoryIcon,
+  PauseIcon,
+  PlayIcon,
+  SquareIcon,
+  Trash2Icon,
+} from 'lucide-solid';
 import { Button } from '@/components/ui/button';
 import { TextField, TextFieldInput } from '@/components/ui/text-field';
 import { cn } from '@/lib/utils';
@@ -24,6 +30,8 @@ interface SessionHistoryItemProps {
   onContinueProcessing: () => void;
   onSelectSession: () => void;
   onStopRun: () => void;
+  onPauseRun: () => void;
+  onResumeRun: () => void;
   onRename: (newTitle: string) => void;
 }
 
@@ -31,6 +39,8 @@ export function SessionHistoryItem(props: SessionHistoryItemProps) {
   const { t } = useI18n();
   const session = () => props.session;
   const isRunning = () => props.isRunning;
+  /** The worker records the pause in the config it keeps rewriting. */
+  const isPaused = () => isRunning() && !!session().status.paused;
 
   const isComplete = () => session()?.status.process_status === 'clustered';
 
@@ -56,6 +66,20 @@ export function SessionHistoryItem(props: SessionHistoryItemProps) {
     if (newTitle !== session().title) props.onRename(newTitle);
   };
 
+  const statusLabel = () => {
+    const labels = t.graph.utilityControls.sessionHistory;
+    if (isPaused()) return labels.statusPaused();
+    if (!isRunning()) return labels.statusStopped();
+    switch (session().status.process_status) {
+      case 'empty':
+        return labels.statusRendering();
+      case 'rendered':
+        return labels.statusAnalyzing();
+      default:
+        return labels.statusClustering();
+    }
+  };
+
   const canRestore = () =>
     isComplete() && !isRunning() && !!session()?.session_id;
 
@@ -82,13 +106,7 @@ export function SessionHistoryItem(props: SessionHistoryItemProps) {
           <div class='flex min-w-0 items-center gap-2'>
             <Show when={!isComplete()}>
               <span class='font-bold capitalize text-muted-foreground'>
-                {isRunning()
-                  ? session().status.process_status === 'empty'
-                    ? t.graph.utilityControls.sessionHistory.statusRendering()
-                    : session().status.process_status === 'rendered'
-                      ? t.graph.utilityControls.sessionHistory.statusAnalyzing()
-                      : t.graph.utilityControls.sessionHistory.statusClustering()
-                  : t.graph.utilityControls.sessionHistory.statusStopped()}
+                {statusLabel()}
               </span>
             </Show>
             <time class='truncate text-muted-foreground'>
@@ -152,6 +170,40 @@ export function SessionHistoryItem(props: SessionHistoryItemProps) {
         </div>
         <div class='flex shrink-0 items-center'>
           <Show when={isRunning() && !isComplete()}>
+            <Show
+              when={isPaused()}
+              fallback={
+                <Tooltip>
+                  <TooltipTrigger
+                    as={Button<'button'>}
+                    size='icon'
+                    variant='ghost'
+                    class='size-7 rounded-full'
+                    onClick={props.onPauseRun}
+                  >
+                    <PauseIcon class='size-3' />
+                  </TooltipTrigger>
+                  <TooltipContent>
+                    {t.graph.utilityControls.sessionHistory.pause()}
+                  </TooltipContent>
+                </Tooltip>
+              }
+            >
+              <Tooltip>
+                <TooltipTrigger
+                  as={Button<'button'>}
+                  size='icon'
+                  variant='ghost'
+                  class='size-7 rounded-full'
+                  onClick={props.onResumeRun}
+                >
+                  <PlayIcon class='size-3.5' />
+                </TooltipTrigger>
+                <TooltipContent>
+                  {t.graph.utilityControls.sessionHistory.resume()}
+                </TooltipContent>
+              </Tooltip>
+            </Show>
             <Tooltip>
               <TooltipTrigger
                 as={Button<'button'>}
@@ -160,7 +212,7 @@ export function SessionHistoryItem(props: SessionHistoryItemProps) {
                 class='size-7 rounded-full'
                 onClick={props.onStopRun}
               >
-                <PauseIcon class='size-3' />
+                <SquareIcon class='size-3' />
               </TooltipTrigger>
               <TooltipContent>
                 {t.graph.utilityControls.sessionHistory.stop()}
@@ -223,7 +275,10 @@ export function SessionHistoryItem(props: SessionHistoryItemProps) {
       <Show when={!isComplete()}>
         <div class='flex flex-col gap-1.5 pt-2'>
           <div
-            class='h-1 w-full animate-pulse overflow-hidden rounded-full bg-primary/25'
+            class={cn(
+              'h-1 w-full overflow-hidden rounded-full bg-primary/25',
+              !isPaused() && 'animate-pulse',
+            )}
             style={{ 'animation-duration': '2000ms' }}
           >
             <div
@@ -233,9 +288,11 @@ export function SessionHistoryItem(props: SessionHistoryItemProps) {
           </div>
           <div class='flex justify-between gap-2 text-muted-foreground'>
             <p class='truncate'>
-              {isRunning()
-                ? t.graph.utilityControls.sessionHistory.processing()
-                : t.graph.utilityControls.sessionHistory.progress()}
+              {isPaused()
+                ? t.graph.utilityControls.sessionHistory.paused()
+                : isRunning()
+                  ? t.graph.utilityControls.sessionHistory.processing()
+                  : t.graph.utilityControls.sessionHistory.progress()}
             </p>
             <p class='shrink-0 tabular-nums'>
               {Math.round(progressValue() * 100)}%
@@ -243,7 +300,10 @@ export function SessionHistoryItem(props: SessionHistoryItemProps) {
           </div>
         </div>
       </Show>
-      <Show when={isRunning()}>
+      <Show when={isPaused()}>
+        <span class='pointer-events-none absolute right-2 top-2 size-1.5 rounded-full bg-amber-500' />
+      </Show>
+      <Show when={isRunning() && !isPaused()}>
         <span class='pointer-events-none absolute right-2 top-2 size-1.5 rounded-full bg-amber-500 after:absolute after:inset-0 after:animate-ping after:rounded-full after:bg-amber-500 after:content-[""]' />
       </Show>
       <Show when={!isRunning() && props.isUnread}>
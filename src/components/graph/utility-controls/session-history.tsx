@@ -12,7 +12,7 @@ import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import { toast } from 'solid-sonner';
 import { Button } from '@/components/ui/button';
-import { cn } from '@/lib/utils';
+import { cn, getErrorMessage } from '@/lib/utils';
 import { useI18n } from '@/i18n';
 import {
   DropdownMenu,
@@ -22,6 +22,8 @@ import {
 } from '@/components/ui/dropdown-menu';
 import { appState } from '@/store';
 import {
+  pauseJobs,
+  resumeJobs,
   runProcessingJobs,
   setCurrentSessionId,
   stopJobs,
@@ -99,6 +101,7 @@ export function SessionHistory(props: SessionHistoryProps) {
       (a, b) =>
         Number(runningIds.has(b.session_id)) -
           Number(runningIds.has(a.session_id)) ||
+        Number(Boolean(b.pinned)) - Number(Boolean(a.pinned)) ||
         new Date(b.modified_at).getTime() - new Date(a.modified_at).getTime(),
     );
   });
@@ -160,6 +163,14 @@ export function SessionHistory(props: SessionHistoryProps) {
     refetchSessions();
   });
 
+  registerListener<{ sessionId: string | null }>('job_paused', () => {
+    refetchSessions();
+  });
+
+  registerListener<{ sessionId: string | null }>('job_resumed', () => {
+    refetchSessions();
+  });
+
   createEffect(() => {
     if (!open() || runningSessionIds().size === 0) {
       return;
@@ -257,7 +268,7 @@ export function SessionHistory(props: SessionHistoryProps) {
       sessionId: session.session_id,
     }).catch((error) => {
       console.error('Failed to process fonts:', error);
-      toast.error(t.jobs.toasts.failed({ error: String(error) }));
+      toast.error(t.jobs.toasts.failed({ error: getErrorMessage(error) }));
     });
   };
 
@@ -266,6 +277,15 @@ export function SessionHistory(props: SessionHistoryProps) {
     await refetchSessions();
   };
 
+  const setCurrentRunPaused = async (sessionId: string, isPaused: boolean) => {
+    try {
+      await (isPaused ? pauseJobs(sessionId) : resumeJobs(sessionId));
+    } catch (error) {
+      console.error('Failed to pause or resume jobs:', error);
+    }
+    await refetchSessions();
+  };
+
   const deleteSession = async (sessionId: string) => {
     if (committedDeletes.has(sessionId)) return;
     committedDeletes.add(sessionId);
@@ -414,6 +434,12 @@ export function SessionHistory(props: SessionHistoryProps) {
                       selectSession(session.session_id);
                     }}
                     onStopRun={() => stopCurrentRun(session.session_id)}
+                    onPauseRun={() =>
+                      setCurrentRunPaused(session.session_id, true)
+                    }
+                    onResumeRun={() =>
+                      setCurrentRunPaused(session.session_id, false)
+                    }
                     onRename={(newTitle) =>
                       renameSession(session.session_id, newTitle)
                     }
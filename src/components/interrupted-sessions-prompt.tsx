@@ -0,0 +1,158 @@
+import { createSignal, For, onCleanup, onMount } from 'solid-js';
+import { listen } from '@tauri-apps/api/event';
+import { PauseIcon } from 'lucide-solid';
+import { toast } from 'solid-sonner';
+
+import { Button } from '@/components/ui/button';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog';
+import {
+  discardInterruptedSession,
+  getInterruptedSessions,
+  resumeSession,
+} from '@/commands/session';
+import { useI18n } from '@/i18n';
+import type { InterruptedSession } from '@/types/session';
+
+/**
+ * Offers to resume or discard the sessions whose job stopped before
+ * clustering completed.
+ *
+ * The backend emits `interrupted_sessions_found` once the page has loaded;
+ * the list is also fetched on mount in case that event fired before the
+ * listener was registered. Closing the dialog leaves the sessions as they
+ * are until the next launch.
+ */
+export function InterruptedSessionsPrompt() {
+  const { t } = useI18n();
+  const [sessions, setSessions] = createSignal<InterruptedSession[]>([]);
+  const [isOpen, setIsOpen] = createSignal(false);
+  const [isDismissed, setIsDismissed] = createSignal(false);
+
+  const offer = (interrupted: InterruptedSession[]) => {
+    if (isDismissed()) return;
+    setSessions(interrupted);
+    setIsOpen(interrupted.length > 0);
+  };
+
+  const remove = (sessionId: string) => {
+    const remaining = sessions().filter(
+      (session) => session.session_id !== sessionId,
+    );
+    setSessions(remaining);
+    if (remaining.length === 0) setIsOpen(false);
+  };
+
+  const dismiss = () => {
+    setIsDismissed(true);
+    setIsOpen(false);
+  };
+
+  const resume = (session: InterruptedSession) => {
+    remove(session.session_id);
+    resumeSession(session.session_id).catch((error) => {
+      toast.error(t.jobs.interrupted.resumeFailed({ title: session.title }), {
+        description: error instanceof Error ? error.message : String(error),
+      });
+    });
+  };
+
+  const discard = async (session: InterruptedSession) => {
+    try {
+      await discardInterruptedSession(session.session_id);
+      remove(session.session_id);
+    } catch (error) {
+      toast.error(t.jobs.interrupted.discardFailed({ title: session.title }), {
+        description: error instanceof Error ? error.message : String(error),
+      });
+    }
+  };
+
+  onMount(() => {
+    const unlistenPromise = listen<InterruptedSession[]>(
+      'interrupted_sessions_found',
+      (event) => offer(event.payload),
+    );
+    onCleanup(() => void unlistenPromise.then((unlisten) => unlisten()));
+
+    getInterruptedSessions()
+      .then(offer)
+      .catch((error) => {
+        console.error('Failed to list interrupted sessions:', error);
+      });
+  });
+
+  return (
+    <Dialog
+      open={isOpen()}
+      onOpenChange={(open) => {
+        if (!open) dismiss();
+      }}
+    >
+      <DialogContent class='max-w-md'>
+        <DialogHeader class='flex-row items-center gap-4 space-y-0 text-left'>
+          <PauseIcon class='size-5 shrink-0' />
+          <div class='flex flex-col gap-2'>
+            <DialogTitle>{t.jobs.interrupted.title()}</DialogTitle>
+            <DialogDescription>
+              {t.jobs.interrupted.description()}
+            </DialogDescription>
+          </div>
+        </DialogHeader>
+
+        <ul class='flex flex-col gap-2'>
+          <For each={sessions()}>
+            {(session) => (
+              <li class='flex items-center gap-2 rounded-md border p-2'>
+                <div class='flex min-w-0 flex-1 flex-col'>
+                  <span class='truncate text-sm font-medium'>
+                    {session.title}
+                  </span>
+                  <span class='text-xs text-muted-foreground'>
+                    {t.jobs.interrupted.progress({
+                      rendered: String(session.rendered_fonts),
+                      analyzed: String(session.analyzed_fonts),
+                      discovered: String(session.discovered_fonts),
+                    })}
+                  </span>
+                </div>
+                <Button
+                  type='button'
+                  variant='ghost'
+                  size='sm'
+                  onClick={() => void discard(session)}
+                  title={
+                    session.has_document
+                      ? t.jobs.interrupted.discardToDocument()
+                      : t.jobs.interrupted.discardDeletes()
+                  }
+                >
+                  {t.jobs.interrupted.discard()}
+                </Button>
+                <Button
+                  type='button'
+                  size='sm'
+                  onClick={() => resume(session)}
+                >
+                  {t.jobs.interrupted.resume()}
+                </Button>
+              </li>
+            )}
+          </For>
+        </ul>
+
+        <DialogFooter>
+          <Button type='button' variant='outline' size='sm' onClick={dismiss}>
+            {t.jobs.interrupted.later()}
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
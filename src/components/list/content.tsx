@@ -21,6 +21,7 @@ import {
 } from '@/commands/font-selection';
 import { setListPreviewText } from '@/commands/list';
 import { applyFontToPlugins } from '@/commands/plugins';
+import { fontMetricValue } from '@/lib/font-metrics';
 import { type FontItem } from '@/types/font';
 import { ListFontItem } from './list-font-item';
 import { ListPreviewTextField } from './preview-text-field';
@@ -57,9 +58,20 @@ export function ListContent() {
   );
   const filteredLeafItems = createMemo(() => {
     const filteredKeys = appState.fonts.filteredKeys;
-    return orderedLeafItems().filter((item) =>
+    const items = orderedLeafItems().filter((item) =>
       filteredKeys.has(item.meta.safe_name),
     );
+    const sortMetric = appState.ui.listSortMetric;
+    if (!sortMetric) return items;
+    // Stable, ascending, fonts lacking the metric last; ties keep leaf order.
+    return items.toSorted((left, right) => {
+      const leftValue = fontMetricValue(left.meta, sortMetric);
+      const rightValue = fontMetricValue(right.meta, sortMetric);
+      if (leftValue === null || rightValue === null) {
+        return Number(leftValue === null) - Number(rightValue === null);
+      }
+      return leftValue - rightValue;
+    });
   });
   const circularBufferItemCount = createMemo(() => {
     const itemCount = filteredLeafItems().length;
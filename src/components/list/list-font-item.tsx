@@ -37,10 +37,17 @@ export function ListFontItem(props: ListFontItemProps) {
   const [previewPath] = createResource(
     () => {
       if (!shouldRenderPreview()) return null;
+      // Shape the preview the way the session's samples were rendered.
+      const rendering = appState.session.algorithm.rendering;
       return {
         font: meta(),
         text: props.previewText,
         font_size: props.previewFontSize,
+        direction: rendering.direction,
+        features: rendering.features,
+        language: rendering.language,
+        canvas: rendering.canvas,
+        session_id: appState.session.session_id,
       };
     },
     async (payload) => {
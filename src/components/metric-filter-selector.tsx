@@ -0,0 +1,126 @@
+import { Show } from 'solid-js';
+import { ArrowDownNarrowWideIcon, XIcon } from 'lucide-solid';
+import { useI18n } from '@/i18n';
+import { appState } from '@/store';
+import { setMetricFilter } from '@/commands/graph';
+import { setListSortMetric } from '@/commands/list';
+import { FONT_METRIC_KEYS } from '@/lib/font-metrics';
+import { cn } from '@/lib/utils';
+import { type FontMetricKey } from '@/types/font';
+import {
+  Select,
+  SelectContent,
+  SelectHiddenSelect,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from './ui/select';
+
+/** Marks the portalled option list so the filter dock doesn't treat picking
+ *  an option as a press outside. */
+export const METRIC_OPTIONS_SELECTOR = '[data-metric-options]';
+
+/** An empty field is an open bound. */
+const parseBound = (text: string) => {
+  const value = Number.parseFloat(text);
+  return Number.isFinite(value) ? value : null;
+};
+
+/**
+ * Picks a typographic metric to filter fonts by (an inclusive min/max range)
+ * and, optionally, to order the list by.
+ */
+export function MetricFilterSelector() {
+  const { t } = useI18n();
+  const metricLabel = (key: FontMetricKey) =>
+    t.graph.filterDock.metrics[key]();
+  const filter = () => appState.ui.metricFilter;
+
+  const selectMetric = (key: FontMetricKey | null) => {
+    setMetricFilter(key ? { key, min: null, max: null } : null);
+    // Keep sorting by whichever metric is selected.
+    if (appState.ui.listSortMetric !== null) setListSortMetric(key);
+  };
+
+  const setBound = (bound: 'min' | 'max', text: string) => {
+    const current = filter();
+    if (current) setMetricFilter({ ...current, [bound]: parseBound(text) });
+  };
+
+  return (
+    <div class='flex items-center gap-0.5'>
+      <Select<FontMetricKey>
+        name='filter-metric'
+        options={[...FONT_METRIC_KEYS]}
+        value={filter()?.key ?? null}
+        onChange={selectMetric}
+        placeholder={t.graph.filterDock.metric()}
+        itemComponent={(props) => (
+          <SelectItem item={props.item}>
+            {metricLabel(props.item.rawValue)}
+          </SelectItem>
+        )}
+      >
+        <SelectHiddenSelect />
+        <SelectTrigger
+          aria-label={t.graph.filterDock.metric()}
+          class='h-8 w-auto gap-1 rounded-full border-0 px-3 text-xs font-medium shadow-none hover:bg-accent focus:ring-0 focus:ring-offset-0'
+        >
+          <SelectValue<FontMetricKey>>
+            {(state) => metricLabel(state.selectedOption())}
+          </SelectValue>
+        </SelectTrigger>
+        <SelectContent data-metric-options />
+      </Select>
+
+      <Show when={filter()?.key} keyed>
+        {(key) => (
+          <>
+            <input
+              type='number'
+              step='any'
+              aria-label={t.graph.filterDock.minimum()}
+              placeholder={t.graph.filterDock.minimum()}
+              class='h-8 w-14 bg-transparent px-1 text-center text-xs outline-none placeholder:text-muted-foreground'
+              onInput={(event) => setBound('min', event.currentTarget.value)}
+            />
+            <span class='text-xs text-muted-foreground'>–</span>
+            <input
+              type='number'
+              step='any'
+              aria-label={t.graph.filterDock.maximum()}
+              placeholder={t.graph.filterDock.maximum()}
+              class='h-8 w-14 bg-transparent px-1 text-center text-xs outline-none placeholder:text-muted-foreground'
+              onInput={(event) => setBound('max', event.currentTarget.value)}
+            />
+            <button
+              type='button'
+              aria-label={t.graph.filterDock.sortList()}
+              aria-pressed={appState.ui.listSortMetric === key}
+              onClick={() =>
+                setListSortMetric(
+                  appState.ui.listSortMetric === key ? null : key,
+                )
+              }
+              class={cn(
+                'flex size-8 items-center justify-center rounded-full text-muted-foreground transition-colors hover:bg-accent hover:text-foreground',
+                appState.ui.listSortMetric === key &&
+                  'bg-accent text-foreground',
+              )}
+            >
+              <ArrowDownNarrowWideIcon class='size-4' />
+            </button>
+            <button
+              type='button'
+              aria-label={t.graph.filterDock.clearMetric()}
+              onClick={() => selectMetric(null)}
+              class='flex size-8 items-center justify-center rounded-full text-muted-foreground transition-colors hover:bg-accent hover:text-foreground'
+            >
+              <XIcon class='size-4' />
+            </button>
+          </>
+        )}
+      </Show>
+    </div>
+  );
+}
@@ -150,6 +150,19 @@ export const en = {
     filterDock: {
       clear: 'Clear',
       searchPlaceholder: 'Font name, Designer, Foundry, etc.',
+      metric: 'Metric',
+      metrics: {
+        x_height: 'x-height',
+        cap_height: 'Cap height',
+        width: 'Width',
+        weight: 'Weight',
+        contrast: 'Contrast',
+        italic_angle: 'Italic angle',
+      },
+      minimum: 'Min',
+      maximum: 'Max',
+      sortList: 'Sort list by metric',
+      clearMetric: 'Clear metric filter',
     },
     emptyState: {
       title: 'No Results',
@@ -189,6 +202,8 @@ export const en = {
         empty: 'No sessions yet.',
         loading: 'Loading history...',
         stop: 'Stop',
+        pause: 'Pause',
+        resume: 'Continue',
         restore: 'Open',
         continueProcessing: 'Resume',
         delete: 'Delete',
@@ -199,7 +214,9 @@ export const en = {
         statusAnalyzing: 'Analyzing',
         statusClustering: 'Clustering',
         statusStopped: 'Stopped',
+        statusPaused: 'Paused',
         processing: 'Processing',
+        paused: 'Paused at a safe point',
         progress: 'Progress',
         summary:
           '{{weights}} weights · {{samples}} samples · {{clusters}} clusters',
@@ -261,6 +278,20 @@ export const en = {
       view: 'View',
       failed: 'Job failed: {{error}}',
     },
+    interrupted: {
+      title: 'Resume interrupted sessions?',
+      description:
+        'These sessions stopped before clustering finished. Resuming reuses the fonts they already processed.',
+      progress:
+        '{{discovered}} fonts · {{rendered}} rendered · {{analyzed}} analyzed',
+      resume: 'Resume',
+      discard: 'Discard',
+      discardToDocument: 'Discard the partial work and keep the last result',
+      discardDeletes: 'Discard the partial work and delete the session',
+      later: 'Later',
+      resumeFailed: 'Failed to resume "{{title}}"',
+      discardFailed: 'Failed to discard "{{title}}"',
+    },
   },
   updater: {
     toasts: {
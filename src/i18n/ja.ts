@@ -138,6 +138,19 @@ export const ja: Dictionary = {
     filterDock: {
       clear: '消去',
       searchPlaceholder: 'フォント、デザイナー名など',
+      metric: '指標',
+      metrics: {
+        x_height: 'xハイト',
+        cap_height: 'キャップハイト',
+        width: '字幅',
+        weight: 'ウェイト',
+        contrast: 'コントラスト',
+        italic_angle: 'イタリック角',
+      },
+      minimum: '最小',
+      maximum: '最大',
+      sortList: '指標でリストを並べ替え',
+      clearMetric: '指標フィルタを解除',
     },
     emptyState: {
       title: 'データがありません',
@@ -177,6 +190,8 @@ export const ja: Dictionary = {
         empty: '履歴がありません',
         loading: '履歴を読み込んでいます...',
         stop: '中止',
+        pause: '一時停止',
+        resume: '続行',
         restore: '開く',
         continueProcessing: '再開',
         delete: '削除',
@@ -187,7 +202,9 @@ export const ja: Dictionary = {
         statusAnalyzing: '解析中',
         statusClustering: 'クラスタリング中',
         statusStopped: '中断',
+        statusPaused: '一時停止中',
         processing: '処理中',
+        paused: '区切りのよいところで一時停止しています',
         progress: '進捗',
         summary:
           'ウェイト {{weights}} 種・サンプル {{samples}} 個・クラスタ {{clusters}} 個',
@@ -250,6 +267,20 @@ export const ja: Dictionary = {
       view: '表示',
       failed: 'ジョブが失敗しました: {{error}}',
     },
+    interrupted: {
+      title: '中断されたセッションを再開しますか？',
+      description:
+        'これらのセッションはクラスタリングの完了前に停止しました。再開すると処理済みのフォントを再利用します。',
+      progress:
+        '{{discovered}} フォント · レンダリング済み {{rendered}} · 解析済み {{analyzed}}',
+      resume: '再開',
+      discard: '破棄',
+      discardToDocument: '途中の処理を破棄して前回の結果に戻します',
+      discardDeletes: '途中の処理を破棄してセッションを削除します',
+      later: 'あとで',
+      resumeFailed: '「{{title}}」を再開できませんでした',
+      discardFailed: '「{{title}}」を破棄できませんでした',
+    },
   },
   updater: {
     toasts: {
@@ -0,0 +1,53 @@
+import {
+  type FontMetadata,
+  type FontMetricKey,
+  type MetricFilter,
+} from '@/types/font';
+
+export const FONT_METRIC_KEYS: readonly FontMetricKey[] = [
+  'x_height',
+  'cap_height',
+  'width',
+  'weight',
+  'contrast',
+  'italic_angle',
+];
+
+/**
+ * Reads one metric from a font's metadata, mirroring `FontMetricKey::value`
+ * in the backend. Null when the face doesn't declare it (or was discovered
+ * before metrics were extracted).
+ */
+export const fontMetricValue = (
+  meta: FontMetadata,
+  key: FontMetricKey,
+): number | null => {
+  if (key === 'weight') return meta.weight;
+  const metrics = meta.metrics;
+  if (!metrics) return null;
+  switch (key) {
+    case 'x_height':
+      return metrics.x_height_ratio;
+    case 'cap_height':
+      return metrics.cap_height_ratio;
+    case 'width':
+      return metrics.average_advance_ratio;
+    case 'contrast':
+      return metrics.stroke_contrast;
+    case 'italic_angle':
+      return metrics.italic_angle;
+  }
+};
+
+/** Fonts lacking the filtered metric never match a bounded range. */
+export const matchesMetricFilter = (
+  meta: FontMetadata,
+  filter: MetricFilter,
+): boolean => {
+  if (filter.min === null && filter.max === null) return true;
+  const value = fontMetricValue(meta, filter.key);
+  if (value === null) return false;
+  if (filter.min !== null && value < filter.min) return false;
+  if (filter.max !== null && value > filter.max) return false;
+  return true;
+};
@@ -1,5 +1,12 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { ModelCatalogResponse } from '@/types/model';
+import type {
+  ModelCatalogEntry,
+  ModelCatalogResponse,
+  ModelDeletion,
+  ModelSource,
+  ModelStorageSummary,
+  ModelVerification,
+} from '@/types/model';
 
 /**
  * Reads the backend-owned model catalog and local installation status.
@@ -9,3 +16,32 @@ import type { ModelCatalogResponse } from '@/types/model';
  * Solid resources decide when to refetch, and processing jobs own downloads.
  */
 export const listModels = () => invoke<ModelCatalogResponse>('list_models');
+
+/**
+ * Imports a model bundle from a local directory or zip archive. The model is
+ * installed as `local:<id>` and its catalog entry returned.
+ */
+export const importLocalModel = (path: string) =>
+  invoke<ModelCatalogEntry>('import_local_model', { path });
+
+/** Reads where published models are listed and downloaded from. */
+export const getModelSource = () => invoke<ModelSource>('get_model_source');
+
+/** Saves the model source; the next `listModels` call lists its releases. */
+export const setModelSource = (source: ModelSource) =>
+  invoke<void>('set_model_source', { source });
+
+/** Re-hashes an installed model against the checksums of its manifest. */
+export const verifyModel = (modelId: string) =>
+  invoke<ModelVerification>('verify_model', { modelId });
+
+/**
+ * Deletes an installed model. Unless `force` is set, a model that sessions
+ * reference is kept and the referencing session ids are returned instead.
+ */
+export const deleteModel = (modelId: string, force = false) =>
+  invoke<ModelDeletion>('delete_model', { modelId, force });
+
+/** Measures the disk usage of every installed model. */
+export const getModelStorage = () =>
+  invoke<ModelStorageSummary>('get_model_storage');
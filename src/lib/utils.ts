@@ -16,3 +16,19 @@ export function cn(...inputs: ClassValue[]) {
 export function getSafeFontName(fontName: string): string {
   return fontName.replace(/\s/g, '_').replace(/\//g, '_');
 }
+
+/**
+ * Human-readable message of a rejected command: backend errors arrive as
+ * `{code, message, details}` objects, other failures as `Error`s or strings.
+ */
+export function getErrorMessage(error: unknown): string {
+  if (error instanceof Error) return error.message;
+  if (
+    typeof error === 'object' &&
+    error !== null &&
+    'message' in error &&
+    typeof error.message === 'string'
+  )
+    return error.message;
+  return String(error);
+}
@@ -3,13 +3,16 @@ import { createMemo, createRoot } from 'solid-js';
 import Fuse from 'fuse.js';
 import {
   type FontItem,
+  type FontMetricKey,
   type FontWeight,
   type FontItemRecord,
+  type MetricFilter,
 } from './types/font';
 import { type DendrogramData, type SessionConfig } from './types/session';
 import { DEFAULT_SESSION_CONFIG } from './constants/session';
 import type { PluginConnection } from './types/plugin';
 import { type GraphMode } from './types/graph';
+import { matchesMetricFilter } from './lib/font-metrics';
 
 export type { GraphMode } from './types/graph';
 
@@ -41,6 +44,10 @@ export interface AppState {
     listPreviewText: string;
     activeGraphWeights: FontWeight[];
     visibleGraphClusters: number[];
+    /** Range on one typographic metric that fonts must fall in to be shown. */
+    metricFilter: MetricFilter | null;
+    /** Orders the list by this metric instead of dendrogram leaf order. */
+    listSortMetric: FontMetricKey | null;
     /** Active graph layout. Lives in the store because graph layout modules
      *  derive their positions and visibility from the same mode. */
     graphMode: GraphMode;
@@ -124,6 +131,8 @@ export const [appState, setAppState] = createStore<AppState>({
     listPreviewText: '',
     activeGraphWeights: [400],
     visibleGraphClusters: [],
+    metricFilter: null,
+    listSortMetric: null,
     graphMode: 'radial-tree',
     selectedDendrogramNode: null,
   },
@@ -149,6 +158,7 @@ export const filteredKeysMemo = createRoot(() => {
     const data = appState.fonts.displayData;
     const activeWeights = new Set(appState.ui.activeGraphWeights);
     const visibleClusters = new Set(appState.ui.visibleGraphClusters);
+    const metricFilter = appState.ui.metricFilter;
     const keys = Object.keys(data);
     if (keys.length === 0) return new Set<string>();
     if (activeWeights.size === 0) return new Set<string>();
@@ -172,6 +182,9 @@ export const filteredKeysMemo = createRoot(() => {
             return false;
           }
         }
+        if (metricFilter && !matchesMetricFilter(item.meta, metricFilter)) {
+          return false;
+        }
         return true;
       }),
     );
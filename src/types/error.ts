@@ -0,0 +1,40 @@
+import type { RunStage } from './session';
+
+/** Stable, machine-readable kind of a backend error. */
+export type ErrorCode =
+  | 'io'
+  | 'font'
+  | 'missing_glyph'
+  | 'image'
+  | 'processing'
+  | 'serialization'
+  | 'tauri'
+  | 'network'
+  | 'model_not_installed'
+  | 'model_invalid'
+  | 'model_source_invalid'
+  | 'no_fonts_matched'
+  | 'no_weight_match'
+  | 'session_not_found'
+  | 'session_busy'
+  | 'worker_failed'
+  | 'cancelled'
+  | 'resource_limit';
+
+/** Structured context a backend error may carry; every field is optional. */
+export interface ErrorDetails {
+  path?: string;
+  model_id?: string;
+  font_name?: string;
+  session_id?: string;
+  /** Pipeline stage the error happened in. */
+  stage?: RunStage;
+}
+
+/** A backend error, as rejected commands and reports carry it. */
+export interface AppError {
+  code: ErrorCode;
+  /** Human-readable message, including the errors it adds context to. */
+  message: string;
+  details: ErrorDetails;
+}
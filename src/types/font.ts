@@ -40,6 +40,41 @@ export interface FontMetadata {
   weight: number;
   weights: string[];
   font_index: number;
+  /** Absent for fonts discovered before metrics were extracted. */
+  metrics?: FontMetrics;
+}
+
+/**
+ * Typographic measurements of a face. Ratios are relative to the em; fields
+ * the face doesn't declare are null. `stroke_contrast` is estimated from the
+ * rendered sample, so it is only set once rendering has run.
+ */
+export interface FontMetrics {
+  x_height_ratio: number | null;
+  cap_height_ratio: number | null;
+  average_advance_ratio: number | null;
+  /** Degrees counter-clockwise from vertical; right-leaning italics are negative. */
+  italic_angle: number | null;
+  width_class: number | null;
+  family_class: [number, number] | null;
+  panose: number[] | null;
+  stroke_contrast: number | null;
+}
+
+/** Scalar metrics usable as sort/filter keys and clustering axes. */
+export type FontMetricKey =
+  | 'x_height'
+  | 'cap_height'
+  | 'width'
+  | 'weight'
+  | 'contrast'
+  | 'italic_angle';
+
+/** Inclusive range on one metric; a null bound is open. */
+export interface MetricFilter {
+  key: FontMetricKey;
+  min: number | null;
+  max: number | null;
 }
 
 export interface ClusteringData {
@@ -7,7 +7,8 @@ export type ModelAvailability = 'available' | 'not_downloaded';
 
 /** Metadata for one selectable analysis model. */
 export interface ModelCatalogEntry {
-  /** Stable release tag and on-disk directory name. */
+  /** Stable release tag and on-disk directory name; `local:<name>` for an
+   *  imported model. */
   id: string;
   /** Human-readable label from the model manifest. */
   name: string;
@@ -31,3 +32,55 @@ export interface ModelCatalogResponse {
   /** Recoverable remote-catalog warning, or `null` for a complete response. */
   warning: string | null;
 }
+
+/**
+ * Where published models are listed and downloaded from. Mirrors serve an
+ * `index.json` of GitHub-shaped releases whose assets carry SHA-256 digests.
+ */
+export type ModelSource =
+  /** A GitHub-compatible API; `null` for the public model repository. */
+  | { kind: 'github'; apiBase: string | null }
+  /** A static HTTP server with `index.json` at `baseUrl`. */
+  | { kind: 'http_mirror'; baseUrl: string }
+  /** An absolute directory laid out like an HTTP mirror. */
+  | { kind: 'filesystem'; path: string };
+
+/** Checksum comparison of one model payload. */
+export interface VerifiedFile {
+  name: string;
+  expectedSha256: string;
+  /** Digest on disk; `null` when the file is missing or unreadable. */
+  actualSha256: string | null;
+  matches: boolean;
+}
+
+/** Result of re-hashing an installed model against its manifest. */
+export interface ModelVerification {
+  modelId: string;
+  valid: boolean;
+  files: VerifiedFile[];
+}
+
+/**
+ * Outcome of a model deletion. An unforced deletion of a model that sessions
+ * reference keeps it and lists those sessions as a warning.
+ */
+export interface ModelDeletion {
+  modelId: string;
+  deleted: boolean;
+  freedBytes: number;
+  referencingSessions: string[];
+}
+
+/** Bytes an installed model bundle occupies. */
+export interface ModelStorage {
+  modelId: string;
+  bytes: number;
+}
+
+/** Disk usage of the installed models, largest first. */
+export interface ModelStorageSummary {
+  models: ModelStorage[];
+  /** Whole model root, including interrupted downloads. */
+  modelsBytes: number;
+}
@@ -1,4 +1,6 @@
-import type { FontWeight } from './font';
+import type { FontMetricKey, FontWeight } from './font';
+import type { AppError } from './error';
+import type { ModelStorage } from './model';
 
 export type ClusteringMethod =
   | 'single'
@@ -23,6 +25,11 @@ export interface ClusteringOptions {
   // eslint-disable-next-line @typescript-eslint/naming-convention
   enable_attribute_emphasis: boolean;
   emphasis: EmphasisLevels;
+  /** Weights of typographic metric axes appended to the clustering features;
+   * a missing key or `0` leaves the metric out. Absent in older sessions. */
+  // snake_case to mirror the backend's serde field name verbatim.
+  // eslint-disable-next-line @typescript-eslint/naming-convention
+  metric_weights?: Partial<Record<FontMetricKey, number>>;
 }
 
 /**
@@ -49,11 +56,27 @@ export type FontSet =
   | 'google_fonts_popular1500'
   | 'google_fonts_all';
 
+export type TextDirection =
+  | 'auto'
+  | 'left_to_right'
+  | 'right_to_left'
+  | 'top_to_bottom';
+
+export type SampleCanvas = 'cropped' | 'normalized';
+
 export interface RenderingOptions {
   text: string;
   weights: FontWeight[];
   font_set: FontSet;
   font_size: number;
+  /** Omitted by older sessions; the backend treats that as `auto`. */
+  direction?: TextDirection;
+  /** OpenType feature settings such as `smcp`, `-liga` or `salt=2`. */
+  features?: string[];
+  /** BCP-47 language tag selecting the font's language system. */
+  language?: string | null;
+  /** Omitted by older sessions; the backend treats that as `cropped`. */
+  canvas?: SampleCanvas;
 }
 
 export interface AnalysisOptions {
@@ -138,6 +161,9 @@ export interface ProcessingStatus {
   samples_amount: number;
   clustering_stats: ClusteringStats;
   progress: SessionProgress;
+  /** True while the session's running job is paused. */
+  // eslint-disable-next-line @typescript-eslint/naming-convention -- set by the backend's pause flag on ProcessingStatus
+  paused?: boolean;
 }
 
 export interface SessionConfig {
@@ -145,11 +171,308 @@ export interface SessionConfig {
   /** User-given display name; empty means "untitled" and the UI falls back to
    *  the rendering sample text. */
   title: string;
+  /** User labels; absent in sessions written before tagging. */
+  tags?: string[];
+  notes?: string;
+  // eslint-disable-next-line @typescript-eslint/naming-convention -- pin flag stored in the session config as `pinned`
+  pinned?: boolean;
+  // eslint-disable-next-line @typescript-eslint/naming-convention -- favourite flag stored in the session config as `favorite`
+  favorite?: boolean;
+  /** `/`-separated folder path; empty means the top level. */
+  folder?: string;
   created_at: string;
   modified_at: string;
   app_version: string;
   modified_app_version: string;
+  /** Storage layout version; older sessions are migrated on startup. */
+  schema_version?: number;
   status: ProcessingStatus;
   discovered_fonts: Record<number, string[]>;
   algorithm: AlgorithmConfig;
 }
+
+export type MigrationOutcome =
+  | { outcome: 'migrated'; steps: string[] }
+  | { outcome: 'quarantined'; reason: string; quarantine_path: string | null }
+  | { outcome: 'skipped'; reason: string };
+
+export type MigrationEntry = {
+  session_id: string | null;
+  path: string;
+  from_schema: number | null;
+} & MigrationOutcome;
+
+/** One startup (or import) pass over stored sessions that changed anything. */
+export interface MigrationRun {
+  ran_at: string;
+  app_version: string;
+  entries: MigrationEntry[];
+}
+
+/** Which system fonts a portable export embedded, by safe name. */
+export interface EmbeddedFontReport {
+  embedded: string[];
+  /** Fonts whose `OS/2 fsType` licence forbids embedding. */
+  restricted: string[];
+  /** Fonts with CFF outlines, which can't be subset. */
+  unsupported: string[];
+  /** Fonts not installed on this machine, or that failed to subset. */
+  unavailable: string[];
+}
+
+export interface MovedFont {
+  safe_name: string;
+  k_a: number;
+  k_b: number;
+  /** Jaccard distance between the font's cluster-mates in either session. */
+  displacement: number;
+}
+
+/** Agreement between two sessions' clusterings over their shared fonts. */
+export interface SessionComparison {
+  session_a: string;
+  session_b: string;
+  shared_fonts: number;
+  only_in_a: string[];
+  only_in_b: string[];
+  adjusted_rand_index: number;
+  normalized_mutual_information: number;
+  clusters_a: number[];
+  clusters_b: number[];
+  /** Rows follow `clusters_a`, columns follow `clusters_b`. */
+  contingency: number[][];
+  moved_fonts: MovedFont[];
+}
+
+/** Values to sweep per clustering parameter; an empty axis keeps the
+ *  session's value. */
+export interface ClusteringSweepGrid {
+  methods?: ClusteringMethod[];
+  distance_thresholds?: number[];
+  preprocessing_dimensions?: number[];
+  target_cluster_counts?: number[];
+}
+
+export interface SweepMetrics {
+  clusters_amount: number;
+  singletons: number;
+  largest_cluster: number;
+  cut_height: number;
+  /** Mean silhouette; `null` with one cluster or all singletons. */
+  silhouette: number | null;
+}
+
+export interface SweepVariant {
+  config: ClusteringOptions;
+  labels: Record<string, number>;
+  metrics: SweepMetrics;
+}
+
+export interface ClusteringSweep {
+  sweep_id: string;
+  session_id: string;
+  created_at: string;
+  app_version: string;
+  grid: ClusteringSweepGrid;
+  variants: SweepVariant[];
+  best_variant: number | null;
+}
+
+/** Partial metadata edit; omitted fields are left unchanged. */
+export interface SessionMetadataPatch {
+  title?: string;
+  tags?: string[];
+  notes?: string;
+  // eslint-disable-next-line @typescript-eslint/naming-convention -- SessionMetadataPatch field deserialised as `pinned`
+  pinned?: boolean;
+  // eslint-disable-next-line @typescript-eslint/naming-convention -- SessionMetadataPatch field deserialised as `favorite`
+  favorite?: boolean;
+  folder?: string;
+}
+
+/** History search filters; every supplied filter must match. */
+export interface SessionSearchQuery {
+  /** Terms matched against title, sample text, notes, model, font set, folder
+   *  and tags. */
+  text?: string;
+  tags?: string[];
+  /** Folder to search in, including its subfolders. */
+  folder?: string;
+  // eslint-disable-next-line @typescript-eslint/naming-convention -- search filter deserialised as `pinned`
+  pinned?: boolean;
+  // eslint-disable-next-line @typescript-eslint/naming-convention -- search filter deserialised as `favorite`
+  favorite?: boolean;
+}
+
+/** Which sessions garbage collection keeps; pinned, favourite, active and
+ *  running sessions are always kept. */
+export interface StoragePolicy {
+  /** Most recent unpinned sessions to keep; `null` keeps all. */
+  keep_latest: number | null;
+  /** Age in days past which unpinned sessions are deleted; `null` never. */
+  max_age_days: number | null;
+}
+
+/** What a job worker may use; `null` leaves a limit off. */
+export interface ResourceProfile {
+  /** Rayon thread pool size; `null` uses every core. */
+  max_threads: number | null;
+  /** ONNX Runtime intra-op threads; `null` follows `max_threads`. */
+  onnx_threads: number | null;
+  // eslint-disable-next-line @typescript-eslint/naming-convention -- ResourceProfile field persisted in settings as `low_priority`
+  low_priority: boolean;
+  /** Soft ceiling on the worker's resident memory, in MiB. */
+  memory_ceiling_mb: number | null;
+  /** Images per ONNX batch; `null` uses the model's declared batch size. */
+  batch_size: number | null;
+  /**
+   * Connections a large model asset is downloaded over, at most 16; `null`
+   * uses one.
+   */
+  download_connections: number | null;
+}
+
+export interface SessionStorage {
+  session_id: string;
+  title: string;
+  modified_at: string | null;
+  // eslint-disable-next-line @typescript-eslint/naming-convention -- SessionStorage field serialised as `pinned`
+  pinned: boolean;
+  // eslint-disable-next-line @typescript-eslint/naming-convention -- SessionStorage field serialised as `favorite`
+  favorite: boolean;
+  document_bytes: number;
+  processing_bytes: number;
+  current_bytes: number;
+  total_bytes: number;
+}
+
+/** Disk usage of everything the app stores, largest entries first. */
+export interface StorageReport {
+  sessions: SessionStorage[];
+  models: ModelStorage[];
+  models_bytes: number;
+  preview_cache_bytes: number;
+  sweeps_bytes: number;
+  quarantine_bytes: number;
+  total_bytes: number;
+}
+
+/** Sessions a garbage collection run deleted, or would delete on a dry run. */
+export interface GcReport {
+  deleted: string[];
+  freed_bytes: number;
+  // eslint-disable-next-line @typescript-eslint/naming-convention -- GcReport field serialised as `dry_run`
+  dry_run: boolean;
+}
+
+/** A session whose job stopped before clustering completed. */
+export interface InterruptedSession {
+  session_id: string;
+  title: string;
+  /** Stage a resume restarts from. */
+  process_status: ProcessStatus;
+  modified_at: string;
+  discovered_fonts: number;
+  /** Fonts the interrupted rendering pass already finished. */
+  rendered_fonts: number;
+  /** Fonts the interrupted analysis pass already finished. */
+  analyzed_fonts: number;
+  /** Discarding falls back to an earlier completed run when one exists. */
+  // eslint-disable-next-line @typescript-eslint/naming-convention -- InterruptedSession field serialised as `has_document`
+  has_document: boolean;
+}
+
+/** A job submitted to the backend job queue. */
+export type QueuedJob = {
+  job_id: string;
+  /** Higher runs first; equal priorities run in queue order. */
+  priority: number;
+  submitted_at: string;
+} & (
+  | {
+      kind: 'pipeline';
+      request: {
+        algorithm: Partial<AlgorithmConfig>;
+        sessionId: string | null;
+        sourceSessionId: string | null;
+        overrideStatus: ProcessStatus | null;
+        runMode: 'duplicate_changed' | 'in_place_changed' | 'fresh';
+        // eslint-disable-next-line @typescript-eslint/naming-convention -- job payload flag serialised as `resume`
+        resume: boolean;
+      };
+    }
+  | {
+      kind: 'sweep';
+      request: { sessionId: string; grid: ClusteringSweepGrid };
+    }
+);
+
+/** Queue state, as returned and as sent with `job_queue_changed`. */
+export interface JobQueueSnapshot {
+  max_concurrent: number;
+  /** Waiting jobs, in queue order. */
+  queued: QueuedJob[];
+  running: QueuedJob[];
+}
+
+/** A pipeline stage as recorded in a session's run log. */
+export type RunStage = 'discovery' | 'rendering' | 'analysis' | 'clustering';
+
+/** One line of a session's run log. */
+export type RunLogEntry = {
+  /** Shared by every entry one run wrote. */
+  run_id: string;
+  at: string;
+} & (
+  | {
+      event: 'run_started';
+      session_id: string;
+      app_version: string;
+      run_mode: 'duplicate_changed' | 'in_place_changed' | 'fresh';
+      // eslint-disable-next-line @typescript-eslint/naming-convention -- run log event field serialised as `resume`
+      resume: boolean;
+      /** Stage the run started from. */
+      resume_status: ProcessStatus;
+      rayon_threads: number;
+      available_parallelism: number;
+      onnx_threads: number;
+      resource_profile: ResourceProfile;
+    }
+  | { event: 'model_selected'; model_id: string; model_sha256: string }
+  | { event: 'stage_started'; stage: RunStage }
+  | { event: 'stage_completed'; stage: RunStage; duration_ms: number }
+  | {
+      event: 'font_failed';
+      stage: RunStage;
+      safe_name: string;
+      /** The error that dropped the font. */
+      reason: AppError;
+    }
+  | { event: 'sample_clipped'; safe_name: string }
+  | {
+      event: 'run_finished';
+      result: 'Success' | 'Cancelled';
+      duration_ms: number;
+    }
+  | { event: 'run_failed'; error: AppError; duration_ms: number }
+);
+
+/** A font or face a session's pipeline excluded. */
+export interface DroppedFont {
+  stage: RunStage;
+  family_name: string;
+  /** PostScript name of a face discovery rejected. */
+  face_name: string | null;
+  /**
+   * Requested weight, unless discovery rejected the face for every weight or
+   * skipped the family's `missing_weights`.
+   */
+  weight: number | null;
+  /** Requested weights a family has no face within the weight window of. */
+  missing_weights: number[];
+  /** Set when discovery kept the font before a later stage dropped it. */
+  safe_name: string | null;
+  path: string | null;
+  /** The error that excluded the font. */
+  reason: AppError;
+}